@@ -0,0 +1,92 @@
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use mono::evaluator::Evaluator;
+use mono::parser::node::Node;
+use mono::parser::Parser;
+use mono::tokenizer::Tokenizer;
+use std::hint::black_box;
+
+const FIB: &str = "
+let fib(n) => {
+    if n < 2 { return n }
+    return fib(n - 1) + fib(n - 2)
+}
+fib(15)
+";
+
+const LIST_SUM: &str = "
+let xs = range(0, 2000)
+let total = 0
+for x in xs { total = total + x }
+total
+";
+
+const STRING_BUILDING: &str = "
+let s = \"\"
+let i = 0
+while i < 1000 {
+    s = s + \"x\"
+    i = i + 1
+}
+s
+";
+
+const STRING_BUILDER: &str = "
+let builder = string_builder()
+let i = 0
+while i < 1000 {
+    append(builder, \"x\")
+    i = i + 1
+}
+build(builder)
+";
+
+fn source() -> String {
+    [FIB, LIST_SUM, STRING_BUILDING].join("\n").repeat(20)
+}
+
+fn parse(code: &str) -> Box<Node> {
+    match Parser::new(Tokenizer::new(code.chars())).parse() {
+        Ok(ast) => ast,
+        Err(error) => panic!("Benchmark code must parse: {}", error),
+    }
+}
+
+fn tokenizer(c: &mut Criterion) {
+    let code = source();
+    let mut group = c.benchmark_group("tokenizer");
+    group.throughput(Throughput::Bytes(code.len() as u64));
+    group.bench_function("tokens", |b| {
+        b.iter(|| Tokenizer::new(black_box(code.as_str()).chars()).count())
+    });
+    group.finish();
+}
+
+fn parser(c: &mut Criterion) {
+    let code = source();
+    let mut group = c.benchmark_group("parser");
+    group.throughput(Throughput::Bytes(code.len() as u64));
+    group.bench_function("program", |b| b.iter(|| parse(black_box(&code))));
+    group.finish();
+}
+
+fn evaluator(c: &mut Criterion) {
+    let mut group = c.benchmark_group("evaluator");
+    for (name, code) in [
+        ("fib", FIB),
+        ("list_sum", LIST_SUM),
+        ("string_building", STRING_BUILDING),
+        ("string_builder", STRING_BUILDER),
+    ] {
+        let ast = parse(code);
+        if let Err(error) = Evaluator::new().evaluate(&ast) {
+            panic!("Benchmark code must evaluate: {}", error);
+        }
+        group.bench_function(name, |b| {
+            b.iter(|| Evaluator::new().evaluate(black_box(&ast)).is_ok())
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, tokenizer, parser, evaluator);
+criterion_main!(benches);
@@ -0,0 +1,21 @@
+use mono::evaluator::Evaluator;
+use mono::extensions::units;
+
+const SCRIPT: &str = r#"
+let distance = unit(3, "km")
+let time = unit(20, "min")
+let speed = distance / time
+
+println(speed)
+println(magnitude(speed, "km/h"))
+println(unit(80, "kg") * unit(9.8, "m/s^2"))
+println(unit(1, "km") > unit(900, "m"))
+"#;
+
+fn main() {
+    let mut evaluator = Evaluator::new();
+    units::register(&mut evaluator);
+    if mono::evaluator(SCRIPT, &mut evaluator).is_err() {
+        std::process::exit(1);
+    }
+}
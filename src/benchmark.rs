@@ -0,0 +1,131 @@
+//! Micro-programs for `mono --bench-internal`, each exercising one part of
+//! the evaluator, timed to give a quick number to compare before and after a
+//! change. The Criterion benchmarks in `benches/` are the thorough ones.
+
+use crate::evaluator::limits::Limits;
+use crate::models::error::MonoError;
+use crate::parser::node::Node;
+use crate::Evaluator;
+use std::fmt;
+use std::time::{Duration, Instant};
+
+pub const PROGRAMS: [(&str, &str); 5] = [
+    (
+        "arithmetic",
+        "let i = 0
+let total = 0
+while i < 10000 {
+    total = total + i % 7 * 3 - 1
+    i = i + 1
+}",
+    ),
+    (
+        "calls",
+        "def fib(n) {
+    if n < 2 { return n }
+    return fib(n - 1) + fib(n - 2)
+}
+fib(15)",
+    ),
+    (
+        "lists",
+        "let xs = []
+let i = 0
+while i < 2000 {
+    list.push(xs, i)
+    i = i + 1
+}
+let total = 0
+for x in xs { total = total + xs[x] }",
+    ),
+    (
+        "strings",
+        "let builder = string_builder()
+let i = 0
+while i < 2000 {
+    append(builder, i)
+    i = i + 1
+}
+build(builder)",
+    ),
+    (
+        "structs",
+        "struct Point { x, y }
+impl Point { norm(self) => { return self.x * self.x + self.y * self.y } }
+let i = 0
+let total = 0
+while i < 2000 {
+    total = total + Point(i % 10, 2).norm()
+    i = i + 1
+}",
+    ),
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Measurement {
+    pub name: &'static str,
+    pub runs: u32,
+    // Nodes evaluated by a single run.
+    pub steps: u64,
+    pub elapsed: Duration,
+}
+
+impl Measurement {
+    pub fn runs_per_second(&self) -> f64 {
+        f64::from(self.runs) / self.elapsed.as_secs_f64()
+    }
+
+    pub fn steps_per_second(&self) -> f64 {
+        self.steps as f64 * self.runs_per_second()
+    }
+}
+
+impl fmt::Display for Measurement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:<12}{:>12.1} runs/s{:>14.0} steps/s",
+            self.name,
+            self.runs_per_second(),
+            self.steps_per_second()
+        )
+    }
+}
+
+// Runs the program over and over in fresh evaluators for about `duration`,
+// at least once.
+pub fn measure(
+    name: &'static str,
+    program: &Node,
+    duration: Duration,
+) -> Result<Measurement, MonoError> {
+    let start = Instant::now();
+    let mut runs = 0;
+    let mut steps = 0;
+    while runs == 0 || start.elapsed() < duration {
+        let mut evaluator = Evaluator::new();
+        // Unbounded, only set for the evaluator to count the steps.
+        evaluator.set_limits(Limits {
+            steps: Some(u64::MAX),
+            ..Limits::default()
+        });
+        evaluator.evaluate(program)?;
+        steps = evaluator.steps();
+        runs += 1;
+    }
+    Ok(Measurement {
+        name,
+        runs,
+        steps,
+        elapsed: start.elapsed(),
+    })
+}
+
+pub fn run(duration: Duration) -> Result<Vec<Measurement>, MonoError> {
+    PROGRAMS
+        .iter()
+        .map(|(name, code)| {
+            crate::parse(code).and_then(|program| measure(name, &program, duration))
+        })
+        .collect()
+}
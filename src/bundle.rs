@@ -0,0 +1,207 @@
+//! Bundles: a script and every script it runs with `run_file`, parsed and
+//! written to a single file that runs without the sources.
+//!
+//! Only `run_file` calls given a string literal are followed, found from the
+//! working directory, the including script's directory or the search paths
+//! of the project it's in. The bundle starts with a
+//! `#!/usr/bin/env -S mono exec` line, so once executable it runs directly.
+
+use crate::evaluator::state;
+use crate::evaluator::Evaluator;
+use crate::models::error::MonoError;
+use crate::parser::node::Node;
+use crate::parser::visitor::{visit_children, NodeVisitor};
+use crate::project::{self, Project};
+use crate::tokenizer::token::{Token, TokenKind};
+use std::collections::HashSet;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+const SHEBANG: &[u8] = b"#!/usr/bin/env -S mono exec\n";
+const MAGIC: &[u8; 4] = b"MONB";
+
+#[derive(Debug)]
+pub enum BuildError {
+    Io(PathBuf, io::Error),
+    // The file with the error, as its tokens' positions don't say.
+    Syntax(PathBuf, MonoError),
+}
+
+impl fmt::Display for BuildError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BuildError::Io(path, error) => write!(f, "{}: {}", path.display(), error),
+            BuildError::Syntax(path, error) => write!(f, "{}: {}", path.display(), error),
+        }
+    }
+}
+
+impl std::error::Error for BuildError {}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Bundle {
+    pub entry: Box<Node>,
+    // Programs by the path `run_file` is called with.
+    pub modules: Vec<(String, Box<Node>)>,
+}
+
+impl Bundle {
+    pub fn build(path: &Path) -> Result<Self, BuildError> {
+        let entry = parse(path)?;
+        let mut modules = Vec::new();
+        let mut pending = vec![(path.to_path_buf(), run_files(&entry))];
+        let mut seen = HashSet::new();
+        while let Some((including, names)) = pending.pop() {
+            let paths = search_paths(&including);
+            for name in names {
+                if !seen.insert(name.clone()) {
+                    continue;
+                }
+                let path = project::resolve(&name, &paths);
+                let program = parse(&path)?;
+                pending.push((path, run_files(&program)));
+                modules.push((name, program));
+            }
+        }
+        Ok(Self { entry, modules })
+    }
+
+    pub fn to_bytes(&self) -> io::Result<Vec<u8>> {
+        let mut bytes = [SHEBANG, MAGIC].concat();
+        bytes.extend((self.modules.len() as u64).to_le_bytes());
+        for (name, program) in &self.modules {
+            chunk(&mut bytes, name.as_bytes());
+            chunk(&mut bytes, &state::save_program(program)?);
+        }
+        chunk(&mut bytes, &state::save_program(&self.entry)?);
+        Ok(bytes)
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> io::Result<Self> {
+        let mut bytes = bytes
+            .strip_prefix(SHEBANG)
+            .and_then(|bytes| bytes.strip_prefix(MAGIC))
+            .ok_or_else(|| invalid("not a mono bundle"))?;
+        let count = u64::from_le_bytes(take(&mut bytes, 8)?.try_into().expect("took 8 bytes"));
+        let mut modules = Vec::new();
+        for _ in 0..count {
+            let name = String::from_utf8(next(&mut bytes)?.to_vec())
+                .map_err(|_| invalid("invalid UTF-8"))?;
+            modules.push((name, state::load_program(next(&mut bytes)?)?));
+        }
+        let entry = state::load_program(next(&mut bytes)?)?;
+        if !bytes.is_empty() {
+            return Err(invalid("trailing bytes"));
+        }
+        Ok(Self { entry, modules })
+    }
+
+    // Writes the bundle, executable where files have permissions.
+    pub fn write(&self, path: &Path) -> io::Result<()> {
+        fs::write(path, self.to_bytes()?)?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(path, fs::Permissions::from_mode(0o755))?;
+        }
+        Ok(())
+    }
+
+    // Makes the modules what `run_file` runs, returning the entry to run.
+    pub fn install(self, evaluator: &mut Evaluator) -> Box<Node> {
+        for (name, program) in self.modules {
+            evaluator.add_module(&name, *program);
+        }
+        self.entry
+    }
+}
+
+fn parse(path: &Path) -> Result<Box<Node>, BuildError> {
+    let code = fs::read_to_string(path).map_err(|error| BuildError::Io(path.into(), error))?;
+    crate::parse(&code).map_err(|error| BuildError::Syntax(path.into(), error))
+}
+
+// Where the scripts a script runs are looked for besides the working
+// directory.
+fn search_paths(including: &Path) -> Vec<PathBuf> {
+    let directory = match including.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+        _ => PathBuf::from("."),
+    };
+    let mut paths = vec![directory.clone()];
+    if let Ok(project) = Project::find(&directory) {
+        paths.extend(project.paths());
+    }
+    paths
+}
+
+// The string literals `run_file` is called with, in order.
+fn run_files(program: &Node) -> Vec<String> {
+    let mut collector = RunFiles(Vec::new());
+    collector.visit(program);
+    collector.0
+}
+
+struct RunFiles(Vec<String>);
+
+impl NodeVisitor for RunFiles {
+    fn visit(&mut self, node: &Node) {
+        match node {
+            Node::FuncCall {
+                identifier:
+                    Token {
+                        kind: TokenKind::Identifier(name),
+                        ..
+                    },
+                parameters,
+                ..
+            } if &**name == "run_file" => {
+                if let [parameter] = parameters.as_slice() {
+                    if let Node::Atom {
+                        value:
+                            Token {
+                                kind: TokenKind::String(path),
+                                ..
+                            },
+                    } = parameter.as_ref()
+                    {
+                        self.0.push(path.clone());
+                    }
+                }
+            }
+            _ => {}
+        }
+        visit_children(self, node);
+    }
+}
+
+fn chunk(bytes: &mut Vec<u8>, data: &[u8]) {
+    bytes.extend((data.len() as u64).to_le_bytes());
+    bytes.extend(data);
+}
+
+fn take<'b>(bytes: &mut &'b [u8], length: usize) -> io::Result<&'b [u8]> {
+    if length > bytes.len() {
+        return Err(invalid("unexpected end"));
+    }
+    let (taken, rest) = bytes.split_at(length);
+    *bytes = rest;
+    Ok(taken)
+}
+
+fn next<'b>(bytes: &mut &'b [u8]) -> io::Result<&'b [u8]> {
+    let length = u64::from_le_bytes(take(bytes, 8)?.try_into().expect("took 8 bytes"));
+    take(
+        bytes,
+        usize::try_from(length).map_err(|_| invalid("length too large"))?,
+    )
+}
+
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("Invalid bundle: {}.", message),
+    )
+}
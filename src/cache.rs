@@ -0,0 +1,53 @@
+//! Parsed programs cached on disk. Running `script.mono` writes its syntax
+//! tree to `script.monoc` next to it, with a hash of the source, and later
+//! runs of the unchanged file load the tree instead of tokenizing and
+//! parsing it again. A missing, stale or unreadable cache is never an error,
+//! the file is just parsed.
+
+use crate::evaluator::state;
+use crate::models::error::MonoError;
+use crate::parser::node::Node;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const MAGIC: &[u8; 4] = b"MONC";
+
+pub fn path(source: &Path) -> PathBuf {
+    source.with_extension("monoc")
+}
+
+// The program in the file, from its cache when the code is unchanged.
+// Syntax errors aren't cached, they're reported again on every run.
+pub fn parse(source: &Path, code: &str) -> Result<Box<Node>, MonoError> {
+    let path = path(source);
+    let hash = hash(code);
+    if let Some(program) = fs::read(&path).ok().and_then(|bytes| load(&bytes, hash)) {
+        return Ok(program);
+    }
+    let program = crate::parse(code)?;
+    // A program that can't be saved or a read-only directory only means
+    // there's no cache.
+    if let Ok(saved) = state::save_program(&program) {
+        let mut bytes = MAGIC.to_vec();
+        bytes.extend(hash.to_le_bytes());
+        bytes.extend(saved);
+        let _ = fs::write(&path, bytes);
+    }
+    Ok(program)
+}
+
+fn load(bytes: &[u8], hash: u64) -> Option<Box<Node>> {
+    let bytes = bytes.strip_prefix(MAGIC)?;
+    let (cached, program) = bytes.split_first_chunk::<8>()?;
+    if u64::from_le_bytes(*cached) != hash {
+        return None;
+    }
+    state::load_program(program).ok()
+}
+
+// 64 bit FNV-1a, which unlike `DefaultHasher` is the same in every build.
+fn hash(code: &str) -> u64 {
+    code.bytes().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
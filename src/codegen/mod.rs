@@ -0,0 +1,359 @@
+mod runtime;
+
+use crate::internal_err;
+use crate::models::position::Position;
+use crate::parser::node::{Argument, Node};
+use crate::tokenizer::token::{Token, TokenKind};
+use runtime::RUNTIME;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+// An experimental backend emitting a standalone Rust program equivalent to a
+// mono one, so hot scripts can be compiled natively with `rustc -O`.
+//
+// Only a subset is supported: integers, floats, booleans and `None`, `let`
+// and assignments, `if`, `while` and `do` loops, and top-level functions
+// taking plain arguments and using only those and their own variables.
+// `print` and `println` are the only builtins. Values keep the evaluator's
+// semantics, including checked arithmetic and operands evaluated right to
+// left, and a runtime error ends the program with status 1. Anything else
+// is `Unsupported`.
+pub fn transpile(program: &Node) -> Result<String, Unsupported> {
+    let Node::Program { statements } = program else {
+        return Err(Unsupported::new("a program", program));
+    };
+    let mut functions = HashMap::new();
+    for statement in statements {
+        if let Node::FuncDeclearion {
+            identifier,
+            arguments,
+            ..
+        } = statement.as_ref()
+        {
+            if functions
+                .insert(name(identifier), arguments.len())
+                .is_some()
+            {
+                return Err(Unsupported::new("functions declared twice", statement));
+            }
+        }
+    }
+    let mut transpiler = Transpiler {
+        functions,
+        variables: HashSet::new(),
+    };
+
+    let mut source = String::from(RUNTIME);
+    for statement in statements {
+        if let Node::FuncDeclearion {
+            identifier,
+            arguments,
+            body,
+            ..
+        } = statement.as_ref()
+        {
+            source.push_str(&transpiler.function(identifier, arguments, body)?);
+        }
+    }
+    transpiler.variables = declarations(statements);
+    let mut lines = Vec::new();
+    for statement in statements {
+        lines.push(match statement.as_ref() {
+            // Transpiled on their own above.
+            Node::FuncDeclearion { .. } => String::from("Value::None"),
+            statement => transpiler.expression(statement)?,
+        });
+    }
+    let body = block(lines);
+    source.push_str(&format!(
+        "\nfn program() -> Value {{\n{}    {}\n}}\n",
+        locals(&transpiler.variables),
+        body
+    ));
+    // The CLI prints the value a program ends with, unless it's `None`.
+    source.push_str(
+        "\nfn main() {\n    let value = program();\n    if value != Value::None {\n        println!(\"{}\\n\", value);\n    }\n}\n",
+    );
+    Ok(source)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Unsupported {
+    pub what: &'static str,
+    pub start: Option<Position>,
+}
+
+impl Unsupported {
+    fn new(what: &'static str, node: &Node) -> Self {
+        Self {
+            what,
+            start: node.position().cloned(),
+        }
+    }
+
+    fn at(what: &'static str, token: &Token) -> Self {
+        Self {
+            what,
+            start: Some(token.start.clone()),
+        }
+    }
+}
+
+impl fmt::Display for Unsupported {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.start {
+            Some(start) => write!(f, "Can't transpile {} at {}.", self.what, start),
+            None => write!(f, "Can't transpile {}.", self.what),
+        }
+    }
+}
+
+impl std::error::Error for Unsupported {}
+
+struct Transpiler {
+    // Arities of the top-level functions.
+    functions: HashMap<String, usize>,
+    // The variables of the function being transpiled, or of the program.
+    variables: HashSet<String>,
+}
+
+impl Transpiler {
+    fn function(
+        &mut self,
+        identifier: &Token,
+        arguments: &[Argument],
+        body: &Node,
+    ) -> Result<String, Unsupported> {
+        let mut parameters = Vec::new();
+        for argument in arguments {
+            if argument.is_variadic || argument.default.is_some() {
+                return Err(Unsupported::at(
+                    "variadic or default arguments",
+                    &argument.identifier,
+                ));
+            }
+            parameters.push(format!("mut v_{}: Value", argument.name()));
+        }
+        let Node::Program { statements } = body else {
+            return Err(Unsupported::new("a function body", body));
+        };
+        let mut variables = declarations(statements);
+        for argument in arguments {
+            variables.remove(argument.name().as_ref());
+        }
+        let declared = locals(&variables);
+        variables.extend(arguments.iter().map(|argument| argument.name().to_string()));
+        self.variables = variables;
+        let body = self.block(statements)?;
+        // Functions return `None` unless they `return`.
+        Ok(format!(
+            "\nfn f_{}({}) -> Value {{\n{}    {};\n    Value::None\n}}\n",
+            name(identifier),
+            parameters.join(", "),
+            declared,
+            body
+        ))
+    }
+
+    fn block(&self, statements: &[Box<Node>]) -> Result<String, Unsupported> {
+        let lines = statements
+            .iter()
+            .map(|statement| self.expression(statement))
+            .collect::<Result<_, _>>()?;
+        Ok(block(lines))
+    }
+
+    fn expression(&self, node: &Node) -> Result<String, Unsupported> {
+        Ok(match node {
+            Node::Atom { value } => match &value.kind {
+                TokenKind::Integer(integer) => format!("Value::Integer({})", integer),
+                TokenKind::Float(float) => format!("Value::Float({:?}_f32)", float),
+                TokenKind::Boolean(boolean) => format!("Value::Boolean({})", boolean),
+                TokenKind::None => String::from("Value::None"),
+                _ => {
+                    return Err(Unsupported::new(
+                        "values other than numbers, booleans and None",
+                        node,
+                    ))
+                }
+            },
+            Node::BinaryOp {
+                left,
+                operator,
+                right,
+            } => {
+                let function = match operator.kind {
+                    TokenKind::Add => "add(left, right)",
+                    TokenKind::Sub => "sub(left, right)",
+                    TokenKind::Mul => "mul(left, right)",
+                    TokenKind::Div => "div(left, right)",
+                    TokenKind::Mod => "modulo(left, right)",
+                    TokenKind::Pow => "pow(left, right)",
+                    TokenKind::Equals => "equals(left, right)",
+                    TokenKind::NotEquals => "not_equals(left, right)",
+                    TokenKind::Greater => "compare(\">\", left, right)",
+                    TokenKind::GreaterEq => "compare(\">=\", left, right)",
+                    TokenKind::LessThan => "compare(\"<\", left, right)",
+                    TokenKind::LessThanEq => "compare(\"<=\", left, right)",
+                    TokenKind::And => "and(left, right)",
+                    TokenKind::Or => "or(left, right)",
+                    _ => return Err(Unsupported::at("this operator", operator)),
+                };
+                format!(
+                    "{{ let right = {}; let left = {}; {} }}",
+                    self.expression(right)?,
+                    self.expression(left)?,
+                    function
+                )
+            }
+            Node::UnaryOp { operator, value } => {
+                let function = match operator.kind {
+                    TokenKind::Add => "pos",
+                    TokenKind::Sub => "neg",
+                    TokenKind::Not => "not",
+                    _ => return Err(Unsupported::at("this operator", operator)),
+                };
+                format!("{}({})", function, self.expression(value)?)
+            }
+            Node::Access { identifier, .. } => format!("v_{}", self.variable(identifier)?),
+            Node::Assignment {
+                identifier, value, ..
+            } => format!(
+                "{{ v_{} = {}; Value::None }}",
+                self.variable(identifier)?,
+                self.expression(value)?
+            ),
+            Node::FuncCall {
+                identifier,
+                parameters,
+                keywords,
+                ..
+            } => {
+                let name = name(identifier);
+                if !keywords.is_empty() {
+                    return Err(Unsupported::new("keyword arguments", node));
+                }
+                let arguments = parameters
+                    .iter()
+                    .map(|parameter| self.expression(parameter))
+                    .collect::<Result<Vec<_>, _>>()?
+                    .join(", ");
+                match (self.functions.get(&name), name.as_str()) {
+                    (Some(&arity), _) if arity == parameters.len() => {
+                        format!("f_{}({})", name, arguments)
+                    }
+                    (Some(_), _) => return Err(Unsupported::new("calls of the wrong arity", node)),
+                    // The printing functions are variadic.
+                    (None, "print" | "println") => format!("{}(&[{}])", name, arguments),
+                    (None, _) => {
+                        return Err(Unsupported::at(
+                            "calls of builtins other than print and println",
+                            identifier,
+                        ))
+                    }
+                }
+            }
+            Node::If {
+                condition,
+                block,
+                else_block,
+            } => format!(
+                "if condition({}) {} else {}",
+                self.expression(condition)?,
+                self.nested(block)?,
+                match else_block {
+                    Some(else_block) => self.nested(else_block)?,
+                    None => String::from("{ Value::None }"),
+                }
+            ),
+            Node::While { condition, block } => format!(
+                "{{ let mut value = Value::None; while condition({}) {{ value = {}; }} value }}",
+                self.expression(condition)?,
+                self.nested(block)?
+            ),
+            Node::DoWhile { block, condition } => format!(
+                "{{ let mut value; loop {{ value = {}; if !condition({}) {{ break; }} }} value }}",
+                self.nested(block)?,
+                self.expression(condition)?
+            ),
+            Node::Return { value } => format!("return {}", self.expression(value)?),
+            Node::FuncDeclearion { .. } => {
+                return Err(Unsupported::new("functions declared in blocks", node))
+            }
+            Node::Program { statements } => self.block(statements)?,
+            node => return Err(Unsupported::new(node.kind(), node)),
+        })
+    }
+
+    // A block, or an `elif` chained as an `If`.
+    fn nested(&self, node: &Node) -> Result<String, Unsupported> {
+        match node {
+            Node::Program { statements } => self.block(statements),
+            node => Ok(format!("{{ {} }}", self.expression(node)?)),
+        }
+    }
+
+    fn variable(&self, identifier: &Token) -> Result<String, Unsupported> {
+        let name = name(identifier);
+        match self.variables.contains(&name) {
+            true => Ok(name),
+            false => Err(Unsupported::at(
+                "variables other than the function's own",
+                identifier,
+            )),
+        }
+    }
+}
+
+// A block is the value of its last statement, as in the evaluator.
+fn block(mut lines: Vec<String>) -> String {
+    if lines.is_empty() {
+        lines.push(String::from("Value::None"));
+    }
+    format!("{{ {} }}", lines.join("; "))
+}
+
+fn name(identifier: &Token) -> String {
+    let TokenKind::Identifier(name) = &identifier.kind else {
+        internal_err!("Token must be of kind Identifier.")
+    };
+    name.to_string()
+}
+
+// The variables declared with `let` in the statements, blocks included, as
+// a block doesn't start a scope. They're all declared up front, `None` until
+// assigned.
+fn declarations(statements: &[Box<Node>]) -> HashSet<String> {
+    let mut variables = HashSet::new();
+    let mut pending: Vec<&Node> = statements.iter().map(AsRef::as_ref).collect();
+    while let Some(node) = pending.pop() {
+        match node {
+            Node::Assignment {
+                identifier,
+                is_declaration: true,
+                ..
+            } => {
+                variables.insert(name(identifier));
+            }
+            Node::Program { statements } => pending.extend(statements.iter().map(AsRef::as_ref)),
+            Node::If {
+                block, else_block, ..
+            } => {
+                pending.push(block);
+                pending.extend(else_block.as_deref());
+            }
+            Node::While { block, .. } | Node::DoWhile { block, .. } => pending.push(block),
+            _ => {}
+        }
+    }
+    variables
+}
+
+fn locals(variables: &HashSet<String>) -> String {
+    let mut variables: Vec<_> = variables.iter().collect();
+    variables.sort();
+    variables
+        .iter()
+        .map(|variable| format!("    let mut v_{} = Value::None;\n", variable))
+        .collect()
+}
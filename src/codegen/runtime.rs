@@ -0,0 +1,234 @@
+// The runtime a transpiled program starts with: the values it supports and
+// their operations, checked as the evaluator checks them.
+pub const RUNTIME: &str = r#"#![allow(unused)]
+
+use std::fmt;
+use std::io::Write;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Value {
+    None,
+    Integer(i32),
+    Float(f32),
+    Boolean(bool),
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::None => write!(f, "None"),
+            Value::Integer(value) => write!(f, "{}", value),
+            Value::Float(value) => write!(f, "{}", float(*value)),
+            Value::Boolean(true) => write!(f, "True"),
+            Value::Boolean(false) => write!(f, "False"),
+        }
+    }
+}
+
+fn float(value: f32) -> String {
+    if !value.is_finite() {
+        return value.to_string();
+    }
+    let magnitude = value.abs();
+    if magnitude != 0.0 && !(1e-4..1e16).contains(&magnitude) {
+        return format!("{value:e}");
+    }
+    let text = value.to_string();
+    match text.contains('.') {
+        true => text,
+        false => format!("{text}.0"),
+    }
+}
+
+fn fail(message: String) -> ! {
+    std::io::stdout().flush().ok();
+    eprintln!("RuntimeError: {}", message);
+    std::process::exit(1);
+}
+
+fn invalid(operator: &str, left: Value, right: Value) -> ! {
+    fail(format!("Invalid operation `{}` between {:?} and {:?}.", operator, left, right))
+}
+
+fn checked(result: Option<i32>, operator: &str) -> Value {
+    match result {
+        Some(result) => Value::Integer(result),
+        None => fail(format!("Integer overflow in `{}`.", operator)),
+    }
+}
+
+fn condition(value: Value) -> bool {
+    match value {
+        Value::Boolean(value) => value,
+        value => fail(format!("Expected a Boolean condition, found {:?}.", value)),
+    }
+}
+
+fn add(left: Value, right: Value) -> Value {
+    match (left, right) {
+        (Value::Integer(a), Value::Integer(b)) => checked(a.checked_add(b), "+"),
+        (Value::Float(a), Value::Float(b)) => Value::Float(a + b),
+        (left, right) => invalid("+", left, right),
+    }
+}
+
+fn sub(left: Value, right: Value) -> Value {
+    match (left, right) {
+        (Value::Integer(a), Value::Integer(b)) => checked(a.checked_sub(b), "-"),
+        (Value::Float(a), Value::Float(b)) => Value::Float(a - b),
+        (left, right) => invalid("-", left, right),
+    }
+}
+
+fn mul(left: Value, right: Value) -> Value {
+    match (left, right) {
+        (Value::Integer(a), Value::Integer(b)) => checked(a.checked_mul(b), "*"),
+        (Value::Float(a), Value::Float(b)) => Value::Float(a * b),
+        (left, right) => invalid("*", left, right),
+    }
+}
+
+fn div(left: Value, right: Value) -> Value {
+    match (left, right) {
+        (Value::Integer(_), Value::Integer(0)) | (Value::Float(_), Value::Float(0.0)) => {
+            fail(String::from("Division by zero."))
+        }
+        (Value::Integer(a), Value::Integer(b)) => checked(floored_div(a, b), "/"),
+        (Value::Float(a), Value::Float(b)) => Value::Float(a / b),
+        (left, right) => invalid("/", left, right),
+    }
+}
+
+fn modulo(left: Value, right: Value) -> Value {
+    match (left, right) {
+        (Value::Integer(_), Value::Integer(0)) => fail(String::from("Division by zero.")),
+        (Value::Integer(a), Value::Integer(b)) => Value::Integer(floored(a.wrapping_rem(b), b)),
+        (Value::Float(a), Value::Float(b)) => {
+            let remainder = a % b;
+            match remainder != 0.0 && (remainder < 0.0) != (b < 0.0) {
+                true => Value::Float(remainder + b),
+                false => Value::Float(remainder),
+            }
+        }
+        (left, right) => invalid("%", left, right),
+    }
+}
+
+// Division rounds down and a remainder takes the divisor's sign.
+fn floored_div(a: i32, b: i32) -> Option<i32> {
+    let quotient = a.checked_div(b)?;
+    match a % b != 0 && (a < 0) != (b < 0) {
+        true => Some(quotient - 1),
+        false => Some(quotient),
+    }
+}
+
+fn floored(remainder: i32, b: i32) -> i32 {
+    match remainder != 0 && (remainder < 0) != (b < 0) {
+        true => remainder + b,
+        false => remainder,
+    }
+}
+
+fn pow(left: Value, right: Value) -> Value {
+    match (left, right) {
+        (Value::Integer(a), Value::Integer(b)) if b >= 0 => checked(a.checked_pow(b as u32), "^"),
+        (Value::Integer(0) | Value::Float(0.0), Value::Integer(b)) if b < 0 => {
+            fail(String::from("Division by zero."))
+        }
+        (Value::Integer(0) | Value::Float(0.0), Value::Float(b)) if b < 0.0 => {
+            fail(String::from("Division by zero."))
+        }
+        (Value::Integer(a), Value::Integer(b)) => Value::Float((a as f32).powi(b)),
+        (Value::Integer(a), Value::Float(b)) => Value::Float((a as f32).powf(b)),
+        (Value::Float(a), Value::Integer(b)) => Value::Float(a.powi(b)),
+        (Value::Float(a), Value::Float(b)) => Value::Float(a.powf(b)),
+        (left, right) => invalid("^", left, right),
+    }
+}
+
+fn equals(left: Value, right: Value) -> Value {
+    match (left, right) {
+        (Value::Integer(a), Value::Integer(b)) => Value::Boolean(a == b),
+        (Value::Float(a), Value::Float(b)) => Value::Boolean(a == b),
+        (Value::Integer(a), Value::Float(b)) | (Value::Float(b), Value::Integer(a)) => {
+            Value::Boolean(f64::from(a) == f64::from(b))
+        }
+        (Value::Boolean(a), Value::Boolean(b)) => Value::Boolean(a == b),
+        (Value::None, Value::None) => Value::Boolean(true),
+        (_, Value::None) | (Value::None, _) => Value::Boolean(false),
+        (left, right) => invalid("==", left, right),
+    }
+}
+
+fn not_equals(left: Value, right: Value) -> Value {
+    match equals(left, right) {
+        Value::Boolean(equal) => Value::Boolean(!equal),
+        _ => unreachable!(),
+    }
+}
+
+fn compare(operator: &str, left: Value, right: Value) -> Value {
+    let ordering = match (left, right) {
+        (Value::Integer(a), Value::Integer(b)) => a.partial_cmp(&b),
+        (Value::Float(a), Value::Float(b)) => a.partial_cmp(&b),
+        (Value::Integer(a), Value::Float(b)) => f64::from(a).partial_cmp(&f64::from(b)),
+        (Value::Float(a), Value::Integer(b)) => f64::from(a).partial_cmp(&f64::from(b)),
+        (left, right) => invalid(operator, left, right),
+    };
+    Value::Boolean(match operator {
+        ">" => ordering.is_some_and(|ordering| ordering.is_gt()),
+        ">=" => ordering.is_some_and(|ordering| ordering.is_ge()),
+        "<" => ordering.is_some_and(|ordering| ordering.is_lt()),
+        _ => ordering.is_some_and(|ordering| ordering.is_le()),
+    })
+}
+
+fn and(left: Value, right: Value) -> Value {
+    match (left, right) {
+        (Value::Boolean(a), Value::Boolean(b)) => Value::Boolean(a && b),
+        (left, right) => invalid("and", left, right),
+    }
+}
+
+fn or(left: Value, right: Value) -> Value {
+    match (left, right) {
+        (Value::Boolean(a), Value::Boolean(b)) => Value::Boolean(a || b),
+        (left, right) => invalid("or", left, right),
+    }
+}
+
+fn pos(value: Value) -> Value {
+    match value {
+        Value::Integer(_) | Value::Float(_) => value,
+        value => fail(format!("Invalid operation `+` on {:?}.", value)),
+    }
+}
+
+fn neg(value: Value) -> Value {
+    match value {
+        Value::Integer(a) => checked(a.checked_neg(), "-"),
+        Value::Float(a) => Value::Float(-a),
+        value => fail(format!("Invalid operation `-` on {:?}.", value)),
+    }
+}
+
+fn not(value: Value) -> Value {
+    match value {
+        Value::Boolean(a) => Value::Boolean(!a),
+        value => fail(format!("Invalid operation `not` on {:?}.", value)),
+    }
+}
+
+fn print(values: &[Value]) -> Value {
+    let values: Vec<String> = values.iter().map(ToString::to_string).collect();
+    print!("{}", values.join(" "));
+    Value::None
+}
+
+fn println(values: &[Value]) -> Value {
+    print(values);
+    println!();
+    Value::None
+}
+"#;
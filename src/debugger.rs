@@ -0,0 +1,142 @@
+use crate::evaluator::hook::Hook;
+use crate::evaluator::symbol_table::SymbolTable;
+use crate::evaluator::value::Value;
+use crate::parser::node::Node;
+use crate::tokenizer::symbol::Symbol;
+use std::collections::BTreeSet;
+use std::io::{self, Write};
+use std::process;
+
+const HELP: &str = "\
+    break <line>    pause whenever a statement on the line runs (alias b)
+    delete <line>   remove the breakpoint on the line (alias d)
+    continue        run until the next breakpoint (alias c)
+    step            run the next statement, entering calls (alias s)
+    next            run the next statement, stepping over calls (alias n)
+    out             run until the current call returns (alias o)
+    print <name>    print a variable (alias p)
+    scope           print the variables of every scope, innermost first
+    list            print the source around the current line (alias l)
+    quit            stop the program (alias q)";
+
+enum Stepping {
+    Continue,
+    Into,
+    // Pause at the next statement at most this deep.
+    Over(usize),
+    // Pause at the next statement shallower than this.
+    Out(usize),
+}
+
+// An interactive debugger reading commands from stdin. It pauses before the
+// program's first statement so breakpoints can be set.
+pub struct Debugger {
+    lines: Vec<String>,
+    breakpoints: BTreeSet<usize>,
+    stepping: Stepping,
+}
+
+impl Debugger {
+    pub fn new(code: &str) -> Self {
+        Self {
+            lines: code.lines().map(ToString::to_string).collect(),
+            breakpoints: BTreeSet::new(),
+            stepping: Stepping::Into,
+        }
+    }
+
+    fn should_pause(&self, line: usize, depth: usize) -> bool {
+        match self.stepping {
+            Stepping::Into => true,
+            Stepping::Over(limit) => depth <= limit,
+            Stepping::Out(limit) => depth < limit,
+            Stepping::Continue => self.breakpoints.contains(&line),
+        }
+    }
+
+    fn pause(&mut self, line: usize, depth: usize, symbol_table: &SymbolTable) {
+        self.list(line, 0);
+        loop {
+            print!("(debug) ");
+            let _ = io::stdout().flush();
+            let mut command = String::new();
+            if io::stdin().read_line(&mut command).unwrap_or(0) == 0 {
+                // Nothing more to read, run the rest of the program.
+                self.breakpoints.clear();
+                self.stepping = Stepping::Continue;
+                return;
+            }
+            let mut words = command.split_whitespace();
+            let argument = words.clone().nth(1);
+            let line_argument = argument.and_then(|line| line.parse::<usize>().ok());
+            match (words.next().unwrap_or(""), line_argument) {
+                ("break" | "b", Some(line)) => {
+                    self.breakpoints.insert(line);
+                }
+                ("delete" | "d", Some(line)) => {
+                    self.breakpoints.remove(&line);
+                }
+                ("continue" | "c", _) => {
+                    self.stepping = Stepping::Continue;
+                    return;
+                }
+                ("step" | "s", _) => {
+                    self.stepping = Stepping::Into;
+                    return;
+                }
+                ("next" | "n", _) => {
+                    self.stepping = Stepping::Over(depth);
+                    return;
+                }
+                ("out" | "o", _) => {
+                    self.stepping = Stepping::Out(depth);
+                    return;
+                }
+                ("print" | "p", _) => match argument.and_then(|name| symbol_table.get(name)) {
+                    Some(value) => println!("{}", value),
+                    None => println!("No variable named `{}`.", argument.unwrap_or("")),
+                },
+                ("scope", _) => scope(symbol_table),
+                ("list" | "l", _) => self.list(line, 3),
+                ("quit" | "q", _) => process::exit(0),
+                _ => println!("{}", HELP),
+            }
+        }
+    }
+
+    fn list(&self, line: usize, context: usize) {
+        let first = line.saturating_sub(context).max(1);
+        for number in first..=(line + context).min(self.lines.len()) {
+            let marker = if number == line { "->" } else { "  " };
+            println!("{} {:>4} | {}", marker, number, self.lines[number - 1]);
+        }
+    }
+}
+
+impl Hook for Debugger {
+    fn on_statement(&mut self, statement: &Node, symbol_table: &SymbolTable) {
+        let Some(line) = statement.position().map(|position| position.row()) else {
+            return;
+        };
+        let depth = symbol_table.depth();
+        if self.should_pause(line, depth) {
+            self.pause(line, depth, symbol_table);
+        }
+    }
+}
+
+fn scope(symbol_table: &SymbolTable) {
+    for (depth, frame) in symbol_table.frames().enumerate() {
+        println!("#{} {}", depth, variables(frame));
+    }
+    let globals = symbol_table.globals().collect();
+    println!("globals {}", variables(globals));
+}
+
+fn variables(variables: Vec<(&Symbol, &Value)>) -> String {
+    variables
+        .iter()
+        .map(|(name, value)| format!("{} = {}", name, value))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
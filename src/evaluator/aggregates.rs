@@ -0,0 +1,67 @@
+use crate::evaluator::sorting::{compare, iterable};
+use crate::evaluator::value::Value;
+use crate::evaluator::{Evaluator, EvaluatorItem};
+use crate::models::error::{MonoError, Runtime, Snapshot};
+use crate::tokenizer::token::{Token, TokenKind};
+use std::cmp::Ordering;
+
+// The first element that no other element is `ordering` to, so ties keep
+// the earliest one.
+fn extreme(call: &Token, value: &Value, ordering: Ordering) -> EvaluatorItem {
+    let mut elements = iterable(call, value)?.into_iter();
+    let Some(mut extreme) = elements.next() else {
+        return Err(MonoError::from(Runtime::EmptyValue {
+            value: Snapshot::from(value),
+            call: call.clone(),
+        }));
+    };
+    for element in elements {
+        if compare(call, &extreme, &element)? == ordering.reverse() {
+            extreme = element;
+        }
+    }
+    Ok(extreme)
+}
+
+pub fn min(_evaluator: &mut Evaluator, call: &Token, values: Vec<Value>) -> EvaluatorItem {
+    extreme(call, &values[0], Ordering::Less)
+}
+
+pub fn max(_evaluator: &mut Evaluator, call: &Token, values: Vec<Value>) -> EvaluatorItem {
+    extreme(call, &values[0], Ordering::Greater)
+}
+
+// Adds the elements up with `+`, so they must all be Integers or all be
+// Floats. The sum of nothing is 0.
+pub fn sum(_evaluator: &mut Evaluator, call: &Token, values: Vec<Value>) -> EvaluatorItem {
+    let operator = Token::new(call.start.clone(), call.end.clone(), TokenKind::Add);
+    let mut total = Value::Integer(0);
+    for (index, element) in iterable(call, &values[0])?.into_iter().enumerate() {
+        total = match (index, &element) {
+            (0, Value::Integer(_) | Value::Float(_)) => element,
+            (_, Value::Integer(_) | Value::Float(_)) => {
+                total.binary_operation(element, &operator)?
+            }
+            // The operands are named the other way around in this error.
+            _ => {
+                return Runtime::InvalidOperation {
+                    operator,
+                    right: Some(Snapshot::from(total)),
+                    left: Snapshot::from(element),
+                }
+                .into()
+            }
+        };
+    }
+    Ok(total)
+}
+
+pub fn any(_evaluator: &mut Evaluator, call: &Token, values: Vec<Value>) -> EvaluatorItem {
+    let elements = iterable(call, &values[0])?;
+    Ok(Value::Boolean(elements.iter().any(Value::is_truthy)))
+}
+
+pub fn all(_evaluator: &mut Evaluator, call: &Token, values: Vec<Value>) -> EvaluatorItem {
+    let elements = iterable(call, &values[0])?;
+    Ok(Value::Boolean(elements.iter().all(Value::is_truthy)))
+}
@@ -1,78 +1,688 @@
+use crate::evaluator::formatting;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::evaluator::interrupt;
+use crate::evaluator::limits;
+use crate::evaluator::shared::Mutable;
+use crate::evaluator::shared::Shared;
+use crate::evaluator::value::Native;
+use crate::evaluator::{Evaluator, EvaluatorItem};
+use crate::models::error::{MonoError, Runtime, Snapshot};
+use crate::parser::node::Argument;
+use crate::parser::Parser;
+use crate::project;
+use crate::tokenizer::symbol::Symbol;
+use crate::tokenizer::token::{Token, TokenKind};
+use crate::tokenizer::Tokenizer;
 use crate::Value;
-use std::io;
-use std::io::Write;
+use std::collections::HashMap;
+use std::fs;
+use std::num::IntErrorKind;
+#[cfg(not(target_arch = "wasm32"))]
 use std::process;
+#[cfg(not(target_arch = "wasm32"))]
+use std::sync::OnceLock;
+#[cfg(not(target_arch = "wasm32"))]
+use std::thread;
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
-pub fn builtin(name: &str, arg_names: Vec<&str>, func: fn(Vec<Value>) -> Value) -> (String, Value) {
-    let arguments: Vec<String> = arg_names.into_iter().map(ToString::to_string).collect();
+pub fn builtin(
+    name: &str,
+    arg_names: Vec<&str>,
+    doc: &'static str,
+    func: fn(Vec<Value>) -> Value,
+) -> (String, Value) {
+    native(name, arg_names, doc, Native::Function(func))
+}
+
+// A builtin that runs with access to the evaluator, see `Native`.
+pub fn intrinsic(
+    name: &str,
+    arg_names: Vec<&str>,
+    doc: &'static str,
+    func: fn(&mut Evaluator, &Token, Vec<Value>) -> EvaluatorItem,
+) -> (String, Value) {
+    native(name, arg_names, doc, Native::Evaluator(func))
+}
+
+// Arguments are declared as `name` or `name: Type`, where the type is one
+// `Value::to_type` returns or several separated by ` | `. The evaluator
+// checks the values a builtin is called with against them, so the builtin
+// only sees the types it declared.
+fn native(
+    name: &str,
+    arg_names: Vec<&str>,
+    doc: &'static str,
+    function: Native,
+) -> (String, Value) {
+    let (arguments, types): (Vec<String>, Vec<Option<Symbol>>) = arg_names
+        .into_iter()
+        .map(|argument| match argument.split_once(':') {
+            Some((argument, kind)) => (argument.trim().to_string(), Some(kind.trim().into())),
+            None => (argument.to_string(), None),
+        })
+        .unzip();
     (
         name.to_string(),
         Value::BuiltInFunction {
-            name: name.to_string(),
-            arguments: arguments,
-            function: func,
+            name: name.into(),
+            arguments: arguments.into(),
+            types: types.into(),
+            doc,
+            function,
         },
     )
 }
 
-pub fn println(values: Vec<Value>) -> Value {
-    if values.len() != 1 {
-        todo!()
+// Whether the value is of the declared type. `Function` covers builtins.
+pub(crate) fn accepts(kind: &str, value: &Value) -> bool {
+    kind.split('|').map(str::trim).any(|kind| {
+        kind == value.to_type()
+            || (kind == "Function" && matches!(value, Value::BuiltInFunction { .. }))
+    })
+}
+
+// A builtin's arguments as declared, with their types.
+pub(crate) fn signature(arguments: &[String], types: &[Option<Symbol>]) -> Vec<String> {
+    arguments
+        .iter()
+        .zip(types)
+        .map(|(argument, kind)| match kind {
+            Some(kind) => format!("{}: {}", argument, kind),
+            None => argument.clone(),
+        })
+        .collect()
+}
+
+// A struct value without methods, for builtins returning several results
+// such as a response's status and body.
+pub fn record(name: &str, fields: Vec<(&str, Value)>) -> Value {
+    Value::Struct {
+        name: name.into(),
+        fields: Shared::new(Mutable::new(
+            fields
+                .into_iter()
+                .map(|(field, value)| (Symbol::from(field), value))
+                .collect(),
+        )),
+        methods: Shared::new(Mutable::new(HashMap::new())),
     }
-    print!("{}\n", values[0]);
-    Value::None
 }
 
-pub fn print(values: Vec<Value>) -> Value {
-    if values.len() != 1 {
-        todo!()
+// Groups builtins under a single name, members are qualified with it so errors
+// read `math.sqrt` rather than `sqrt`.
+pub fn namespace(name: &str, members: Vec<(String, Value)>) -> (String, Value) {
+    let members = members
+        .into_iter()
+        .map(|(member, value)| {
+            let value = match value {
+                Value::BuiltInFunction {
+                    arguments,
+                    types,
+                    doc,
+                    function,
+                    ..
+                } => Value::BuiltInFunction {
+                    name: format!("{name}.{member}").into(),
+                    arguments,
+                    types,
+                    doc,
+                    function,
+                },
+                value => value,
+            };
+            (Symbol::from(member), value)
+        })
+        .collect::<HashMap<Symbol, Value>>();
+    (
+        name.to_string(),
+        Value::Namespace {
+            name: name.into(),
+            members: Shared::new(members),
+        },
+    )
+}
+
+pub fn println(evaluator: &mut Evaluator, _call: &Token, values: Vec<Value>) -> EvaluatorItem {
+    evaluator.write(&format!("{}\n", join_spaced(&values)));
+    Ok(Value::None)
+}
+
+pub fn print(evaluator: &mut Evaluator, _call: &Token, values: Vec<Value>) -> EvaluatorItem {
+    evaluator.write(&join_spaced(&values));
+    Ok(Value::None)
+}
+
+// The values as printed, separated by spaces.
+fn join_spaced(values: &[Value]) -> String {
+    values
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+// Prints a function's signature and documentation, the docstring of a user
+// defined function or the doc a builtin was registered with.
+pub fn help(evaluator: &mut Evaluator, _call: &Token, values: Vec<Value>) -> EvaluatorItem {
+    let (signature, doc) = match &values[0] {
+        Value::Function {
+            name,
+            arguments,
+            body,
+            ..
+        } => (
+            format!(
+                "{}({})",
+                name,
+                join(arguments.iter().map(Argument::signature))
+            ),
+            body.docstring().unwrap_or_default(),
+        ),
+        Value::BuiltInFunction {
+            name,
+            arguments,
+            types,
+            doc,
+            ..
+        } => (
+            format!("{}({})", name, join(signature(arguments, types).iter())),
+            *doc,
+        ),
+        value => (format!("{}: {}", value, value.to_type()), ""),
+    };
+    let doc = match doc.trim() {
+        "" => String::from("    No documentation."),
+        doc => doc
+            .lines()
+            .map(|line| format!("    {}", line.trim()))
+            .collect::<Vec<_>>()
+            .join("\n"),
+    };
+    evaluator.write(&format!("{}\n{}\n", signature, doc));
+    Ok(Value::None)
+}
+
+pub(crate) fn join(items: impl Iterator<Item = impl ToString>) -> String {
+    items
+        .map(|item| item.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+// Prints the prompt, if any, and reads a line. A blank line is an empty
+// string and only the end of the input is `None`.
+pub fn input(evaluator: &mut Evaluator, _call: &Token, values: Vec<Value>) -> EvaluatorItem {
+    if !values.is_empty() {
+        evaluator.write(&join_spaced(&values));
     }
+    Ok(evaluator.read_line().map_or(Value::None, Value::from))
+}
 
-    print!("{}", values[0]);
-    io::stdout().flush().unwrap();
-    Value::None
+// Evaluates the code in the current scope, so it sees and declares the
+// caller's variables. Its value is the value of its last statement, or what
+// it returned.
+pub fn eval(evaluator: &mut Evaluator, _call: &Token, values: Vec<Value>) -> EvaluatorItem {
+    let code = string_argument(&values[0])?;
+    let program = Parser::new(Tokenizer::new(code.chars())).parse()?;
+    evaluator.evaluate_nested(&program)
 }
 
-pub fn input(values: Vec<Value>) -> Value {
-    if values.len() != 0 {
-        todo!()
+// Runs another script like `eval`, in the current scope. Relative paths are
+// also looked for in the evaluator's search paths, and bundled scripts are
+// run without reading anything.
+pub fn run_file(evaluator: &mut Evaluator, call: &Token, values: Vec<Value>) -> EvaluatorItem {
+    let path = string_argument(&values[0])?;
+    if let Some(program) = evaluator.modules.get(path).cloned() {
+        return evaluator.evaluate_nested(&program);
     }
-    let mut input = String::new();
-    if io::stdin().read_line(&mut input).is_ok() {
-        return Value::String(input.trim_end().to_owned());
+    let code = fs::read_to_string(project::resolve(path, &evaluator.paths)).map_err(|error| {
+        Runtime::IoError {
+            call: call.clone(),
+            message: format!("{}: {}", path, error),
+        }
+    })?;
+    let program = Parser::new(Tokenizer::new(code.chars())).parse()?;
+    evaluator.evaluate_nested(&program)
+}
+
+pub(crate) fn string_argument(value: &Value) -> Result<&str, MonoError> {
+    match value {
+        Value::String(string) => Ok(string),
+        value => Err(MonoError::from(Runtime::InvalidValue {
+            expected: Value::from("").into(),
+            found: value.into(),
+        })),
+    }
+}
+
+// There's no process to exit on the web, so `exit` isn't available there.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn exit(_evaluator: &mut Evaluator, call: &Token, values: Vec<Value>) -> EvaluatorItem {
+    // Only a byte of the status reaches the parent, so a larger one would be
+    // cut to another, possibly zero.
+    match values[0] {
+        Value::Integer(code @ 0..=255) => process::exit(code),
+        Value::Integer(code) => Err(MonoError::from(Runtime::InvalidExitCode {
+            call: call.clone(),
+            code,
+        })),
+        _ => Ok(Value::None),
     }
-    Value::None
 }
 
-pub fn exit(values: Vec<Value>) -> Value {
-    if values.len() != 1 {
-        todo!()
-    } else if let Value::Integer(int) = values[0] {
-        if int >= 0 && int <= 255 {
-            process::exit(int);
-        } else {
-            todo!()
+pub fn integer(values: Vec<Value>) -> Value {
+    match &values[0] {
+        Value::String(string) => parse_integer(string).map_or(Value::None, Value::Integer),
+        _ => Value::None,
+    }
+}
+
+pub fn float_from(values: Vec<Value>) -> Value {
+    match &values[0] {
+        Value::String(string) => parse_float(string).map_or(Value::None, Value::Float),
+        value => float(value).map_or(Value::None, Value::Float),
+    }
+}
+
+// The parsed Integer and `None`, or `None` and why the string isn't one.
+pub fn try_integer(values: Vec<Value>) -> Value {
+    match &values[0] {
+        Value::String(string) => attempt(parse_integer(string).map(Value::Integer)),
+        _ => Value::None,
+    }
+}
+
+pub fn try_float(values: Vec<Value>) -> Value {
+    match &values[0] {
+        Value::String(string) => attempt(parse_float(string).map(Value::Float)),
+        _ => Value::None,
+    }
+}
+
+fn attempt(result: Result<Value, String>) -> Value {
+    let (value, error) = match result {
+        Ok(value) => (value, Value::None),
+        Err(error) => (Value::None, Value::from(error)),
+    };
+    Value::Tuple(Shared::new(vec![value, error]))
+}
+
+fn parse_integer(string: &str) -> Result<i32, String> {
+    string.parse::<i32>().map_err(|error| match error.kind() {
+        IntErrorKind::Empty => String::from("empty string"),
+        IntErrorKind::PosOverflow | IntErrorKind::NegOverflow => {
+            format!("`{}` doesn't fit in an Integer", string)
         }
+        _ => format!("`{}` isn't an Integer", string),
+    })
+}
+
+fn parse_float(string: &str) -> Result<f32, String> {
+    match string.parse::<f32>() {
+        Ok(float) => Ok(float),
+        Err(_) if string.is_empty() => Err(String::from("empty string")),
+        Err(_) => Err(format!("`{}` isn't a Float", string)),
+    }
+}
+
+pub fn boolean(values: Vec<Value>) -> Value {
+    Value::Boolean(values[0].is_truthy())
+}
+
+pub fn is_none(values: Vec<Value>) -> Value {
+    Value::Boolean(values[0] == Value::None)
+}
+
+pub fn string(values: Vec<Value>) -> Value {
+    Value::String(format!("{}", values[0]).into())
+}
+
+fn float(value: &Value) -> Option<f32> {
+    match value {
+        Value::Integer(integer) => Some(*integer as f32),
+        Value::Float(float) => Some(*float),
+        _ => None,
+    }
+}
+
+pub fn sqrt(values: Vec<Value>) -> Value {
+    match float(&values[0]) {
+        Some(value) if value >= 0.0 => Value::Float(value.sqrt()),
+        _ => Value::None,
+    }
+}
+
+pub fn abs(_evaluator: &mut Evaluator, call: &Token, values: Vec<Value>) -> EvaluatorItem {
+    match &values[0] {
+        Value::Integer(integer) => integer.checked_abs().map(Value::Integer).ok_or_else(|| {
+            MonoError::from(Runtime::IntegerOverflow {
+                operator: call.clone(),
+            })
+        }),
+        Value::Float(float) => Ok(Value::Float(float.abs())),
+        _ => Ok(Value::None),
+    }
+}
+
+pub fn floor(_evaluator: &mut Evaluator, call: &Token, values: Vec<Value>) -> EvaluatorItem {
+    to_integer(call, &values[0], f32::floor)
+}
+
+pub fn ceil(_evaluator: &mut Evaluator, call: &Token, values: Vec<Value>) -> EvaluatorItem {
+    to_integer(call, &values[0], f32::ceil)
+}
+
+// A Float rounded to an Integer, failing rather than saturating when it's
+// NaN or out of range. `i32::MIN` is exact as a Float, the bound above it is
+// 2^31 since `i32::MAX` isn't.
+fn to_integer(call: &Token, value: &Value, round: fn(f32) -> f32) -> EvaluatorItem {
+    match value {
+        Value::Integer(integer) => Ok(Value::Integer(*integer)),
+        Value::Float(float) => {
+            let rounded = round(*float);
+            if (i32::MIN as f32..-(i32::MIN as f32)).contains(&rounded) {
+                Ok(Value::Integer(rounded as i32))
+            } else {
+                Err(MonoError::from(Runtime::IntegerOverflow {
+                    operator: call.clone(),
+                }))
+            }
+        }
+        _ => Ok(Value::None),
+    }
+}
+
+pub fn min(values: Vec<Value>) -> Value {
+    match (&values[0], &values[1]) {
+        (Value::Integer(a), Value::Integer(b)) => Value::Integer(*a.min(b)),
+        (a, b) => match (float(a), float(b)) {
+            (Some(a), Some(b)) => Value::Float(a.min(b)),
+            _ => Value::None,
+        },
+    }
+}
+
+pub fn max(values: Vec<Value>) -> Value {
+    match (&values[0], &values[1]) {
+        (Value::Integer(a), Value::Integer(b)) => Value::Integer(*a.max(b)),
+        (a, b) => match (float(a), float(b)) {
+            (Some(a), Some(b)) => Value::Float(a.max(b)),
+            _ => Value::None,
+        },
+    }
+}
+
+pub fn upper(values: Vec<Value>) -> Value {
+    match &values[0] {
+        Value::String(string) => Value::String(string.to_uppercase().into()),
+        Value::Character(character) => Value::String(character.to_uppercase().to_string().into()),
+        _ => Value::None,
+    }
+}
+
+pub fn lower(values: Vec<Value>) -> Value {
+    match &values[0] {
+        Value::String(string) => Value::String(string.to_lowercase().into()),
+        Value::Character(character) => Value::String(character.to_lowercase().to_string().into()),
+        _ => Value::None,
+    }
+}
+
+pub fn trim(values: Vec<Value>) -> Value {
+    match &values[0] {
+        Value::String(string) => Value::String(string.trim().into()),
+        _ => Value::None,
+    }
+}
+
+pub fn split(values: Vec<Value>) -> Value {
+    let separator = match &values[1] {
+        Value::String(separator) => separator.to_string(),
+        Value::Character(separator) => separator.to_string(),
+        _ => return Value::None,
+    };
+    match &values[0] {
+        Value::String(string) => Value::List(Shared::new(Mutable::new(
+            string
+                .split(&separator)
+                .map(|part| Value::String(part.into()))
+                .collect(),
+        ))),
+        _ => Value::None,
+    }
+}
+
+pub fn push(values: Vec<Value>) -> Value {
+    if let Value::List(list) = &values[0] {
+        list.borrow_mut().push(values[1].clone());
     }
     Value::None
 }
 
-pub fn integer(values: Vec<Value>) -> Value {
-    if values.len() != 1 {
-        todo!()
+pub fn pop(values: Vec<Value>) -> Value {
+    match &values[0] {
+        Value::List(list) => list.borrow_mut().pop().unwrap_or(Value::None),
+        _ => Value::None,
     }
+}
+
+// Strings are measured in characters, like they're indexed.
+pub fn len(_evaluator: &mut Evaluator, call: &Token, values: Vec<Value>) -> EvaluatorItem {
+    let length = match &values[0] {
+        Value::String(string) => string.chars().count(),
+        Value::Bytes(bytes) => bytes.len(),
+        Value::List(list) => list.borrow().len(),
+        Value::Tuple(values) => values.len(),
+        value => {
+            return Runtime::NoLength {
+                value: value.into(),
+                call: call.clone(),
+            }
+            .into()
+        }
+    };
+    Ok(Value::Integer(length as i32))
+}
+
+// The elements from `start` up to but not including `end`, both clamped to
+// the value's bounds. Strings are sliced by characters.
+pub fn slice(values: Vec<Value>) -> Value {
+    let (Value::Integer(start), Value::Integer(end)) = (&values[1], &values[2]) else {
+        return Value::None;
+    };
+    let bounds = |length: usize| {
+        let clamp = |index: i32| (index.max(0) as usize).min(length);
+        clamp(*start)..clamp(*end).max(clamp(*start))
+    };
     match &values[0] {
-        Value::String(value) => match value.parse::<i32>() {
-            Ok(integer) => Value::Integer(integer),
-            Err(_) => Value::None,
+        Value::String(string) => {
+            let range = bounds(string.chars().count());
+            let sliced: String = string.chars().skip(range.start).take(range.len()).collect();
+            Value::String(sliced.into())
+        }
+        Value::Bytes(bytes) => Value::Bytes(bytes[bounds(bytes.len())].into()),
+        Value::List(list) => {
+            let list = list.borrow();
+            Value::List(Shared::new(Mutable::new(list[bounds(list.len())].to_vec())))
+        }
+        Value::Tuple(values) => Value::Tuple(Shared::new(values[bounds(values.len())].to_vec())),
+        _ => Value::None,
+    }
+}
+
+// The elements of a sequence, as a `for` loop would walk them.
+pub(crate) fn elements(value: &Value) -> Option<Vec<Value>> {
+    match value {
+        Value::List(list) => Some(list.borrow().clone()),
+        Value::Tuple(values) => Some(values.to_vec()),
+        Value::String(string) => Some(string.chars().map(Value::Character).collect()),
+        Value::Bytes(bytes) => Some(
+            bytes
+                .iter()
+                .map(|byte| Value::Integer(*byte as i32))
+                .collect(),
+        ),
+        _ => None,
+    }
+}
+
+fn pair(first: Value, second: Value) -> Value {
+    Value::List(Shared::new(Mutable::new(vec![first, second])))
+}
+
+// `[index, value]` pairs, for `for i, x in enumerate(xs)`.
+pub fn enumerate(values: Vec<Value>) -> Value {
+    match elements(&values[0]) {
+        Some(elements) => Value::List(Shared::new(Mutable::new(
+            elements
+                .into_iter()
+                .enumerate()
+                .map(|(index, value)| pair(Value::Integer(index as i32), value))
+                .collect(),
+        ))),
+        None => Value::None,
+    }
+}
+
+// Pairs the elements of both sequences, stopping at the end of the shorter.
+pub fn zip(values: Vec<Value>) -> Value {
+    match (elements(&values[0]), elements(&values[1])) {
+        (Some(a), Some(b)) => Value::List(Shared::new(Mutable::new(
+            a.into_iter().zip(b).map(|(a, b)| pair(a, b)).collect(),
+        ))),
+        _ => Value::None,
+    }
+}
+
+pub fn range(values: Vec<Value>) -> Value {
+    match (&values[0], &values[1]) {
+        (Value::Integer(start), Value::Integer(end))
+            if limits::maximum_length()
+                .is_some_and(|maximum| i64::from(*end) - i64::from(*start) > maximum as i64) =>
+        {
+            Value::None
+        }
+        (Value::Integer(start), Value::Integer(end)) => Value::List(Shared::new(Mutable::new(
+            (*start..*end).map(Value::Integer).collect(),
+        ))),
+        _ => Value::None,
+    }
+}
+
+pub fn format(_evaluator: &mut Evaluator, call: &Token, values: Vec<Value>) -> EvaluatorItem {
+    let Value::String(template) = &values[0] else {
+        return Ok(Value::None);
+    };
+    formatting::format(template, &values[1..])
+        .map(|string| Value::String(string.into()))
+        .map_err(|reason| {
+            MonoError::from(Runtime::InvalidFormat {
+                call: call.clone(),
+                reason,
+            })
+        })
+}
+
+pub fn round(values: Vec<Value>) -> Value {
+    match (&values[0], &values[1]) {
+        (Value::Integer(integer), Value::Integer(digits)) if *digits >= 0 => {
+            Value::Integer(*integer)
+        }
+        (value, Value::Integer(digits)) => match float(value) {
+            Some(value) => {
+                let scale = 10f64.powi(*digits);
+                Value::Float(((value as f64 * scale).round() / scale) as f32)
+            }
+            None => Value::None,
         },
         _ => Value::None,
     }
 }
 
-pub fn string(values: Vec<Value>) -> Value {
-    if values.len() != 1 {
-        todo!()
+// The rounded down quotient and the remainder, as `/` and `%` give them for
+// Integers. For Floats the quotient is rounded down to a whole Float.
+pub fn divmod(_evaluator: &mut Evaluator, call: &Token, values: Vec<Value>) -> EvaluatorItem {
+    let operator = |kind| Token::new(call.start.clone(), call.end.clone(), kind);
+    let [a, b] = &values[..] else {
+        return Err(MonoError::from(Runtime::IncorrectParameters {
+            name: "divmod".into(),
+            call: call.clone(),
+            expected: vec!["a".into(), "b".into()],
+            found: values.iter().map(Snapshot::from).collect(),
+        }));
+    };
+    let quotient = match a
+        .clone()
+        .binary_operation(b.clone(), &operator(TokenKind::Div))?
+    {
+        Value::Float(quotient) => Value::Float(quotient.floor()),
+        quotient => quotient,
+    };
+    let remainder = a
+        .clone()
+        .binary_operation(b.clone(), &operator(TokenKind::Mod))?;
+    Ok(Value::Tuple(Shared::new(vec![quotient, remainder])))
+}
+
+// Integers are 32 bit, so the wall clock is in whole seconds since the epoch
+// while the monotonic clock is in milliseconds since its first reading. The
+// browser has neither clock, so these aren't available there.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn time(_values: Vec<Value>) -> Value {
+    match SystemTime::now().duration_since(UNIX_EPOCH) {
+        Ok(elapsed) => i32::try_from(elapsed.as_secs()).map_or(Value::None, Value::Integer),
+        Err(_) => Value::None,
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn clock(_values: Vec<Value>) -> Value {
+    static START: OnceLock<Instant> = OnceLock::new();
+    let elapsed = START.get_or_init(Instant::now).elapsed();
+    i32::try_from(elapsed.as_millis()).map_or(Value::None, Value::Integer)
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn sleep(values: Vec<Value>) -> Value {
+    let Value::Integer(milliseconds) = values[0] else {
+        return Value::None;
+    };
+    sleep_until(Instant::now() + Duration::from_millis(milliseconds.max(0) as u64));
+    Value::None
+}
+
+// Sleeps in short slices so an interrupt ends it early, returning whether it
+// wasn't interrupted.
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn sleep_until(end: Instant) -> bool {
+    const SLICE: Duration = Duration::from_millis(10);
+    while !interrupt::is_interrupted() {
+        let remaining = end.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            return true;
+        }
+        thread::sleep(remaining.min(SLICE));
+    }
+    false
+}
+
+pub fn hex(values: Vec<Value>) -> Value {
+    match &values[0] {
+        Value::Integer(integer) if *integer < 0 => {
+            Value::String(format!("-0x{:x}", integer.unsigned_abs()).into())
+        }
+        Value::Integer(integer) => Value::String(format!("0x{:x}", integer).into()),
+        _ => Value::None,
+    }
+}
+
+pub fn bin(values: Vec<Value>) -> Value {
+    match &values[0] {
+        Value::Integer(integer) if *integer < 0 => {
+            Value::String(format!("-0b{:b}", integer.unsigned_abs()).into())
+        }
+        Value::Integer(integer) => Value::String(format!("0b{:b}", integer).into()),
+        _ => Value::None,
     }
-    Value::String(format!("{}", values[0]))
 }
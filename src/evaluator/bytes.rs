@@ -0,0 +1,73 @@
+use crate::evaluator::builtins::string_argument;
+use crate::evaluator::value::Value;
+use crate::evaluator::{Evaluator, EvaluatorItem};
+use crate::models::error::Runtime;
+use crate::tokenizer::token::Token;
+use std::fs;
+
+pub fn read_file_bytes(
+    _evaluator: &mut Evaluator,
+    call: &Token,
+    values: Vec<Value>,
+) -> EvaluatorItem {
+    let path = string_argument(&values[0])?;
+    let bytes = fs::read(path).map_err(|error| Runtime::IoError {
+        call: call.clone(),
+        message: format!("{}: {}", path, error),
+    })?;
+    Ok(Value::Bytes(bytes.into()))
+}
+
+// A string as its UTF-8 bytes, or a list of integers from 0 to 255.
+pub fn from(values: Vec<Value>) -> Value {
+    match &values[0] {
+        Value::String(string) => Value::Bytes(string.as_bytes().into()),
+        Value::Bytes(bytes) => Value::Bytes(bytes.clone()),
+        Value::List(list) => list
+            .borrow()
+            .iter()
+            .map(|value| match value {
+                Value::Integer(byte) => u8::try_from(*byte).ok(),
+                _ => None,
+            })
+            .collect::<Option<Vec<u8>>>()
+            .map_or(Value::None, |bytes| Value::Bytes(bytes.into())),
+        _ => Value::None,
+    }
+}
+
+// The bytes as a UTF-8 string, `None` when they aren't valid UTF-8.
+pub fn decode(values: Vec<Value>) -> Value {
+    match &values[0] {
+        Value::Bytes(bytes) => std::str::from_utf8(bytes).map_or(Value::None, Value::from),
+        _ => Value::None,
+    }
+}
+
+pub fn hex_encode(values: Vec<Value>) -> Value {
+    match &values[0] {
+        Value::Bytes(bytes) => Value::from(
+            bytes
+                .iter()
+                .map(|byte| format!("{:02x}", byte))
+                .collect::<String>(),
+        ),
+        _ => Value::None,
+    }
+}
+
+// The inverse of `hex_encode`, `None` for an odd number of digits or a
+// character that isn't one.
+pub fn hex_decode(values: Vec<Value>) -> Value {
+    let Value::String(string) = &values[0] else {
+        return Value::None;
+    };
+    if string.len() % 2 != 0 || !string.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Value::None;
+    }
+    let bytes: Vec<u8> = (0..string.len())
+        .step_by(2)
+        .filter_map(|index| u8::from_str_radix(&string[index..index + 2], 16).ok())
+        .collect();
+    Value::Bytes(bytes.into())
+}
@@ -0,0 +1,152 @@
+use crate::evaluator::shared::Mutable;
+use crate::evaluator::shared::Shared;
+use crate::evaluator::value::Value;
+use crate::models::error::{MonoError, Runtime};
+use std::num::TryFromIntError;
+
+// Conversions between Rust types and `Value`s, for hosts passing data into
+// scripts and reading results back. Integers and floats are 32 bit, so wider
+// Rust numbers only convert when they fit.
+
+impl From<i32> for Value {
+    fn from(value: i32) -> Self {
+        Value::Integer(value)
+    }
+}
+
+impl TryFrom<i64> for Value {
+    type Error = TryFromIntError;
+
+    fn try_from(value: i64) -> Result<Self, Self::Error> {
+        i32::try_from(value).map(Value::Integer)
+    }
+}
+
+impl From<f32> for Value {
+    fn from(value: f32) -> Self {
+        Value::Float(value)
+    }
+}
+
+impl From<bool> for Value {
+    fn from(value: bool) -> Self {
+        Value::Boolean(value)
+    }
+}
+
+impl From<char> for Value {
+    fn from(value: char) -> Self {
+        Value::Character(value)
+    }
+}
+
+impl From<&str> for Value {
+    fn from(value: &str) -> Self {
+        Value::String(value.into())
+    }
+}
+
+impl From<String> for Value {
+    fn from(value: String) -> Self {
+        Value::String(value.into())
+    }
+}
+
+impl From<()> for Value {
+    fn from(_: ()) -> Self {
+        Value::None
+    }
+}
+
+impl<T: Into<Value>> From<Option<T>> for Value {
+    fn from(value: Option<T>) -> Self {
+        value.map_or(Value::None, Into::into)
+    }
+}
+
+impl<T: Into<Value>> From<Vec<T>> for Value {
+    fn from(values: Vec<T>) -> Self {
+        Value::List(Shared::new(Mutable::new(
+            values.into_iter().map(Into::into).collect(),
+        )))
+    }
+}
+
+macro_rules! tuple_into_value {
+    ($($name:ident),+) => {
+        impl<$($name: Into<Value>),+> From<($($name,)+)> for Value {
+            #[allow(non_snake_case)]
+            fn from(($($name,)+): ($($name,)+)) -> Self {
+                Value::Tuple(Shared::new(vec![$($name.into()),+]))
+            }
+        }
+    };
+}
+
+tuple_into_value!(A, B);
+tuple_into_value!(A, B, C);
+tuple_into_value!(A, B, C, D);
+
+fn invalid(expected: Value, found: Value) -> MonoError {
+    Runtime::InvalidValue {
+        expected: expected.into(),
+        found: found.into(),
+    }
+    .into()
+}
+
+macro_rules! try_from_value {
+    ($type:ty, $variant:ident, $expected:expr) => {
+        impl TryFrom<Value> for $type {
+            type Error = MonoError;
+
+            fn try_from(value: Value) -> Result<Self, Self::Error> {
+                match value {
+                    Value::$variant(value) => Ok(value.into()),
+                    value => Err(invalid($expected, value)),
+                }
+            }
+        }
+    };
+}
+
+try_from_value!(i32, Integer, Value::Integer(0));
+try_from_value!(i64, Integer, Value::Integer(0));
+try_from_value!(f32, Float, Value::Float(0.0));
+try_from_value!(f64, Float, Value::Float(0.0));
+try_from_value!(bool, Boolean, Value::Boolean(false));
+try_from_value!(char, Character, Value::Character('\0'));
+
+impl TryFrom<Value> for String {
+    type Error = MonoError;
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::String(value) => Ok(value.to_string()),
+            value => Err(invalid(Value::String("".into()), value)),
+        }
+    }
+}
+
+impl<T: TryFrom<Value, Error = MonoError>> TryFrom<Value> for Vec<T> {
+    type Error = MonoError;
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::List(values) => values.borrow().iter().cloned().map(T::try_from).collect(),
+            Value::Tuple(values) => values.iter().cloned().map(T::try_from).collect(),
+            value => Err(invalid(Value::from(Vec::<Value>::new()), value)),
+        }
+    }
+}
+
+impl<T: TryFrom<Value, Error = MonoError>> TryFrom<Value> for Option<T> {
+    type Error = MonoError;
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::None => Ok(None),
+            value => T::try_from(value).map(Some),
+        }
+    }
+}
@@ -0,0 +1,39 @@
+use super::EvaluatorItem;
+use crate::evaluator::shared::Sendable;
+use crate::evaluator::value::Value;
+use crate::tokenizer::token::Token;
+use std::any::Any;
+use std::fmt;
+
+// Host defined values. Every operation is optional, returning `None` falls back
+// to the regular `InvalidOperation` error.
+pub trait External: fmt::Debug + fmt::Display + Sendable {
+    fn type_name(&self) -> &'static str;
+
+    fn as_any(&self) -> &dyn Any;
+
+    // Called when the external value is the left operand.
+    fn binary_operation(&self, _other: &Value, _operator: &Token) -> Option<EvaluatorItem> {
+        None
+    }
+
+    // Called when the external value is the right operand and the left one
+    // didn't handle the operation.
+    fn reflected_binary_operation(
+        &self,
+        _other: &Value,
+        _operator: &Token,
+    ) -> Option<EvaluatorItem> {
+        None
+    }
+
+    fn unary_operation(&self, _operator: &Token) -> Option<EvaluatorItem> {
+        None
+    }
+}
+
+impl dyn External {
+    pub fn downcast_ref<T: External + 'static>(&self) -> Option<&T> {
+        self.as_any().downcast_ref::<T>()
+    }
+}
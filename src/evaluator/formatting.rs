@@ -0,0 +1,168 @@
+use crate::evaluator::value::Value;
+
+// Floats print their shortest representation that reads back to the same
+// value, keeping a `.0` so they're distinguishable from integers and switching
+// to scientific notation for very large or small magnitudes.
+pub fn float(value: f32) -> String {
+    if !value.is_finite() {
+        return value.to_string();
+    }
+    let magnitude = value.abs();
+    if magnitude != 0.0 && !(1e-4..1e16).contains(&magnitude) {
+        return format!("{value:e}");
+    }
+    let text = value.to_string();
+    match text.contains('.') {
+        true => text,
+        false => format!("{text}.0"),
+    }
+}
+
+// Rust like format strings: `{}` takes the next value, `{1}` a value by its
+// index, and an optional `:[[fill]align][0][width][.precision]` spec such as
+// `{:>8.2}` pads and rounds it. `{{` and `}}` are literal braces.
+#[derive(Debug, Default, PartialEq)]
+struct Spec {
+    fill: Option<char>,
+    align: Option<char>,
+    width: usize,
+    precision: Option<usize>,
+}
+
+impl Spec {
+    fn parse(spec: &str) -> Option<Self> {
+        let mut result = Self::default();
+        let characters: Vec<char> = spec.chars().collect();
+        let mut rest = &characters[..];
+
+        match rest {
+            [fill, align @ ('<' | '>' | '^'), ..] => {
+                result.fill = Some(*fill);
+                result.align = Some(*align);
+                rest = &rest[2..];
+            }
+            [align @ ('<' | '>' | '^'), ..] => {
+                result.align = Some(*align);
+                rest = &rest[1..];
+            }
+            _ => (),
+        }
+
+        let rest: String = rest.iter().collect();
+        let (width, precision) = match rest.split_once('.') {
+            Some((width, precision)) => (width, Some(precision.parse::<usize>().ok()?)),
+            None => (rest.as_str(), None),
+        };
+        if width.len() > 1 && width.starts_with('0') && result.fill.is_none() {
+            result.fill = Some('0');
+            result.align = result.align.or(Some('>'));
+        }
+        if !width.is_empty() {
+            result.width = width.parse::<usize>().ok()?;
+        }
+        result.precision = precision;
+        Some(result)
+    }
+
+    fn apply(&self, value: &Value) -> String {
+        let text = match (value, self.precision) {
+            (Value::Float(float), Some(precision)) => format!("{:.*}", precision, float),
+            (Value::Integer(integer), Some(precision)) => {
+                format!("{:.*}", precision, *integer as f32)
+            }
+            (value, Some(precision)) => value.to_string().chars().take(precision).collect(),
+            (value, None) => value.to_string(),
+        };
+
+        let length = text.chars().count();
+        if length >= self.width {
+            return text;
+        }
+        let padding = self.width - length;
+        let fill = self.fill.unwrap_or(' ').to_string();
+        let is_number = matches!(value, Value::Integer(_) | Value::Float(_));
+        match self.align.unwrap_or(if is_number { '>' } else { '<' }) {
+            '>' => format!("{}{}", fill.repeat(padding), text),
+            '^' => format!(
+                "{}{}{}",
+                fill.repeat(padding / 2),
+                text,
+                fill.repeat(padding - padding / 2)
+            ),
+            _ => format!("{}{}", text, fill.repeat(padding)),
+        }
+    }
+}
+
+// The formatted string, or why the template can't be formatted with the
+// values: an unclosed or unmatched brace, a placeholder without a value or a
+// spec that doesn't parse.
+pub fn format(template: &str, values: &[Value]) -> Result<String, String> {
+    let mut result = String::new();
+    let mut next = 0;
+    let mut characters = template.chars().enumerate().peekable();
+
+    while let Some((position, character)) = characters.next() {
+        match character {
+            '{' if characters.peek().map(|(_, next)| *next) == Some('{') => {
+                characters.next();
+                result.push('{');
+            }
+            '}' if characters.peek().map(|(_, next)| *next) == Some('}') => {
+                characters.next();
+                result.push('}');
+            }
+            '{' => {
+                let mut placeholder = String::new();
+                loop {
+                    match characters.next() {
+                        Some((_, '}')) => break,
+                        Some((_, character)) => placeholder.push(character),
+                        None => {
+                            return Err(format!(
+                                "the `{{` at character {} is never closed",
+                                position + 1
+                            ))
+                        }
+                    }
+                }
+                let (index, spec) = match placeholder.split_once(':') {
+                    Some((index, spec)) => (
+                        index,
+                        Spec::parse(spec)
+                            .ok_or_else(|| format!("`{}` isn't a valid format spec", spec))?,
+                    ),
+                    None => (placeholder.as_str(), Spec::default()),
+                };
+                let index = match index.trim() {
+                    "" => {
+                        next += 1;
+                        next - 1
+                    }
+                    index => index
+                        .parse::<usize>()
+                        .map_err(|_| format!("`{}` isn't a value's index", index))?,
+                };
+                let value = values.get(index).ok_or_else(|| {
+                    format!(
+                        "`{{{}}}` refers to value {} but only {} {} given",
+                        placeholder,
+                        index,
+                        values.len(),
+                        if values.len() == 1 { "was" } else { "were" }
+                    )
+                })?;
+                result.push_str(&spec.apply(value));
+            }
+            '}' => {
+                return Err(format!(
+                    "the `}}` at character {} has no matching `{{`, use `}}}}` for a brace",
+                    position + 1
+                ))
+            }
+            character => result.push(character),
+        }
+    }
+
+    Ok(result)
+}
@@ -0,0 +1,10 @@
+use crate::evaluator::shared::Sendable;
+use crate::evaluator::symbol_table::SymbolTable;
+use crate::parser::node::Node;
+
+// Called by the evaluator before every statement it runs, at any depth, with
+// the variables visible to it. Debuggers, tracers and coverage tools build on
+// it.
+pub trait Hook: Sendable {
+    fn on_statement(&mut self, statement: &Node, symbol_table: &SymbolTable);
+}
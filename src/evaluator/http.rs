@@ -0,0 +1,160 @@
+use crate::evaluator::builtins::{record, string_argument};
+use crate::evaluator::shared::{Mutable, Shared};
+use crate::evaluator::value::Value;
+use crate::evaluator::{Evaluator, EvaluatorItem};
+use crate::models::error::{MonoError, Runtime};
+use crate::tokenizer::token::Token;
+use std::io::{Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::time::Duration;
+
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+// A minimal HTTP/1.1 client over plain TCP, so only `http://` URLs are
+// supported. Both builtins return a `Response` struct with the `status`, the
+// `headers` as a list of `(name, value)` tuples and the `body`.
+pub fn http_get(_evaluator: &mut Evaluator, call: &Token, values: Vec<Value>) -> EvaluatorItem {
+    let url = string_argument(&values[0])?;
+    request(call, "GET", url, &[], "")
+}
+
+// The headers are a list of `(name, value)` tuples.
+pub fn http_post(_evaluator: &mut Evaluator, call: &Token, values: Vec<Value>) -> EvaluatorItem {
+    let url = string_argument(&values[0])?;
+    let body = string_argument(&values[1])?;
+    let headers = match &values[2] {
+        Value::List(headers) => headers
+            .borrow()
+            .iter()
+            .map(header)
+            .collect::<Result<Vec<_>, _>>()?,
+        value => {
+            return Err(MonoError::from(Runtime::InvalidValue {
+                expected: Value::from(Vec::<Value>::new()).into(),
+                found: value.into(),
+            }))
+        }
+    };
+    request(call, "POST", url, &headers, body)
+}
+
+fn header(value: &Value) -> Result<(String, String), MonoError> {
+    if let Value::Tuple(pair) = value {
+        if let [Value::String(name), Value::String(value)] = pair.as_slice() {
+            return Ok((name.to_string(), value.to_string()));
+        }
+    }
+    Err(MonoError::from(Runtime::InvalidValue {
+        expected: Value::Tuple(Shared::new(vec![Value::from(""), Value::from("")])).into(),
+        found: value.into(),
+    }))
+}
+
+fn request(
+    call: &Token,
+    method: &str,
+    url: &str,
+    headers: &[(String, String)],
+    body: &str,
+) -> EvaluatorItem {
+    let failed = |message: String| {
+        MonoError::from(Runtime::IoError {
+            call: call.clone(),
+            message: format!("{} {}: {}", method, url, message),
+        })
+    };
+    let Some(rest) = url.strip_prefix("http://") else {
+        return Err(failed(String::from("only http:// URLs are supported")));
+    };
+    let (authority, path) = match rest.find('/') {
+        Some(index) => rest.split_at(index),
+        None => (rest, "/"),
+    };
+    let address = match authority.contains(':') {
+        true => authority.to_string(),
+        false => format!("{}:80", authority),
+    };
+
+    let mut request = format!(
+        "{method} {path} HTTP/1.1\r\n\
+         Host: {authority}\r\n\
+         Connection: close\r\n\
+         Content-Length: {}\r\n",
+        body.len()
+    );
+    for (name, value) in headers {
+        request.push_str(&format!("{}: {}\r\n", name, value));
+    }
+    request.push_str("\r\n");
+    request.push_str(body);
+
+    let mut response = Vec::new();
+    address
+        .to_socket_addrs()
+        .and_then(|mut addresses| {
+            let address = addresses.next().ok_or(std::io::ErrorKind::NotFound)?;
+            let mut stream = TcpStream::connect_timeout(&address, TIMEOUT)?;
+            stream.set_read_timeout(Some(TIMEOUT))?;
+            stream.write_all(request.as_bytes())?;
+            stream.read_to_end(&mut response)
+        })
+        .map_err(|error| failed(error.to_string()))?;
+    parse(&response).ok_or_else(|| failed(String::from("invalid response")))
+}
+
+fn parse(response: &[u8]) -> Option<Value> {
+    let split = response
+        .windows(4)
+        .position(|window| window == b"\r\n\r\n")?;
+    let head = std::str::from_utf8(&response[..split]).ok()?;
+    let mut body = &response[split + 4..];
+    let mut lines = head.split("\r\n");
+    let status = lines.next()?.split_whitespace().nth(1)?.parse().ok()?;
+
+    let mut headers = Vec::new();
+    let mut chunked = false;
+    for line in lines {
+        let (name, value) = line.split_once(':')?;
+        let value = value.trim();
+        if name.eq_ignore_ascii_case("content-length") {
+            body = body.get(..value.parse().ok()?)?;
+        }
+        chunked |= name.eq_ignore_ascii_case("transfer-encoding") && value == "chunked";
+        headers.push(Value::Tuple(Shared::new(vec![
+            Value::from(name),
+            Value::from(value),
+        ])));
+    }
+    let body = match chunked {
+        true => dechunk(body)?,
+        false => body.to_vec(),
+    };
+
+    Some(record(
+        "Response",
+        vec![
+            ("status", Value::Integer(status)),
+            ("headers", Value::List(Shared::new(Mutable::new(headers)))),
+            (
+                "body",
+                Value::from(String::from_utf8_lossy(&body).into_owned()),
+            ),
+        ],
+    ))
+}
+
+// Joins a body sent with `Transfer-Encoding: chunked`, each chunk is its
+// length in hex on its own line followed by that many bytes.
+fn dechunk(mut body: &[u8]) -> Option<Vec<u8>> {
+    let mut joined = Vec::new();
+    loop {
+        let end = body.windows(2).position(|window| window == b"\r\n")?;
+        let size = std::str::from_utf8(&body[..end]).ok()?;
+        let size = usize::from_str_radix(size.split(';').next()?.trim(), 16).ok()?;
+        if size == 0 {
+            return Some(joined);
+        }
+        joined.extend_from_slice(body.get(end + 2..end + 2 + size)?);
+        body = body.get(end + 4 + size..)?;
+    }
+}
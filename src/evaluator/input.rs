@@ -0,0 +1,28 @@
+use crate::evaluator::shared::{Mutable, Sendable, Shared};
+use std::io::{self, BufRead};
+
+// Where `input` reads from. Standard input unless the host gives the
+// evaluator its own reader with `Evaluator::set_input`, e.g. to feed a script
+// canned answers. Threads the evaluator spawns read from the same one.
+pub trait Input: BufRead + Sendable {}
+
+impl<T: BufRead + Sendable> Input for T {}
+
+pub(crate) type Reader = Shared<Mutable<Box<dyn Input>>>;
+
+// The next line without its line ending, `None` at the end of the input.
+pub(crate) fn read_line(reader: Option<&Reader>) -> Option<String> {
+    let mut line = String::new();
+    let read = match reader {
+        Some(reader) => reader.borrow_mut().read_line(&mut line),
+        None => io::stdin().lock().read_line(&mut line),
+    };
+    match read {
+        Ok(0) | Err(_) => None,
+        Ok(_) => {
+            let length = line.trim_end_matches(['\n', '\r']).len();
+            line.truncate(length);
+            Some(line)
+        }
+    }
+}
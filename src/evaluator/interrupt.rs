@@ -0,0 +1,17 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+// Set by the host, e.g. from a Ctrl-C handler, to cut blocking builtins such
+// as `sleep` short. It stays set until cleared.
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+pub fn interrupt() {
+    INTERRUPTED.store(true, Ordering::Relaxed);
+}
+
+pub fn clear() {
+    INTERRUPTED.store(false, Ordering::Relaxed);
+}
+
+pub fn is_interrupted() -> bool {
+    INTERRUPTED.load(Ordering::Relaxed)
+}
@@ -0,0 +1,122 @@
+use super::{Evaluator, EvaluatorItem};
+use crate::evaluator::shared::Mutable;
+use crate::evaluator::shared::Shared;
+use crate::evaluator::value::Value;
+use crate::models::error::{MonoError, Runtime};
+use crate::tokenizer::token::Token;
+
+// The state of a single `for` loop. Sequences are walked by index,
+// any other value has to follow the iteration protocol: either define a
+// `next(self)` method returning `None` once exhausted, or an `iter(self)`
+// method returning such an iterator (or a list/string).
+pub enum Iteration {
+    List {
+        list: Shared<Mutable<Vec<Value>>>,
+        index: usize,
+    },
+    Tuple {
+        values: Shared<Vec<Value>>,
+        index: usize,
+    },
+    String {
+        characters: Vec<char>,
+        index: usize,
+    },
+    Bytes {
+        bytes: Shared<[u8]>,
+        index: usize,
+    },
+    Protocol {
+        iterator: Value,
+    },
+}
+
+impl Evaluator {
+    pub(super) fn iterate(
+        &mut self,
+        value: Value,
+        operator: &Token,
+    ) -> Result<Iteration, MonoError> {
+        match value {
+            Value::List(list) => Ok(Iteration::List { list, index: 0 }),
+            Value::Tuple(values) => Ok(Iteration::Tuple { values, index: 0 }),
+            Value::String(string) => Ok(Iteration::String {
+                characters: string.chars().collect(),
+                index: 0,
+            }),
+            Value::Bytes(bytes) => Ok(Iteration::Bytes { bytes, index: 0 }),
+            iterator if Self::has_method(&iterator, "next") => Ok(Iteration::Protocol { iterator }),
+            iterable if Self::has_method(&iterable, "iter") => {
+                let iterator = self.call_method(&iterable, "iter", operator)?;
+                match iterator {
+                    Value::List(_) | Value::Tuple(_) | Value::String(_) | Value::Bytes(_) => {
+                        self.iterate(iterator, operator)
+                    }
+                    iterator if Self::has_method(&iterator, "next") => {
+                        Ok(Iteration::Protocol { iterator })
+                    }
+                    value => Err(MonoError::from(Runtime::NonIterable {
+                        value: value.into(),
+                        operator: operator.clone(),
+                    })),
+                }
+            }
+            value => Err(MonoError::from(Runtime::NonIterable {
+                value: value.into(),
+                operator: operator.clone(),
+            })),
+        }
+    }
+
+    // Advances the iteration, `None` marks its end.
+    pub(super) fn advance(
+        &mut self,
+        iteration: &mut Iteration,
+        operator: &Token,
+    ) -> Result<Option<Value>, MonoError> {
+        match iteration {
+            Iteration::List { list, index } => {
+                let value = list.borrow().get(*index).cloned();
+                *index += 1;
+                Ok(value)
+            }
+            Iteration::Tuple { values, index } => {
+                let value = values.get(*index).cloned();
+                *index += 1;
+                Ok(value)
+            }
+            Iteration::String { characters, index } => {
+                let value = characters.get(*index).copied().map(Value::Character);
+                *index += 1;
+                Ok(value)
+            }
+            Iteration::Bytes { bytes, index } => {
+                let value = bytes.get(*index).map(|byte| Value::Integer(*byte as i32));
+                *index += 1;
+                Ok(value)
+            }
+            Iteration::Protocol { iterator } => {
+                match self.call_method(&iterator.clone(), "next", operator)? {
+                    Value::None => Ok(None),
+                    value => Ok(Some(value)),
+                }
+            }
+        }
+    }
+
+    fn has_method(value: &Value, name: &str) -> bool {
+        matches!(value, Value::Struct { methods, .. } if methods.borrow().contains_key(name))
+    }
+
+    fn call_method(&mut self, object: &Value, name: &str, operator: &Token) -> EvaluatorItem {
+        let Value::Struct { methods, .. } = object else {
+            return Runtime::NonIterable {
+                value: object.clone().into(),
+                operator: operator.clone(),
+            }
+            .into();
+        };
+        let method = methods.borrow().get(name).cloned();
+        self.call(operator, method, vec![object.clone()], Vec::new())
+    }
+}
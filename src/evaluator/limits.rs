@@ -0,0 +1,29 @@
+use std::cell::Cell;
+
+// Bounds on what a program may use, for running untrusted code. Steps are
+// counted per call to `evaluate` or `evaluate_statement`. `None` leaves that
+// resource unbounded.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Limits {
+    // Nodes evaluated.
+    pub steps: Option<u64>,
+    // Nested function calls and comprehensions.
+    pub depth: Option<usize>,
+    // Elements of a list or bytes of a string produced by a single step.
+    pub length: Option<usize>,
+}
+
+// The length limit of the evaluator running on this thread. Builtins and
+// operators that build a whole list or string at once check it up front,
+// as they'd run out of memory before the result could be checked.
+thread_local! {
+    static MAXIMUM_LENGTH: Cell<Option<usize>> = const { Cell::new(None) };
+}
+
+pub(crate) fn set_maximum_length(length: Option<usize>) {
+    MAXIMUM_LENGTH.with(|maximum| maximum.set(length));
+}
+
+pub(crate) fn maximum_length() -> Option<usize> {
+    MAXIMUM_LENGTH.with(Cell::get)
+}
@@ -0,0 +1,127 @@
+use crate::evaluator::value::Value;
+use crate::parser::node::CasePattern;
+use crate::tokenizer::token::{Token, TokenKind};
+
+// Whether the value matches the pattern, pushing what each name the pattern
+// captures is bound to. On a mismatch some bindings may have been pushed
+// already, the caller drops them.
+pub fn matches<'p>(
+    pattern: &'p CasePattern,
+    value: &Value,
+    bindings: &mut Vec<(&'p Token, Value)>,
+) -> bool {
+    match pattern {
+        CasePattern::Wildcard => true,
+        CasePattern::Binding(identifier) => {
+            bind(identifier, value.clone(), bindings);
+            true
+        }
+        CasePattern::Literal(literal) => Value::from(literal) == *value,
+        // Only meaningful inside a list or a tuple, where it's handled.
+        CasePattern::Rest(_) => false,
+        CasePattern::Variant {
+            enumeration,
+            variant,
+        } => match value {
+            Value::Variant {
+                name,
+                variant: found,
+            } => is_named(enumeration, name) && is_named(variant, found),
+            _ => false,
+        },
+        CasePattern::List(patterns) => match value {
+            Value::List(list) => matches_all(patterns, &list.borrow(), bindings),
+            _ => false,
+        },
+        CasePattern::Tuple(patterns) => match value {
+            Value::Tuple(values) => matches_all(patterns, values, bindings),
+            _ => false,
+        },
+        CasePattern::Struct { identifier, fields } => {
+            let Value::Struct {
+                name,
+                fields: values,
+                ..
+            } = value
+            else {
+                return false;
+            };
+            if identifier
+                .as_ref()
+                .is_some_and(|identifier| !is_named(identifier, name))
+            {
+                return false;
+            }
+            let values = values.borrow();
+            fields.iter().all(|(field, pattern)| {
+                values
+                    .iter()
+                    .find(|(name, _)| is_named(field, name))
+                    .is_some_and(|(_, value)| matches(pattern, value, bindings))
+            })
+        }
+        CasePattern::Or(patterns) => patterns.iter().any(|pattern| {
+            let bound = bindings.len();
+            let is_match = matches(pattern, value, bindings);
+            if !is_match {
+                bindings.truncate(bound);
+            }
+            is_match
+        }),
+    }
+}
+
+// Matches the elements of a list or a tuple one to one, except for a single
+// `*rest` pattern which collects the elements left over into a list.
+fn matches_all<'p>(
+    patterns: &'p [CasePattern],
+    values: &[Value],
+    bindings: &mut Vec<(&'p Token, Value)>,
+) -> bool {
+    let rest = patterns
+        .iter()
+        .position(|pattern| matches!(pattern, CasePattern::Rest(_)));
+    let Some(rest) = rest else {
+        return patterns.len() == values.len()
+            && patterns
+                .iter()
+                .zip(values)
+                .all(|(pattern, value)| matches(pattern, value, bindings));
+    };
+
+    let (before, after) = (&patterns[..rest], &patterns[rest + 1..]);
+    if values.len() < before.len() + after.len()
+        || after
+            .iter()
+            .any(|pattern| matches!(pattern, CasePattern::Rest(_)))
+    {
+        return false;
+    }
+    let tail = values.len() - after.len();
+    let is_match = before
+        .iter()
+        .zip(values)
+        .all(|(pattern, value)| matches(pattern, value, bindings))
+        && after
+            .iter()
+            .zip(&values[tail..])
+            .all(|(pattern, value)| matches(pattern, value, bindings));
+    if is_match {
+        if let CasePattern::Rest(identifier) = &patterns[rest] {
+            let rest = values[before.len()..tail].to_vec();
+            bind(identifier, Value::from(rest), bindings);
+        }
+    }
+    is_match
+}
+
+// `_` matches anything without binding it, even as `*_`.
+fn bind<'p>(identifier: &'p Token, value: Value, bindings: &mut Vec<(&'p Token, Value)>) {
+    if !is_named(identifier, "_") {
+        bindings.push((identifier, value));
+    }
+}
+
+fn is_named(identifier: &Token, name: &str) -> bool {
+    matches!(&identifier.kind, TokenKind::Identifier(identifier) if identifier.as_ref() == name)
+}
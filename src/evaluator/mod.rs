@@ -1,61 +1,447 @@
+pub mod aggregates;
 pub mod builtins;
+pub mod bytes;
+pub mod conversions;
+pub mod external;
+pub mod formatting;
+pub mod hook;
+#[cfg(all(feature = "http", not(target_arch = "wasm32")))]
+pub mod http;
+pub mod input;
+pub mod interrupt;
+pub mod iteration;
+pub mod limits;
+pub mod matching;
+pub mod os;
+pub mod output;
+pub mod patterns;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod process;
+pub mod profile;
+pub mod reflection;
+#[cfg(feature = "serde")]
+pub mod serialization;
+pub mod shared;
+pub mod sorting;
+pub mod state;
 pub mod symbol_table;
+#[cfg(all(feature = "sync", not(target_arch = "wasm32")))]
+pub mod threads;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod timers;
 pub mod value;
 
-use crate::evaluator::symbol_table::SymbolTable;
-use crate::evaluator::value::Value;
+use crate::evaluator::builtins::builtin;
+use crate::evaluator::hook::Hook;
+use crate::evaluator::input::{Input, Reader};
+use crate::evaluator::iteration::Iteration;
+use crate::evaluator::limits::Limits;
+use crate::evaluator::output::{Buffer, Output, Writer};
+use crate::evaluator::profile::Profile;
+use crate::evaluator::shared::Mutable;
+use crate::evaluator::shared::Shared;
+use crate::evaluator::symbol_table::{Outer, SymbolTable};
+#[cfg(not(target_arch = "wasm32"))]
+use crate::evaluator::timers::Timers;
+use crate::evaluator::value::{Native, Value};
 use crate::internal_err;
-use crate::models::error::MonoError;
 use crate::models::error::Runtime;
-use crate::parser::node::Node;
+use crate::models::error::{MonoError, Snapshot};
+use crate::models::suggestion::closest;
+use crate::parser::node::{Argument, Arm, Node, Pattern, Slot};
+use crate::parser::Parser;
+use crate::tokenizer::symbol::Symbol;
 use crate::tokenizer::token::Token;
 use crate::tokenizer::token::TokenKind;
-use std::cell::RefCell;
-use std::rc::Rc;
+use crate::tokenizer::Tokenizer;
+use std::collections::HashMap;
+use std::io;
+use std::mem;
+use std::ops::ControlFlow;
+use std::path::PathBuf;
+use std::time::Instant;
 
 pub struct Evaluator {
     symbol_table: SymbolTable,
+    returning: Option<Value>,
+    // The functions `hoist` declared for the program about to run, in
+    // source order.
+    hoisted: Vec<Value>,
+    profile: Option<Profile>,
+    limits: Option<Limits>,
+    steps: u64,
+    hook: Option<Box<dyn Hook>>,
+    // Where `input` reads and `print` writes, standard input and output when
+    // they're `None`.
+    input: Option<Reader>,
+    output: Option<Writer>,
+    paths: Vec<PathBuf>,
+    modules: HashMap<String, Shared<Node>>,
+    #[cfg(not(target_arch = "wasm32"))]
+    timers: Timers,
 }
 
-pub type EvaluatorItem = Result<Value, Box<dyn MonoError>>;
+pub type EvaluatorItem = Result<Value, MonoError>;
+
+impl Default for Evaluator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
 impl Evaluator {
     pub fn new() -> Self {
         let mut symbol_table = SymbolTable::new();
         symbol_table.add_builtins();
         Self {
-            symbol_table: symbol_table,
+            symbol_table,
+            returning: None,
+            hoisted: Vec::new(),
+            profile: None,
+            limits: None,
+            steps: 0,
+            hook: None,
+            input: None,
+            output: None,
+            paths: Vec::new(),
+            modules: HashMap::new(),
+            #[cfg(not(target_arch = "wasm32"))]
+            timers: Timers::default(),
         }
     }
 
     pub fn from(symbol_table: SymbolTable) -> Self {
         Self {
-            symbol_table: symbol_table,
+            symbol_table,
+            returning: None,
+            hoisted: Vec::new(),
+            profile: None,
+            limits: None,
+            steps: 0,
+            hook: None,
+            input: None,
+            output: None,
+            paths: Vec::new(),
+            modules: HashMap::new(),
+            #[cfg(not(target_arch = "wasm32"))]
+            timers: Timers::default(),
         }
     }
 
+    pub fn register_builtin(
+        &mut self,
+        name: &str,
+        arg_names: Vec<&str>,
+        doc: &'static str,
+        function: fn(Vec<Value>) -> Value,
+    ) {
+        self.symbol_table
+            .insert_builtin(builtin(name, arg_names, doc, function));
+    }
+
+    // Starts recording evaluation counts and timings per node kind.
+    pub fn enable_profiling(&mut self) {
+        self.profile = Some(Profile::new());
+    }
+
+    pub fn profile(&self) -> Option<&Profile> {
+        self.profile.as_ref()
+    }
+
+    pub fn set_limits(&mut self, limits: Limits) {
+        self.limits = Some(limits);
+    }
+
+    // Nodes evaluated by the last `evaluate` or `evaluate_statement`, only
+    // counted while there are limits.
+    pub fn steps(&self) -> u64 {
+        self.steps
+    }
+
+    pub fn set_hook(&mut self, hook: impl Hook + 'static) {
+        self.hook = Some(Box::new(hook));
+    }
+
+    pub fn set_input(&mut self, reader: impl Input + 'static) {
+        self.input = Some(Shared::new(Mutable::new(Box::new(reader))));
+    }
+
+    pub fn reset_input(&mut self) {
+        self.input = None;
+    }
+
+    pub fn set_output(&mut self, writer: impl Output + 'static) {
+        self.output = Some(Shared::new(Mutable::new(Box::new(writer))));
+    }
+
+    pub fn reset_output(&mut self) {
+        self.output = None;
+    }
+
+    // Runs `f` with everything the evaluator prints collected into a string,
+    // restoring its writer afterwards.
+    pub fn capture<T>(&mut self, f: impl FnOnce(&mut Self) -> T) -> (T, String) {
+        let buffer = Buffer::default();
+        let previous = self
+            .output
+            .replace(Shared::new(Mutable::new(Box::new(buffer.clone()))));
+        let result = f(self);
+        self.output = previous;
+        (result, buffer.contents())
+    }
+
+    pub(crate) fn write(&self, text: &str) {
+        output::write(self.output.as_ref(), text);
+    }
+
+    pub(crate) fn read_line(&self) -> Option<String> {
+        input::read_line(self.input.as_ref())
+    }
+
+    // Directories `run_file` looks in, in order, for a relative path that
+    // isn't found from the working directory.
+    pub fn set_paths(&mut self, paths: Vec<PathBuf>) {
+        self.paths = paths;
+    }
+
+    // Makes `run_file(path)` run the program rather than read the file, for
+    // scripts bundled with the one being run.
+    pub fn add_module(&mut self, path: &str, program: Node) {
+        self.modules.insert(path.to_string(), Shared::new(program));
+    }
+
+    // Sets a global before a program runs, for the host to pass it values.
+    pub fn set_var(&mut self, name: &str, value: impl Into<Value>) {
+        self.symbol_table.insert_global(name, value.into());
+    }
+
+    // The variable as the program would see it, for the host to read what
+    // a program left behind.
+    pub fn get_var(&self, name: &str) -> Option<Value> {
+        self.symbol_table.get(name)
+    }
+
+    pub fn symbol_table(&self) -> &SymbolTable {
+        &self.symbol_table
+    }
+
+    pub fn symbol_table_mut(&mut self) -> &mut SymbolTable {
+        &mut self.symbol_table
+    }
+
+    // The session's globals, to be restored with `load_state`, failing on a
+    // host's external value.
+    pub fn save_state(&self) -> io::Result<Vec<u8>> {
+        state::save(&self.symbol_table)
+    }
+
+    pub fn load_state(&mut self, state: &[u8]) -> io::Result<()> {
+        state::load(state, &mut self.symbol_table)
+    }
+
     pub fn evaluate(&mut self, program: &Node) -> EvaluatorItem {
+        self.start();
+        let result = self.hoist(program).and_then(|_| self.eval_node(program));
+        self.hoisted.clear();
+        match self.returning.take() {
+            Some(value) if result.is_ok() => Ok(value),
+            _ => result,
+        }
+    }
+
+    // Runs the source in a scope of its own that's dropped afterwards, so the
+    // variables and functions it declares don't outlive it. It still sees
+    // the globals, and assigning one changes it.
+    pub fn eval_in_scope(&mut self, source: &str) -> EvaluatorItem {
+        let program = Parser::new(Tokenizer::new(source.chars())).parse()?;
+        self.symbol_table.scope();
+        let result = self.evaluate(&program);
+        self.symbol_table.unscope();
+        result
+    }
+
+    // Evaluates a single top-level statement of a streamed program, breaking
+    // once a `return` ended the program.
+    pub fn evaluate_statement(
+        &mut self,
+        statement: &Node,
+    ) -> Result<ControlFlow<Value, Value>, MonoError> {
+        self.start();
+        if let Some(hook) = &mut self.hook {
+            hook.on_statement(statement, &self.symbol_table);
+        }
+        let value = self.eval_node(statement)?;
+        Ok(match self.returning.take() {
+            Some(value) => ControlFlow::Break(value),
+            None => ControlFlow::Continue(value),
+        })
+    }
+
+    // Runs a program in the current scope, for `eval` and `run_file`. A
+    // `return` in it ends the program rather than the call it runs in.
+    pub(crate) fn evaluate_nested(&mut self, program: &Node) -> EvaluatorItem {
+        let result = self.hoist(program).and_then(|_| self.eval_node(program));
+        self.hoisted.clear();
+        let returned = self.returning.take();
+        result.map(|value| returned.unwrap_or(value))
+    }
+
+    // Declares the program's top-level functions before any of it runs, so
+    // they can call each other and be called from above their declarations.
+    // Each declaration is bound again once it's reached, to the function
+    // hoisted for it so the two are the same. A streamed program is run
+    // before the rest of it is read, so it isn't hoisted.
+    // The other top-level names are reserved first so the globals stay in
+    // source order.
+    fn hoist(&mut self, program: &Node) -> Result<(), MonoError> {
+        let Node::Program { statements } = program else {
+            return Ok(());
+        };
+        for statement in statements {
+            let (Node::Assignment { identifier, .. }
+            | Node::FuncDeclearion { identifier, .. }
+            | Node::StructDeclaration { identifier, .. }
+            | Node::EnumDeclaration { identifier, .. }) = statement.as_ref()
+            else {
+                continue;
+            };
+            if let TokenKind::Identifier(name) = &identifier.kind {
+                self.symbol_table.reserve(name.clone());
+            }
+        }
+        let mut hoisted = Vec::new();
+        for statement in statements {
+            if let Node::FuncDeclearion {
+                identifier,
+                arguments,
+                body,
+                locals,
+            } = statement.as_ref()
+            {
+                hoisted.push(self.declare_function(identifier, arguments, body, locals)?);
+            }
+        }
+        self.hoisted = hoisted;
+        Ok(())
+    }
+
+    fn start(&mut self) {
+        self.steps = 0;
+        limits::set_maximum_length(self.limits.and_then(|limits| limits.length));
+    }
+
+    fn eval_node(&mut self, program: &Node) -> EvaluatorItem {
+        if let Some(limits) = self.limits {
+            return self.eval_limited(program, limits);
+        }
+        self.eval_profiled(program)
+    }
+
+    fn eval_limited(&mut self, program: &Node, limits: Limits) -> EvaluatorItem {
+        self.steps += 1;
+        if let Some(steps) = limits.steps.filter(|steps| self.steps > *steps) {
+            return limit_exceeded("steps", steps as usize);
+        }
+        if let Some(depth) = limits
+            .depth
+            .filter(|depth| self.symbol_table.depth() > *depth)
+        {
+            return limit_exceeded("nested calls", depth);
+        }
+        let value = self.eval_profiled(program)?;
+        let length = match &value {
+            Value::List(list) => list.borrow().len(),
+            Value::String(string) => string.len(),
+            Value::Bytes(bytes) => bytes.len(),
+            _ => 0,
+        };
+        match limits.length {
+            Some(maximum) if length > maximum => limit_exceeded("elements", maximum),
+            _ => Ok(value),
+        }
+    }
+
+    fn eval_profiled(&mut self, program: &Node) -> EvaluatorItem {
+        if self.profile.is_none() {
+            return self.eval_kind(program);
+        }
+        let start = Instant::now();
+        let result = self.eval_kind(program);
+        if let Some(profile) = &mut self.profile {
+            profile.record(program.kind(), start.elapsed());
+        }
+        result
+    }
+
+    fn eval_kind(&mut self, program: &Node) -> EvaluatorItem {
         match program {
-            Node::Atom { value } => self.eval_atom(&value),
-            Node::List { values } => self.eval_list(&values),
+            Node::Atom { value } => self.eval_atom(value),
+            Node::List { values } => self.eval_list(values),
+            Node::Tuple { values } => {
+                let mut tuple = Vec::new();
+                for value in values {
+                    tuple.push(self.eval_node(value)?);
+                }
+                Ok(Value::Tuple(Shared::new(tuple)))
+            }
+            Node::Spread { .. } => internal_err!("Spread must be a list or call parameter."),
             Node::BinaryOp {
                 right,
                 operator,
                 left,
-            } => self.eval_binary_op(&right, &operator, &left),
+            } => self.eval_binary_op(right, operator, left),
             Node::UnaryOp { operator, value } => self.eval_unary_op(operator, value),
             Node::Assignment {
                 identifier,
+                slot,
+                value,
+                is_declaration,
+            } => self.eval_assignment(identifier, *slot, value, is_declaration),
+            Node::Destructuring {
+                pattern,
+                operator,
                 value,
                 is_declaration,
-            } => self.eval_assignment(identifier, value, is_declaration),
+            } => {
+                let value = self.eval_node(value)?;
+                self.destructure(pattern, value, operator, *is_declaration)?;
+                Ok(Value::None)
+            }
             Node::ListAssignment {
                 identifier,
+                slot,
                 index,
                 value,
-            } => self.eval_list_assignment(identifier, index, value),
-            Node::Access { identifier } => self.eval_access(identifier),
-            Node::Index { identifier, index } => self.eval_index(identifier, index),
+            } => self.eval_list_assignment(identifier, *slot, index, value),
+            Node::Access { identifier, slot } => self.eval_access(identifier, *slot),
+            Node::Member { object, member } => self.eval_node(object)?.member(member),
+            Node::MemberAssignment {
+                object,
+                member,
+                value,
+            } => self.eval_member_assignment(object, member, value),
+            Node::StructDeclaration { identifier, fields } => {
+                self.eval_struct_declaration(identifier, fields)
+            }
+            Node::Impl {
+                identifier,
+                methods,
+            } => self.eval_impl(identifier, methods),
+            Node::EnumDeclaration {
+                identifier,
+                variants,
+            } => self.eval_enum_declaration(identifier, variants),
+            Node::MethodCall {
+                object,
+                method,
+                parameters,
+                keywords,
+            } => self.eval_method_call(object, method, parameters, keywords),
+            Node::Index {
+                identifier,
+                slot,
+                index,
+            } => self.eval_index(identifier, *slot, index),
             Node::Program { statements } => self.eval_program(statements),
             Node::If {
                 condition,
@@ -63,16 +449,41 @@ impl Evaluator {
                 else_block,
             } => self.eval_if(condition, block, else_block),
             Node::While { condition, block } => self.eval_while(condition, block),
+            Node::DoWhile { block, condition } => self.eval_do_while(block, condition),
+            Node::For {
+                identifier,
+                slot,
+                operator,
+                iterable,
+                block,
+            } => self.eval_for(identifier, *slot, operator, iterable, block),
+            Node::Comprehension {
+                value,
+                identifier,
+                operator,
+                iterable,
+                condition,
+            } => self.eval_comprehension(value, identifier, operator, iterable, condition),
             Node::FuncDeclearion {
                 identifier,
                 arguments,
                 body,
-            } => self.eval_func_declaration(identifier, arguments, body),
+                locals,
+            } => self.eval_func_declaration(identifier, arguments, body, locals),
             Node::FuncCall {
                 identifier,
+                slot,
                 parameters,
-            } => self.eval_func_call(identifier, parameters),
+                keywords,
+            } => self.eval_func_call(identifier, *slot, parameters, keywords),
             Node::Return { value } => self.eval_return(value),
+            Node::Delete { identifier, slot } => self.eval_delete(identifier, *slot),
+            Node::Global {
+                identifiers,
+                is_nonlocal,
+            } => self.eval_global(identifiers, *is_nonlocal),
+            Node::Optional { value } => self.eval_optional(value),
+            Node::Match { value, arms } => self.eval_match(value, arms),
         }
     }
 
@@ -80,103 +491,306 @@ impl Evaluator {
         Ok(Value::from(value))
     }
 
-    fn eval_list(&mut self, nodes: &Vec<Box<Node>>) -> EvaluatorItem {
-        let mut list = Vec::new();
+    fn eval_list(&mut self, nodes: &[Box<Node>]) -> EvaluatorItem {
+        let list = self.eval_parameters(nodes)?;
+        Ok(Value::List(Shared::new(Mutable::new(list))))
+    }
+
+    fn eval_parameters(&mut self, nodes: &[Box<Node>]) -> Result<Vec<Value>, MonoError> {
+        let mut values = Vec::new();
         for node in nodes.iter() {
-            list.push(self.evaluate(node)?);
+            let Node::Spread { value, .. } = node.as_ref() else {
+                values.push(self.eval_node(node)?);
+                continue;
+            };
+            match self.eval_node(value)? {
+                Value::List(list) => values.extend(list.borrow().iter().cloned()),
+                found => {
+                    return Err(MonoError::from(Runtime::InvalidValue {
+                        expected: Value::List(Shared::new(Mutable::new(Vec::new()))).into(),
+                        found: found.into(),
+                    }))
+                }
+            }
         }
-        Ok(Value::List(Rc::new(RefCell::new(list))))
+        Ok(values)
     }
 
     fn eval_binary_op(&mut self, right: &Node, operator: &Token, left: &Node) -> EvaluatorItem {
-        let right_value = self.evaluate(right)?;
-        let left_value = self.evaluate(left)?;
-        Ok(left_value.binary_operation(right_value, operator)?)
+        // The fallback of `??` is only evaluated when it's needed.
+        if operator.kind == TokenKind::Coalesce {
+            return match self.eval_node(left)? {
+                Value::None => self.eval_node(right),
+                value => Ok(value),
+            };
+        }
+        let right_value = self.eval_node(right)?;
+        let left_value = self.eval_node(left)?;
+        left_value.binary_operation(right_value, operator)
     }
 
     fn eval_unary_op(&mut self, operator: &Token, value: &Node) -> EvaluatorItem {
-        let value = self.evaluate(value)?;
-        Ok(value.unary_operation(operator)?)
+        let value = self.eval_node(value)?;
+        value.unary_operation(operator)
     }
 
     fn eval_assignment(
         &mut self,
         identifier: &Token,
+        slot: Option<Slot>,
         value: &Node,
         is_declaration: &bool,
     ) -> EvaluatorItem {
-        let value = self.evaluate(value)?;
+        let value = self.eval_node(value)?;
+        self.assign(identifier, slot, value, *is_declaration)?;
+        Ok(Value::None)
+    }
+
+    // Resolved variables are read from their slot, falling back to a lookup
+    // by name while the slot is still empty.
+    fn lookup(&self, name: &str, slot: Option<Slot>) -> Option<Value> {
+        slot.and_then(|slot| self.symbol_table.get_at(slot))
+            .or_else(|| self.symbol_table.get(name))
+    }
+
+    // Suggests the closest of the names a lookup could have found instead.
+    fn unknown_identifier(&self, identifier: &Token) -> Runtime {
+        let suggestion = match &identifier.kind {
+            TokenKind::Identifier(name) => {
+                closest(name, self.symbol_table.names().map(AsRef::as_ref))
+            }
+            _ => None,
+        };
+        Runtime::UnknownIdentifier {
+            identifier: identifier.clone(),
+            suggestion: suggestion.map(String::from),
+        }
+    }
+
+    fn assign(
+        &mut self,
+        identifier: &Token,
+        slot: Option<Slot>,
+        value: Value,
+        is_declaration: bool,
+    ) -> Result<(), MonoError> {
         let TokenKind::Identifier(name) = &identifier.kind else {
             internal_err!("Token must be of type Indetifier.")
         };
 
-        if *is_declaration {
-            self.symbol_table.insert(name.to_string(), value);
+        if is_declaration && self.symbol_table.outer(name).is_some() {
+            return Err(MonoError::from(Runtime::InvalidDeclaration {
+                identifier: identifier.clone(),
+                reason: "it's declared global or nonlocal in this scope, assign it without `let`",
+            }));
+        }
+
+        if is_declaration {
+            match slot {
+                Some(slot) => self.symbol_table.insert_at(slot, name.clone(), value),
+                None => self.symbol_table.insert(name.clone(), value),
+            }
+        } else if let Some(old) = slot.and_then(|slot| self.symbol_table.get_at_mut(slot)) {
+            *old = value;
         } else if let Some(old) = self.symbol_table.get_mut(name) {
             *old = value;
+        } else if self.symbol_table.outer(name) == Some(Outer::Global) {
+            // Assigning a global that doesn't exist yet creates it.
+            self.symbol_table.insert_global(name.clone(), value);
+        } else if self.symbol_table.is_builtin(name) {
+            // Reassigning a builtin shadows it in the current scope.
+            self.symbol_table.insert(name.clone(), value);
         } else {
-            return Runtime::UnknownIdentifier {
-                identifier: identifier.clone(),
+            return Err(MonoError::from(self.unknown_identifier(identifier)));
+        }
+        Ok(())
+    }
+
+    // Binds a tuple or a list to the pattern's identifiers. A single `*rest`
+    // pattern collects the values left over by the others into a list.
+    fn destructure(
+        &mut self,
+        pattern: &Pattern,
+        value: Value,
+        operator: &Token,
+        is_declaration: bool,
+    ) -> Result<(), MonoError> {
+        let invalid = |value: Value| -> Result<(), MonoError> {
+            Err(MonoError::from(Runtime::InvalidDestructuring {
+                pattern: pattern.to_string(),
+                value: value.into(),
+                operator: operator.clone(),
+            }))
+        };
+        let patterns = match pattern {
+            Pattern::Identifier(identifier) => {
+                return self.assign(identifier, None, value, is_declaration)
             }
-            .into();
+            Pattern::Rest(_) => return invalid(value),
+            Pattern::Tuple(patterns) | Pattern::List(patterns) => patterns,
+        };
+        let values = match &value {
+            Value::Tuple(values) => values.to_vec(),
+            Value::List(list) => list.borrow().clone(),
+            _ => return invalid(value),
+        };
+
+        let rests = patterns
+            .iter()
+            .filter(|pattern| matches!(pattern, Pattern::Rest(_)))
+            .count();
+        let fixed = patterns.len() - rests;
+        if rests > 1 || (rests == 0 && values.len() != fixed) || values.len() < fixed {
+            return invalid(value);
         }
 
-        Ok(Value::None)
+        let rest_length = values.len() - fixed;
+        let mut values = values.into_iter();
+        for pattern in patterns {
+            match pattern {
+                Pattern::Rest(identifier) => {
+                    let rest = values.by_ref().take(rest_length).collect();
+                    let rest = Value::List(Shared::new(Mutable::new(rest)));
+                    self.assign(identifier, None, rest, is_declaration)?;
+                }
+                pattern => {
+                    let value = values.next().unwrap_or(Value::None);
+                    self.destructure(pattern, value, operator, is_declaration)?;
+                }
+            }
+        }
+        Ok(())
     }
 
     fn eval_list_assignment(
         &mut self,
         identifier: &Token,
+        slot: Option<Slot>,
         index: &Node,
         value: &Node,
     ) -> EvaluatorItem {
-        let value = self.evaluate(value)?;
-        let index = self.evaluate(index)?;
+        let value = self.eval_node(value)?;
+        let index = self.eval_node(index)?;
         let TokenKind::Identifier(name) = &identifier.kind else {
             internal_err!("Token must be of kind Identifier");
         };
 
-        if let Some(list) = self.symbol_table.get(name) {
-            return Ok(list.list_assign(index, value, identifier)?);
-        }
-        return Runtime::UnknownIdentifier {
-            identifier: identifier.clone(),
+        if let Some(list) = self.lookup(name, slot) {
+            return list.list_assign(index, value, identifier);
         }
-        .into();
+        self.unknown_identifier(identifier).into()
     }
 
-    fn eval_access(&mut self, identifier: &Token) -> EvaluatorItem {
+    fn eval_access(&mut self, identifier: &Token, slot: Option<Slot>) -> EvaluatorItem {
         let TokenKind::Identifier(name) = &identifier.kind else {
             internal_err!("Token must be of kind Identifier.")
         };
-        if let Some(value) = self.symbol_table.get(name) {
+        if let Some(value) = self.lookup(name, slot) {
             return Ok(value);
         }
-        return Runtime::UnknownIdentifier {
-            identifier: identifier.clone(),
+        self.unknown_identifier(identifier).into()
+    }
+
+    // Indexes or calls the variable unless it's `None`.
+    fn eval_optional(&mut self, value: &Node) -> EvaluatorItem {
+        let (Node::Index {
+            identifier, slot, ..
+        }
+        | Node::FuncCall {
+            identifier, slot, ..
+        }) = value
+        else {
+            internal_err!("Optional must wrap an Index or a FuncCall.")
+        };
+        match self.eval_access(identifier, *slot)? {
+            Value::None => Ok(Value::None),
+            _ => self.eval_node(value),
+        }
+    }
+
+    // Removes the variable from the current scope, revealing a builtin of the
+    // same name if it shadowed one.
+    fn eval_delete(&mut self, identifier: &Token, slot: Option<Slot>) -> EvaluatorItem {
+        let TokenKind::Identifier(name) = &identifier.kind else {
+            internal_err!("Token must be of kind Identifier.")
+        };
+        let removed = match slot.and_then(|slot| self.symbol_table.remove_at(slot)) {
+            Some(value) => Some(value),
+            None => self.symbol_table.remove(name),
+        };
+        match removed {
+            Some(_) => Ok(Value::None),
+            None => self.unknown_identifier(identifier).into(),
         }
-        .into();
     }
 
-    fn eval_index(&mut self, identifier: &Token, index: &Box<Node>) -> EvaluatorItem {
-        let index = self.evaluate(index)?;
+    // Sends the current scope's uses of the identifiers to the globals, or to
+    // the scopes it was called from.
+    fn eval_global(&mut self, identifiers: &[Token], is_nonlocal: bool) -> EvaluatorItem {
+        let outer = if is_nonlocal {
+            Outer::Nonlocal
+        } else {
+            Outer::Global
+        };
+        for identifier in identifiers {
+            let TokenKind::Identifier(name) = &identifier.kind else {
+                internal_err!("Token must be of kind Identifier.")
+            };
+            if let Err(reason) = self.symbol_table.declare_outer(name.clone(), outer) {
+                return Err(MonoError::from(Runtime::InvalidDeclaration {
+                    identifier: identifier.clone(),
+                    reason,
+                }));
+            }
+        }
+        Ok(Value::None)
+    }
+
+    fn eval_member_assignment(
+        &mut self,
+        object: &Node,
+        member: &Token,
+        value: &Node,
+    ) -> EvaluatorItem {
+        let value = self.eval_node(value)?;
+        self.eval_node(object)?.member_assign(member, value)
+    }
+
+    fn eval_index(
+        &mut self,
+        identifier: &Token,
+        slot: Option<Slot>,
+        index: &Node,
+    ) -> EvaluatorItem {
+        let index = self.eval_node(index)?;
         let TokenKind::Identifier(name) = &identifier.kind else {
             internal_err!("Token must be of kind Identifier.");
         };
-        match self.symbol_table.get(name) {
+        match self.lookup(name, slot) {
             Some(value) => Ok(value.index(index, identifier)?),
-            None => Runtime::UnknownIdentifier {
-                identifier: identifier.clone(),
-            }
-            .into(),
+            None => self.unknown_identifier(identifier).into(),
         }
     }
 
-    fn eval_program(&mut self, statements: &Vec<Box<Node>>) -> EvaluatorItem {
+    fn eval_program(&mut self, statements: &[Box<Node>]) -> EvaluatorItem {
+        // Only the hoisted program itself, not the blocks inside it.
+        let mut hoisted = mem::take(&mut self.hoisted).into_iter();
         let mut value = Value::None;
         for statement in statements {
-            value = self.evaluate(&statement)?;
-            if value != Value::None {
+            if let Some(hook) = &mut self.hook {
+                hook.on_statement(statement, &self.symbol_table);
+            }
+            if let Node::FuncDeclearion { identifier, .. } = statement.as_ref() {
+                if let (TokenKind::Identifier(name), Some(function)) =
+                    (&identifier.kind, hoisted.next())
+                {
+                    self.symbol_table.insert(name.clone(), function);
+                    value = Value::None;
+                    continue;
+                }
+            }
+            value = self.eval_node(statement)?;
+            if self.returning.is_some() {
                 break;
             }
         }
@@ -189,30 +803,138 @@ impl Evaluator {
         block: &Node,
         else_block: &Option<Box<Node>>,
     ) -> EvaluatorItem {
-        let result = self.evaluate(&condition)?;
+        let result = self.eval_node(condition)?;
         match result {
-            Value::Boolean(true) => return Ok(self.evaluate(&block)?),
+            Value::Boolean(true) => return self.eval_node(block),
             Value::Boolean(false) => {}
-            _ => {
-                return Runtime::InvalidValue {
-                    expected: Value::Boolean(false),
-                    found: result,
-                }
-                .into()
-            }
+            found => return Err(invalid_condition(condition, found)),
         }
 
         if let Some(some_else_block) = else_block {
-            return Ok(self.evaluate(&some_else_block)?);
+            return self.eval_node(some_else_block);
+        }
+        Ok(Value::None)
+    }
+
+    fn eval_condition(&mut self, condition: &Node) -> Result<bool, MonoError> {
+        match self.eval_node(condition)? {
+            Value::Boolean(boolean) => Ok(boolean),
+            found => Err(invalid_condition(condition, found)),
+        }
+    }
+
+    // The names an arm's pattern captures are only bound once the whole
+    // pattern matched, into the current scope like those of a `let`. They
+    // stay bound when the arm's guard then fails.
+    fn eval_match(&mut self, value: &Node, arms: &[Arm]) -> EvaluatorItem {
+        let value = self.eval_node(value)?;
+        for arm in arms {
+            let mut bindings = Vec::new();
+            if !matching::matches(&arm.pattern, &value, &mut bindings) {
+                continue;
+            }
+            for (identifier, value) in bindings {
+                self.assign(identifier, None, value, true)?;
+            }
+            if let Some(guard) = &arm.guard {
+                if !self.eval_condition(guard)? {
+                    continue;
+                }
+            }
+            return self.eval_node(&arm.block);
         }
         Ok(Value::None)
     }
 
     fn eval_while(&mut self, condition: &Node, block: &Node) -> EvaluatorItem {
         let mut value = Value::None;
-        while let Value::Boolean(true) = self.evaluate(&condition)? {
-            value = self.evaluate(&block)?;
-            if value != Value::None {
+        while self.eval_condition(condition)? {
+            value = self.eval_node(block)?;
+            if self.returning.is_some() {
+                break;
+            }
+        }
+        Ok(value)
+    }
+
+    fn eval_for(
+        &mut self,
+        identifier: &Token,
+        slot: Option<Slot>,
+        operator: &Token,
+        iterable: &Node,
+        block: &Node,
+    ) -> EvaluatorItem {
+        let TokenKind::Identifier(name) = &identifier.kind else {
+            internal_err!("Token must be of type Identifier.");
+        };
+        let iterable = self.eval_node(iterable)?;
+        let mut iteration = self.iterate(iterable, operator)?;
+
+        let mut value = Value::None;
+        while let Some(item) = self.advance(&mut iteration, operator)? {
+            match slot {
+                Some(slot) => self.symbol_table.insert_at(slot, name.clone(), item),
+                None => self.symbol_table.insert(name.clone(), item),
+            }
+            value = self.eval_node(block)?;
+            if self.returning.is_some() {
+                break;
+            }
+        }
+        Ok(value)
+    }
+
+    fn eval_comprehension(
+        &mut self,
+        value: &Node,
+        identifier: &Token,
+        operator: &Token,
+        iterable: &Node,
+        condition: &Option<Box<Node>>,
+    ) -> EvaluatorItem {
+        let TokenKind::Identifier(name) = &identifier.kind else {
+            internal_err!("Token must be of type Identifier.");
+        };
+        let iterable = self.eval_node(iterable)?;
+        let mut iteration = self.iterate(iterable, operator)?;
+
+        // The loop variable lives in its own scope so it doesn't leak.
+        self.symbol_table.scope_with(Shared::new([name.clone()]));
+        let result = self.collect_comprehension(value, name, operator, &mut iteration, condition);
+        self.symbol_table.unscope();
+        result.map(|values| Value::List(Shared::new(Mutable::new(values))))
+    }
+
+    fn collect_comprehension(
+        &mut self,
+        value: &Node,
+        name: &Symbol,
+        operator: &Token,
+        iteration: &mut Iteration,
+        condition: &Option<Box<Node>>,
+    ) -> Result<Vec<Value>, MonoError> {
+        let mut values = Vec::new();
+        while let Some(item) = self.advance(iteration, operator)? {
+            self.symbol_table.insert(name.clone(), item);
+            if let Some(condition) = condition {
+                if !self.eval_condition(condition)? {
+                    continue;
+                }
+            }
+            values.push(self.eval_node(value)?);
+        }
+        Ok(values)
+    }
+
+    fn eval_do_while(&mut self, block: &Node, condition: &Node) -> EvaluatorItem {
+        let mut value;
+        loop {
+            value = self.eval_node(block)?;
+            if self.returning.is_some() {
+                break;
+            }
+            if !self.eval_condition(condition)? {
                 break;
             }
         }
@@ -222,88 +944,417 @@ impl Evaluator {
     fn eval_func_declaration(
         &mut self,
         identifier: &Token,
-        arguments: &[Token],
-        body: &Box<Node>,
+        arguments: &[Argument],
+        body: &Node,
+        locals: &[Symbol],
+    ) -> EvaluatorItem {
+        self.declare_function(identifier, arguments, body, locals)?;
+        Ok(Value::None)
+    }
+
+    fn declare_function(
+        &mut self,
+        identifier: &Token,
+        arguments: &[Argument],
+        body: &Node,
+        locals: &[Symbol],
     ) -> EvaluatorItem {
         let TokenKind::Identifier(n) = &identifier.kind else {
             internal_err!("Token must be of type Identifier.");
         };
-        let string_arguments = arguments
-            .iter()
-            .map(|arg| {
-                if let TokenKind::Identifier(name) = &arg.kind {
-                    name.to_string()
-                } else {
-                    panic!("Expected identifier in function arguments");
-                }
-            })
-            .collect::<Vec<String>>();
 
         let function = Value::Function {
-            name: n.to_string(),
-            arguments: string_arguments,
-            body: body.clone(),
+            name: n.clone(),
+            position: identifier.start.clone(),
+            arguments: arguments.into(),
+            body: Shared::new(body.clone()),
+            locals: locals.into(),
         };
-        self.symbol_table.insert(n.to_string(), function);
+        self.symbol_table.insert(n.clone(), function.clone());
+
+        Ok(function)
+    }
+
+    fn eval_struct_declaration(
+        &mut self,
+        identifier: &Token,
+        fields: &[Argument],
+    ) -> EvaluatorItem {
+        let TokenKind::Identifier(n) = &identifier.kind else {
+            internal_err!("Token must be of type Identifier.");
+        };
+
+        let struct_type = Value::StructType {
+            name: n.clone(),
+            fields: fields.into(),
+            methods: Shared::new(Mutable::new(HashMap::new())),
+        };
+        self.symbol_table.insert(n.clone(), struct_type);
 
         Ok(Value::None)
     }
 
-    fn eval_func_call(&mut self, identifier: &Token, parameters: &Vec<Box<Node>>) -> EvaluatorItem {
-        let mut values = Vec::new();
-        for parameter in parameters {
-            values.push(self.evaluate(parameter)?);
+    fn eval_enum_declaration(&mut self, identifier: &Token, variants: &[Token]) -> EvaluatorItem {
+        let TokenKind::Identifier(n) = &identifier.kind else {
+            internal_err!("Token must be of type Identifier.");
+        };
+
+        let mut names = Vec::new();
+        for variant in variants {
+            let TokenKind::Identifier(variant) = &variant.kind else {
+                internal_err!("Token must be of type Identifier.");
+            };
+            names.push(variant.clone());
         }
-        let TokenKind::Identifier(name) = &identifier.kind else {
+        let enum_type = Value::EnumType {
+            name: n.clone(),
+            variants: names.into(),
+        };
+        self.symbol_table.insert(n.clone(), enum_type);
+
+        Ok(Value::None)
+    }
+
+    fn eval_impl(&mut self, identifier: &Token, methods: &[Box<Node>]) -> EvaluatorItem {
+        let TokenKind::Identifier(n) = &identifier.kind else {
             internal_err!("Token must be of type Identifier.");
         };
+        let struct_methods = match self.symbol_table.get(n) {
+            Some(Value::StructType { methods, .. }) => methods,
+            Some(found) => {
+                return Runtime::InvalidValue {
+                    expected: Value::StructType {
+                        name: n.clone(),
+                        fields: Shared::new([]),
+                        methods: Shared::new(Mutable::new(HashMap::new())),
+                    }
+                    .into(),
+                    found: found.into(),
+                }
+                .into()
+            }
+            None => return self.unknown_identifier(identifier).into(),
+        };
 
-        return match self.symbol_table.get(&name) {
-            Some(Value::Function {
-                name,
+        for method in methods {
+            let Node::FuncDeclearion {
+                identifier,
                 arguments,
                 body,
+                locals,
+            } = method.as_ref()
+            else {
+                internal_err!("Impl blocks must only contain function declarations.");
+            };
+            let TokenKind::Identifier(name) = &identifier.kind else {
+                internal_err!("Token must be of type Identifier.");
+            };
+            struct_methods.borrow_mut().insert(
+                name.clone(),
+                Value::Function {
+                    name: format!("{n}.{name}").into(),
+                    position: identifier.start.clone(),
+                    arguments: arguments.as_slice().into(),
+                    body: Shared::new(body.as_ref().clone()),
+                    locals: locals.as_slice().into(),
+                },
+            );
+        }
+
+        Ok(Value::None)
+    }
+
+    fn eval_func_call(
+        &mut self,
+        identifier: &Token,
+        slot: Option<Slot>,
+        parameters: &[Box<Node>],
+        keywords: &[(Token, Box<Node>)],
+    ) -> EvaluatorItem {
+        let values = self.eval_parameters(parameters)?;
+        let mut keyword_values = Vec::new();
+        for (keyword, value) in keywords {
+            keyword_values.push((keyword, self.eval_node(value)?));
+        }
+        let TokenKind::Identifier(name) = &identifier.kind else {
+            internal_err!("Token must be of type Identifier.");
+        };
+
+        let callee = self.lookup(name, slot);
+        self.call(identifier, callee, values, keyword_values)
+    }
+
+    fn eval_method_call(
+        &mut self,
+        object: &Node,
+        method: &Token,
+        parameters: &[Box<Node>],
+        keywords: &[(Token, Box<Node>)],
+    ) -> EvaluatorItem {
+        let object = self.eval_node(object)?;
+        let mut values = match object {
+            Value::Namespace { .. } => Vec::new(),
+            _ => vec![object.clone()],
+        };
+        values.extend(self.eval_parameters(parameters)?);
+        let mut keyword_values = Vec::new();
+        for (keyword, value) in keywords {
+            keyword_values.push((keyword, self.eval_node(value)?));
+        }
+        let TokenKind::Identifier(name) = &method.kind else {
+            internal_err!("Token must be of type Identifier.");
+        };
+
+        let callee = match &object {
+            Value::Struct { methods, .. } => methods.borrow().get(name).cloned(),
+            Value::Namespace { members, .. } => members.get(name).cloned(),
+            _ => None,
+        };
+        match callee {
+            Some(callee) => self.call(method, Some(callee), values, keyword_values),
+            None => Runtime::UnknownMember {
+                value: object.into(),
+                member: method.clone(),
+            }
+            .into(),
+        }
+    }
+
+    fn call(
+        &mut self,
+        identifier: &Token,
+        callee: Option<Value>,
+        values: Vec<Value>,
+        keyword_values: Vec<(&Token, Value)>,
+    ) -> EvaluatorItem {
+        match callee {
+            Some(
+                ref function @ Value::Function {
+                    ref name,
+                    ref arguments,
+                    ref body,
+                    ref locals,
+                    ..
+                },
+            ) => {
+                let bound =
+                    self.match_parameters(identifier, name, arguments, &values, keyword_values)?;
+                let incorrect_parameters = || Runtime::IncorrectParameters {
+                    name: name.to_string(),
+                    call: identifier.clone(),
+                    expected: arguments.iter().map(ToString::to_string).collect(),
+                    found: values.iter().map(Snapshot::from).collect(),
+                };
+
+                self.symbol_table.scope_with(locals.clone());
+                // The resolver keeps a slot for the function's own name, an
+                // argument of the same name takes its place.
+                if locals.contains(name) {
+                    self.symbol_table.insert(name.clone(), function.clone());
+                }
+                let result = self.bind_arguments(arguments, bound).and_then(|bound| {
+                    if bound {
+                        self.eval_node(body)
+                    } else {
+                        incorrect_parameters().into()
+                    }
+                });
+                self.symbol_table.unscope();
+                let returned = self.returning.take();
+                result.map(|_| returned.unwrap_or(Value::None))
+            }
+            Some(Value::StructType {
+                name,
+                fields,
+                methods,
             }) => {
-                if arguments.len() != parameters.len() {
-                    return Runtime::IncorrectParameters {
-                        name: name,
+                let bound =
+                    self.match_parameters(identifier, &name, &fields, &values, keyword_values)?;
+
+                self.symbol_table
+                    .scope_with(fields.iter().map(|field| field.name().clone()).collect());
+                let result = self.bind_arguments(&fields, bound).map(|bound| {
+                    bound.then(|| {
+                        fields
+                            .iter()
+                            .map(|field| {
+                                let name = field.name().clone();
+                                let value = self.symbol_table.get(&name).unwrap_or(Value::None);
+                                (name, value)
+                            })
+                            .collect()
+                    })
+                });
+                self.symbol_table.unscope();
+                match result? {
+                    Some(fields) => Ok(Value::Struct {
+                        name,
+                        fields: Shared::new(Mutable::new(fields)),
+                        methods,
+                    }),
+                    None => Runtime::IncorrectParameters {
+                        name: name.to_string(),
                         call: identifier.clone(),
-                        expected: arguments,
-                        found: values,
+                        expected: fields.iter().map(ToString::to_string).collect(),
+                        found: values.iter().map(Snapshot::from).collect(),
                     }
-                    .into();
+                    .into(),
                 }
-
-                self.symbol_table.scope();
-                for (arg, val) in arguments.into_iter().zip(values.into_iter()) {
-                    self.symbol_table.insert(arg, val);
+            }
+            Some(Value::Namespace { members, .. }) if members.contains_key("from") => {
+                let from = members.get("from").cloned();
+                self.call(identifier, from, values, keyword_values)
+            }
+            Some(Value::BuiltInFunction { name, .. }) if !keyword_values.is_empty() => {
+                Runtime::InvalidKeyword {
+                    name: name.to_string(),
+                    keyword: keyword_values[0].0.clone(),
                 }
-                let result = self.evaluate(&body);
-                self.symbol_table.unscope();
-                result
+                .into()
             }
             Some(Value::BuiltInFunction {
                 name,
                 arguments,
+                types,
                 function,
-            }) => match arguments.len() != parameters.len() {
-                true => Runtime::IncorrectParameters {
-                    name: name,
-                    call: identifier.clone(),
-                    expected: arguments,
-                    found: values,
+                ..
+            }) => {
+                // Variadic builtins mark their last argument as `*name`.
+                let is_variadic = arguments.last().is_some_and(|last| last.starts_with('*'));
+                let is_valid = match is_variadic {
+                    true => values.len() >= arguments.len() - 1,
+                    false => arguments.len() == values.len(),
+                };
+                if is_valid {
+                    // The variadic argument's type applies to each of its values.
+                    for (index, value) in values.iter().enumerate() {
+                        let index = index.min(arguments.len() - 1);
+                        if let Some(kind) = &types[index] {
+                            if !builtins::accepts(kind, value) {
+                                return Runtime::InvalidArgumentType {
+                                    name: name.to_string(),
+                                    call: identifier.clone(),
+                                    argument: arguments[index].clone(),
+                                    expected: kind.to_string(),
+                                    found: value.into(),
+                                }
+                                .into();
+                            }
+                        }
+                    }
+                }
+                match (is_valid, function) {
+                    (true, Native::Function(function)) => Ok(function(values)),
+                    (true, Native::Evaluator(function)) => function(self, identifier, values),
+                    (false, _) => Runtime::IncorrectParameters {
+                        name: name.to_string(),
+                        call: identifier.clone(),
+                        expected: arguments.to_vec(),
+                        found: values.iter().map(Snapshot::from).collect(),
+                    }
+                    .into(),
                 }
-                .into(),
-                false => Ok(function(values)),
-            },
-            _ => Runtime::UnknownIdentifier {
-                identifier: identifier.clone(),
             }
-            .into(),
-        };
+            _ => self.unknown_identifier(identifier).into(),
+        }
+    }
+
+    // Calls a function value from inside an intrinsic, such as the key of
+    // `sort`, as if the script had called it at `call`.
+    pub(crate) fn call_value(
+        &mut self,
+        call: &Token,
+        callee: Value,
+        values: Vec<Value>,
+    ) -> EvaluatorItem {
+        self.call(call, Some(callee), values, Vec::new())
+    }
+
+    // Matches positional and keyword values to the declared arguments, leaving
+    // the slots of missing arguments empty for `bind_arguments` to fill.
+    fn match_parameters(
+        &self,
+        identifier: &Token,
+        name: &str,
+        arguments: &[Argument],
+        values: &[Value],
+        keyword_values: Vec<(&Token, Value)>,
+    ) -> Result<Vec<Option<Value>>, MonoError> {
+        let is_variadic = arguments.last().is_some_and(|a| a.is_variadic);
+        let fixed = arguments.len() - is_variadic as usize;
+        if values.len() > fixed && !is_variadic {
+            return Err(MonoError::from(Runtime::IncorrectParameters {
+                name: name.to_string(),
+                call: identifier.clone(),
+                expected: arguments.iter().map(ToString::to_string).collect(),
+                found: values.iter().map(Snapshot::from).collect(),
+            }));
+        }
+
+        let mut bound: Vec<Option<Value>> = vec![None; arguments.len()];
+        for (slot, value) in bound.iter_mut().zip(values.iter().take(fixed)) {
+            *slot = Some(value.clone());
+        }
+        if is_variadic {
+            let rest = values.iter().skip(fixed).cloned().collect();
+            bound[fixed] = Some(Value::List(Shared::new(Mutable::new(rest))));
+        }
+        for (keyword, value) in keyword_values {
+            let TokenKind::Identifier(keyword_name) = &keyword.kind else {
+                internal_err!("Token must be of type Identifier.");
+            };
+            match arguments
+                .iter()
+                .position(|a| a.name() == keyword_name && !a.is_variadic)
+            {
+                Some(index) if bound[index].is_none() => bound[index] = Some(value),
+                _ => {
+                    return Err(MonoError::from(Runtime::InvalidKeyword {
+                        name: name.to_string(),
+                        keyword: keyword.clone(),
+                    }))
+                }
+            }
+        }
+        Ok(bound)
+    }
+
+    // Binds the given values into the current scope, evaluating the defaults
+    // of missing arguments in order so they can refer to earlier arguments.
+    // Returns false if an argument without a default value is missing.
+    fn bind_arguments(
+        &mut self,
+        arguments: &[Argument],
+        values: Vec<Option<Value>>,
+    ) -> Result<bool, MonoError> {
+        for (argument, value) in arguments.iter().zip(values) {
+            let value = match (value, &argument.default) {
+                (Some(value), _) => value,
+                (None, Some(default)) => self.eval_node(default)?,
+                (None, None) => return Ok(false),
+            };
+            self.symbol_table.insert(argument.name().clone(), value);
+        }
+        Ok(true)
     }
 
-    fn eval_return(&mut self, value: &Box<Node>) -> EvaluatorItem {
-        self.evaluate(value)
+    fn eval_return(&mut self, value: &Node) -> EvaluatorItem {
+        let value = self.eval_node(value)?;
+        self.returning = Some(value.clone());
+        Ok(value)
+    }
+}
+
+fn limit_exceeded(limit: &'static str, maximum: usize) -> EvaluatorItem {
+    Runtime::LimitExceeded { limit, maximum }.into()
+}
+
+// Conditions must be Booleans, the error points at the whole condition.
+fn invalid_condition(condition: &Node, found: Value) -> MonoError {
+    Runtime::InvalidCondition {
+        found: found.into(),
+        start: condition.position().cloned(),
+        end: condition.end_position().cloned(),
     }
+    .into()
 }
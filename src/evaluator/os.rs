@@ -0,0 +1,90 @@
+use crate::evaluator::builtins::string_argument;
+use crate::evaluator::shared::{Mutable, Shared};
+use crate::evaluator::value::Value;
+use crate::evaluator::{Evaluator, EvaluatorItem};
+use crate::models::error::{MonoError, Runtime};
+use crate::tokenizer::token::Token;
+use std::path::Path;
+use std::{env, fs, io};
+
+// Failing file system calls raise a runtime error naming the path.
+fn failed(call: &Token, path: &str) -> impl FnOnce(io::Error) -> MonoError {
+    let (call, path) = (call.clone(), path.to_string());
+    move |error| {
+        MonoError::from(Runtime::IoError {
+            call,
+            message: format!("{}: {}", path, error),
+        })
+    }
+}
+
+// The names of a directory's entries, sorted.
+pub fn list_dir(_evaluator: &mut Evaluator, call: &Token, values: Vec<Value>) -> EvaluatorItem {
+    let path = string_argument(&values[0])?;
+    let mut names = fs::read_dir(path)
+        .and_then(|entries| {
+            entries
+                .map(|entry| Ok(entry?.file_name().to_string_lossy().into_owned()))
+                .collect::<io::Result<Vec<_>>>()
+        })
+        .map_err(failed(call, path))?;
+    names.sort();
+    let names = names.into_iter().map(Value::from).collect();
+    Ok(Value::List(Shared::new(Mutable::new(names))))
+}
+
+// Creates the directory along with any missing parents.
+pub fn mkdir(_evaluator: &mut Evaluator, call: &Token, values: Vec<Value>) -> EvaluatorItem {
+    let path = string_argument(&values[0])?;
+    fs::create_dir_all(path).map_err(failed(call, path))?;
+    Ok(Value::None)
+}
+
+pub fn remove_file(_evaluator: &mut Evaluator, call: &Token, values: Vec<Value>) -> EvaluatorItem {
+    let path = string_argument(&values[0])?;
+    fs::remove_file(path).map_err(failed(call, path))?;
+    Ok(Value::None)
+}
+
+pub fn cwd(_evaluator: &mut Evaluator, call: &Token, _values: Vec<Value>) -> EvaluatorItem {
+    let path = env::current_dir().map_err(failed(call, "."))?;
+    Ok(Value::from(path.to_string_lossy().into_owned()))
+}
+
+// The path functions only look at the strings, not the file system. A joined
+// absolute path replaces the one before it.
+pub fn join_path(values: Vec<Value>) -> Value {
+    match (&values[0], &values[1]) {
+        (Value::String(a), Value::String(b)) => Value::from(
+            Path::new(a.as_ref())
+                .join(b.as_ref())
+                .to_string_lossy()
+                .into_owned(),
+        ),
+        _ => Value::None,
+    }
+}
+
+// The last component of the path, `None` when there isn't one as for `/`.
+pub fn basename(values: Vec<Value>) -> Value {
+    match &values[0] {
+        Value::String(path) => Path::new(path.as_ref())
+            .file_name()
+            .map_or(Value::None, |name| {
+                Value::from(name.to_string_lossy().into_owned())
+            }),
+        _ => Value::None,
+    }
+}
+
+// The path without its last component, `None` when there isn't one.
+pub fn dirname(values: Vec<Value>) -> Value {
+    match &values[0] {
+        Value::String(path) => Path::new(path.as_ref())
+            .parent()
+            .map_or(Value::None, |parent| {
+                Value::from(parent.to_string_lossy().into_owned())
+            }),
+        _ => Value::None,
+    }
+}
@@ -0,0 +1,48 @@
+use crate::evaluator::shared::{Mutable, Sendable, Shared};
+use std::io::{self, Write};
+
+// Where `print` and `println` write. Standard output unless the host gives
+// the evaluator its own writer with `Evaluator::set_output`, e.g. when
+// there's no standard output as in a browser. Threads the evaluator spawns
+// write to the same one.
+pub trait Output: Write + Sendable {}
+
+impl<T: Write + Sendable> Output for T {}
+
+pub(crate) type Writer = Shared<Mutable<Box<dyn Output>>>;
+
+pub(crate) fn write(writer: Option<&Writer>, text: &str) {
+    match writer {
+        Some(writer) => {
+            let mut writer = writer.borrow_mut();
+            let _ = writer.write_all(text.as_bytes());
+            let _ = writer.flush();
+        }
+        None => {
+            let mut stdout = io::stdout();
+            let _ = stdout.write_all(text.as_bytes());
+            let _ = stdout.flush();
+        }
+    }
+}
+
+// What `Evaluator::capture` collects the output into.
+#[derive(Clone, Default)]
+pub(crate) struct Buffer(Shared<Mutable<Vec<u8>>>);
+
+impl Buffer {
+    pub(crate) fn contents(&self) -> String {
+        String::from_utf8_lossy(&self.0.borrow()).into_owned()
+    }
+}
+
+impl Write for Buffer {
+    fn write(&mut self, bytes: &[u8]) -> io::Result<usize> {
+        self.0.borrow_mut().extend_from_slice(bytes);
+        Ok(bytes.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
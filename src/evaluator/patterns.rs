@@ -0,0 +1,105 @@
+use crate::evaluator::shared::{Mutable, Shared};
+use crate::evaluator::value::Value;
+use crate::evaluator::{Evaluator, EvaluatorItem};
+use crate::models::error::{MonoError, Runtime};
+use crate::tokenizer::token::Token;
+use regex::{Captures, Regex};
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+// The most patterns kept compiled at once, the cache is dropped as a whole
+// once it's full.
+const CACHED: usize = 64;
+
+// Scripts usually match the same few patterns against every line of their
+// input, so compiled patterns are kept around rather than rebuilt per call.
+thread_local! {
+    static PATTERNS: RefCell<HashMap<String, Regex>> = RefCell::new(HashMap::new());
+}
+
+// Runs `f` with the compiled pattern and the string, or returns `None` when
+// either argument isn't a string. A pattern that doesn't compile is an error.
+fn with_pattern(
+    call: &Token,
+    values: &[Value],
+    f: impl FnOnce(&Regex, &str) -> Value,
+) -> EvaluatorItem {
+    let (Value::String(pattern), Value::String(string)) = (&values[0], &values[1]) else {
+        return Ok(Value::None);
+    };
+    PATTERNS.with(|patterns| {
+        let mut patterns = patterns.borrow_mut();
+        if !patterns.contains_key(pattern.as_ref()) {
+            let regex = Regex::new(pattern).map_err(|error| {
+                MonoError::from(Runtime::InvalidPattern {
+                    call: call.clone(),
+                    pattern: pattern.to_string(),
+                    reason: error.to_string(),
+                })
+            })?;
+            if patterns.len() >= CACHED {
+                patterns.clear();
+            }
+            patterns.insert(pattern.to_string(), regex);
+        }
+        Ok(f(&patterns[pattern.as_ref()], string))
+    })
+}
+
+fn list(values: Vec<Value>) -> Value {
+    Value::List(Shared::new(Mutable::new(values)))
+}
+
+// Groups that didn't take part in the match are `None`.
+fn groups(captures: &Captures, skip: usize) -> Vec<Value> {
+    captures
+        .iter()
+        .skip(skip)
+        .map(|group| group.map_or(Value::None, |group| Value::String(group.as_str().into())))
+        .collect()
+}
+
+// The first match as a list of its groups, the whole match first.
+pub fn re_match(_evaluator: &mut Evaluator, call: &Token, values: Vec<Value>) -> EvaluatorItem {
+    with_pattern(call, &values, |regex, string| {
+        match regex.captures(string) {
+            Some(captures) => list(groups(&captures, 0)),
+            None => Value::None,
+        }
+    })
+}
+
+// Every match as a string, or as a list of its groups when the pattern has
+// any.
+pub fn re_find_all(_evaluator: &mut Evaluator, call: &Token, values: Vec<Value>) -> EvaluatorItem {
+    with_pattern(call, &values, |regex, string| {
+        let matches = regex
+            .captures_iter(string)
+            .map(|captures| match captures.len() {
+                1 => Value::String(captures[0].into()),
+                _ => list(groups(&captures, 1)),
+            });
+        list(matches.collect())
+    })
+}
+
+// `$1` or `${name}` in the replacement refer to the groups of each match.
+pub fn re_replace(_evaluator: &mut Evaluator, call: &Token, values: Vec<Value>) -> EvaluatorItem {
+    let Value::String(replacement) = &values[2] else {
+        return Ok(Value::None);
+    };
+    with_pattern(call, &values, |regex, string| {
+        Value::String(regex.replace_all(string, replacement.as_ref()).into())
+    })
+}
+
+pub fn re_split(_evaluator: &mut Evaluator, call: &Token, values: Vec<Value>) -> EvaluatorItem {
+    with_pattern(call, &values, |regex, string| {
+        list(
+            regex
+                .split(string)
+                .map(|part| Value::String(part.into()))
+                .collect(),
+        )
+    })
+}
@@ -0,0 +1,65 @@
+use crate::evaluator::builtins::{record, string_argument};
+use crate::evaluator::value::Value;
+use crate::evaluator::{Evaluator, EvaluatorItem};
+use crate::models::error::{MonoError, Runtime};
+use crate::tokenizer::token::Token;
+use std::process::Command;
+
+// Runs a program with a list of string arguments, waiting for it to finish.
+// Returns a `Process` struct with its exit `status` and what it wrote to
+// `stdout` and `stderr`.
+pub fn exec(_evaluator: &mut Evaluator, call: &Token, values: Vec<Value>) -> EvaluatorItem {
+    let program = string_argument(&values[0])?;
+    let arguments = match &values[1] {
+        Value::List(arguments) => arguments
+            .borrow()
+            .iter()
+            .map(|argument| string_argument(argument).map(ToString::to_string))
+            .collect::<Result<Vec<_>, _>>()?,
+        value => {
+            return Err(MonoError::from(Runtime::InvalidValue {
+                expected: Value::from(Vec::<Value>::new()).into(),
+                found: value.into(),
+            }))
+        }
+    };
+    let mut command = Command::new(program);
+    command.args(arguments);
+    run(call, program, command)
+}
+
+// Runs a command line through the system shell, `sh` or `cmd` on Windows.
+pub fn shell(_evaluator: &mut Evaluator, call: &Token, values: Vec<Value>) -> EvaluatorItem {
+    let line = string_argument(&values[0])?;
+    let (shell, flag) = if cfg!(windows) {
+        ("cmd", "/C")
+    } else {
+        ("sh", "-c")
+    };
+    let mut command = Command::new(shell);
+    command.arg(flag).arg(line);
+    run(call, line, command)
+}
+
+fn run(call: &Token, name: &str, mut command: Command) -> EvaluatorItem {
+    let output = command.output().map_err(|error| Runtime::IoError {
+        call: call.clone(),
+        message: format!("{}: {}", name, error),
+    })?;
+    // A process killed by a signal has no exit code.
+    let status = output.status.code().map_or(Value::None, Value::Integer);
+    Ok(record(
+        "Process",
+        vec![
+            ("status", status),
+            (
+                "stdout",
+                Value::from(String::from_utf8_lossy(&output.stdout).into_owned()),
+            ),
+            (
+                "stderr",
+                Value::from(String::from_utf8_lossy(&output.stderr).into_owned()),
+            ),
+        ],
+    ))
+}
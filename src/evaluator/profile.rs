@@ -0,0 +1,57 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::time::Duration;
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Entry {
+    pub count: u64,
+    pub total: Duration,
+}
+
+// Evaluation counts and timings per node kind. Timings are inclusive, the
+// time of a `FuncCall` also contains the time spent evaluating its body.
+#[derive(Debug, Default)]
+pub struct Profile {
+    entries: HashMap<&'static str, Entry>,
+}
+
+impl Profile {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&mut self, kind: &'static str, elapsed: Duration) {
+        let entry = self.entries.entry(kind).or_default();
+        entry.count += 1;
+        entry.total += elapsed;
+    }
+
+    pub fn get(&self, kind: &str) -> Option<Entry> {
+        self.entries.get(kind).copied()
+    }
+}
+
+impl fmt::Display for Profile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut entries: Vec<(&&str, &Entry)> = self.entries.iter().collect();
+        entries.sort_by(|(a_kind, a), (b_kind, b)| b.total.cmp(&a.total).then(a_kind.cmp(b_kind)));
+
+        writeln!(
+            f,
+            "{:<20} {:>10} {:>14} {:>12}",
+            "Node", "Count", "Total", "Average"
+        )?;
+        for (kind, entry) in entries {
+            let average = entry.total / entry.count.max(1) as u32;
+            writeln!(
+                f,
+                "{:<20} {:>10} {:>14} {:>12}",
+                kind,
+                entry.count,
+                format!("{:.3?}", entry.total),
+                format!("{:.1?}", average)
+            )?;
+        }
+        Ok(())
+    }
+}
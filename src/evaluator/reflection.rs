@@ -0,0 +1,60 @@
+use crate::evaluator::value::Value;
+use crate::evaluator::{Evaluator, EvaluatorItem};
+use crate::tokenizer::token::Token;
+
+// The names of the variables in scope, innermost first, then the globals,
+// each in the order they were declared. Builtins aren't listed, they're the
+// members of `builtins`.
+pub fn dir(evaluator: &mut Evaluator, _call: &Token, _values: Vec<Value>) -> EvaluatorItem {
+    let symbol_table = &evaluator.symbol_table;
+    let mut names = Vec::new();
+    for frame in symbol_table.frames() {
+        names.extend(frame.into_iter().map(|(name, _)| name));
+    }
+    names.extend(symbol_table.globals().map(|(name, _)| name));
+
+    let mut unique = Vec::new();
+    for name in names {
+        if !unique.contains(&name) {
+            unique.push(name);
+        }
+    }
+    Ok(Value::from(
+        unique
+            .into_iter()
+            .map(|name| name.as_ref())
+            .collect::<Vec<&str>>(),
+    ))
+}
+
+// The globals as `(name, value)` tuples, in the order they were declared.
+pub fn globals(evaluator: &mut Evaluator, _call: &Token, _values: Vec<Value>) -> EvaluatorItem {
+    Ok(Value::from(
+        evaluator
+            .symbol_table
+            .globals()
+            .map(|(name, value)| (name.as_ref(), value.clone()))
+            .collect::<Vec<_>>(),
+    ))
+}
+
+// The parameters a function, builtin or struct is called with, a variadic
+// one written as `*name`. `None` for anything else.
+fn parameters(value: &Value) -> Option<Vec<String>> {
+    match value {
+        Value::Function { arguments, .. }
+        | Value::StructType {
+            fields: arguments, ..
+        } => Some(arguments.iter().map(ToString::to_string).collect()),
+        Value::BuiltInFunction { arguments, .. } => Some(arguments.to_vec()),
+        _ => None,
+    }
+}
+
+pub fn params(values: Vec<Value>) -> Value {
+    Value::from(parameters(&values[0]))
+}
+
+pub fn arity(values: Vec<Value>) -> Value {
+    Value::from(parameters(&values[0]).map(|parameters| parameters.len() as i32))
+}
@@ -0,0 +1,117 @@
+use crate::evaluator::value::Value;
+use serde::de::{self, Deserialize, Deserializer, MapAccess, SeqAccess, Visitor};
+use serde::ser::{self, Serialize, SerializeMap, SerializeSeq, Serializer};
+use std::fmt;
+
+// Data values serialize to their natural form: lists and tuples as sequences,
+// structs as maps of their fields, enum variants as their variant name, bytes
+// as bytes and `None` as unit. Functions, types and namespaces have no data
+// form.
+impl Serialize for Value {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Value::Integer(value) => serializer.serialize_i32(*value),
+            Value::Float(value) => serializer.serialize_f32(*value),
+            Value::Boolean(value) => serializer.serialize_bool(*value),
+            Value::String(value) => serializer.serialize_str(value),
+            Value::Bytes(value) => serializer.serialize_bytes(value),
+            Value::Character(value) => serializer.serialize_char(*value),
+            Value::List(values) => serialize_seq(&values.borrow(), serializer),
+            Value::Tuple(values) => serialize_seq(values, serializer),
+            Value::Struct { fields, .. } => {
+                let fields = fields.borrow();
+                let mut map = serializer.serialize_map(Some(fields.len()))?;
+                for (name, value) in fields.iter() {
+                    map.serialize_entry(name.as_ref(), value)?;
+                }
+                map.end()
+            }
+            Value::Variant { variant, .. } => serializer.serialize_str(variant),
+            Value::None => serializer.serialize_unit(),
+            value => Err(ser::Error::custom(format!(
+                "a value of type {} can't be serialized",
+                value.to_type()
+            ))),
+        }
+    }
+}
+
+fn serialize_seq<S: Serializer>(values: &[Value], serializer: S) -> Result<S::Ok, S::Error> {
+    let mut seq = serializer.serialize_seq(Some(values.len()))?;
+    for value in values {
+        seq.serialize_element(value)?;
+    }
+    seq.end()
+}
+
+// Sequences deserialize to lists. Mono has no map type, so maps are rejected.
+impl<'de> Deserialize<'de> for Value {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(ValueVisitor)
+    }
+}
+
+struct ValueVisitor;
+
+impl<'de> Visitor<'de> for ValueVisitor {
+    type Value = Value;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "a Mono value")
+    }
+
+    fn visit_bool<E: de::Error>(self, value: bool) -> Result<Value, E> {
+        Ok(Value::Boolean(value))
+    }
+
+    fn visit_i64<E: de::Error>(self, value: i64) -> Result<Value, E> {
+        Value::try_from(value)
+            .map_err(|_| E::invalid_value(de::Unexpected::Signed(value), &"a 32 bit integer"))
+    }
+
+    fn visit_u64<E: de::Error>(self, value: u64) -> Result<Value, E> {
+        i32::try_from(value)
+            .map(Value::Integer)
+            .map_err(|_| E::invalid_value(de::Unexpected::Unsigned(value), &"a 32 bit integer"))
+    }
+
+    fn visit_f64<E: de::Error>(self, value: f64) -> Result<Value, E> {
+        Ok(Value::Float(value as f32))
+    }
+
+    fn visit_char<E: de::Error>(self, value: char) -> Result<Value, E> {
+        Ok(Value::Character(value))
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<Value, E> {
+        Ok(Value::from(value))
+    }
+
+    fn visit_bytes<E: de::Error>(self, value: &[u8]) -> Result<Value, E> {
+        Ok(Value::Bytes(value.into()))
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<Value, E> {
+        Ok(Value::None)
+    }
+
+    fn visit_none<E: de::Error>(self) -> Result<Value, E> {
+        Ok(Value::None)
+    }
+
+    fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<Value, D::Error> {
+        Value::deserialize(deserializer)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Value, A::Error> {
+        let mut values: Vec<Value> = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+        while let Some(value) = seq.next_element()? {
+            values.push(value);
+        }
+        Ok(Value::from(values))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, _map: A) -> Result<Value, A::Error> {
+        Err(de::Error::invalid_type(de::Unexpected::Map, &self))
+    }
+}
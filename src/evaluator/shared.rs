@@ -0,0 +1,69 @@
+// The pointers values share their data through. By default they're the
+// single threaded `Rc` and `RefCell`. The `sync` feature swaps in `Arc` and
+// an `RwLock`, which makes `Value` and `Evaluator` `Send` so scripts can run
+// on worker threads, at some cost in speed.
+
+#[cfg(not(feature = "sync"))]
+pub use std::cell::RefCell as Mutable;
+#[cfg(not(feature = "sync"))]
+pub use std::rc::Rc as Shared;
+
+#[cfg(feature = "sync")]
+pub use std::sync::Arc as Shared;
+
+// Host types stored in values must be thread safe when values are.
+#[cfg(not(feature = "sync"))]
+pub trait Sendable {}
+#[cfg(not(feature = "sync"))]
+impl<T: ?Sized> Sendable for T {}
+
+#[cfg(feature = "sync")]
+pub trait Sendable: Send + Sync {}
+#[cfg(feature = "sync")]
+impl<T: ?Sized + Send + Sync> Sendable for T {}
+
+#[cfg(feature = "sync")]
+pub use self::sync::Mutable;
+
+#[cfg(feature = "sync")]
+mod sync {
+    use std::fmt;
+    use std::sync::{PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+    // Mirrors the part of `RefCell`'s API the evaluator uses. A panic while
+    // holding the lock doesn't leave the value unusable.
+    #[derive(Default)]
+    pub struct Mutable<T>(RwLock<T>);
+
+    impl<T> Mutable<T> {
+        pub fn new(value: T) -> Self {
+            Self(RwLock::new(value))
+        }
+
+        pub fn borrow(&self) -> RwLockReadGuard<'_, T> {
+            self.0.read().unwrap_or_else(PoisonError::into_inner)
+        }
+
+        pub fn borrow_mut(&self) -> RwLockWriteGuard<'_, T> {
+            self.0.write().unwrap_or_else(PoisonError::into_inner)
+        }
+    }
+
+    impl<T: PartialEq> PartialEq for Mutable<T> {
+        fn eq(&self, other: &Self) -> bool {
+            *self.borrow() == *other.borrow()
+        }
+    }
+
+    impl<T: fmt::Debug> fmt::Debug for Mutable<T> {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            self.borrow().fmt(f)
+        }
+    }
+
+    const _: fn() = || {
+        fn assert_send<T: Send>() {}
+        assert_send::<crate::evaluator::Evaluator>();
+        assert_send::<crate::evaluator::value::Value>();
+    };
+}
@@ -0,0 +1,109 @@
+use crate::evaluator::builtins::elements;
+use crate::evaluator::shared::{Mutable, Shared};
+use crate::evaluator::value::Value;
+use crate::evaluator::{Evaluator, EvaluatorItem};
+use crate::models::error::{MonoError, Runtime, Snapshot};
+use crate::tokenizer::token::Token;
+use std::cmp::Ordering;
+
+// A stable merge sort that stops at the first failed comparison. The
+// standard library's sorts may panic when a comparison isn't a total order,
+// which a script's comparator doesn't have to be.
+fn merge_sort<T>(
+    mut values: Vec<T>,
+    compare: &mut impl FnMut(&T, &T) -> Result<Ordering, MonoError>,
+) -> Result<Vec<T>, MonoError> {
+    if values.len() <= 1 {
+        return Ok(values);
+    }
+    let right = values.split_off(values.len() / 2);
+    let left = merge_sort(values, compare)?;
+    let right = merge_sort(right, compare)?;
+
+    let mut merged = Vec::with_capacity(left.len() + right.len());
+    let mut left = left.into_iter().peekable();
+    let mut right = right.into_iter().peekable();
+    while let (Some(a), Some(b)) = (left.peek(), right.peek()) {
+        // Taking from the left on ties keeps equal values in their order.
+        if compare(a, b)? == Ordering::Greater {
+            merged.extend(right.next());
+        } else {
+            merged.extend(left.next());
+        }
+    }
+    merged.extend(left);
+    merged.extend(right);
+    Ok(merged)
+}
+
+pub(crate) fn compare(call: &Token, left: &Value, right: &Value) -> Result<Ordering, MonoError> {
+    left.compare(right).ok_or_else(|| {
+        MonoError::from(Runtime::Incomparable {
+            left: Snapshot::from(left),
+            right: Snapshot::from(right),
+            call: call.clone(),
+        })
+    })
+}
+
+pub(crate) fn iterable(call: &Token, value: &Value) -> Result<Vec<Value>, MonoError> {
+    elements(value).ok_or_else(|| {
+        MonoError::from(Runtime::NonIterable {
+            value: Snapshot::from(value),
+            operator: call.clone(),
+        })
+    })
+}
+
+fn list(values: Vec<Value>) -> Value {
+    Value::List(Shared::new(Mutable::new(values)))
+}
+
+// `sort(xs)` and `sort(xs, key)`, a new sorted list of the elements. With a
+// key, elements are ordered by what it returns for them, calling it once
+// per element.
+pub fn sort(evaluator: &mut Evaluator, call: &Token, values: Vec<Value>) -> EvaluatorItem {
+    let elements = iterable(call, &values[0])?;
+    let Some(key) = values.get(1) else {
+        return Ok(list(merge_sort(elements, &mut |a, b| compare(call, a, b))?));
+    };
+    if values.len() > 2 {
+        return Runtime::IncorrectParameters {
+            name: "sort".into(),
+            call: call.clone(),
+            expected: vec!["values".into(), "key".into()],
+            found: values.iter().map(Snapshot::from).collect(),
+        }
+        .into();
+    }
+
+    let mut keyed = Vec::with_capacity(elements.len());
+    for element in elements {
+        keyed.push((
+            evaluator.call_value(call, key.clone(), vec![element.clone()])?,
+            element,
+        ));
+    }
+    let sorted = merge_sort(keyed, &mut |(a, _), (b, _)| compare(call, a, b))?;
+    Ok(list(
+        sorted.into_iter().map(|(_, element)| element).collect(),
+    ))
+}
+
+// `sort_by(xs, cmp)`, where `cmp(a, b)` returns a negative Integer when `a`
+// goes before `b`, a positive one when it goes after, and zero otherwise.
+pub fn sort_by(evaluator: &mut Evaluator, call: &Token, values: Vec<Value>) -> EvaluatorItem {
+    let elements = iterable(call, &values[0])?;
+    let comparator = &values[1];
+    let sorted = merge_sort(elements, &mut |a, b| {
+        let ordering = evaluator.call_value(call, comparator.clone(), vec![a.clone(), b.clone()]);
+        match ordering? {
+            Value::Integer(ordering) => Ok(ordering.cmp(&0)),
+            found => Err(MonoError::from(Runtime::InvalidComparison {
+                found: Snapshot::from(&found),
+                call: call.clone(),
+            })),
+        }
+    })?;
+    Ok(list(sorted))
+}
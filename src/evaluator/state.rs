@@ -0,0 +1,1248 @@
+use crate::evaluator::external::External;
+use crate::evaluator::shared::{Mutable, Shared};
+use crate::evaluator::symbol_table::SymbolTable;
+use crate::evaluator::value::{Methods, Value};
+use crate::extensions::datetime::DateTime;
+use crate::extensions::string_builder::StringBuilder;
+use crate::models::position::Position;
+use crate::parser::node::{Argument, Arm, CasePattern, Node, Pattern, Slot};
+use crate::tokenizer::symbol::Symbol;
+use crate::tokenizer::token::{Token, TokenKind};
+use std::any::Any;
+use std::collections::HashMap;
+use std::io::{self, ErrorKind};
+
+const MAGIC: &[u8; 4] = b"MONO";
+const VERSION: u8 = 5;
+
+// Token kinds without a payload, encoded by their index in this table after
+// the seven kinds that carry one.
+const KINDS: [TokenKind; 50] = [
+    TokenKind::None,
+    TokenKind::Not,
+    TokenKind::And,
+    TokenKind::Or,
+    TokenKind::Let,
+    TokenKind::If,
+    TokenKind::Else,
+    TokenKind::While,
+    TokenKind::Do,
+    TokenKind::For,
+    TokenKind::In,
+    TokenKind::Struct,
+    TokenKind::Impl,
+    TokenKind::Enum,
+    TokenKind::Return,
+    TokenKind::Del,
+    TokenKind::Add,
+    TokenKind::Sub,
+    TokenKind::Mul,
+    TokenKind::Div,
+    TokenKind::Mod,
+    TokenKind::Pow,
+    TokenKind::Assignment,
+    TokenKind::Equals,
+    TokenKind::NotEquals,
+    TokenKind::Greater,
+    TokenKind::GreaterEq,
+    TokenKind::LessThan,
+    TokenKind::LessThanEq,
+    TokenKind::RightParen,
+    TokenKind::LeftParen,
+    TokenKind::RightCurly,
+    TokenKind::LeftCurly,
+    TokenKind::RightBracket,
+    TokenKind::LeftBracket,
+    TokenKind::Arrow,
+    TokenKind::DoubleArrow,
+    TokenKind::Comma,
+    TokenKind::Dot,
+    TokenKind::NewLine,
+    TokenKind::Coalesce,
+    TokenKind::Question,
+    TokenKind::Match,
+    TokenKind::Case,
+    TokenKind::Pipe,
+    TokenKind::Colon,
+    TokenKind::Elif,
+    TokenKind::Def,
+    TokenKind::Global,
+    TokenKind::Nonlocal,
+];
+
+// Encodes the globals of a session: every user defined variable, function,
+// struct and enum, with the syntax trees functions run. Lists, struct fields
+// and methods are written once and referenced after, so values sharing them
+// still do once restored. Builtins are written by name and looked up again
+// when loading. Dates and string builders are written by value, other
+// externals belong to the host and can't be saved.
+pub fn save(symbol_table: &SymbolTable) -> io::Result<Vec<u8>> {
+    let mut writer = Writer {
+        bytes: MAGIC.to_vec(),
+        shared: HashMap::new(),
+    };
+    writer.u8(VERSION);
+    let globals: Vec<_> = symbol_table.globals().collect();
+    writer.usize(globals.len());
+    for (name, value) in globals {
+        writer.str(name);
+        writer.value(value)?;
+    }
+    Ok(writer.bytes)
+}
+
+// Restores the globals `save` encoded, replacing those of the same name.
+// Nothing is restored if the state is invalid.
+pub fn load(state: &[u8], symbol_table: &mut SymbolTable) -> io::Result<()> {
+    let mut reader = Reader {
+        bytes: state,
+        shared: Vec::new(),
+        symbol_table,
+    };
+    if reader.take(MAGIC.len())? != MAGIC || reader.u8()? != VERSION {
+        return Err(invalid("not a mono state, or one of another version"));
+    }
+    let mut globals = Vec::new();
+    for _ in 0..reader.usize()? {
+        globals.push((reader.symbol()?, reader.value()?));
+    }
+    if !reader.bytes.is_empty() {
+        return Err(invalid("trailing bytes"));
+    }
+    for (name, value) in globals {
+        symbol_table.insert(name, value);
+    }
+    Ok(())
+}
+
+// Encodes a parsed program, so it can be run again without parsing it.
+pub fn save_program(program: &Node) -> io::Result<Vec<u8>> {
+    let mut writer = Writer {
+        bytes: MAGIC.to_vec(),
+        shared: HashMap::new(),
+    };
+    writer.u8(VERSION);
+    writer.node(program)?;
+    Ok(writer.bytes)
+}
+
+pub fn load_program(bytes: &[u8]) -> io::Result<Box<Node>> {
+    let symbol_table = SymbolTable::new();
+    let mut reader = Reader {
+        bytes,
+        shared: Vec::new(),
+        symbol_table: &symbol_table,
+    };
+    if reader.take(MAGIC.len())? != MAGIC || reader.u8()? != VERSION {
+        return Err(invalid("not a mono program, or one of another version"));
+    }
+    let program = reader.node()?;
+    if !reader.bytes.is_empty() {
+        return Err(invalid("trailing bytes"));
+    }
+    Ok(program)
+}
+
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(
+        ErrorKind::InvalidData,
+        format!("Invalid state: {}.", message),
+    )
+}
+
+struct Writer {
+    bytes: Vec<u8>,
+    // The address of every shared value written so far, by its index.
+    shared: HashMap<usize, usize>,
+}
+
+impl Writer {
+    fn u8(&mut self, value: u8) {
+        self.bytes.push(value);
+    }
+
+    fn usize(&mut self, value: usize) {
+        self.bytes.extend((value as u64).to_le_bytes());
+    }
+
+    fn bool(&mut self, value: bool) {
+        self.u8(value as u8);
+    }
+
+    fn raw(&mut self, value: &[u8]) {
+        self.usize(value.len());
+        self.bytes.extend(value);
+    }
+
+    fn str(&mut self, value: &str) {
+        self.raw(value.as_bytes());
+    }
+
+    fn symbols(&mut self, symbols: &[Symbol]) {
+        self.usize(symbols.len());
+        for symbol in symbols {
+            self.str(symbol);
+        }
+    }
+
+    // Writes a reference to a shared value written before and returns false,
+    // or marks it as new and returns true, its contents following.
+    fn shared<T: ?Sized>(&mut self, pointer: &Shared<T>) -> bool {
+        let address = Shared::as_ptr(pointer) as *const () as usize;
+        match self.shared.get(&address) {
+            Some(&index) => {
+                self.u8(1);
+                self.usize(index);
+                false
+            }
+            None => {
+                self.shared.insert(address, self.shared.len());
+                self.u8(0);
+                true
+            }
+        }
+    }
+
+    fn position(&mut self, position: &Position) {
+        self.usize(position.row());
+        self.usize(position.column());
+    }
+
+    fn token(&mut self, token: &Token) -> io::Result<()> {
+        self.position(&token.start);
+        self.position(&token.end);
+        match &token.kind {
+            TokenKind::Identifier(name) => {
+                self.u8(0);
+                self.str(name);
+            }
+            TokenKind::Character(c) => {
+                self.u8(1);
+                self.bytes.extend((*c as u32).to_le_bytes());
+            }
+            TokenKind::String(string) => {
+                self.u8(2);
+                self.str(string);
+            }
+            TokenKind::Integer(integer) => {
+                self.u8(3);
+                self.bytes.extend(integer.to_le_bytes());
+            }
+            TokenKind::Float(float) => {
+                self.u8(4);
+                self.bytes.extend(float.to_le_bytes());
+            }
+            TokenKind::Boolean(boolean) => {
+                self.u8(5);
+                self.bool(*boolean);
+            }
+            TokenKind::Bytes(bytes) => {
+                self.u8(6);
+                self.raw(bytes);
+            }
+            kind => match KINDS.iter().position(|known| known == kind) {
+                Some(index) => self.u8(7 + index as u8),
+                None => return Err(invalid(&format!("token kind {:?} can't be saved", kind))),
+            },
+        }
+        Ok(())
+    }
+
+    fn tokens(&mut self, tokens: &[Token]) -> io::Result<()> {
+        self.usize(tokens.len());
+        for token in tokens {
+            self.token(token)?;
+        }
+        Ok(())
+    }
+
+    fn slot(&mut self, slot: &Option<Slot>) {
+        match slot {
+            Some(slot) => {
+                self.u8(1);
+                self.usize(slot.depth);
+                self.usize(slot.index);
+            }
+            None => self.u8(0),
+        }
+    }
+
+    fn arguments(&mut self, arguments: &[Argument]) -> io::Result<()> {
+        self.usize(arguments.len());
+        for argument in arguments {
+            self.token(&argument.identifier)?;
+            self.optional_node(&argument.default)?;
+            self.bool(argument.is_variadic);
+        }
+        Ok(())
+    }
+
+    fn pattern(&mut self, pattern: &Pattern) -> io::Result<()> {
+        match pattern {
+            Pattern::Identifier(identifier) => {
+                self.u8(0);
+                self.token(identifier)?;
+            }
+            Pattern::Rest(identifier) => {
+                self.u8(1);
+                self.token(identifier)?;
+            }
+            Pattern::Tuple(patterns) | Pattern::List(patterns) => {
+                self.u8(match pattern {
+                    Pattern::Tuple(_) => 2,
+                    _ => 3,
+                });
+                self.usize(patterns.len());
+                for pattern in patterns {
+                    self.pattern(pattern)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn case_pattern(&mut self, pattern: &CasePattern) -> io::Result<()> {
+        match pattern {
+            CasePattern::Wildcard => self.u8(0),
+            CasePattern::Binding(identifier) => {
+                self.u8(1);
+                self.token(identifier)?;
+            }
+            CasePattern::Literal(literal) => {
+                self.u8(2);
+                self.token(literal)?;
+            }
+            CasePattern::Rest(identifier) => {
+                self.u8(3);
+                self.token(identifier)?;
+            }
+            CasePattern::Variant {
+                enumeration,
+                variant,
+            } => {
+                self.u8(4);
+                self.token(enumeration)?;
+                self.token(variant)?;
+            }
+            CasePattern::List(patterns)
+            | CasePattern::Tuple(patterns)
+            | CasePattern::Or(patterns) => {
+                self.u8(match pattern {
+                    CasePattern::List(_) => 5,
+                    CasePattern::Tuple(_) => 6,
+                    _ => 8,
+                });
+                self.usize(patterns.len());
+                for pattern in patterns {
+                    self.case_pattern(pattern)?;
+                }
+            }
+            CasePattern::Struct { identifier, fields } => {
+                self.u8(7);
+                match identifier {
+                    Some(identifier) => {
+                        self.u8(1);
+                        self.token(identifier)?;
+                    }
+                    None => self.u8(0),
+                }
+                self.usize(fields.len());
+                for (field, pattern) in fields {
+                    self.token(field)?;
+                    self.case_pattern(pattern)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn nodes(&mut self, nodes: &[Box<Node>]) -> io::Result<()> {
+        self.usize(nodes.len());
+        for node in nodes {
+            self.node(node)?;
+        }
+        Ok(())
+    }
+
+    fn optional_node(&mut self, node: &Option<Box<Node>>) -> io::Result<()> {
+        match node {
+            Some(node) => {
+                self.u8(1);
+                self.node(node)?;
+            }
+            None => self.u8(0),
+        }
+        Ok(())
+    }
+
+    fn keywords(&mut self, keywords: &[(Token, Box<Node>)]) -> io::Result<()> {
+        self.usize(keywords.len());
+        for (keyword, value) in keywords {
+            self.token(keyword)?;
+            self.node(value)?;
+        }
+        Ok(())
+    }
+
+    fn node(&mut self, node: &Node) -> io::Result<()> {
+        match node {
+            Node::Atom { value } => {
+                self.u8(0);
+                self.token(value)?;
+            }
+            Node::List { values } => {
+                self.u8(1);
+                self.nodes(values)?;
+            }
+            Node::Tuple { values } => {
+                self.u8(2);
+                self.nodes(values)?;
+            }
+            Node::Spread { operator, value } => {
+                self.u8(3);
+                self.token(operator)?;
+                self.node(value)?;
+            }
+            Node::BinaryOp {
+                left,
+                operator,
+                right,
+            } => {
+                self.u8(4);
+                self.node(left)?;
+                self.token(operator)?;
+                self.node(right)?;
+            }
+            Node::UnaryOp { operator, value } => {
+                self.u8(5);
+                self.token(operator)?;
+                self.node(value)?;
+            }
+            Node::FuncDeclearion {
+                identifier,
+                arguments,
+                body,
+                locals,
+            } => {
+                self.u8(6);
+                self.token(identifier)?;
+                self.arguments(arguments)?;
+                self.node(body)?;
+                self.symbols(locals);
+            }
+            Node::FuncCall {
+                identifier,
+                slot,
+                parameters,
+                keywords,
+            } => {
+                self.u8(7);
+                self.token(identifier)?;
+                self.slot(slot);
+                self.nodes(parameters)?;
+                self.keywords(keywords)?;
+            }
+            Node::Assignment {
+                identifier,
+                slot,
+                value,
+                is_declaration,
+            } => {
+                self.u8(8);
+                self.token(identifier)?;
+                self.slot(slot);
+                self.node(value)?;
+                self.bool(*is_declaration);
+            }
+            Node::Destructuring {
+                pattern,
+                operator,
+                value,
+                is_declaration,
+            } => {
+                self.u8(9);
+                self.pattern(pattern)?;
+                self.token(operator)?;
+                self.node(value)?;
+                self.bool(*is_declaration);
+            }
+            Node::ListAssignment {
+                identifier,
+                slot,
+                index,
+                value,
+            } => {
+                self.u8(10);
+                self.token(identifier)?;
+                self.slot(slot);
+                self.node(index)?;
+                self.node(value)?;
+            }
+            Node::Access { identifier, slot } => {
+                self.u8(11);
+                self.token(identifier)?;
+                self.slot(slot);
+            }
+            Node::Member { object, member } => {
+                self.u8(12);
+                self.node(object)?;
+                self.token(member)?;
+            }
+            Node::MemberAssignment {
+                object,
+                member,
+                value,
+            } => {
+                self.u8(13);
+                self.node(object)?;
+                self.token(member)?;
+                self.node(value)?;
+            }
+            Node::StructDeclaration { identifier, fields } => {
+                self.u8(14);
+                self.token(identifier)?;
+                self.arguments(fields)?;
+            }
+            Node::Impl {
+                identifier,
+                methods,
+            } => {
+                self.u8(15);
+                self.token(identifier)?;
+                self.nodes(methods)?;
+            }
+            Node::EnumDeclaration {
+                identifier,
+                variants,
+            } => {
+                self.u8(16);
+                self.token(identifier)?;
+                self.tokens(variants)?;
+            }
+            Node::MethodCall {
+                object,
+                method,
+                parameters,
+                keywords,
+            } => {
+                self.u8(17);
+                self.node(object)?;
+                self.token(method)?;
+                self.nodes(parameters)?;
+                self.keywords(keywords)?;
+            }
+            Node::Index {
+                identifier,
+                slot,
+                index,
+            } => {
+                self.u8(18);
+                self.token(identifier)?;
+                self.slot(slot);
+                self.node(index)?;
+            }
+            Node::If {
+                condition,
+                block,
+                else_block,
+            } => {
+                self.u8(19);
+                self.node(condition)?;
+                self.node(block)?;
+                self.optional_node(else_block)?;
+            }
+            Node::While { condition, block } => {
+                self.u8(20);
+                self.node(condition)?;
+                self.node(block)?;
+            }
+            Node::Comprehension {
+                value,
+                identifier,
+                operator,
+                iterable,
+                condition,
+            } => {
+                self.u8(21);
+                self.node(value)?;
+                self.token(identifier)?;
+                self.token(operator)?;
+                self.node(iterable)?;
+                self.optional_node(condition)?;
+            }
+            Node::For {
+                identifier,
+                slot,
+                operator,
+                iterable,
+                block,
+            } => {
+                self.u8(22);
+                self.token(identifier)?;
+                self.slot(slot);
+                self.token(operator)?;
+                self.node(iterable)?;
+                self.node(block)?;
+            }
+            Node::DoWhile { block, condition } => {
+                self.u8(23);
+                self.node(block)?;
+                self.node(condition)?;
+            }
+            Node::Return { value } => {
+                self.u8(24);
+                self.node(value)?;
+            }
+            Node::Delete { identifier, slot } => {
+                self.u8(25);
+                self.token(identifier)?;
+                self.slot(slot);
+            }
+            Node::Global {
+                identifiers,
+                is_nonlocal,
+            } => {
+                self.u8(29);
+                self.tokens(identifiers)?;
+                self.bool(*is_nonlocal);
+            }
+            Node::Optional { value } => {
+                self.u8(27);
+                self.node(value)?;
+            }
+            Node::Match { value, arms } => {
+                self.u8(28);
+                self.node(value)?;
+                self.usize(arms.len());
+                for arm in arms {
+                    self.case_pattern(&arm.pattern)?;
+                    self.optional_node(&arm.guard)?;
+                    self.node(&arm.block)?;
+                }
+            }
+            Node::Program { statements } => {
+                self.u8(26);
+                self.nodes(statements)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn values(&mut self, values: &[Value]) -> io::Result<()> {
+        self.usize(values.len());
+        for value in values {
+            self.value(value)?;
+        }
+        Ok(())
+    }
+
+    fn methods(&mut self, methods: &Methods) -> io::Result<()> {
+        if self.shared(methods) {
+            let methods = methods.borrow();
+            let mut methods: Vec<_> = methods.iter().collect();
+            methods.sort_by_key(|(name, _)| *name);
+            self.usize(methods.len());
+            for (name, method) in methods {
+                self.str(name);
+                self.value(method)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn value(&mut self, value: &Value) -> io::Result<()> {
+        match value {
+            Value::Integer(integer) => {
+                self.u8(0);
+                self.bytes.extend(integer.to_le_bytes());
+            }
+            Value::Float(float) => {
+                self.u8(1);
+                self.bytes.extend(float.to_le_bytes());
+            }
+            Value::Boolean(boolean) => {
+                self.u8(2);
+                self.bool(*boolean);
+            }
+            Value::String(string) => {
+                self.u8(3);
+                self.str(string);
+            }
+            Value::Character(c) => {
+                self.u8(4);
+                self.bytes.extend((*c as u32).to_le_bytes());
+            }
+            Value::List(values) => {
+                self.u8(5);
+                if self.shared(values) {
+                    self.values(&values.borrow())?;
+                }
+            }
+            Value::Tuple(values) => {
+                self.u8(6);
+                self.values(values)?;
+            }
+            Value::Function {
+                name,
+                position,
+                arguments,
+                body,
+                locals,
+            } => {
+                self.u8(7);
+                self.str(name);
+                self.position(position);
+                self.arguments(arguments)?;
+                self.node(body)?;
+                self.symbols(locals);
+            }
+            Value::BuiltInFunction { name, .. } => {
+                self.u8(8);
+                self.str(name);
+            }
+            Value::Struct {
+                name,
+                fields,
+                methods,
+            } => {
+                self.u8(9);
+                self.str(name);
+                if self.shared(fields) {
+                    let fields = fields.borrow();
+                    self.usize(fields.len());
+                    for (field, value) in fields.iter() {
+                        self.str(field);
+                        self.value(value)?;
+                    }
+                }
+                self.methods(methods)?;
+            }
+            Value::StructType {
+                name,
+                fields,
+                methods,
+            } => {
+                self.u8(10);
+                self.str(name);
+                self.arguments(fields)?;
+                self.methods(methods)?;
+            }
+            Value::EnumType { name, variants } => {
+                self.u8(11);
+                self.str(name);
+                self.symbols(variants);
+            }
+            Value::Variant { name, variant } => {
+                self.u8(12);
+                self.str(name);
+                self.str(variant);
+            }
+            Value::Namespace { name, .. } => {
+                self.u8(13);
+                self.str(name);
+            }
+            Value::External(external) => self.external(external)?,
+            Value::None => self.u8(14),
+            Value::Bytes(bytes) => {
+                self.u8(15);
+                self.raw(bytes);
+            }
+        }
+        Ok(())
+    }
+
+    fn external(&mut self, external: &Shared<dyn External>) -> io::Result<()> {
+        if let Some(date) = external.downcast_ref::<DateTime>() {
+            self.u8(16);
+            self.bytes.extend(date.timestamp().to_le_bytes());
+        } else if let Some(builder) = external.downcast_ref::<StringBuilder>() {
+            self.u8(17);
+            if self.shared(external) {
+                self.str(&builder.contents());
+            }
+        } else {
+            return Err(invalid(&format!(
+                "a {} can't be saved",
+                external.type_name()
+            )));
+        }
+        Ok(())
+    }
+}
+
+struct Reader<'s> {
+    bytes: &'s [u8],
+    // Shared values by the index they were written with.
+    shared: Vec<Box<dyn Any>>,
+    symbol_table: &'s SymbolTable,
+}
+
+impl Reader<'_> {
+    fn take(&mut self, length: usize) -> io::Result<&[u8]> {
+        if length > self.bytes.len() {
+            return Err(invalid("unexpected end"));
+        }
+        let (taken, rest) = self.bytes.split_at(length);
+        self.bytes = rest;
+        Ok(taken)
+    }
+
+    fn array<const N: usize>(&mut self) -> io::Result<[u8; N]> {
+        Ok(self.take(N)?.try_into().expect("took N bytes"))
+    }
+
+    fn u8(&mut self) -> io::Result<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn usize(&mut self) -> io::Result<usize> {
+        usize::try_from(u64::from_le_bytes(self.array()?)).map_err(|_| invalid("length too large"))
+    }
+
+    // A count of items that each take at least a byte, so a corrupt count
+    // fails instead of allocating.
+    fn count(&mut self) -> io::Result<usize> {
+        match self.usize()? {
+            count if count > self.bytes.len() => Err(invalid("unexpected end")),
+            count => Ok(count),
+        }
+    }
+
+    fn bool(&mut self) -> io::Result<bool> {
+        match self.u8()? {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(invalid("invalid boolean")),
+        }
+    }
+
+    fn char(&mut self) -> io::Result<char> {
+        char::from_u32(u32::from_le_bytes(self.array()?))
+            .ok_or_else(|| invalid("invalid character"))
+    }
+
+    fn raw(&mut self) -> io::Result<Vec<u8>> {
+        let length = self.usize()?;
+        Ok(self.take(length)?.to_vec())
+    }
+
+    fn string(&mut self) -> io::Result<String> {
+        String::from_utf8(self.raw()?).map_err(|_| invalid("invalid UTF-8"))
+    }
+
+    fn symbol(&mut self) -> io::Result<Symbol> {
+        Ok(self.string()?.into())
+    }
+
+    fn symbols(&mut self) -> io::Result<Vec<Symbol>> {
+        (0..self.count()?).map(|_| self.symbol()).collect()
+    }
+
+    // The counterpart of `Writer::shared`: `Ok(None)` when a new shared value
+    // follows, which the caller creates and registers before reading it.
+    fn shared<T: Clone + 'static>(&mut self) -> io::Result<Option<T>> {
+        match self.u8()? {
+            0 => Ok(None),
+            1 => {
+                let index = self.usize()?;
+                let shared = self
+                    .shared
+                    .get(index)
+                    .and_then(|shared| shared.downcast_ref::<T>())
+                    .ok_or_else(|| invalid("invalid reference"))?;
+                Ok(Some(shared.clone()))
+            }
+            _ => Err(invalid("invalid reference")),
+        }
+    }
+
+    fn register<T: Clone + 'static>(&mut self, shared: &T) {
+        self.shared.push(Box::new(shared.clone()));
+    }
+
+    fn position(&mut self) -> io::Result<Position> {
+        Ok(Position::new(self.usize()?, self.usize()?))
+    }
+
+    fn token(&mut self) -> io::Result<Token> {
+        let start = self.position()?;
+        let end = self.position()?;
+        let kind = match self.u8()? {
+            0 => TokenKind::Identifier(self.symbol()?),
+            1 => TokenKind::Character(self.char()?),
+            2 => TokenKind::String(self.string()?),
+            3 => TokenKind::Integer(i32::from_le_bytes(self.array()?)),
+            4 => TokenKind::Float(f32::from_le_bytes(self.array()?)),
+            5 => TokenKind::Boolean(self.bool()?),
+            6 => TokenKind::Bytes(self.raw()?),
+            tag => KINDS
+                .get(tag as usize - 7)
+                .cloned()
+                .ok_or_else(|| invalid("invalid token"))?,
+        };
+        Ok(Token::new(start, end, kind))
+    }
+
+    fn identifier(&mut self) -> io::Result<Token> {
+        let token = self.token()?;
+        match token.kind {
+            TokenKind::Identifier(_) => Ok(token),
+            _ => Err(invalid("expected an identifier")),
+        }
+    }
+
+    fn tokens(&mut self) -> io::Result<Vec<Token>> {
+        (0..self.count()?).map(|_| self.identifier()).collect()
+    }
+
+    fn slot(&mut self) -> io::Result<Option<Slot>> {
+        Ok(match self.bool()? {
+            true => Some(Slot {
+                depth: self.usize()?,
+                index: self.usize()?,
+            }),
+            false => None,
+        })
+    }
+
+    fn arguments(&mut self) -> io::Result<Vec<Argument>> {
+        (0..self.count()?)
+            .map(|_| {
+                Ok(Argument {
+                    identifier: self.identifier()?,
+                    default: self.optional_node()?,
+                    is_variadic: self.bool()?,
+                })
+            })
+            .collect()
+    }
+
+    fn pattern(&mut self) -> io::Result<Pattern> {
+        Ok(match self.u8()? {
+            0 => Pattern::Identifier(self.identifier()?),
+            1 => Pattern::Rest(self.identifier()?),
+            2 => Pattern::Tuple(self.patterns()?),
+            3 => Pattern::List(self.patterns()?),
+            _ => return Err(invalid("invalid pattern")),
+        })
+    }
+
+    fn patterns(&mut self) -> io::Result<Vec<Pattern>> {
+        (0..self.count()?).map(|_| self.pattern()).collect()
+    }
+
+    fn case_pattern(&mut self) -> io::Result<CasePattern> {
+        Ok(match self.u8()? {
+            0 => CasePattern::Wildcard,
+            1 => CasePattern::Binding(self.identifier()?),
+            2 => CasePattern::Literal(self.token()?),
+            3 => CasePattern::Rest(self.identifier()?),
+            4 => CasePattern::Variant {
+                enumeration: self.identifier()?,
+                variant: self.identifier()?,
+            },
+            5 => CasePattern::List(self.case_patterns()?),
+            6 => CasePattern::Tuple(self.case_patterns()?),
+            7 => CasePattern::Struct {
+                identifier: match self.bool()? {
+                    true => Some(self.identifier()?),
+                    false => None,
+                },
+                fields: (0..self.count()?)
+                    .map(|_| Ok((self.identifier()?, self.case_pattern()?)))
+                    .collect::<io::Result<_>>()?,
+            },
+            8 => CasePattern::Or(self.case_patterns()?),
+            _ => return Err(invalid("invalid case pattern")),
+        })
+    }
+
+    fn case_patterns(&mut self) -> io::Result<Vec<CasePattern>> {
+        (0..self.count()?).map(|_| self.case_pattern()).collect()
+    }
+
+    fn nodes(&mut self) -> io::Result<Vec<Box<Node>>> {
+        (0..self.count()?).map(|_| self.node()).collect()
+    }
+
+    fn optional_node(&mut self) -> io::Result<Option<Box<Node>>> {
+        Ok(match self.bool()? {
+            true => Some(self.node()?),
+            false => None,
+        })
+    }
+
+    fn keywords(&mut self) -> io::Result<Vec<(Token, Box<Node>)>> {
+        (0..self.count()?)
+            .map(|_| Ok((self.identifier()?, self.node()?)))
+            .collect()
+    }
+
+    fn node(&mut self) -> io::Result<Box<Node>> {
+        let node = match self.u8()? {
+            0 => Node::Atom {
+                value: self.token()?,
+            },
+            1 => Node::List {
+                values: self.nodes()?,
+            },
+            2 => Node::Tuple {
+                values: self.nodes()?,
+            },
+            3 => Node::Spread {
+                operator: self.token()?,
+                value: self.node()?,
+            },
+            4 => Node::BinaryOp {
+                left: self.node()?,
+                operator: self.token()?,
+                right: self.node()?,
+            },
+            5 => Node::UnaryOp {
+                operator: self.token()?,
+                value: self.node()?,
+            },
+            6 => Node::FuncDeclearion {
+                identifier: self.identifier()?,
+                arguments: self.arguments()?,
+                body: self.node()?,
+                locals: self.symbols()?,
+            },
+            7 => Node::FuncCall {
+                identifier: self.identifier()?,
+                slot: self.slot()?,
+                parameters: self.nodes()?,
+                keywords: self.keywords()?,
+            },
+            8 => Node::Assignment {
+                identifier: self.identifier()?,
+                slot: self.slot()?,
+                value: self.node()?,
+                is_declaration: self.bool()?,
+            },
+            9 => Node::Destructuring {
+                pattern: self.pattern()?,
+                operator: self.token()?,
+                value: self.node()?,
+                is_declaration: self.bool()?,
+            },
+            10 => Node::ListAssignment {
+                identifier: self.identifier()?,
+                slot: self.slot()?,
+                index: self.node()?,
+                value: self.node()?,
+            },
+            11 => Node::Access {
+                identifier: self.identifier()?,
+                slot: self.slot()?,
+            },
+            12 => Node::Member {
+                object: self.node()?,
+                member: self.identifier()?,
+            },
+            13 => Node::MemberAssignment {
+                object: self.node()?,
+                member: self.identifier()?,
+                value: self.node()?,
+            },
+            14 => Node::StructDeclaration {
+                identifier: self.identifier()?,
+                fields: self.arguments()?,
+            },
+            15 => Node::Impl {
+                identifier: self.identifier()?,
+                methods: self.nodes()?,
+            },
+            16 => Node::EnumDeclaration {
+                identifier: self.identifier()?,
+                variants: self.tokens()?,
+            },
+            17 => Node::MethodCall {
+                object: self.node()?,
+                method: self.identifier()?,
+                parameters: self.nodes()?,
+                keywords: self.keywords()?,
+            },
+            18 => Node::Index {
+                identifier: self.identifier()?,
+                slot: self.slot()?,
+                index: self.node()?,
+            },
+            19 => Node::If {
+                condition: self.node()?,
+                block: self.node()?,
+                else_block: self.optional_node()?,
+            },
+            20 => Node::While {
+                condition: self.node()?,
+                block: self.node()?,
+            },
+            21 => Node::Comprehension {
+                value: self.node()?,
+                identifier: self.identifier()?,
+                operator: self.token()?,
+                iterable: self.node()?,
+                condition: self.optional_node()?,
+            },
+            22 => Node::For {
+                identifier: self.identifier()?,
+                slot: self.slot()?,
+                operator: self.token()?,
+                iterable: self.node()?,
+                block: self.node()?,
+            },
+            23 => Node::DoWhile {
+                block: self.node()?,
+                condition: self.node()?,
+            },
+            24 => Node::Return {
+                value: self.node()?,
+            },
+            25 => Node::Delete {
+                identifier: self.identifier()?,
+                slot: self.slot()?,
+            },
+            29 => Node::Global {
+                identifiers: self.tokens()?,
+                is_nonlocal: self.bool()?,
+            },
+            26 => Node::Program {
+                statements: self.nodes()?,
+            },
+            27 => Node::Optional {
+                value: self.node()?,
+            },
+            28 => Node::Match {
+                value: self.node()?,
+                arms: (0..self.count()?)
+                    .map(|_| {
+                        Ok(Arm {
+                            pattern: self.case_pattern()?,
+                            guard: self.optional_node()?,
+                            block: self.node()?,
+                        })
+                    })
+                    .collect::<io::Result<_>>()?,
+            },
+            _ => return Err(invalid("invalid node")),
+        };
+        Ok(Box::new(node))
+    }
+
+    fn methods(&mut self) -> io::Result<Methods> {
+        if let Some(methods) = self.shared()? {
+            return Ok(methods);
+        }
+        let methods: Methods = Shared::new(Mutable::new(HashMap::new()));
+        self.register(&methods);
+        for _ in 0..self.count()? {
+            let (name, method) = (self.symbol()?, self.value()?);
+            methods.borrow_mut().insert(name, method);
+        }
+        Ok(methods)
+    }
+
+    // Builtins are found by their qualified name, e.g. `math.sqrt`.
+    fn builtin(&self, name: &str) -> io::Result<Value> {
+        let mut names = name.split('.');
+        let first = names.next().unwrap_or_default();
+        let builtin = self
+            .symbol_table
+            .builtins()
+            .find(|(builtin, _)| builtin.as_ref() == first)
+            .map(|(_, value)| value.clone());
+        names
+            .try_fold(builtin, |value, name| match value {
+                Some(Value::Namespace { members, .. }) => Some(members.get(name).cloned()),
+                _ => None,
+            })
+            .flatten()
+            .ok_or_else(|| invalid(&format!("unknown builtin `{}`", name)))
+    }
+
+    fn value(&mut self) -> io::Result<Value> {
+        Ok(match self.u8()? {
+            0 => Value::Integer(i32::from_le_bytes(self.array()?)),
+            1 => Value::Float(f32::from_le_bytes(self.array()?)),
+            2 => Value::Boolean(self.bool()?),
+            3 => Value::String(self.string()?.into()),
+            4 => Value::Character(self.char()?),
+            5 => match self.shared()? {
+                Some(values) => Value::List(values),
+                None => {
+                    let values = Shared::new(Mutable::new(Vec::new()));
+                    self.register(&values);
+                    for _ in 0..self.count()? {
+                        let value = self.value()?;
+                        values.borrow_mut().push(value);
+                    }
+                    Value::List(values)
+                }
+            },
+            6 => Value::Tuple(Shared::new(
+                (0..self.count()?)
+                    .map(|_| self.value())
+                    .collect::<io::Result<_>>()?,
+            )),
+            7 => Value::Function {
+                name: self.symbol()?,
+                position: self.position()?,
+                arguments: self.arguments()?.into(),
+                body: Shared::from(self.node()?),
+                locals: self.symbols()?.into(),
+            },
+            8 | 13 => {
+                let name = self.string()?;
+                self.builtin(&name)?
+            }
+            9 => {
+                let name = self.symbol()?;
+                let fields = match self.shared()? {
+                    Some(fields) => fields,
+                    None => {
+                        let fields = Shared::new(Mutable::new(Vec::new()));
+                        self.register(&fields);
+                        for _ in 0..self.count()? {
+                            let field = (self.symbol()?, self.value()?);
+                            fields.borrow_mut().push(field);
+                        }
+                        fields
+                    }
+                };
+                Value::Struct {
+                    name,
+                    fields,
+                    methods: self.methods()?,
+                }
+            }
+            10 => Value::StructType {
+                name: self.symbol()?,
+                fields: self.arguments()?.into(),
+                methods: self.methods()?,
+            },
+            11 => Value::EnumType {
+                name: self.symbol()?,
+                variants: self.symbols()?.into(),
+            },
+            12 => Value::Variant {
+                name: self.symbol()?,
+                variant: self.symbol()?,
+            },
+            14 => Value::None,
+            15 => Value::Bytes(self.raw()?.into()),
+            16 => Value::External(Shared::new(DateTime::from_timestamp(i64::from_le_bytes(
+                self.array()?,
+            )))),
+            17 => Value::External(match self.shared()? {
+                Some(builder) => builder,
+                None => {
+                    let builder: Shared<dyn External> =
+                        Shared::new(StringBuilder::new(self.string()?));
+                    self.register(&builder);
+                    builder
+                }
+            }),
+            _ => return Err(invalid("invalid value")),
+        })
+    }
+}
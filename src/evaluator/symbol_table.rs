@@ -1,70 +1,1103 @@
+use crate::evaluator::aggregates;
 use crate::evaluator::builtins;
-use crate::evaluator::builtins::builtin;
+use crate::evaluator::builtins::{builtin, intrinsic, namespace};
+use crate::evaluator::bytes;
+#[cfg(all(feature = "http", not(target_arch = "wasm32")))]
+use crate::evaluator::http;
+use crate::evaluator::os;
+use crate::evaluator::patterns;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::evaluator::process;
+use crate::evaluator::reflection;
+use crate::evaluator::shared::Shared;
+use crate::evaluator::sorting;
+#[cfg(all(feature = "sync", not(target_arch = "wasm32")))]
+use crate::evaluator::threads;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::evaluator::timers;
 use crate::evaluator::value::Value;
-use std::collections::HashMap;
+use crate::extensions::datetime;
+use crate::extensions::string_builder;
+use crate::parser::node::Slot;
+use crate::tokenizer::symbol::Symbol;
+use std::collections::{HashMap, HashSet};
 
-// TODO: Make the symbol table, stack based.
+// Where a function's uses of a name go once it's declared `global` or
+// `nonlocal`, rather than to a variable of its own.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Outer {
+    // The global variable, even when a caller has one of the same name.
+    Global,
+    // The variable of the closest scope the function was called from.
+    Nonlocal,
+}
+
+// The variables of a single function call (or comprehension), laid out in
+// the slots the resolver assigned them. A slot stays empty until its
+// variable is declared.
+struct Frame {
+    names: Shared<[Symbol]>,
+    values: Vec<Option<Value>>,
+    outer: Vec<(Symbol, Outer)>,
+}
+
+impl Frame {
+    fn new(names: Shared<[Symbol]>) -> Self {
+        Self {
+            values: vec![None; names.len()],
+            names,
+            outer: Vec::new(),
+        }
+    }
+
+    fn outer(&self, identifier: &str) -> Option<Outer> {
+        self.outer
+            .iter()
+            .find(|(name, _)| **name == *identifier)
+            .map(|(_, outer)| *outer)
+    }
+
+    fn position(&self, identifier: &str) -> Option<usize> {
+        self.names.iter().position(|name| **name == *identifier)
+    }
+
+    fn get(&self, identifier: &str) -> Option<&Value> {
+        self.values[self.position(identifier)?].as_ref()
+    }
+
+    fn get_mut(&mut self, identifier: &str) -> Option<&mut Value> {
+        let index = self.position(identifier)?;
+        self.values[index].as_mut()
+    }
+
+    fn remove(&mut self, identifier: &str) -> Option<Value> {
+        let index = self.position(identifier)?;
+        self.values[index].take()
+    }
+
+    // Variables the resolver didn't know about are appended to the layout.
+    fn insert(&mut self, identifier: Symbol, value: Value) {
+        match self.position(&identifier) {
+            Some(index) => self.values[index] = Some(value),
+            None => {
+                let mut names = self.names.to_vec();
+                names.push(identifier);
+                self.names = names.into();
+                self.values.push(Some(value));
+            }
+        }
+    }
+}
+
+// The globals in the order they were first assigned, so listing them is the
+// same from one run to the next. Reassigning a global keeps its place, and a
+// reserved name takes its place once it's first assigned.
+#[derive(Default)]
+struct Globals {
+    values: HashMap<Symbol, Value>,
+    order: Vec<Symbol>,
+    reserved: HashSet<Symbol>,
+}
+
+impl Globals {
+    fn insert(&mut self, identifier: Symbol, value: Value) {
+        if self.values.insert(identifier.clone(), value).is_none()
+            && !self.reserved.remove(&identifier)
+        {
+            self.order.push(identifier);
+        }
+    }
+
+    fn reserve(&mut self, identifier: Symbol) {
+        if !self.values.contains_key(&identifier) && self.reserved.insert(identifier.clone()) {
+            self.order.push(identifier);
+        }
+    }
+
+    fn remove(&mut self, identifier: &str) -> Option<Value> {
+        let removed = self.values.remove(identifier)?;
+        self.order.retain(|name| name.as_ref() != identifier);
+        Some(removed)
+    }
+
+    fn get(&self, identifier: &str) -> Option<&Value> {
+        self.values.get(identifier)
+    }
+
+    fn get_mut(&mut self, identifier: &str) -> Option<&mut Value> {
+        self.values.get_mut(identifier)
+    }
+
+    fn iter(&self) -> impl Iterator<Item = (&Symbol, &Value)> {
+        self.order
+            .iter()
+            .filter_map(|name| Some((name, self.values.get(name)?)))
+    }
+
+    fn iter_mut(&mut self) -> impl Iterator<Item = (&Symbol, &mut Value)> {
+        let index: HashMap<&Symbol, usize> = self
+            .order
+            .iter()
+            .enumerate()
+            .map(|(index, name)| (name, index))
+            .collect();
+        let mut values: Vec<_> = self.values.iter_mut().collect();
+        values.sort_by_key(|(name, _)| index[*name]);
+        values.into_iter()
+    }
+}
+
+// The name of the namespace holding every builtin, it stays reachable when
+// a builtin is shadowed.
+const BUILTINS: &str = "builtins";
+
+// Globals and builtins are kept by name, function frames are indexed by the
+// slots the resolver assigned. Looking a name up walks the frames from the
+// innermost one out before falling back to the globals and then the builtins,
+// so a global shadows a builtin rather than replacing it.
 pub struct SymbolTable {
-    tables: Vec<HashMap<String, Value>>,
+    builtins: HashMap<Symbol, Value>,
+    globals: Globals,
+    frames: Vec<Frame>,
+}
+
+impl Default for SymbolTable {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl SymbolTable {
     pub fn new() -> Self {
         Self {
-            tables: vec![HashMap::new()],
+            builtins: HashMap::new(),
+            globals: Globals::default(),
+            frames: Vec::new(),
         }
     }
 
-    pub fn insert(&mut self, identifier: String, value: Value) {
-        if self.tables.is_empty() {
-            panic!("Internal Error: Symbol Table dropped.")
+    // A table with no variables and the same builtins as the other, for an
+    // evaluator that mustn't reach more than the one it was started from.
+    pub fn with_builtins_of(other: &SymbolTable) -> Self {
+        Self {
+            builtins: other.builtins.clone(),
+            ..Self::new()
         }
-        self.tables.first_mut().unwrap().insert(identifier, value);
+    }
+
+    pub fn insert(&mut self, identifier: impl Into<Symbol>, value: Value) {
+        match self.frames.last_mut() {
+            Some(frame) => frame.insert(identifier.into(), value),
+            None => {
+                self.globals.insert(identifier.into(), value);
+            }
+        }
+    }
+
+    // Sends the current scope's uses of the name elsewhere, failing with the
+    // reason when it can't. At the top level every variable is already
+    // global, and there's no scope to be nonlocal to.
+    pub fn declare_outer(&mut self, identifier: Symbol, outer: Outer) -> Result<(), &'static str> {
+        let Some((frame, callers)) = self.frames.split_last_mut() else {
+            return match outer {
+                Outer::Global => Ok(()),
+                Outer::Nonlocal => Err("there's no enclosing scope at the top level"),
+            };
+        };
+        match frame.outer(&identifier) {
+            Some(declared) if declared == outer => return Ok(()),
+            Some(_) => return Err("it's declared both global and nonlocal"),
+            None => {}
+        }
+        if frame.get(&identifier).is_some() {
+            return Err("it's already a variable of this scope");
+        }
+        let is_enclosed = callers
+            .iter()
+            .any(|caller| caller.get(&identifier).is_some() || caller.outer(&identifier).is_some());
+        if outer == Outer::Nonlocal && !is_enclosed {
+            return Err("no enclosing scope has a variable of that name");
+        }
+        frame.outer.push((identifier, outer));
+        Ok(())
+    }
+
+    // How the current scope declared the name, if it did.
+    pub fn outer(&self, identifier: &str) -> Option<Outer> {
+        self.frames.last()?.outer(identifier)
+    }
+
+    // Keeps a place among the globals for a name the top level declares
+    // further on, so it's listed in source order even if something declared
+    // after it is assigned first.
+    pub fn reserve(&mut self, identifier: impl Into<Symbol>) {
+        if self.frames.is_empty() {
+            self.globals.reserve(identifier.into());
+        }
+    }
+
+    pub fn insert_global(&mut self, identifier: impl Into<Symbol>, value: Value) {
+        self.globals.insert(identifier.into(), value);
+    }
+
+    // The variables of each scope, from the innermost one out.
+    pub fn frames(&self) -> impl Iterator<Item = Vec<(&Symbol, &Value)>> {
+        self.frames.iter().rev().map(|frame| {
+            frame
+                .names
+                .iter()
+                .zip(&frame.values)
+                .filter_map(|(name, value)| Some((name, value.as_ref()?)))
+                .collect()
+        })
+    }
+
+    // In the order they were declared.
+    pub fn globals(&self) -> impl Iterator<Item = (&Symbol, &Value)> {
+        self.globals.iter()
+    }
+
+    pub fn globals_mut(&mut self) -> impl Iterator<Item = (&Symbol, &mut Value)> {
+        self.globals.iter_mut()
+    }
+
+    // Every variable a lookup could find, the current scope's first and the
+    // globals last, without the builtins.
+    pub fn iter(&self) -> impl Iterator<Item = (&Symbol, &Value)> {
+        self.frames().flatten().chain(self.globals())
+    }
+
+    pub fn builtins(&self) -> impl Iterator<Item = (&Symbol, &Value)> {
+        self.builtins.iter()
+    }
+
+    // Every name a lookup could find, in no particular order.
+    pub fn names(&self) -> impl Iterator<Item = &Symbol> {
+        self.frames()
+            .flatten()
+            .map(|(name, _)| name)
+            .chain(self.globals().map(|(name, _)| name))
+            .chain(self.builtins.keys())
+    }
+
+    // Removes a variable of the current scope, builtins are never removed.
+    pub fn remove(&mut self, identifier: &str) -> Option<Value> {
+        match self.frames.last_mut() {
+            Some(frame) => frame.remove(identifier),
+            None => self.globals.remove(identifier),
+        }
+    }
+
+    pub fn remove_at(&mut self, slot: Slot) -> Option<Value> {
+        self.slot(slot)?.take()
+    }
+
+    pub fn insert_builtin(&mut self, builtin: (String, Value)) {
+        self.add(builtin);
+        self.update_namespace();
+    }
+
+    pub fn remove_builtin(&mut self, identifier: &str) -> Option<Value> {
+        let removed = self.builtins.remove(identifier);
+        self.update_namespace();
+        removed
+    }
+
+    pub fn is_builtin(&self, identifier: &str) -> bool {
+        self.builtins.contains_key(identifier)
+    }
+
+    fn update_namespace(&mut self) {
+        let members = self
+            .builtins
+            .iter()
+            .filter(|(name, _)| name.as_ref() != BUILTINS)
+            .map(|(name, value)| (name.to_string(), value.clone()))
+            .collect();
+        let (identifier, value) = namespace(BUILTINS, members);
+        self.builtins.insert(identifier.into(), value);
     }
 
     pub fn insert_tuple(&mut self, (identifier, value): (String, Value)) {
         self.insert(identifier, value);
     }
 
+    // Every `Value` is a cheap handle (strings, lists and functions are
+    // reference counted), so cloning one out never copies its contents.
     pub fn get(&self, identifier: &str) -> Option<Value> {
-        for table in &self.tables {
-            if let Some(value) = table.get(identifier) {
+        for frame in self.frames.iter().rev() {
+            match frame.outer(identifier) {
+                Some(Outer::Global) => break,
+                Some(Outer::Nonlocal) => continue,
+                None => {}
+            }
+            if let Some(value) = frame.get(identifier) {
                 return Some(value.clone());
             }
         }
-        None
+        self.globals
+            .get(identifier)
+            .or_else(|| self.builtins.get(identifier))
+            .cloned()
     }
 
     pub fn get_mut(&mut self, identifier: &str) -> Option<&mut Value> {
-        for table in &mut self.tables {
-            if let Some(value) = table.get_mut(identifier) {
+        for frame in self.frames.iter_mut().rev() {
+            match frame.outer(identifier) {
+                Some(Outer::Global) => break,
+                Some(Outer::Nonlocal) => continue,
+                None => {}
+            }
+            if let Some(value) = frame.get_mut(identifier) {
                 return Some(value);
             }
         }
-        None
+        self.globals.get_mut(identifier)
     }
 
     pub fn contains(&mut self, identifier: &str) -> bool {
-        return self.get(identifier) != None;
+        self.get(identifier).is_some()
+    }
+
+    fn slot(&mut self, slot: Slot) -> Option<&mut Option<Value>> {
+        let frame = self.frames.len().checked_sub(slot.depth + 1)?;
+        self.frames[frame].values.get_mut(slot.index)
+    }
+
+    // Slot accessors return `None` for an empty slot, callers fall back to
+    // looking the variable up by name.
+    pub fn get_at(&self, slot: Slot) -> Option<Value> {
+        let frame = self.frames.len().checked_sub(slot.depth + 1)?;
+        self.frames[frame].values.get(slot.index)?.clone()
+    }
+
+    pub fn get_at_mut(&mut self, slot: Slot) -> Option<&mut Value> {
+        self.slot(slot)?.as_mut()
+    }
+
+    pub fn insert_at(&mut self, slot: Slot, identifier: impl Into<Symbol>, value: Value) {
+        match self.slot(slot) {
+            Some(stored) => *stored = Some(value),
+            None => self.insert(identifier, value),
+        }
     }
 
     pub fn scope(&mut self) {
-        self.tables.push(HashMap::new());
+        self.scope_with(Shared::new([]));
+    }
+
+    // Opens a frame laid out for the given variables, as resolved.
+    pub fn scope_with(&mut self, names: Shared<[Symbol]>) {
+        self.frames.push(Frame::new(names));
+    }
+
+    // The number of scopes currently pushed, i.e. how deep the calls are nested.
+    pub fn depth(&self) -> usize {
+        self.frames.len()
     }
 
     pub fn unscope(&mut self) {
-        if self.tables.len() <= 1 {
+        if self.frames.pop().is_none() {
             panic!("Internal Error: Tried to drop the main symbol table.")
         }
-        self.tables.remove(self.tables.len() - 1);
     }
 
     pub fn add_builtins(&mut self) {
-        self.insert_tuple(builtin("println", vec!["x"], builtins::println));
-        self.insert_tuple(builtin("print", vec!["x"], builtins::print));
-        self.insert_tuple(builtin("exit", vec!["exit_code"], builtins::exit));
-        self.insert_tuple(builtin("input", Vec::new(), builtins::input));
-        self.insert_tuple(builtin("integer", vec!["string"], builtins::integer));
-        self.insert_tuple(builtin("string", vec!["value"], builtins::string));
+        self.add(intrinsic(
+            "println",
+            vec!["*values"],
+            "Prints the values separated by spaces, followed by a new line.",
+            builtins::println,
+        ));
+        self.add(intrinsic(
+            "print",
+            vec!["*values"],
+            "Prints the values separated by spaces.",
+            builtins::print,
+        ));
+        self.add(intrinsic(
+            "dir",
+            Vec::new(),
+            "The names of the variables in scope, innermost first.",
+            reflection::dir,
+        ));
+        self.add(intrinsic(
+            "globals",
+            Vec::new(),
+            "The global variables as `(name, value)` tuples.",
+            reflection::globals,
+        ));
+        self.add(builtin(
+            "params",
+            vec!["function"],
+            "The names of the function's parameters, a variadic one as `*name`.",
+            reflection::params,
+        ));
+        self.add(builtin(
+            "arity",
+            vec!["function"],
+            "The number of parameters the function declares.",
+            reflection::arity,
+        ));
+        self.add(intrinsic(
+            "help",
+            vec!["function"],
+            "Prints the function's parameters and documentation.",
+            builtins::help,
+        ));
+        #[cfg(not(target_arch = "wasm32"))]
+        self.add(intrinsic(
+            "exit",
+            vec!["exit_code: Integer"],
+            "Exits the program with the exit code, from 0 to 255.",
+            builtins::exit,
+        ));
+        self.add(intrinsic(
+            "input",
+            vec!["*prompt"],
+            "Prints the prompt and reads a line, `None` at the end of the input.",
+            builtins::input,
+        ));
+        self.add(builtin(
+            "range",
+            vec!["start: Integer", "end: Integer"],
+            "The Integers from start up to, not including, end.",
+            builtins::range,
+        ));
+        self.add(builtin(
+            "round",
+            vec!["x: Integer | Float", "digits: Integer"],
+            "x rounded to the given number of digits after the point.",
+            builtins::round,
+        ));
+        self.add(intrinsic(
+            "divmod",
+            vec!["a: Integer | Float", "b: Integer | Float"],
+            "A `(quotient, remainder)` tuple, the quotient rounded down and the remainder with the sign of b.",
+            builtins::divmod,
+        ));
+        self.add(builtin(
+            "hex",
+            vec!["integer: Integer"],
+            "The integer in hex, such as `0xff`.",
+            builtins::hex,
+        ));
+        self.add(builtin(
+            "bin",
+            vec!["integer: Integer"],
+            "The integer in binary, such as `0b1010`.",
+            builtins::bin,
+        ));
+        self.add(builtin(
+            "bool",
+            vec!["value"],
+            "The value's truthiness, zero, empty values and `None` are False.",
+            builtins::boolean,
+        ));
+        self.add(builtin(
+            "is_none",
+            vec!["value"],
+            "Whether the value is `None`.",
+            builtins::is_none,
+        ));
+        self.add(intrinsic(
+            "eval",
+            vec!["code: String"],
+            "Runs a string of code in the current scope, returning the value of its last statement.",
+            builtins::eval,
+        ));
+        self.add(intrinsic(
+            "run_file",
+            vec!["path: String"],
+            "Runs another script, returning the value of its last statement.",
+            builtins::run_file,
+        ));
+        self.add(intrinsic(
+            "read_file_bytes",
+            vec!["path: String"],
+            "The contents of the file as a byte string.",
+            bytes::read_file_bytes,
+        ));
+        self.add(intrinsic(
+            "len",
+            vec!["value"],
+            "The length of a string (in characters), byte string, list or tuple.",
+            builtins::len,
+        ));
+        self.add(builtin(
+            "enumerate",
+            vec!["values"],
+            "`[index, value]` pairs of the values.",
+            builtins::enumerate,
+        ));
+        self.add(builtin(
+            "zip",
+            vec!["a", "b"],
+            "`[a, b]` pairs of the elements of a and b, as many as the shorter has.",
+            builtins::zip,
+        ));
+        self.add(intrinsic(
+            "sort",
+            vec!["values", "*key"],
+            "A sorted copy of the values, ordered by what the key returns for each when given.",
+            sorting::sort,
+        ));
+        self.add(intrinsic(
+            "sort_by",
+            vec!["values", "cmp: Function"],
+            "A copy of the values sorted by a comparator returning a negative, zero or positive Integer.",
+            sorting::sort_by,
+        ));
+        self.add(intrinsic(
+            "min",
+            vec!["values"],
+            "The smallest element of the values, failing when they're empty. `math.min` compares two numbers instead.",
+            aggregates::min,
+        ));
+        self.add(intrinsic(
+            "max",
+            vec!["values"],
+            "The largest element of the values, failing when they're empty. `math.max` compares two numbers instead.",
+            aggregates::max,
+        ));
+        self.add(intrinsic(
+            "sum",
+            vec!["values"],
+            "The sum of a list of Integers or of Floats, 0 when it's empty.",
+            aggregates::sum,
+        ));
+        self.add(intrinsic(
+            "any",
+            vec!["values"],
+            "Whether any element of the values is truthy.",
+            aggregates::any,
+        ));
+        self.add(intrinsic(
+            "all",
+            vec!["values"],
+            "Whether every element of the values is truthy.",
+            aggregates::all,
+        ));
+        self.add(builtin(
+            "slice",
+            vec!["value", "start: Integer", "end: Integer"],
+            "The elements from start up to, not including, end.",
+            builtins::slice,
+        ));
+        self.add(builtin(
+            "string_builder",
+            Vec::new(),
+            "An empty builder to `append` to, which builds a string in place.",
+            string_builder::string_builder,
+        ));
+        self.add(builtin(
+            "append",
+            vec!["builder: StringBuilder", "value"],
+            "Appends the value, as it would be printed, to the builder.",
+            string_builder::append,
+        ));
+        self.add(builtin(
+            "build",
+            vec!["builder: StringBuilder"],
+            "The string the builder holds.",
+            string_builder::build,
+        ));
+        self.add(builtin(
+            "hex_encode",
+            vec!["bytes: Bytes"],
+            "The bytes as a string of hex digits.",
+            bytes::hex_encode,
+        ));
+        self.add(builtin(
+            "hex_decode",
+            vec!["string: String"],
+            "The byte string a string of hex digits encodes.",
+            bytes::hex_decode,
+        ));
+        self.add(intrinsic(
+            "list_dir",
+            vec!["path: String"],
+            "The sorted names of the entries in the directory.",
+            os::list_dir,
+        ));
+        self.add(intrinsic(
+            "mkdir",
+            vec!["path: String"],
+            "Creates the directory along with any missing parents.",
+            os::mkdir,
+        ));
+        self.add(intrinsic(
+            "remove_file",
+            vec!["path: String"],
+            "Removes the file.",
+            os::remove_file,
+        ));
+        self.add(intrinsic(
+            "cwd",
+            Vec::new(),
+            "The current working directory.",
+            os::cwd,
+        ));
+        self.add(builtin(
+            "join_path",
+            vec!["a: String", "b: String"],
+            "The path b joined onto a.",
+            os::join_path,
+        ));
+        self.add(builtin(
+            "basename",
+            vec!["path: String"],
+            "The last component of the path.",
+            os::basename,
+        ));
+        self.add(builtin(
+            "dirname",
+            vec!["path: String"],
+            "The path without its last component.",
+            os::dirname,
+        ));
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            self.add(builtin(
+                "time",
+                Vec::new(),
+                "Seconds since the epoch.",
+                builtins::time,
+            ));
+            self.add(builtin(
+                "clock",
+                Vec::new(),
+                "Monotonic milliseconds, for timing code.",
+                builtins::clock,
+            ));
+            self.add(builtin(
+                "sleep",
+                vec!["milliseconds: Integer"],
+                "Pauses for the given number of milliseconds.",
+                builtins::sleep,
+            ));
+            self.add(intrinsic(
+                "after",
+                vec!["milliseconds: Integer", "function: Function"],
+                "Schedules the function to be called once after the delay, returning the timer's id.",
+                timers::after,
+            ));
+            self.add(intrinsic(
+                "every",
+                vec!["milliseconds: Integer", "function: Function"],
+                "Schedules the function to be called every interval, returning the timer's id.",
+                timers::every,
+            ));
+            self.add(intrinsic(
+                "cancel",
+                vec!["timer: Integer"],
+                "Cancels the timer with the id, returning whether it was pending.",
+                timers::cancel,
+            ));
+            self.add(intrinsic(
+                "run_loop",
+                Vec::new(),
+                "Calls the scheduled functions as they come due until no timers are left.",
+                timers::run_loop,
+            ));
+            self.add(intrinsic(
+                "exec",
+                vec!["program: String", "arguments: List"],
+                "Runs the program with a list of arguments, returning a `Process` with `status`, `stdout` and `stderr`.",
+                process::exec,
+            ));
+            self.add(intrinsic(
+                "shell",
+                vec!["command: String"],
+                "Runs the command with the system's shell, returning a `Process`.",
+                process::shell,
+            ));
+        }
+        self.add(intrinsic(
+            "re_match",
+            vec!["pattern: String", "string: String"],
+            "The groups of the pattern's first match in the string, the whole match first.",
+            patterns::re_match,
+        ));
+        self.add(intrinsic(
+            "re_find_all",
+            vec!["pattern: String", "string: String"],
+            "Every match of the pattern in the string, as the groups when it has any.",
+            patterns::re_find_all,
+        ));
+        self.add(intrinsic(
+            "re_replace",
+            vec!["pattern: String", "string: String", "replacement: String"],
+            "The string with each match of the pattern replaced, `$1` refers to a group.",
+            patterns::re_replace,
+        ));
+        self.add(intrinsic(
+            "re_split",
+            vec!["pattern: String", "string: String"],
+            "The parts of the string between the pattern's matches.",
+            patterns::re_split,
+        ));
+        #[cfg(not(target_arch = "wasm32"))]
+        self.add(builtin(
+            "now",
+            Vec::new(),
+            "The current date and time, in UTC.",
+            datetime::now,
+        ));
+        self.add(builtin(
+            "date_format",
+            vec!["datetime: DateTime", "format: String"],
+            "The date formatted with `%Y %m %d %H %M %S`.",
+            datetime::date_format,
+        ));
+        self.add(builtin(
+            "date_parse",
+            vec!["string: String", "format: String"],
+            "A date parsed from the string with `%Y %m %d %H %M %S`.",
+            datetime::date_parse,
+        ));
+        self.add(builtin(
+            "days",
+            vec!["days: Integer"],
+            "The number of seconds in the given number of days.",
+            datetime::days,
+        ));
+        self.add(intrinsic(
+            "format",
+            vec!["template: String", "*values"],
+            "The template with each `{}` replaced by the next value, `{:.2}` sets a Float's precision.",
+            builtins::format,
+        ));
+
+        // Namespaced builtins, the flat names above are kept as aliases. Calling
+        // a namespace such as `string(5)` calls its `from` member.
+        self.add(namespace(
+            "io",
+            vec![
+                intrinsic(
+                    "println",
+                    vec!["*values"],
+                    "Prints the values separated by spaces, followed by a new line.",
+                    builtins::println,
+                ),
+                intrinsic(
+                    "print",
+                    vec!["*values"],
+                    "Prints the values separated by spaces.",
+                    builtins::print,
+                ),
+                intrinsic(
+                    "input",
+                    vec!["*prompt"],
+                    "Prints the prompt and reads a line, `None` at the end of the input.",
+                    builtins::input,
+                ),
+                #[cfg(not(target_arch = "wasm32"))]
+                intrinsic(
+                    "exit",
+                    vec!["exit_code: Integer"],
+                    "Exits the program with the exit code, from 0 to 255.",
+                    builtins::exit,
+                ),
+            ],
+        ));
+        self.add(namespace(
+            "math",
+            vec![
+                (String::from("pi"), Value::Float(std::f32::consts::PI)),
+                builtin(
+                    "sqrt",
+                    vec!["x: Integer | Float"],
+                    "The square root of x.",
+                    builtins::sqrt,
+                ),
+                intrinsic(
+                    "abs",
+                    vec!["x: Integer | Float"],
+                    "The absolute value of x.",
+                    builtins::abs,
+                ),
+                intrinsic(
+                    "floor",
+                    vec!["x: Integer | Float"],
+                    "x rounded down to an Integer.",
+                    builtins::floor,
+                ),
+                intrinsic(
+                    "ceil",
+                    vec!["x: Integer | Float"],
+                    "x rounded up to an Integer.",
+                    builtins::ceil,
+                ),
+                builtin(
+                    "round",
+                    vec!["x: Integer | Float", "digits: Integer"],
+                    "x rounded to the given number of digits after the point.",
+                    builtins::round,
+                ),
+                builtin(
+                    "min",
+                    vec!["a: Integer | Float", "b: Integer | Float"],
+                    "The smaller of a and b, the global `min` takes a list instead.",
+                    builtins::min,
+                ),
+                builtin(
+                    "max",
+                    vec!["a: Integer | Float", "b: Integer | Float"],
+                    "The larger of a and b, the global `max` takes a list instead.",
+                    builtins::max,
+                ),
+            ],
+        ));
+        self.add(namespace(
+            "integer",
+            vec![builtin(
+                "from",
+                vec!["string: String"],
+                "The string parsed as an Integer, `None` when it isn't one.",
+                builtins::integer,
+            )],
+        ));
+        self.add(namespace(
+            "float",
+            vec![builtin(
+                "from",
+                vec!["value: String | Integer | Float"],
+                "The string parsed as a Float, or the number as one, `None` when it isn't one.",
+                builtins::float_from,
+            )],
+        ));
+        self.add(builtin(
+            "try_integer",
+            vec!["string: String"],
+            "The string parsed as an Integer and `None`, or `None` and why it isn't one.",
+            builtins::try_integer,
+        ));
+        self.add(builtin(
+            "try_float",
+            vec!["string: String"],
+            "The string parsed as a Float and `None`, or `None` and why it isn't one.",
+            builtins::try_float,
+        ));
+        self.add(namespace(
+            "boolean",
+            vec![builtin(
+                "from",
+                vec!["value"],
+                "The value's truthiness, zero, empty values and `None` are False.",
+                builtins::boolean,
+            )],
+        ));
+        self.add(namespace(
+            "string",
+            vec![
+                builtin(
+                    "from",
+                    vec!["value"],
+                    "The value as it would be printed.",
+                    builtins::string,
+                ),
+                builtin(
+                    "upper",
+                    vec!["string: String | Character"],
+                    "The string in uppercase.",
+                    builtins::upper,
+                ),
+                builtin(
+                    "lower",
+                    vec!["string: String | Character"],
+                    "The string in lowercase.",
+                    builtins::lower,
+                ),
+                builtin(
+                    "trim",
+                    vec!["string: String"],
+                    "The string without leading and trailing whitespace.",
+                    builtins::trim,
+                ),
+                builtin(
+                    "split",
+                    vec!["string: String", "separator: String | Character"],
+                    "The parts of the string between each occurrence of the separator.",
+                    builtins::split,
+                ),
+                intrinsic(
+                    "format",
+                    vec!["template: String", "*values"],
+                    "The template with each `{}` replaced by the next value, `{:.2}` sets a Float's precision.",
+                    builtins::format,
+                ),
+            ],
+        ));
+        self.add(namespace(
+            "re",
+            vec![
+                intrinsic(
+                    "match",
+                    vec!["pattern: String", "string: String"],
+                    "The groups of the pattern's first match in the string, the whole match first.",
+                    patterns::re_match,
+                ),
+                intrinsic(
+                    "find_all",
+                    vec!["pattern: String", "string: String"],
+                    "Every match of the pattern in the string, as the groups when it has any.",
+                    patterns::re_find_all,
+                ),
+                intrinsic(
+                    "replace",
+                    vec!["pattern: String", "string: String", "replacement: String"],
+                    "The string with each match of the pattern replaced, `$1` refers to a group.",
+                    patterns::re_replace,
+                ),
+                intrinsic(
+                    "split",
+                    vec!["pattern: String", "string: String"],
+                    "The parts of the string between the pattern's matches.",
+                    patterns::re_split,
+                ),
+            ],
+        ));
+        self.add(namespace(
+            "date",
+            vec![
+                #[cfg(not(target_arch = "wasm32"))]
+                builtin(
+                    "now",
+                    Vec::new(),
+                    "The current date and time, in UTC.",
+                    datetime::now,
+                ),
+                builtin(
+                    "format",
+                    vec!["datetime: DateTime", "format: String"],
+                    "The date formatted with `%Y %m %d %H %M %S`.",
+                    datetime::date_format,
+                ),
+                builtin(
+                    "parse",
+                    vec!["string: String", "format: String"],
+                    "A date parsed from the string with `%Y %m %d %H %M %S`.",
+                    datetime::date_parse,
+                ),
+                builtin(
+                    "timestamp",
+                    vec!["datetime: DateTime"],
+                    "The number of seconds from the epoch to the date.",
+                    datetime::timestamp,
+                ),
+                builtin(
+                    "from_timestamp",
+                    vec!["seconds: Integer"],
+                    "The date the given number of seconds after the epoch.",
+                    datetime::from_timestamp,
+                ),
+                builtin(
+                    "days",
+                    vec!["days: Integer"],
+                    "The number of seconds in the given number of days.",
+                    datetime::days,
+                ),
+            ],
+        ));
+        #[cfg(all(feature = "http", not(target_arch = "wasm32")))]
+        {
+            self.add(intrinsic(
+                "http_get",
+                vec!["url: String"],
+                "Sends a GET request, returning a `Response` with `status`, `headers` and `body`.",
+                http::http_get,
+            ));
+            self.add(intrinsic(
+                "http_post",
+                vec!["url: String", "body: String", "headers: List"],
+                "Sends a POST request with the body and `(name, value)` headers, returning a `Response`.",
+                http::http_post,
+            ));
+        }
+        #[cfg(all(feature = "sync", not(target_arch = "wasm32")))]
+        {
+            self.add(intrinsic(
+                "spawn",
+                vec!["function: Function", "*arguments"],
+                "Calls the function with the arguments on a new thread, returning a `Thread` to `join`.",
+                threads::spawn,
+            ));
+            self.add(intrinsic(
+                "join",
+                vec!["thread: Thread"],
+                "Waits for the thread to finish, returning what its function returned.",
+                threads::join,
+            ));
+            self.add(intrinsic(
+                "channel",
+                vec![],
+                "A `(sender, receiver)` tuple for passing values between threads.",
+                threads::channel,
+            ));
+            self.add(intrinsic(
+                "send",
+                vec!["sender: Sender", "value"],
+                "Sends the value over the channel, `False` when the receiver is gone.",
+                threads::send,
+            ));
+            self.add(intrinsic(
+                "recv",
+                vec!["receiver: Receiver"],
+                "Waits for the next value from the channel, `None` once it's closed.",
+                threads::recv,
+            ));
+        }
+        self.add(namespace(
+            "bytes",
+            vec![
+                builtin(
+                    "from",
+                    vec!["value"],
+                    "A byte string from a string's UTF-8 bytes or a list of Integers from 0 to 255.",
+                    bytes::from,
+                ),
+                builtin(
+                    "decode",
+                    vec!["bytes: Bytes"],
+                    "The bytes as a UTF-8 string, `None` when they aren't valid UTF-8.",
+                    bytes::decode,
+                ),
+                builtin(
+                    "hex",
+                    vec!["bytes: Bytes"],
+                    "The bytes as a string of hex digits.",
+                    bytes::hex_encode,
+                ),
+                builtin(
+                    "from_hex",
+                    vec!["string: String"],
+                    "The byte string a string of hex digits encodes.",
+                    bytes::hex_decode,
+                ),
+            ],
+        ));
+        self.add(namespace(
+            "list",
+            vec![
+                builtin(
+                    "push",
+                    vec!["list: List", "value"],
+                    "Appends the value to the end of the list.",
+                    builtins::push,
+                ),
+                builtin(
+                    "pop",
+                    vec!["list: List"],
+                    "Removes and returns the last element of the list.",
+                    builtins::pop,
+                ),
+            ],
+        ));
+        self.update_namespace();
+    }
+
+    fn add(&mut self, (identifier, value): (String, Value)) {
+        self.builtins.insert(identifier.into(), value);
     }
 }
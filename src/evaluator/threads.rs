@@ -0,0 +1,195 @@
+//! Threads and channels, for scripts doing a few things at once such as
+//! waiting on several processes or requests.
+//!
+//! `spawn` calls a function on a new thread with an evaluator of its own,
+//! starting with only the spawning evaluator's builtins and limits and the
+//! function itself, so a sandboxed program's threads are just as sandboxed.
+//! Threads share nothing but what they're given: the arguments and the
+//! channels they communicate over. Values passed either way are shared as
+//! with any assignment, not copied. A spawned thread reads and prints where
+//! the spawning one does.
+
+use crate::evaluator::external::External;
+use crate::evaluator::interrupt;
+use crate::evaluator::shared::Shared;
+use crate::evaluator::symbol_table::SymbolTable;
+use crate::evaluator::value::Value;
+use crate::evaluator::{Evaluator, EvaluatorItem};
+use crate::models::error::{MonoError, Runtime, Snapshot};
+use crate::tokenizer::token::Token;
+use std::any::Any;
+use std::fmt;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::{Mutex, PoisonError};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+#[derive(Debug)]
+pub struct Thread(Mutex<Option<JoinHandle<EvaluatorItem>>>);
+
+impl fmt::Display for Thread {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "<Thread>")
+    }
+}
+
+impl External for Thread {
+    fn type_name(&self) -> &'static str {
+        "Thread"
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+#[derive(Debug)]
+pub struct ChannelSender(Sender<Value>);
+
+impl fmt::Display for ChannelSender {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "<Sender>")
+    }
+}
+
+impl External for ChannelSender {
+    fn type_name(&self) -> &'static str {
+        "Sender"
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+// `Receiver` isn't `Sync`, values holding it must be.
+#[derive(Debug)]
+pub struct ChannelReceiver(Mutex<Receiver<Value>>);
+
+impl fmt::Display for ChannelReceiver {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "<Receiver>")
+    }
+}
+
+impl External for ChannelReceiver {
+    fn type_name(&self) -> &'static str {
+        "Receiver"
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+fn external<'v, T: External + 'static>(
+    value: &'v Value,
+    expected: &'static str,
+) -> Result<&'v T, MonoError> {
+    match value {
+        Value::External(external) => external.downcast_ref::<T>(),
+        _ => None,
+    }
+    .ok_or_else(|| {
+        MonoError::from(Runtime::InvalidValue {
+            expected: Snapshot {
+                value: format!("<{}>", expected),
+                kind: expected,
+            },
+            found: value.into(),
+        })
+    })
+}
+
+// Calls the function with the rest of the values on a new thread, returning
+// the `Thread` to `join`.
+pub fn spawn(evaluator: &mut Evaluator, call: &Token, mut values: Vec<Value>) -> EvaluatorItem {
+    let function = values.remove(0);
+    if !matches!(
+        function,
+        Value::Function { .. } | Value::BuiltInFunction { .. }
+    ) {
+        return Err(MonoError::from(Runtime::InvalidValue {
+            expected: Snapshot {
+                value: String::from("<Function>"),
+                kind: "Function",
+            },
+            found: function.into(),
+        }));
+    }
+    let token = call.clone();
+    let paths = evaluator.paths.clone();
+    let limits = evaluator.limits;
+    let (input, output) = (evaluator.input.clone(), evaluator.output.clone());
+    let symbol_table = SymbolTable::with_builtins_of(&evaluator.symbol_table);
+    let handle = thread::Builder::new()
+        .spawn(move || {
+            let mut evaluator = Evaluator::from(symbol_table);
+            evaluator.set_paths(paths);
+            (evaluator.input, evaluator.output) = (input, output);
+            if let Some(limits) = limits {
+                evaluator.set_limits(limits);
+            }
+            evaluator.start();
+            evaluator.call_value(&token, function, values)
+        })
+        .map_err(|error| Runtime::IoError {
+            call: call.clone(),
+            message: error.to_string(),
+        })?;
+    Ok(Value::External(Shared::new(Thread(Mutex::new(Some(
+        handle,
+    ))))))
+}
+
+// Waits for the thread to finish, returning what its function returned or
+// failing with its error. A thread can only be joined once.
+pub fn join(_evaluator: &mut Evaluator, call: &Token, values: Vec<Value>) -> EvaluatorItem {
+    let thread = external::<Thread>(&values[0], "Thread")?;
+    let handle = thread
+        .0
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .take();
+    let error = |message: &str| {
+        MonoError::from(Runtime::IoError {
+            call: call.clone(),
+            message: message.to_string(),
+        })
+    };
+    match handle {
+        Some(handle) => handle.join().map_err(|_| error("the thread panicked"))?,
+        None => Err(error("the thread was already joined")),
+    }
+}
+
+// A `(sender, receiver)` tuple. The channel closes once every sender is gone.
+pub fn channel(_evaluator: &mut Evaluator, _call: &Token, _values: Vec<Value>) -> EvaluatorItem {
+    let (sender, receiver) = mpsc::channel();
+    Ok(Value::Tuple(Shared::new(vec![
+        Value::External(Shared::new(ChannelSender(sender))),
+        Value::External(Shared::new(ChannelReceiver(Mutex::new(receiver)))),
+    ])))
+}
+
+// Whether the value was sent, `False` once the receiver is gone.
+pub fn send(_evaluator: &mut Evaluator, _call: &Token, values: Vec<Value>) -> EvaluatorItem {
+    let sender = external::<ChannelSender>(&values[0], "Sender")?;
+    Ok(Value::Boolean(sender.0.send(values[1].clone()).is_ok()))
+}
+
+// Waits for the next value, `None` once the channel is closed and empty or
+// when interrupted.
+pub fn recv(_evaluator: &mut Evaluator, _call: &Token, values: Vec<Value>) -> EvaluatorItem {
+    const SLICE: Duration = Duration::from_millis(10);
+    let receiver = external::<ChannelReceiver>(&values[0], "Receiver")?;
+    let receiver = receiver.0.lock().unwrap_or_else(PoisonError::into_inner);
+    while !interrupt::is_interrupted() {
+        match receiver.recv_timeout(SLICE) {
+            Ok(value) => return Ok(value),
+            Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => break,
+        }
+    }
+    Ok(Value::None)
+}
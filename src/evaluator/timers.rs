@@ -0,0 +1,122 @@
+//! Timers for scripts that poll or wait without threads: `after` and `every`
+//! schedule a function and `run_loop` calls each when it's due, one at a
+//! time on the evaluator's own thread, until none are left.
+
+use crate::evaluator::builtins;
+use crate::evaluator::value::Value;
+use crate::evaluator::{Evaluator, EvaluatorItem};
+use crate::models::error::{MonoError, Runtime, Snapshot};
+use crate::tokenizer::token::Token;
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Default)]
+pub struct Timers {
+    next: i32,
+    pending: Vec<Timer>,
+}
+
+#[derive(Debug)]
+struct Timer {
+    id: i32,
+    due: Instant,
+    // Set for the timers `every` schedules.
+    interval: Option<Duration>,
+    function: Value,
+}
+
+impl Timers {
+    fn schedule(&mut self, delay: Duration, interval: Option<Duration>, function: Value) -> i32 {
+        let id = self.next;
+        self.next = self.next.wrapping_add(1);
+        self.pending.push(Timer {
+            id,
+            due: Instant::now() + delay,
+            interval,
+            function,
+        });
+        id
+    }
+
+    // The index of the timer due first, the first scheduled on a tie.
+    fn earliest(&self) -> Option<usize> {
+        (0..self.pending.len()).min_by_key(|&index| {
+            let timer = &self.pending[index];
+            (timer.due, timer.id)
+        })
+    }
+}
+
+fn arguments(values: &[Value]) -> Result<(Duration, Value), MonoError> {
+    let Value::Integer(milliseconds) = values[0] else {
+        return Err(MonoError::from(Runtime::InvalidValue {
+            expected: Value::Integer(0).into(),
+            found: (&values[0]).into(),
+        }));
+    };
+    let function = &values[1];
+    if !matches!(
+        function,
+        Value::Function { .. } | Value::BuiltInFunction { .. }
+    ) {
+        return Err(MonoError::from(Runtime::InvalidValue {
+            expected: Snapshot {
+                value: String::from("<Function>"),
+                kind: "Function",
+            },
+            found: function.into(),
+        }));
+    }
+    Ok((
+        Duration::from_millis(milliseconds.max(0) as u64),
+        function.clone(),
+    ))
+}
+
+// Schedules the function to be called once, returning the timer's id.
+pub fn after(evaluator: &mut Evaluator, _call: &Token, values: Vec<Value>) -> EvaluatorItem {
+    let (delay, function) = arguments(&values)?;
+    Ok(Value::Integer(
+        evaluator.timers.schedule(delay, None, function),
+    ))
+}
+
+// Schedules the function to be called every interval until cancelled,
+// returning the timer's id.
+pub fn every(evaluator: &mut Evaluator, _call: &Token, values: Vec<Value>) -> EvaluatorItem {
+    let (interval, function) = arguments(&values)?;
+    Ok(Value::Integer(evaluator.timers.schedule(
+        interval,
+        Some(interval),
+        function,
+    )))
+}
+
+// Whether the timer was still pending.
+pub fn cancel(evaluator: &mut Evaluator, _call: &Token, values: Vec<Value>) -> EvaluatorItem {
+    let pending = &mut evaluator.timers.pending;
+    let count = pending.len();
+    pending.retain(|timer| Value::Integer(timer.id) != values[0]);
+    Ok(Value::Boolean(pending.len() < count))
+}
+
+// Calls the timers' functions as they come due until none are pending or
+// the evaluator is interrupted. A repeating timer is rescheduled before its
+// function is called, so the function can cancel it, and an error ends the
+// loop with the remaining timers still pending.
+pub fn run_loop(evaluator: &mut Evaluator, call: &Token, _values: Vec<Value>) -> EvaluatorItem {
+    while let Some(index) = evaluator.timers.earliest() {
+        if !builtins::sleep_until(evaluator.timers.pending[index].due) {
+            break;
+        }
+        let timer = evaluator.timers.pending.remove(index);
+        let function = timer.function.clone();
+        if let Some(interval) = timer.interval {
+            // A late timer runs once rather than catching up on every
+            // interval it missed.
+            let due = (timer.due + interval).max(Instant::now());
+            evaluator.timers.pending.push(Timer { due, ..timer });
+        }
+        evaluator.call_value(call, function, Vec::new())?;
+    }
+    Ok(Value::None)
+}
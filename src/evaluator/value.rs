@@ -1,52 +1,195 @@
-use super::EvaluatorItem;
-use crate::models::error::Runtime;
-use crate::parser::node::Node;
+use super::{Evaluator, EvaluatorItem};
+use crate::evaluator::builtins;
+use crate::evaluator::external::External;
+use crate::evaluator::formatting;
+use crate::evaluator::limits;
+use crate::evaluator::shared::Mutable;
+use crate::evaluator::shared::Shared;
+use crate::models::error::{MonoError, Runtime, Snapshot};
+use crate::models::position::Position;
+use crate::parser::node::{Argument, Node};
+use crate::tokenizer::symbol::Symbol;
 use crate::tokenizer::token::Token;
 use crate::tokenizer::token::TokenKind;
-use std::cell::RefCell;
-use std::rc::Rc;
+use std::cmp::Ordering;
+use std::collections::HashMap;
 
 use std::fmt;
 
+macro_rules! checked {
+    ($result:expr, $operator:expr) => {
+        match $result {
+            Some(result) => Ok(Value::Integer(result)),
+            None => Err(MonoError::from(Runtime::IntegerOverflow {
+                operator: $operator.clone(),
+            })),
+        }
+    };
+}
+
 macro_rules! invalid_operation {
     ($operator:expr, $right:expr, $left:expr) => {
-        Err(Box::new(Runtime::InvalidOperation {
+        Err(MonoError::from(Runtime::InvalidOperation {
             operator: $operator.clone(),
-            right: $right,
-            left: $left,
+            right: Option::<Value>::map($right, Snapshot::from),
+            left: $left.into(),
         }))
     };
 }
 
-#[derive(Debug, PartialEq, Clone)]
+// How a builtin is implemented. Most only need their arguments, the few that
+// run code or can fail get the evaluator and the call too.
+#[derive(Debug, Clone, Copy)]
+pub enum Native {
+    Function(fn(Vec<Value>) -> Value),
+    Evaluator(fn(&mut Evaluator, &Token, Vec<Value>) -> EvaluatorItem),
+}
+
+// Methods are shared between a struct type and all of its instances, so an
+// `impl` block also applies to values created before it.
+pub type Methods = Shared<Mutable<HashMap<Symbol, Value>>>;
+
+#[derive(Debug, Clone)]
 pub enum Value {
     Integer(i32),
     Float(f32),
     Boolean(bool),
-    String(String),
+    String(Shared<str>),
+    Bytes(Shared<[u8]>),
     Character(char),
-    List(Rc<RefCell<Vec<Value>>>),
+    List(Shared<Mutable<Vec<Value>>>),
+    Tuple(Shared<Vec<Value>>),
     Function {
-        name: String,
-        arguments: Vec<String>,
-        body: Box<Node>,
+        name: Symbol,
+        // Where it was declared, shown when it's printed.
+        position: Position,
+        arguments: Shared<[Argument]>,
+        body: Shared<Node>,
+        locals: Shared<[Symbol]>,
     },
     BuiltInFunction {
-        name: String,
-        arguments: Vec<String>,
-        function: fn(Vec<Value>) -> Value,
+        name: Symbol,
+        arguments: Shared<[String]>,
+        // The types each argument accepts, as declared with `name: Type`,
+        // alternatives separated by ` | `. Any value for `None`.
+        types: Shared<[Option<Symbol>]>,
+        // What `help` prints for it.
+        doc: &'static str,
+        function: Native,
+    },
+    Struct {
+        name: Symbol,
+        fields: Shared<Mutable<Vec<(Symbol, Value)>>>,
+        methods: Methods,
     },
+    StructType {
+        name: Symbol,
+        fields: Shared<[Argument]>,
+        methods: Methods,
+    },
+    EnumType {
+        name: Symbol,
+        variants: Shared<[Symbol]>,
+    },
+    Variant {
+        name: Symbol,
+        variant: Symbol,
+    },
+    Namespace {
+        name: Symbol,
+        members: Shared<HashMap<Symbol, Value>>,
+    },
+    External(Shared<dyn External>),
     None,
 }
 
+impl PartialEq for Value {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Value::Integer(a), Value::Integer(b)) => a == b,
+            (Value::Float(a), Value::Float(b)) => a == b,
+            // Numbers are equal by value, whatever their type.
+            (Value::Integer(a), Value::Float(b)) | (Value::Float(b), Value::Integer(a)) => {
+                f64::from(*a) == f64::from(*b)
+            }
+            (Value::Boolean(a), Value::Boolean(b)) => a == b,
+            (Value::String(a), Value::String(b)) => a == b,
+            (Value::Bytes(a), Value::Bytes(b)) => a == b,
+            (Value::Character(a), Value::Character(b)) => a == b,
+            (Value::List(a), Value::List(b)) => a == b,
+            (Value::Tuple(a), Value::Tuple(b)) => a == b,
+            // Each declaration makes a new function, so two are equal only
+            // when they're the same one.
+            (Value::Function { body: a, .. }, Value::Function { body: b, .. }) => {
+                Shared::ptr_eq(a, b)
+            }
+            (
+                Value::BuiltInFunction { name: a_name, .. },
+                Value::BuiltInFunction { name: b_name, .. },
+            ) => a_name == b_name,
+            (
+                Value::Struct {
+                    name: a_name,
+                    fields: a_fields,
+                    ..
+                },
+                Value::Struct {
+                    name: b_name,
+                    fields: b_fields,
+                    ..
+                },
+            ) => a_name == b_name && a_fields == b_fields,
+            (
+                Value::StructType {
+                    name: a_name,
+                    fields: a_fields,
+                    ..
+                },
+                Value::StructType {
+                    name: b_name,
+                    fields: b_fields,
+                    ..
+                },
+            ) => a_name == b_name && a_fields == b_fields,
+            (
+                Value::EnumType {
+                    name: a_name,
+                    variants: a_variants,
+                },
+                Value::EnumType {
+                    name: b_name,
+                    variants: b_variants,
+                },
+            ) => a_name == b_name && a_variants == b_variants,
+            (
+                Value::Variant {
+                    name: a_name,
+                    variant: a_variant,
+                },
+                Value::Variant {
+                    name: b_name,
+                    variant: b_variant,
+                },
+            ) => a_name == b_name && a_variant == b_variant,
+            (Value::Namespace { members: a, .. }, Value::Namespace { members: b, .. }) => {
+                Shared::ptr_eq(a, b)
+            }
+            (Value::External(a), Value::External(b)) => Shared::ptr_eq(a, b),
+            (Value::None, Value::None) => true,
+            _ => false,
+        }
+    }
+}
+
 impl fmt::Display for Value {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Value::Integer(value) => write!(f, "{value}"),
-            Value::Float(value) => write!(f, "{value}"),
+            Value::Float(value) => write!(f, "{}", formatting::float(*value)),
             Value::Boolean(true) => write!(f, "True"),
             Value::Boolean(false) => write!(f, "False"),
             Value::String(value) => write!(f, "{value}"),
+            Value::Bytes(bytes) => write!(f, "b\"{}\"", bytes.escape_ascii()),
             Value::Character(value) => write!(f, "{value}"),
             Value::List(list) => {
                 let format = list
@@ -57,8 +200,54 @@ impl fmt::Display for Value {
                     .join(", ");
                 write!(f, "[{format}]")
             }
-            Value::Function { name, .. } => write!(f, "<Function: {}>", name),
-            Value::BuiltInFunction { name, .. } => write!(f, "<Function: {}>", name),
+            Value::Tuple(values) => {
+                let format = values
+                    .iter()
+                    .map(|value| format!("{}", value))
+                    .collect::<Vec<String>>()
+                    .join(", ");
+                match values.len() {
+                    1 => write!(f, "({format},)"),
+                    _ => write!(f, "({format})"),
+                }
+            }
+            Value::Function {
+                name,
+                position,
+                arguments,
+                ..
+            } => write!(
+                f,
+                "<Function {}({}) at {}>",
+                name,
+                builtins::join(arguments.iter()),
+                position
+            ),
+            Value::BuiltInFunction {
+                name, arguments, ..
+            } => write!(
+                f,
+                "<Function {}({})>",
+                name,
+                builtins::join(arguments.iter())
+            ),
+            Value::Struct { name, fields, .. } => {
+                let format = fields
+                    .borrow()
+                    .iter()
+                    .map(|(field, value)| format!("{}: {}", field, value))
+                    .collect::<Vec<String>>()
+                    .join(", ");
+                match format.is_empty() {
+                    true => write!(f, "{name} {{}}"),
+                    false => write!(f, "{name} {{ {format} }}"),
+                }
+            }
+            Value::StructType { name, .. } => write!(f, "<Struct: {}>", name),
+            Value::EnumType { name, .. } => write!(f, "<Enum: {}>", name),
+            Value::Namespace { name, .. } => write!(f, "<Namespace: {}>", name),
+            Value::Variant { name, variant } => write!(f, "{}.{}", name, variant),
+            Value::External(external) => write!(f, "{external}"),
             Value::None => write!(f, "None"),
         }
     }
@@ -71,14 +260,99 @@ impl Value {
             Value::Float(_) => "Float",
             Value::Boolean(_) => "Boolean",
             Value::String(_) => "String",
+            Value::Bytes(_) => "Bytes",
             Value::Character(_) => "Character",
             Value::List(_) => "List",
+            Value::Tuple(_) => "Tuple",
             Value::Function { .. } => "Function",
             Value::BuiltInFunction { .. } => "BuiltInFunction",
+            Value::Struct { .. } => "Struct",
+            Value::StructType { .. } => "StructType",
+            Value::EnumType { .. } => "EnumType",
+            Value::Variant { .. } => "Enum",
+            Value::Namespace { .. } => "Namespace",
+            Value::External(external) => external.type_name(),
             Value::None => "None",
         }
     }
+
+    // Conditions only accept Booleans, this is what `bool(x)` converts by:
+    // zero, empty strings and collections and None are false.
+    pub fn is_truthy(&self) -> bool {
+        match self {
+            Value::Integer(integer) => *integer != 0,
+            Value::Float(float) => *float != 0.0,
+            Value::Boolean(boolean) => *boolean,
+            Value::String(string) => !string.is_empty(),
+            Value::Bytes(bytes) => !bytes.is_empty(),
+            Value::List(list) => !list.borrow().is_empty(),
+            Value::Tuple(values) => !values.is_empty(),
+            Value::None => false,
+            _ => true,
+        }
+    }
+
+    // Whether `item in self`, `None` when `self` can't contain it. Strings
+    // contain characters and substrings, bytes contain bytes and byte
+    // substrings, and lists and tuples contain equal values.
+    pub fn contains(&self, item: &Value) -> Option<bool> {
+        match (self, item) {
+            (Value::String(string), Value::Character(c)) => Some(string.contains(*c)),
+            (Value::String(string), Value::String(substring)) => {
+                Some(string.contains(substring.as_ref()))
+            }
+            (Value::Bytes(bytes), Value::Integer(byte)) => {
+                Some(u8::try_from(*byte).is_ok_and(|byte| bytes.contains(&byte)))
+            }
+            (Value::Bytes(bytes), Value::Bytes(needle)) => Some(
+                needle.is_empty()
+                    || bytes
+                        .windows(needle.len())
+                        .any(|window| window == &needle[..]),
+            ),
+            (Value::List(list), item) => Some(list.borrow().contains(item)),
+            (Value::Tuple(values), item) => Some(values.contains(item)),
+            _ => None,
+        }
+    }
+
+    // The order `<` and sorting use: numbers by value whatever their type,
+    // strings, byte strings and characters, and lists and tuples element by
+    // element. `None` for values of different types and for NaN.
+    pub fn compare(&self, other: &Value) -> Option<Ordering> {
+        match (self, other) {
+            (Value::Integer(a), Value::Integer(b)) => Some(a.cmp(b)),
+            (Value::Float(a), Value::Float(b)) => a.partial_cmp(b),
+            // Both convert to `f64` exactly.
+            (Value::Integer(a), Value::Float(b)) => f64::from(*a).partial_cmp(&f64::from(*b)),
+            (Value::Float(a), Value::Integer(b)) => f64::from(*a).partial_cmp(&f64::from(*b)),
+            (Value::String(a), Value::String(b)) => Some(a.cmp(b)),
+            (Value::Bytes(a), Value::Bytes(b)) => Some(a.cmp(b)),
+            (Value::Character(a), Value::Character(b)) => Some(a.cmp(b)),
+            (Value::Tuple(a), Value::Tuple(b)) => lexicographic(a, b),
+            (Value::List(a), Value::List(b)) => lexicographic(&a.borrow(), &b.borrow()),
+            _ => None,
+        }
+    }
+
     pub fn binary_operation(self, other: Self, operator: &Token) -> EvaluatorItem {
+        if operator.kind == TokenKind::Coalesce {
+            return Ok(match self {
+                Value::None => other,
+                value => value,
+            });
+        }
+        if let Value::External(external) = &self {
+            if let Some(result) = external.binary_operation(&other, operator) {
+                return result;
+            }
+        }
+        if let Value::External(external) = &other {
+            if let Some(result) = external.reflected_binary_operation(&self, operator) {
+                return result;
+            }
+        }
+
         match operator.kind {
             TokenKind::Add => self.add(other, operator),
             TokenKind::Sub => self.sub(other, operator),
@@ -94,28 +368,41 @@ impl Value {
             TokenKind::GreaterEq => self.greater_eq(other, operator),
             TokenKind::LessThan => self.less_than(other, operator),
             TokenKind::LessThanEq => self.less_than_eq(other, operator),
-            _ => unreachable!(),
+            TokenKind::In => match other.contains(&self) {
+                Some(contains) => Ok(Value::Boolean(contains)),
+                None => invalid_operation!(operator, Some(self), other),
+            },
+            _ => invalid_operation!(operator, Some(self), other),
         }
     }
 
     pub fn unary_operation(self, operator: &Token) -> EvaluatorItem {
+        if let Value::External(external) = &self {
+            if let Some(result) = external.unary_operation(operator) {
+                return result;
+            }
+        }
+
         match operator.kind {
             TokenKind::Add => self.pos(operator),
             TokenKind::Sub => self.neg(operator),
             TokenKind::Not => self.not(operator),
-            _ => unreachable!(),
+            _ => invalid_operation!(operator, None, self),
         }
     }
 
     pub fn index(self, index: Self, identifier: &Token) -> EvaluatorItem {
         match (self, &index) {
             (Value::String(string), Value::Integer(i)) => {
-                if i >= &0 && i < &(string.len() as i32) {
-                    return Ok(Value::Character(string.chars().nth(*i as usize).unwrap()));
+                // Strings are indexed by characters rather than bytes.
+                if let Some(character) =
+                    usize::try_from(*i).ok().and_then(|i| string.chars().nth(i))
+                {
+                    return Ok(Value::Character(character));
                 }
                 Runtime::InvalidIndex {
                     identifier: identifier.clone(),
-                    index,
+                    index: index.into(),
                 }
                 .into()
             }
@@ -126,13 +413,33 @@ impl Value {
                 }
                 Runtime::InvalidIndex {
                     identifier: identifier.clone(),
-                    index,
+                    index: index.into(),
+                }
+                .into()
+            }
+            (Value::Tuple(values), Value::Integer(i)) => {
+                if i >= &0 && i < &(values.len() as i32) {
+                    return Ok(values[*i as usize].clone());
+                }
+                Runtime::InvalidIndex {
+                    identifier: identifier.clone(),
+                    index: index.into(),
+                }
+                .into()
+            }
+            (Value::Bytes(bytes), Value::Integer(i)) => {
+                if let Some(byte) = usize::try_from(*i).ok().and_then(|i| bytes.get(i)) {
+                    return Ok(Value::Integer(*byte as i32));
+                }
+                Runtime::InvalidIndex {
+                    identifier: identifier.clone(),
+                    index: index.into(),
                 }
                 .into()
             }
             _ => Runtime::NonIndexable {
                 identifier: identifier.clone(),
-                index,
+                index: index.into(),
             }
             .into(),
         }
@@ -148,27 +455,94 @@ impl Value {
                 } else {
                     Runtime::InvalidIndex {
                         identifier: identifier.clone(),
-                        index,
+                        index: index.into(),
                     }
                     .into()
                 }
             }
             _ => Runtime::NonIndexable {
                 identifier: identifier.clone(),
-                index,
+                index: index.into(),
             }
             .into(),
         }
     }
 
+    pub fn member(&self, member: &Token) -> EvaluatorItem {
+        let TokenKind::Identifier(name) = &member.kind else {
+            return Runtime::UnknownMember {
+                value: self.clone().into(),
+                member: member.clone(),
+            }
+            .into();
+        };
+        match self {
+            Value::Struct { fields, .. } => {
+                if let Some((_, value)) = fields.borrow().iter().find(|(field, _)| field == name) {
+                    return Ok(value.clone());
+                }
+            }
+            Value::EnumType {
+                name: enum_name,
+                variants,
+            } if variants.contains(name) => {
+                return Ok(Value::Variant {
+                    name: enum_name.clone(),
+                    variant: name.clone(),
+                })
+            }
+            Value::Namespace { members, .. } => {
+                if let Some(value) = members.get(name) {
+                    return Ok(value.clone());
+                }
+            }
+            _ => {}
+        }
+        Runtime::UnknownMember {
+            value: self.clone().into(),
+            member: member.clone(),
+        }
+        .into()
+    }
+
+    pub fn member_assign(self, member: &Token, value: Self) -> EvaluatorItem {
+        let TokenKind::Identifier(name) = &member.kind else {
+            return Runtime::UnknownMember {
+                value: self.into(),
+                member: member.clone(),
+            }
+            .into();
+        };
+        if let Value::Struct { fields, .. } = &self {
+            if let Some((_, old)) = fields
+                .borrow_mut()
+                .iter_mut()
+                .find(|(field, _)| field == name)
+            {
+                *old = value;
+                return Ok(Value::None);
+            }
+        }
+        Runtime::UnknownMember {
+            value: self.into(),
+            member: member.clone(),
+        }
+        .into()
+    }
+
     fn add(self, other: Self, operator: &Token) -> EvaluatorItem {
         match (self, other) {
-            (Value::Integer(a), Value::Integer(b)) => Ok(Value::Integer(a + b)),
+            (Value::Integer(a), Value::Integer(b)) => checked!(a.checked_add(b), operator),
             (Value::Float(a), Value::Float(b)) => Ok(Value::Float(a + b)),
-            (Value::String(a), Value::String(b)) => Ok(Value::String(format!("{a}{b}"))),
-            (Value::Character(a), Value::Character(b)) => Ok(Value::String(format!("{a}{b}"))),
-            (Value::String(a), Value::Character(b)) => Ok(Value::String(format!("{a}{b}"))),
-            (Value::Character(a), Value::String(b)) => Ok(Value::String(format!("{a}{b}"))),
+            (Value::String(a), Value::String(b)) => Ok(Value::String(format!("{a}{b}").into())),
+            (Value::Bytes(a), Value::Bytes(b)) => {
+                Ok(Value::Bytes([&a[..], &b[..]].concat().into()))
+            }
+            (Value::Character(a), Value::Character(b)) => {
+                Ok(Value::String(format!("{a}{b}").into()))
+            }
+            (Value::String(a), Value::Character(b)) => Ok(Value::String(format!("{a}{b}").into())),
+            (Value::Character(a), Value::String(b)) => Ok(Value::String(format!("{a}{b}").into())),
             (right, left) => invalid_operation!(operator, Some(right), left),
         }
     }
@@ -183,7 +557,7 @@ impl Value {
 
     fn sub(self, other: Self, operator: &Token) -> EvaluatorItem {
         match (self, other) {
-            (Value::Integer(a), Value::Integer(b)) => Ok(Value::Integer(a - b)),
+            (Value::Integer(a), Value::Integer(b)) => checked!(a.checked_sub(b), operator),
             (Value::Float(a), Value::Float(b)) => Ok(Value::Float(a - b)),
             (right, left) => invalid_operation!(operator, Some(right), left),
         }
@@ -191,7 +565,7 @@ impl Value {
 
     fn neg(self, operator: &Token) -> EvaluatorItem {
         match self {
-            Value::Integer(a) => Ok(Value::Integer(-a)),
+            Value::Integer(a) => checked!(a.checked_neg(), operator),
             Value::Float(a) => Ok(Value::Float(-a)),
             left => invalid_operation!(operator, None, left),
         }
@@ -199,13 +573,11 @@ impl Value {
 
     fn mul(self, other: Self, operator: &Token) -> EvaluatorItem {
         match (self, other) {
-            (Value::Integer(a), Value::Integer(b)) => Ok(Value::Integer(a * b)),
+            (Value::Integer(a), Value::Integer(b)) => checked!(a.checked_mul(b), operator),
             (Value::Float(a), Value::Float(b)) => Ok(Value::Float(a * b)),
-            (Value::String(a), Value::Integer(b)) if b >= 0 => {
-                Ok(Value::String(a.repeat(b as usize)))
-            }
+            (Value::String(a), Value::Integer(b)) if b >= 0 => repeat(&a, b as usize),
             (Value::Character(a), Value::Integer(b)) if b >= 0 => {
-                Ok(Value::String(a.to_string().repeat(b as usize)))
+                repeat(&a.to_string(), b as usize)
             }
             (right, left) => invalid_operation!(operator, Some(right), left),
         }
@@ -213,15 +585,15 @@ impl Value {
 
     fn div(self, other: Self, operator: &Token) -> EvaluatorItem {
         match (self, other) {
-            (Value::Integer(_), Value::Integer(0)) => Err(Box::new(Runtime::DivisionByZero {
-                division: operator.clone(),
-            })),
-            (Value::Integer(a), Value::Integer(b)) => Ok(Value::Integer(a / b)),
-            (Value::Float(_), Value::Float(b)) if b == 0.0 => {
-                Err(Box::new(Runtime::DivisionByZero {
+            (Value::Integer(_), Value::Integer(0)) => {
+                Err(MonoError::from(Runtime::DivisionByZero {
                     division: operator.clone(),
                 }))
             }
+            (Value::Integer(a), Value::Integer(b)) => checked!(floored_div(a, b), operator),
+            (Value::Float(_), Value::Float(0.0)) => Err(MonoError::from(Runtime::DivisionByZero {
+                division: operator.clone(),
+            })),
             (Value::Float(a), Value::Float(b)) => Ok(Value::Float(a / b)),
             (right, left) => invalid_operation!(operator, Some(right), left),
         }
@@ -229,20 +601,34 @@ impl Value {
 
     fn modulo(self, other: Self, operator: &Token) -> EvaluatorItem {
         match (self, other) {
-            (Value::Integer(a), Value::Integer(b)) => Ok(Value::Integer(a % b)),
-            (Value::Float(a), Value::Float(b)) => Ok(Value::Float(a % b)),
+            (Value::Integer(_), Value::Integer(0)) => {
+                Err(MonoError::from(Runtime::DivisionByZero {
+                    division: operator.clone(),
+                }))
+            }
+            (Value::Integer(a), Value::Integer(b)) => Ok(Value::Integer(floored_rem(a, b))),
+            (Value::Float(a), Value::Float(b)) => Ok(Value::Float(floored_rem_float(a, b))),
             (right, left) => invalid_operation!(operator, Some(right), left),
         }
     }
 
+    // A negative Integer exponent gives a Float, `2 ^ -1` is `0.5`, and zero
+    // to a negative power is a division by zero as `1 / 0` is.
     fn pow(self, other: Self, operator: &Token) -> EvaluatorItem {
         match (self, other) {
             (Value::Integer(a), Value::Integer(b)) if b >= 0 => {
-                Ok(Value::Integer((a as f64).powi(b as i32) as i32))
+                checked!(a.checked_pow(b as u32), operator)
+            }
+            (Value::Integer(0) | Value::Float(0.0), ref b)
+                if b.compare(&Value::Integer(0)) == Some(Ordering::Less) =>
+            {
+                Err(MonoError::from(Runtime::DivisionByZero {
+                    division: operator.clone(),
+                }))
             }
-            (Value::Integer(a), Value::Integer(b)) => Ok(Value::Float((a as f32).powf(b as f32))),
+            (Value::Integer(a), Value::Integer(b)) => Ok(Value::Float((a as f32).powi(b))),
             (Value::Integer(a), Value::Float(b)) => Ok(Value::Float((a as f32).powf(b))),
-            (Value::Float(a), Value::Integer(b)) => Ok(Value::Float(a.powf(b as f32))),
+            (Value::Float(a), Value::Integer(b)) => Ok(Value::Float(a.powi(b))),
             (Value::Float(a), Value::Float(b)) => Ok(Value::Float(a.powf(b))),
             (right, left) => invalid_operation!(operator, Some(right), left),
         }
@@ -273,9 +659,22 @@ impl Value {
         match (self, other) {
             (Value::Integer(a), Value::Integer(b)) => Ok(Value::Boolean(a == b)),
             (Value::Float(a), Value::Float(b)) => Ok(Value::Boolean(a == b)),
+            (
+                a @ (Value::Integer(_) | Value::Float(_)),
+                b @ (Value::Integer(_) | Value::Float(_)),
+            ) => Ok(Value::Boolean(a == b)),
             (Value::Boolean(a), Value::Boolean(b)) => Ok(Value::Boolean(a == b)),
             (Value::String(a), Value::String(b)) => Ok(Value::Boolean(a == b)),
+            (Value::Bytes(a), Value::Bytes(b)) => Ok(Value::Boolean(a == b)),
             (Value::Character(a), Value::Character(b)) => Ok(Value::Boolean(a == b)),
+            (a @ Value::Struct { .. }, b @ Value::Struct { .. }) => Ok(Value::Boolean(a == b)),
+            (a @ Value::Tuple(_), b @ Value::Tuple(_)) => Ok(Value::Boolean(a == b)),
+            (a @ Value::List(_), b @ Value::List(_)) => Ok(Value::Boolean(a == b)),
+            (a @ Value::Variant { .. }, b @ Value::Variant { .. }) => Ok(Value::Boolean(a == b)),
+            (
+                a @ (Value::Function { .. } | Value::BuiltInFunction { .. }),
+                b @ (Value::Function { .. } | Value::BuiltInFunction { .. }),
+            ) => Ok(Value::Boolean(a == b)),
             (Value::None, Value::None) => Ok(Value::Boolean(true)),
             (_, Value::None) | (Value::None, _) => Ok(Value::Boolean(false)),
             (right, left) => invalid_operation!(operator, Some(right), left),
@@ -286,9 +685,22 @@ impl Value {
         match (self, other) {
             (Value::Integer(a), Value::Integer(b)) => Ok(Value::Boolean(a != b)),
             (Value::Float(a), Value::Float(b)) => Ok(Value::Boolean(a != b)),
+            (
+                a @ (Value::Integer(_) | Value::Float(_)),
+                b @ (Value::Integer(_) | Value::Float(_)),
+            ) => Ok(Value::Boolean(a != b)),
             (Value::Boolean(a), Value::Boolean(b)) => Ok(Value::Boolean(a ^ b)),
             (Value::String(a), Value::String(b)) => Ok(Value::Boolean(a != b)),
+            (Value::Bytes(a), Value::Bytes(b)) => Ok(Value::Boolean(a != b)),
             (Value::Character(a), Value::Character(b)) => Ok(Value::Boolean(a != b)),
+            (a @ Value::Struct { .. }, b @ Value::Struct { .. }) => Ok(Value::Boolean(a != b)),
+            (a @ Value::Tuple(_), b @ Value::Tuple(_)) => Ok(Value::Boolean(a != b)),
+            (a @ Value::List(_), b @ Value::List(_)) => Ok(Value::Boolean(a != b)),
+            (a @ Value::Variant { .. }, b @ Value::Variant { .. }) => Ok(Value::Boolean(a != b)),
+            (
+                a @ (Value::Function { .. } | Value::BuiltInFunction { .. }),
+                b @ (Value::Function { .. } | Value::BuiltInFunction { .. }),
+            ) => Ok(Value::Boolean(a != b)),
             (Value::None, Value::None) => Ok(Value::Boolean(false)),
             (_, Value::None) | (Value::None, _) => Ok(Value::Boolean(true)),
             (right, left) => invalid_operation!(operator, Some(right), left),
@@ -296,43 +708,90 @@ impl Value {
     }
 
     fn greater(self, other: Self, operator: &Token) -> EvaluatorItem {
-        match (self, other) {
-            (Value::Integer(a), Value::Integer(b)) => Ok(Value::Boolean(a > b)),
-            (Value::Float(a), Value::Float(b)) => Ok(Value::Boolean(a > b)),
-            (Value::String(a), Value::String(b)) => Ok(Value::Boolean(a > b)),
-            (Value::Character(a), Value::Character(b)) => Ok(Value::Boolean(a > b)),
-            (right, left) => invalid_operation!(operator, Some(right), left),
-        }
+        self.ordered(other, operator, Ordering::is_gt)
     }
 
     fn greater_eq(self, other: Self, operator: &Token) -> EvaluatorItem {
-        match (self, other) {
-            (Value::Integer(a), Value::Integer(b)) => Ok(Value::Boolean(a >= b)),
-            (Value::Float(a), Value::Float(b)) => Ok(Value::Boolean(a >= b)),
-            (Value::String(a), Value::String(b)) => Ok(Value::Boolean(a >= b)),
-            (Value::Character(a), Value::Character(b)) => Ok(Value::Boolean(a >= b)),
-            (right, left) => invalid_operation!(operator, Some(right), left),
-        }
+        self.ordered(other, operator, Ordering::is_ge)
     }
 
     fn less_than(self, other: Self, operator: &Token) -> EvaluatorItem {
-        match (self, other) {
-            (Value::Integer(a), Value::Integer(b)) => Ok(Value::Boolean(a < b)),
-            (Value::Float(a), Value::Float(b)) => Ok(Value::Boolean(a < b)),
-            (Value::String(a), Value::String(b)) => Ok(Value::Boolean(a < b)),
-            (Value::Character(a), Value::Character(b)) => Ok(Value::Boolean(a < b)),
-            (right, left) => invalid_operation!(operator, Some(right), left),
-        }
+        self.ordered(other, operator, Ordering::is_lt)
     }
 
     fn less_than_eq(self, other: Self, operator: &Token) -> EvaluatorItem {
-        match (self, other) {
-            (Value::Integer(a), Value::Integer(b)) => Ok(Value::Boolean(a <= b)),
-            (Value::Float(a), Value::Float(b)) => Ok(Value::Boolean(a <= b)),
-            (Value::String(a), Value::String(b)) => Ok(Value::Boolean(a <= b)),
-            (Value::Character(a), Value::Character(b)) => Ok(Value::Boolean(a <= b)),
-            (right, left) => invalid_operation!(operator, Some(right), left),
+        self.ordered(other, operator, Ordering::is_le)
+    }
+
+    // Whether the values are in an order `is_ordered` accepts. A NaN isn't
+    // in any order with a number, as with floats in Rust.
+    fn ordered(
+        self,
+        other: Self,
+        operator: &Token,
+        is_ordered: fn(Ordering) -> bool,
+    ) -> EvaluatorItem {
+        match (&self, &other) {
+            (Value::Float(a), Value::Integer(_) | Value::Float(_)) if a.is_nan() => {
+                Ok(Value::Boolean(false))
+            }
+            (Value::Integer(_) | Value::Float(_), Value::Float(b)) if b.is_nan() => {
+                Ok(Value::Boolean(false))
+            }
+            _ => match self.compare(&other) {
+                Some(ordering) => Ok(Value::Boolean(is_ordered(ordering))),
+                None => invalid_operation!(operator, Some(self), other),
+            },
+        }
+    }
+}
+
+// Integer division rounds down rather than toward zero, so `-7 / 2` is `-4`,
+// and the remainder takes the sign of the divisor, so `-7 % 3` is `2`. The
+// two agree: `a == (a / b) * b + a % b`.
+fn floored_div(a: i32, b: i32) -> Option<i32> {
+    let quotient = a.checked_div(b)?;
+    match a % b != 0 && (a < 0) != (b < 0) {
+        true => Some(quotient - 1),
+        false => Some(quotient),
+    }
+}
+
+// Never overflows, `i32::MIN % -1` is `0`.
+fn floored_rem(a: i32, b: i32) -> i32 {
+    let remainder = a.wrapping_rem(b);
+    match remainder != 0 && (remainder < 0) != (b < 0) {
+        true => remainder + b,
+        false => remainder,
+    }
+}
+
+fn floored_rem_float(a: f32, b: f32) -> f32 {
+    let remainder = a % b;
+    match remainder != 0.0 && (remainder < 0.0) != (b < 0.0) {
+        true => remainder + b,
+        false => remainder,
+    }
+}
+
+fn lexicographic(a: &[Value], b: &[Value]) -> Option<Ordering> {
+    for (a, b) in a.iter().zip(b) {
+        match a.compare(b)? {
+            Ordering::Equal => continue,
+            ordering => return Some(ordering),
+        }
+    }
+    Some(a.len().cmp(&b.len()))
+}
+
+fn repeat(string: &str, times: usize) -> EvaluatorItem {
+    match limits::maximum_length() {
+        Some(maximum) if string.len().saturating_mul(times) > maximum => Runtime::LimitExceeded {
+            limit: "elements",
+            maximum,
         }
+        .into(),
+        _ => Ok(Value::String(string.repeat(times).into())),
     }
 }
 
@@ -342,10 +801,11 @@ impl From<&Token> for Value {
             TokenKind::Integer(value) => Self::Integer(*value),
             TokenKind::Float(value) => Self::Float(*value),
             TokenKind::Boolean(value) => Self::Boolean(*value),
-            TokenKind::String(value) => Self::String(value.to_string()),
+            TokenKind::String(value) => Self::String(value.as_str().into()),
+            TokenKind::Bytes(value) => Self::Bytes(value.as_slice().into()),
             TokenKind::Character(value) => Self::Character(*value),
-            TokenKind::None => Self::None,
-            _ => unreachable!(),
+            // Only literal tokens are converted, the parser makes sure of it.
+            _ => Self::None,
         }
     }
 }
@@ -0,0 +1,277 @@
+//! Dates and times built on top of `Value::External`.
+//!
+//! A `DateTime` is a UTC instant with second precision. Adding or subtracting
+//! an integer moves it by that many seconds (`days(n)` converts days to
+//! seconds) and subtracting two of them gives the seconds between them.
+
+use crate::evaluator::external::External;
+use crate::evaluator::shared::Shared;
+use crate::evaluator::value::Value;
+use crate::evaluator::EvaluatorItem;
+use crate::tokenizer::token::{Token, TokenKind};
+use std::any::Any;
+use std::fmt;
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const DAY: i64 = 24 * 60 * 60;
+const FORMAT: &str = "%Y-%m-%dT%H:%M:%SZ";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct DateTime {
+    // Seconds since the Unix epoch.
+    timestamp: i64,
+}
+
+impl DateTime {
+    pub fn from_timestamp(timestamp: i64) -> Self {
+        Self { timestamp }
+    }
+
+    pub fn timestamp(&self) -> i64 {
+        self.timestamp
+    }
+
+    // Returns `None` for a date that doesn't exist, such as February 30th.
+    pub fn from_parts(
+        (year, month, day): (i64, i64, i64),
+        (hour, minute, second): (i64, i64, i64),
+    ) -> Option<Self> {
+        let days = days_from_civil(year, month, day);
+        if civil_from_days(days) != (year, month, day)
+            || !(0..24).contains(&hour)
+            || !(0..60).contains(&minute)
+            || !(0..60).contains(&second)
+        {
+            return None;
+        }
+        Some(Self::from_timestamp(
+            days * DAY + hour * 3600 + minute * 60 + second,
+        ))
+    }
+
+    // The date as (year, month, day) and the time as (hour, minute, second).
+    pub fn parts(&self) -> ((i64, i64, i64), (i64, i64, i64)) {
+        let (days, seconds) = (
+            self.timestamp.div_euclid(DAY),
+            self.timestamp.rem_euclid(DAY),
+        );
+        (
+            civil_from_days(days),
+            (seconds / 3600, seconds % 3600 / 60, seconds % 60),
+        )
+    }
+
+    // Supports `%Y`, `%m`, `%d`, `%H`, `%M`, `%S` and `%%`, other characters
+    // are copied as they are.
+    pub fn format(&self, format: &str) -> Option<String> {
+        let ((year, month, day), (hour, minute, second)) = self.parts();
+        let mut formatted = String::new();
+        let mut chars = format.chars();
+        while let Some(c) = chars.next() {
+            if c != '%' {
+                formatted.push(c);
+                continue;
+            }
+            match chars.next()? {
+                'Y' => formatted.push_str(&format!("{:04}", year)),
+                'm' => formatted.push_str(&format!("{:02}", month)),
+                'd' => formatted.push_str(&format!("{:02}", day)),
+                'H' => formatted.push_str(&format!("{:02}", hour)),
+                'M' => formatted.push_str(&format!("{:02}", minute)),
+                'S' => formatted.push_str(&format!("{:02}", second)),
+                '%' => formatted.push('%'),
+                _ => return None,
+            }
+        }
+        Some(formatted)
+    }
+
+    // The inverse of `format`. Fields missing from the format default to the
+    // start of the epoch's day, month and year.
+    pub fn parse(string: &str, format: &str) -> Option<Self> {
+        let (mut date, mut time) = ((1970, 1, 1), (0, 0, 0));
+        let mut input = string.chars().peekable();
+        let mut chars = format.chars();
+        while let Some(c) = chars.next() {
+            if c != '%' {
+                if input.next()? != c {
+                    return None;
+                }
+                continue;
+            }
+            let specifier = chars.next()?;
+            if specifier == '%' {
+                if input.next()? != '%' {
+                    return None;
+                }
+                continue;
+            }
+            let width = if specifier == 'Y' { 4 } else { 2 };
+            let mut digits = String::new();
+            while let Some(digit) = input.next_if(|c| c.is_ascii_digit()) {
+                digits.push(digit);
+                if digits.len() == width {
+                    break;
+                }
+            }
+            let number = digits.parse::<i64>().ok()?;
+            match specifier {
+                'Y' => date.0 = number,
+                'm' => date.1 = number,
+                'd' => date.2 = number,
+                'H' => time.0 = number,
+                'M' => time.1 = number,
+                'S' => time.2 = number,
+                _ => return None,
+            }
+        }
+        match input.next() {
+            Some(_) => None,
+            None => Self::from_parts(date, time),
+        }
+    }
+
+    fn into_value(self) -> Value {
+        Value::External(Shared::new(self))
+    }
+
+    fn from_value(value: &Value) -> Option<Self> {
+        match value {
+            Value::External(external) => external.downcast_ref::<Self>().copied(),
+            _ => None,
+        }
+    }
+
+    fn shift(&self, seconds: i32) -> Option<Value> {
+        let timestamp = self.timestamp.checked_add(seconds as i64)?;
+        Some(Self::from_timestamp(timestamp).into_value())
+    }
+}
+
+impl fmt::Display for DateTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let formatted = self.format(FORMAT).unwrap_or_default();
+        write!(f, "{}", formatted)
+    }
+}
+
+impl External for DateTime {
+    fn type_name(&self) -> &'static str {
+        "DateTime"
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn binary_operation(&self, other: &Value, operator: &Token) -> Option<EvaluatorItem> {
+        if let Value::Integer(seconds) = other {
+            return match operator.kind {
+                TokenKind::Add => self.shift(*seconds),
+                TokenKind::Sub => self.shift(seconds.checked_neg()?),
+                _ => None,
+            }
+            .map(Ok);
+        }
+        let other = Self::from_value(other)?;
+        let result = match operator.kind {
+            TokenKind::Sub => Value::Integer(i32::try_from(self.timestamp - other.timestamp).ok()?),
+            TokenKind::Equals => Value::Boolean(*self == other),
+            TokenKind::NotEquals => Value::Boolean(*self != other),
+            TokenKind::Greater => Value::Boolean(*self > other),
+            TokenKind::GreaterEq => Value::Boolean(*self >= other),
+            TokenKind::LessThan => Value::Boolean(*self < other),
+            TokenKind::LessThanEq => Value::Boolean(*self <= other),
+            _ => return None,
+        };
+        Some(Ok(result))
+    }
+
+    fn reflected_binary_operation(&self, other: &Value, operator: &Token) -> Option<EvaluatorItem> {
+        match (other, &operator.kind) {
+            (Value::Integer(seconds), TokenKind::Add) => self.shift(*seconds).map(Ok),
+            _ => None,
+        }
+    }
+}
+
+// Days since the epoch of a date in the proleptic Gregorian calendar, after
+// Howard Hinnant's `days_from_civil`.
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146097 + day_of_era - 719468
+}
+
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719468;
+    let era = days.div_euclid(146097);
+    let day_of_era = days - era * 146097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + (month <= 2) as i64;
+    (year, month, day)
+}
+
+// The browser has no system clock, so `now` isn't available there.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn now(_values: Vec<Value>) -> Value {
+    match SystemTime::now().duration_since(UNIX_EPOCH) {
+        Ok(elapsed) => DateTime::from_timestamp(elapsed.as_secs() as i64).into_value(),
+        Err(_) => Value::None,
+    }
+}
+
+pub fn date_format(values: Vec<Value>) -> Value {
+    match (DateTime::from_value(&values[0]), &values[1]) {
+        (Some(datetime), Value::String(format)) => datetime
+            .format(format)
+            .map_or(Value::None, |formatted| Value::String(formatted.into())),
+        _ => Value::None,
+    }
+}
+
+pub fn date_parse(values: Vec<Value>) -> Value {
+    match (&values[0], &values[1]) {
+        (Value::String(string), Value::String(format)) => {
+            DateTime::parse(string, format).map_or(Value::None, DateTime::into_value)
+        }
+        _ => Value::None,
+    }
+}
+
+pub fn timestamp(values: Vec<Value>) -> Value {
+    match DateTime::from_value(&values[0]) {
+        Some(datetime) => i32::try_from(datetime.timestamp).map_or(Value::None, Value::Integer),
+        None => Value::None,
+    }
+}
+
+pub fn from_timestamp(values: Vec<Value>) -> Value {
+    match values[0] {
+        Value::Integer(seconds) => DateTime::from_timestamp(seconds as i64).into_value(),
+        _ => Value::None,
+    }
+}
+
+// The number of seconds in that many days, to shift a `DateTime` by.
+pub fn days(values: Vec<Value>) -> Value {
+    match values[0] {
+        Value::Integer(days) => days
+            .checked_mul(DAY as i32)
+            .map_or(Value::None, Value::Integer),
+        _ => Value::None,
+    }
+}
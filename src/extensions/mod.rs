@@ -0,0 +1,4 @@
+pub mod datetime;
+pub mod string_builder;
+#[cfg(feature = "units")]
+pub mod units;
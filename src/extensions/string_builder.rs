@@ -0,0 +1,78 @@
+//! A growable string built on top of `Value::External`.
+//!
+//! Strings are immutable, so `s = s + piece` copies `s` every time and
+//! building a string in a loop is quadratic. A `StringBuilder` appends in
+//! place and `build` copies its contents out once.
+
+use crate::evaluator::external::External;
+use crate::evaluator::limits;
+use crate::evaluator::shared::{Mutable, Shared};
+use crate::evaluator::value::Value;
+use std::any::Any;
+use std::fmt;
+
+#[derive(Debug, Default)]
+pub struct StringBuilder {
+    buffer: Mutable<String>,
+}
+
+impl StringBuilder {
+    // A builder already holding the contents, as a saved one is restored.
+    pub fn new(contents: String) -> Self {
+        Self {
+            buffer: Mutable::new(contents),
+        }
+    }
+
+    pub fn contents(&self) -> String {
+        self.buffer.borrow().clone()
+    }
+}
+
+impl fmt::Display for StringBuilder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "<StringBuilder: {:?}>", self.buffer.borrow().as_str())
+    }
+}
+
+impl External for StringBuilder {
+    fn type_name(&self) -> &'static str {
+        "StringBuilder"
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+fn builder(value: &Value) -> Option<&StringBuilder> {
+    match value {
+        Value::External(external) => external.downcast_ref::<StringBuilder>(),
+        _ => None,
+    }
+}
+
+pub fn string_builder(_values: Vec<Value>) -> Value {
+    Value::External(Shared::new(StringBuilder::default()))
+}
+
+// Appends any value as it would be printed. Like any other string, the
+// builder can't grow past the evaluator's length limit.
+pub fn append(values: Vec<Value>) -> Value {
+    if let Some(builder) = builder(&values[0]) {
+        let piece = values[1].to_string();
+        let mut buffer = builder.buffer.borrow_mut();
+        if limits::maximum_length().is_some_and(|maximum| buffer.len() + piece.len() > maximum) {
+            return Value::None;
+        }
+        buffer.push_str(&piece);
+    }
+    Value::None
+}
+
+pub fn build(values: Vec<Value>) -> Value {
+    match builder(&values[0]) {
+        Some(builder) => Value::from(builder.buffer.borrow().as_str()),
+        None => Value::None,
+    }
+}
@@ -0,0 +1,269 @@
+//! Units of measure built on top of `Value::External`.
+//!
+//! Quantities are stored in SI base units and carry their dimension, so
+//! `unit(3, "km") / unit(20, "min")` evaluates to `2.5 m/s`. Call
+//! [`register`] to expose the `unit` and `magnitude` builtins to a script.
+
+use crate::evaluator::external::External;
+use crate::evaluator::shared::Shared;
+use crate::evaluator::value::Value;
+use crate::evaluator::{Evaluator, EvaluatorItem};
+use crate::models::error::Runtime;
+use crate::tokenizer::token::{Token, TokenKind};
+use std::any::Any;
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Dimension {
+    length: i32,
+    mass: i32,
+    time: i32,
+}
+
+impl Dimension {
+    const LENGTH: Self = Self::new(1, 0, 0);
+    const MASS: Self = Self::new(0, 1, 0);
+    const TIME: Self = Self::new(0, 0, 1);
+
+    pub const fn new(length: i32, mass: i32, time: i32) -> Self {
+        Self { length, mass, time }
+    }
+
+    pub fn is_dimensionless(&self) -> bool {
+        *self == Self::default()
+    }
+
+    fn combine(self, other: Self, sign: i32) -> Self {
+        Self::new(
+            self.length + sign * other.length,
+            self.mass + sign * other.mass,
+            self.time + sign * other.time,
+        )
+    }
+
+    fn scale(self, exponent: i32) -> Self {
+        Self::new(
+            self.length * exponent,
+            self.mass * exponent,
+            self.time * exponent,
+        )
+    }
+}
+
+impl fmt::Display for Dimension {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let symbols = [("kg", self.mass), ("m", self.length), ("s", self.time)];
+        let format = |positive: bool| {
+            symbols
+                .iter()
+                .filter(|(_, exponent)| *exponent != 0 && (*exponent > 0) == positive)
+                .map(|(symbol, exponent)| match exponent.abs() {
+                    1 => symbol.to_string(),
+                    n => format!("{symbol}^{n}"),
+                })
+                .collect::<Vec<String>>()
+                .join("*")
+        };
+
+        match (format(true), format(false)) {
+            (numerator, denominator) if denominator.is_empty() => write!(f, "{numerator}"),
+            (numerator, denominator) if numerator.is_empty() => write!(f, "1/{denominator}"),
+            (numerator, denominator) => write!(f, "{numerator}/{denominator}"),
+        }
+    }
+}
+
+const UNITS: [(&str, f32, Dimension); 10] = [
+    ("m", 1.0, Dimension::LENGTH),
+    ("km", 1000.0, Dimension::LENGTH),
+    ("cm", 0.01, Dimension::LENGTH),
+    ("mm", 0.001, Dimension::LENGTH),
+    ("kg", 1.0, Dimension::MASS),
+    ("g", 0.001, Dimension::MASS),
+    ("s", 1.0, Dimension::TIME),
+    ("ms", 0.001, Dimension::TIME),
+    ("min", 60.0, Dimension::TIME),
+    ("h", 3600.0, Dimension::TIME),
+];
+
+// Parses unit expressions such as `km`, `m/s^2` or `kg*m/s^2` into the factor
+// converting them to SI base units and their dimension.
+pub fn parse_unit(unit: &str) -> Option<(f32, Dimension)> {
+    let mut factor = 1.0;
+    let mut dimension = Dimension::default();
+
+    for (index, part) in unit.split('/').enumerate() {
+        let sign = if index == 0 { 1 } else { -1 };
+        for symbol in part.split('*') {
+            let (symbol, exponent) = match symbol.trim().split_once('^') {
+                Some((symbol, exponent)) => (symbol, exponent.parse::<i32>().ok()?),
+                None => (symbol.trim(), 1),
+            };
+            let (_, scale, base) = UNITS.iter().find(|(name, ..)| *name == symbol)?;
+            factor *= scale.powi(sign * exponent);
+            dimension = dimension.combine(base.scale(exponent), sign);
+        }
+    }
+
+    Some((factor, dimension))
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Quantity {
+    magnitude: f32,
+    dimension: Dimension,
+}
+
+impl Quantity {
+    pub fn new(magnitude: f32, dimension: Dimension) -> Self {
+        Self {
+            magnitude,
+            dimension,
+        }
+    }
+
+    pub fn magnitude(&self) -> f32 {
+        self.magnitude
+    }
+
+    pub fn dimension(&self) -> Dimension {
+        self.dimension
+    }
+
+    fn into_value(self) -> Value {
+        if self.dimension.is_dimensionless() {
+            return Value::Float(self.magnitude);
+        }
+        Value::External(Shared::new(self))
+    }
+
+    fn from_value(value: &Value) -> Option<Self> {
+        match value {
+            Value::Integer(integer) => Some(Self::new(*integer as f32, Dimension::default())),
+            Value::Float(float) => Some(Self::new(*float, Dimension::default())),
+            Value::External(external) => external.downcast_ref::<Self>().cloned(),
+            _ => None,
+        }
+    }
+
+    fn operate(&self, other: &Self, operator: &Token) -> Option<Value> {
+        let same = self.dimension == other.dimension;
+        let (a, b) = (self.magnitude, other.magnitude);
+
+        let result = match operator.kind {
+            TokenKind::Add if same => Self::new(a + b, self.dimension).into_value(),
+            TokenKind::Sub if same => Self::new(a - b, self.dimension).into_value(),
+            TokenKind::Mul => {
+                Self::new(a * b, self.dimension.combine(other.dimension, 1)).into_value()
+            }
+            TokenKind::Div => {
+                Self::new(a / b, self.dimension.combine(other.dimension, -1)).into_value()
+            }
+            TokenKind::Equals => Value::Boolean(same && a == b),
+            TokenKind::NotEquals => Value::Boolean(!same || a != b),
+            TokenKind::Greater if same => Value::Boolean(a > b),
+            TokenKind::GreaterEq if same => Value::Boolean(a >= b),
+            TokenKind::LessThan if same => Value::Boolean(a < b),
+            TokenKind::LessThanEq if same => Value::Boolean(a <= b),
+            _ => return None,
+        };
+        Some(result)
+    }
+}
+
+impl fmt::Display for Quantity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.magnitude, self.dimension)
+    }
+}
+
+impl External for Quantity {
+    fn type_name(&self) -> &'static str {
+        "Quantity"
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn binary_operation(&self, other: &Value, operator: &Token) -> Option<EvaluatorItem> {
+        if let (TokenKind::Pow, Value::Integer(exponent)) = (&operator.kind, other) {
+            let magnitude = self.magnitude.powi(*exponent);
+            return Some(Ok(
+                Self::new(magnitude, self.dimension.scale(*exponent)).into_value()
+            ));
+        }
+        let other = Self::from_value(other)?;
+        if operator.kind == TokenKind::Div && other.magnitude == 0.0 {
+            return Some(
+                Runtime::DivisionByZero {
+                    division: operator.clone(),
+                }
+                .into(),
+            );
+        }
+        self.operate(&other, operator).map(Ok)
+    }
+
+    fn reflected_binary_operation(&self, other: &Value, operator: &Token) -> Option<EvaluatorItem> {
+        if operator.kind == TokenKind::Div && self.magnitude == 0.0 {
+            return Some(
+                Runtime::DivisionByZero {
+                    division: operator.clone(),
+                }
+                .into(),
+            );
+        }
+        let other = Self::from_value(other)?;
+        other.operate(self, operator).map(Ok)
+    }
+
+    fn unary_operation(&self, operator: &Token) -> Option<EvaluatorItem> {
+        match operator.kind {
+            TokenKind::Add => Some(Ok(self.clone().into_value())),
+            TokenKind::Sub => Some(Ok(Self::new(-self.magnitude, self.dimension).into_value())),
+            _ => None,
+        }
+    }
+}
+
+pub fn unit(values: Vec<Value>) -> Value {
+    match (Quantity::from_value(&values[0]), &values[1]) {
+        (Some(quantity), Value::String(symbol)) if quantity.dimension.is_dimensionless() => {
+            match parse_unit(symbol) {
+                Some((factor, dimension)) => {
+                    Quantity::new(quantity.magnitude * factor, dimension).into_value()
+                }
+                None => Value::None,
+            }
+        }
+        _ => Value::None,
+    }
+}
+
+pub fn magnitude(values: Vec<Value>) -> Value {
+    match (Quantity::from_value(&values[0]), &values[1]) {
+        (Some(quantity), Value::String(symbol)) => match parse_unit(symbol) {
+            Some((factor, dimension)) if dimension == quantity.dimension => {
+                Value::Float(quantity.magnitude / factor)
+            }
+            _ => Value::None,
+        },
+        _ => Value::None,
+    }
+}
+
+pub fn register(evaluator: &mut Evaluator) {
+    evaluator.register_builtin(
+        "unit",
+        vec!["magnitude", "unit"],
+        "A quantity of the magnitude in the unit, such as `unit(3, \"km\")`.",
+        unit,
+    );
+    evaluator.register_builtin(
+        "magnitude",
+        vec!["quantity", "unit"],
+        "The quantity's magnitude in the unit, `None` when their dimensions differ.",
+        magnitude,
+    );
+}
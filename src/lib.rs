@@ -1,23 +1,70 @@
+//! Mono, an interpreted programming language.
+//!
+//! Source goes through a single pipeline: the `Tokenizer` turns characters
+//! into tokens, the `Parser` builds and resolves a `Node` tree, the
+//! `Optimizer` may fold it and the `Evaluator` runs it to a `Value`. Errors
+//! from every stage are a `MonoError`.
+//!
+//! `tokenize`, `parse` and `eval` run the pipeline over a string, the types
+//! are re-exported here for anything more involved, such as keeping an
+//! `Evaluator` across programs.
+#![allow(clippy::vec_box)]
+
+pub mod benchmark;
+pub mod bundle;
+pub mod cache;
+pub mod codegen;
+pub mod debugger;
 pub mod evaluator;
+pub mod extensions;
+pub mod linter;
+#[cfg(feature = "lsp")]
+pub mod lsp;
 pub mod models;
+pub mod optimizer;
 pub mod parser;
+pub mod project;
+pub mod serve;
 pub mod tokenizer;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+pub use crate::evaluator::value::Value;
+pub use crate::evaluator::Evaluator;
+pub use crate::models::error::MonoError;
+pub use crate::optimizer::Optimizer;
+pub use crate::parser::node::Node;
+pub use crate::parser::Parser;
+pub use crate::tokenizer::token::Token;
+pub use crate::tokenizer::Tokenizer;
 
-use crate::evaluator::value::Value;
-use crate::evaluator::Evaluator;
-use crate::parser::Parser;
-use crate::tokenizer::Tokenizer;
+use crate::bundle::{BuildError, Bundle};
+use crate::debugger::Debugger;
+use crate::evaluator::limits::Limits;
+use crate::linter::{Lint, Linter, Rule};
+use crate::models::diagnostic::Diagnostic;
+use crate::parser::ParserToken;
+use crate::tokenizer::token::TokenKind;
 use colored::*;
+use std::io::Read;
+use std::ops::ControlFlow;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use std::{fs, io, thread};
 
 macro_rules! ereport {
     ($color:ident, $header:expr, $error:expr) => {
-        eprintln!(
-            "{}\n{}{} {}\n",
-            $header.$color().bold(),
-            ($error.kind()).$color().underline(),
-            ":".red(),
-            (format!("{}", $error)).$color()
-        )
+        match error_format() {
+            ErrorFormat::Json => eprintln!("{}", Diagnostic::from($error.as_ref()).to_json()),
+            ErrorFormat::Human => eprintln!(
+                "{}\n{}{} {}\n",
+                $header.$color().bold(),
+                ($error.kind()).$color().underline(),
+                ":".red(),
+                (format!("{}", $error)).$color()
+            ),
+        }
     };
 }
 
@@ -27,11 +74,69 @@ macro_rules! report {
     };
 }
 
-pub fn tokenizer(code: &str) {
+// How the CLI reports errors and lints: for people, or as one JSON
+// `Diagnostic` per line for editors and CI.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum ErrorFormat {
+    #[default]
+    Human,
+    Json,
+}
+
+static JSON_ERRORS: AtomicBool = AtomicBool::new(false);
+
+pub fn set_error_format(format: ErrorFormat) {
+    JSON_ERRORS.store(format == ErrorFormat::Json, Ordering::Relaxed);
+}
+
+pub fn error_format() -> ErrorFormat {
+    match JSON_ERRORS.load(Ordering::Relaxed) {
+        true => ErrorFormat::Json,
+        false => ErrorFormat::Human,
+    }
+}
+
+// How deeply the CLI's programs may nest calls, and the stack it runs them
+// on, which holds that many calls with room to spare even in a debug build.
+// Deep recursion is then a runtime error rather than a stack overflow.
+pub const MAXIMUM_DEPTH: usize = 1_000;
+pub const STACK_SIZE: usize = 256 * 1024 * 1024;
+
+// A fresh evaluator as the CLI runs programs in, limited to `MAXIMUM_DEPTH`.
+pub fn session() -> Evaluator {
+    let mut evaluator = Evaluator::new();
+    evaluator.set_limits(Limits {
+        depth: Some(MAXIMUM_DEPTH),
+        ..Limits::default()
+    });
+    evaluator
+}
+
+// The tokens of the code, or the first error. Never panics, whatever the
+// input, which makes it an entry point for fuzzing.
+pub fn tokenize(code: &str) -> Result<Vec<Token>, MonoError> {
+    Tokenizer::new(code.chars()).collect()
+}
+
+// The resolved program, or the first error. Never panics either.
+pub fn parse(code: &str) -> Result<Box<Node>, MonoError> {
+    Parser::new(Tokenizer::new(code.chars())).parse()
+}
+
+// Runs the code in a fresh evaluator, returning its value or the first
+// error. Like the two above, malformed code is an error and never a panic.
+pub fn eval(code: &str) -> Result<Value, MonoError> {
+    let program = parse(code)?;
+    Evaluator::new().evaluate(&program)
+}
+
+// The CLI's modes print what they produce and report any error, returning
+// the result so the caller can tell how the program ended.
+pub fn tokenizer(code: &str) -> Result<Vec<Token>, MonoError> {
     let tok = Tokenizer::new(code.chars());
     let results: Result<Vec<_>, _> = tok.collect();
 
-    match results {
+    match &results {
         Ok(tokens) => {
             let tokens_string = tokens
                 .iter()
@@ -42,34 +147,259 @@ pub fn tokenizer(code: &str) {
         }
         Err(error) => ereport!(red, "Error", error),
     }
+    results
 }
 
-pub fn parser(code: &str) {
+pub fn parser(code: &str) -> Result<Box<Node>, MonoError> {
     let tokenizer = Tokenizer::new(code.chars());
     let mut parser = Parser::new(tokenizer);
-    match parser.parse() {
+    let result = parser.parse();
+    match &result {
         Err(error) => {
             ereport!(red, "Parser Error", error);
-            return;
         }
         Ok(ast) => report!(green, "Ok", format!("{}", ast)),
     }
+    result
 }
 
-pub fn evaluator(code: &str, evaluator: &mut Evaluator) {
-    let tokenizer = Tokenizer::new(code.chars());
-    let mut parser = Parser::new(tokenizer);
+pub fn profiler(code: &str) -> Result<Value, MonoError> {
+    let mut profiled = session();
+    profiled.enable_profiling();
+    let result = evaluator(code, &mut profiled);
+    if let Some(profile) = profiled.profile() {
+        report!(blue, "Profile", format!("{}", profile));
+    }
+    result
+}
+
+pub fn evaluator(code: &str, evaluator: &mut Evaluator) -> Result<Value, MonoError> {
+    evaluate(parse(code), evaluator, false)
+}
+
+pub fn optimized(code: &str, evaluator: &mut Evaluator) -> Result<Value, MonoError> {
+    evaluate(parse(code), evaluator, true)
+}
+
+// Like `evaluator`, or `optimized` when `optimize` is set, for the code of
+// the file at `path`, whose parse is reused from its cache while the code is
+// unchanged.
+pub fn cached(
+    path: &Path,
+    code: &str,
+    evaluator: &mut Evaluator,
+    optimize: bool,
+) -> Result<Value, MonoError> {
+    evaluate(cache::parse(path, code), evaluator, optimize)
+}
 
-    match parser.parse() {
+fn evaluate(
+    parsed: Result<Box<Node>, MonoError>,
+    evaluator: &mut Evaluator,
+    optimize: bool,
+) -> Result<Value, MonoError> {
+    let ast = match parsed {
         Err(error) => {
             ereport!(red, "Parser Error", error);
+            return Err(error);
+        }
+        Ok(ast) => ast,
+    };
+    let result = evaluator.evaluate(&match optimize {
+        true => Optimizer::optimize(*ast),
+        false => *ast,
+    });
+    match &result {
+        Err(error) => {
+            ereport!(red, "Evaluator Error", error);
         }
-        Ok(mut ast) => match evaluator.evaluate(&mut ast) {
+        Ok(Value::None) => {}
+        Ok(value) => println!("{}\n", format!("{}", value).green()),
+    }
+    result
+}
+
+// Evaluates each top-level statement as soon as it's parsed, statements before
+// a syntax error still run.
+pub fn streamed(code: &str, evaluator: &mut Evaluator) -> Result<Value, MonoError> {
+    stream(Parser::new(Tokenizer::new(code.chars())), evaluator)
+}
+
+// Like `streamed`, reading the program as it's executed, e.g. from stdin.
+pub fn streamed_from(reader: impl Read, evaluator: &mut Evaluator) -> Result<Value, MonoError> {
+    stream(Parser::new(Tokenizer::from_reader(reader)), evaluator)
+}
+
+fn stream<Tokens>(mut parser: Parser<Tokens>, evaluator: &mut Evaluator) -> Result<Value, MonoError>
+where
+    Tokens: Iterator<Item = ParserToken>,
+{
+    let mut value = Value::None;
+    for statement in parser.statements() {
+        let statement = match statement {
             Err(error) => {
+                ereport!(red, "Parser Error", error);
+                return Err(error);
+            }
+            Ok(statement) => statement,
+        };
+        match evaluator.evaluate_statement(&statement) {
+            Err(error) => {
+                ereport!(red, "Evaluator Error", error);
+                return Err(error);
+            }
+            Ok(ControlFlow::Continue(result)) => value = result,
+            Ok(ControlFlow::Break(result)) => {
+                value = result;
+                break;
+            }
+        }
+    }
+    if !matches!(value, Value::None) {
+        println!("{}\n", format!("{}", value).green());
+    }
+    Ok(value)
+}
+
+pub fn debug(path: &Path) -> io::Result<()> {
+    let code = fs::read_to_string(path)?;
+    let mut debugged = session();
+    debugged.set_hook(Debugger::new(&code));
+    // Errors end the session, they're reported like any other run's.
+    let _ = evaluator(&code, &mut debugged);
+    Ok(())
+}
+
+// Bundles the script and the scripts it runs into `output`, reporting a
+// syntax error in any of them.
+pub fn build(path: &Path, output: &Path) -> io::Result<Result<(), MonoError>> {
+    match Bundle::build(path) {
+        Ok(bundle) => bundle.write(output).map(Ok),
+        Err(BuildError::Io(path, error)) => Err(io::Error::new(
+            error.kind(),
+            format!("{}: {}", path.display(), error),
+        )),
+        Err(BuildError::Syntax(path, error)) => {
+            ereport!(red, format!("Parser Error in {}", path.display()), error);
+            Ok(Err(error))
+        }
+    }
+}
+
+// The Rust program equivalent to the code, reporting a syntax error. Code
+// outside what the backend supports is an `Unsupported` error.
+pub fn transpiler(code: &str) -> io::Result<Result<String, MonoError>> {
+    let program = match parse(code) {
+        Err(error) => {
+            ereport!(red, "Parser Error", error);
+            return Ok(Err(error));
+        }
+        Ok(program) => program,
+    };
+    codegen::transpile(&program)
+        .map(Ok)
+        .map_err(|error| io::Error::new(io::ErrorKind::Unsupported, error))
+}
+
+// Runs a bundle `build` wrote, like `evaluator` runs code.
+pub fn exec(path: &Path) -> io::Result<Result<Value, MonoError>> {
+    let bundle = Bundle::from_bytes(&fs::read(path)?)?;
+    let mut evaluator = session();
+    let entry = bundle.install(&mut evaluator);
+    Ok(evaluate(Ok(entry), &mut evaluator, false))
+}
+
+// Prints the program's lints under the given rules.
+pub fn linter(code: &str, rules: &[Rule]) -> Result<Vec<Lint>, MonoError> {
+    let program = match parse(code) {
+        Err(error) => {
+            ereport!(red, "Parser Error", error);
+            return Err(error);
+        }
+        Ok(program) => program,
+    };
+    let lints = Linter::with_rules(rules).lint(&program);
+    for lint in &lints {
+        match error_format() {
+            ErrorFormat::Json => eprintln!("{}", Diagnostic::from(lint).to_json()),
+            ErrorFormat::Human => println!("{}", format!("{}", lint).yellow()),
+        }
+    }
+    Ok(lints)
+}
+
+// Calls `changed` with the file's contents now and every time it's modified
+// after, checking every 250ms. Editors that save by replacing the file leave
+// it missing for a moment, so only a missing file on the first read is an
+// error.
+pub fn poll(path: &Path, mut changed: impl FnMut(&str)) -> io::Result<()> {
+    let mut modified = None;
+    loop {
+        let read = fs::metadata(path)
+            .and_then(|metadata| metadata.modified())
+            .and_then(|last_modified| match modified == Some(last_modified) {
+                true => Ok(None),
+                false => Ok(Some((last_modified, fs::read_to_string(path)?))),
+            });
+        match read {
+            Ok(Some((last_modified, code))) => {
+                modified = Some(last_modified);
+                changed(&code);
+            }
+            Err(error) if modified.is_none() => return Err(error),
+            _ => {}
+        }
+        thread::sleep(Duration::from_millis(250));
+    }
+}
+
+pub fn watch(path: &Path) -> io::Result<()> {
+    let mut evaluator = session();
+    let mut previous: Option<Box<Node>> = None;
+    poll(path, |code| {
+        if let Some(program) = reload(code, previous.as_deref(), &mut evaluator) {
+            previous = Some(program);
+        }
+    })
+}
+
+fn reload(code: &str, previous: Option<&Node>, evaluator: &mut Evaluator) -> Option<Box<Node>> {
+    let tokenizer = Tokenizer::new(code.chars());
+    let mut parser = Parser::new(tokenizer);
+    let program = match parser.parse() {
+        Err(error) => {
+            ereport!(red, "Parser Error", error);
+            return None;
+        }
+        Ok(program) => program,
+    };
+
+    if let Some(changed) = previous.and_then(|previous| program.changed_functions(previous)) {
+        let mut names = Vec::new();
+        for function in changed {
+            if let Node::FuncDeclearion {
+                identifier: token, ..
+            } = function
+            {
+                if let TokenKind::Identifier(name) = &token.kind {
+                    names.push(name.to_string());
+                }
+            }
+            if let Err(error) = evaluator.evaluate(function) {
                 ereport!(red, "Evaluator Error", error);
             }
-            Ok(Value::None) => {}
-            Ok(value) => println!("{}\n", format!("{}", value).green()),
-        },
+        }
+        if !names.is_empty() {
+            report!(blue, "Reloaded", names.join(", "));
+        }
+        return Some(program);
+    }
+
+    *evaluator = session();
+    match evaluator.evaluate(&program) {
+        Err(error) => ereport!(red, "Evaluator Error", error),
+        Ok(Value::None) => {}
+        Ok(value) => println!("{}\n", format!("{}", value).green()),
     }
+    Some(program)
 }
@@ -0,0 +1,547 @@
+use crate::evaluator::symbol_table::SymbolTable;
+use crate::models::diagnostic::{Diagnostic, Severity};
+use crate::models::position::Position;
+use crate::optimizer::Optimizer;
+use crate::parser::node::{Argument, CasePattern, Node, Pattern};
+use crate::parser::visitor::{visit_children, NodeVisitor};
+use crate::tokenizer::symbol::Symbol;
+use crate::tokenizer::token::{Token, TokenKind};
+use std::collections::HashSet;
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rule {
+    UnusedVariable,
+    UnusedParameter,
+    UnreachableCode,
+    ConstantCondition,
+    ShadowedName,
+}
+
+impl Rule {
+    pub const ALL: [Rule; 5] = [
+        Rule::UnusedVariable,
+        Rule::UnusedParameter,
+        Rule::UnreachableCode,
+        Rule::ConstantCondition,
+        Rule::ShadowedName,
+    ];
+
+    // The name a rule goes by on the command line.
+    pub fn id(&self) -> &'static str {
+        match self {
+            Rule::UnusedVariable => "unused-variable",
+            Rule::UnusedParameter => "unused-parameter",
+            Rule::UnreachableCode => "unreachable-code",
+            Rule::ConstantCondition => "constant-condition",
+            Rule::ShadowedName => "shadowed-name",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Rule> {
+        Rule::ALL.into_iter().find(|rule| rule.id() == id)
+    }
+}
+
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.id())
+    }
+}
+
+// A finding, spanning `[start, end)` like a token.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Lint {
+    pub rule: Rule,
+    pub start: Position,
+    pub end: Position,
+    pub message: String,
+}
+
+impl fmt::Display for Lint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at {}: {}.", self.rule, self.start, self.message)
+    }
+}
+
+impl From<&Lint> for Diagnostic {
+    fn from(lint: &Lint) -> Self {
+        Self {
+            kind: lint.rule.id().to_string(),
+            message: format!("{}.", lint.message),
+            severity: Severity::Warning,
+            start: Some(lint.start.clone()),
+            end: Some(lint.end.clone()),
+        }
+    }
+}
+
+struct Binding {
+    identifier: Token,
+    name: Symbol,
+    // The rule reporting the binding when it's never read, declarations
+    // such as functions and structs aren't reported.
+    unused: Option<Rule>,
+    is_used: bool,
+}
+
+// The same scopes the resolver tracks: the globals, one per function and one
+// for the variable of a comprehension.
+struct Scope {
+    bindings: Vec<Binding>,
+    is_function: bool,
+}
+
+// Looks for likely mistakes in a parsed program. Names are followed the way
+// the evaluator finds them at runtime: a function sees its own locals and
+// the globals, never the locals of the function it's declared in.
+pub struct Linter {
+    rules: Vec<Rule>,
+    scopes: Vec<Scope>,
+    // Names read inside functions that weren't local to them, they may be
+    // globals declared after the function.
+    globals_read: HashSet<Symbol>,
+    builtins: SymbolTable,
+    lints: Vec<Lint>,
+}
+
+impl Default for Linter {
+    fn default() -> Self {
+        Self::with_rules(&Rule::ALL)
+    }
+}
+
+impl Linter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_rules(rules: &[Rule]) -> Self {
+        let mut builtins = SymbolTable::new();
+        builtins.add_builtins();
+        Self {
+            rules: rules.to_vec(),
+            scopes: Vec::new(),
+            globals_read: HashSet::new(),
+            builtins,
+            lints: Vec::new(),
+        }
+    }
+
+    pub fn allow(&mut self, rule: Rule) {
+        self.rules.retain(|enabled| *enabled != rule);
+    }
+
+    // The lints of the program, in the order they appear in the source.
+    pub fn lint(mut self, program: &Node) -> Vec<Lint> {
+        self.push(false);
+        self.visit(program);
+        let globals = self.scopes.pop().map(|scope| scope.bindings);
+        for binding in globals.into_iter().flatten() {
+            if !self.globals_read.contains(&binding.name) {
+                self.report_unused(binding);
+            }
+        }
+        self.lints.sort_by(|a, b| a.start.cmp(&b.start));
+        self.lints
+    }
+
+    fn report(&mut self, rule: Rule, start: &Position, end: &Position, message: String) {
+        if self.rules.contains(&rule) {
+            self.lints.push(Lint {
+                rule,
+                start: start.clone(),
+                end: end.clone(),
+                message,
+            });
+        }
+    }
+
+    fn report_node(&mut self, rule: Rule, first: &Node, last: &Node, message: String) {
+        if let (Some(start), Some(end)) = (first.position(), last.end_position()) {
+            self.report(rule, start, end, message);
+        }
+    }
+
+    fn report_unused(&mut self, binding: Binding) {
+        let (Some(rule), false) = (binding.unused, binding.is_used) else {
+            return;
+        };
+        let what = match rule {
+            Rule::UnusedParameter => "Parameter",
+            _ => "Variable",
+        };
+        self.report(
+            rule,
+            &binding.identifier.start,
+            &binding.identifier.end,
+            format!("{} `{}` is never used", what, binding.name),
+        );
+    }
+
+    fn push(&mut self, is_function: bool) {
+        self.scopes.push(Scope {
+            bindings: Vec::new(),
+            is_function,
+        });
+    }
+
+    fn pop(&mut self) {
+        if let Some(scope) = self.scopes.pop() {
+            for binding in scope.bindings {
+                self.report_unused(binding);
+            }
+        }
+    }
+
+    // The scopes a read in the innermost one can reach, innermost first.
+    fn visible(&self) -> impl Iterator<Item = &Scope> {
+        let function = self
+            .scopes
+            .iter()
+            .rposition(|scope| scope.is_function)
+            .unwrap_or(0);
+        self.scopes[function..]
+            .iter()
+            .rev()
+            .chain(self.scopes.first().filter(|_| function > 0))
+    }
+
+    fn declare(&mut self, identifier: &Token, unused: Option<Rule>) {
+        let Some(name) = symbol(identifier) else {
+            return;
+        };
+        let redeclared = self
+            .scopes
+            .last()
+            .is_some_and(|scope| scope.bindings.iter().any(|binding| binding.name == name));
+        if redeclared {
+            return;
+        }
+        let shadowed = self
+            .visible()
+            .skip(1)
+            .flat_map(|scope| &scope.bindings)
+            .find(|binding| binding.name == name)
+            .map(|binding| format!("the declaration at {}", binding.identifier.start));
+        let shadowed = shadowed.or_else(|| {
+            self.builtins
+                .is_builtin(&name)
+                .then(|| String::from("a builtin"))
+        });
+        if let Some(shadowed) = shadowed {
+            self.report(
+                Rule::ShadowedName,
+                &identifier.start,
+                &identifier.end,
+                format!("`{}` shadows {}", name, shadowed),
+            );
+        }
+        let unused = unused.filter(|_| !name.starts_with('_') && name.as_ref() != "self");
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.bindings.push(Binding {
+                identifier: identifier.clone(),
+                name,
+                unused,
+                is_used: false,
+            });
+        }
+    }
+
+    fn declare_pattern(&mut self, pattern: &Pattern) {
+        match pattern {
+            Pattern::Identifier(identifier) | Pattern::Rest(identifier) => {
+                self.declare(identifier, Some(Rule::UnusedVariable))
+            }
+            Pattern::Tuple(patterns) | Pattern::List(patterns) => {
+                for pattern in patterns {
+                    self.declare_pattern(pattern);
+                }
+            }
+        }
+    }
+
+    fn declare_case_pattern(&mut self, pattern: &CasePattern) {
+        match pattern {
+            CasePattern::Binding(identifier) | CasePattern::Rest(identifier) => {
+                self.declare(identifier, Some(Rule::UnusedVariable))
+            }
+            CasePattern::List(patterns)
+            | CasePattern::Tuple(patterns)
+            | CasePattern::Or(patterns) => {
+                for pattern in patterns {
+                    self.declare_case_pattern(pattern);
+                }
+            }
+            CasePattern::Struct { fields, .. } => {
+                for (_, pattern) in fields {
+                    self.declare_case_pattern(pattern);
+                }
+            }
+            CasePattern::Wildcard | CasePattern::Literal(_) | CasePattern::Variant { .. } => {}
+        }
+    }
+
+    fn read(&mut self, identifier: &Token) {
+        let Some(name) = symbol(identifier) else {
+            return;
+        };
+        let function = self
+            .scopes
+            .iter()
+            .rposition(|scope| scope.is_function)
+            .unwrap_or(0);
+        let local = self.scopes[function..]
+            .iter_mut()
+            .rev()
+            .flat_map(|scope| scope.bindings.iter_mut())
+            .find(|binding| binding.name == name);
+        match local {
+            Some(binding) => binding.is_used = true,
+            None => {
+                self.globals_read.insert(name);
+            }
+        }
+    }
+
+    // Arguments are bound one by one, a default only sees those before it.
+    fn function(&mut self, arguments: &[Argument], body: &Node) {
+        self.push(true);
+        for argument in arguments {
+            if let Some(default) = &argument.default {
+                self.visit(default);
+            }
+            self.declare(&argument.identifier, Some(Rule::UnusedParameter));
+        }
+        self.visit(body);
+        self.pop();
+    }
+
+    fn unreachable(&mut self, statements: &[Box<Node>]) {
+        let Some(end) = statements.iter().position(|statement| returns(statement)) else {
+            return;
+        };
+        if let (Some(first), Some(last)) = (statements.get(end + 1), statements.last()) {
+            self.report_node(
+                Rule::UnreachableCode,
+                first,
+                last,
+                String::from("Unreachable code after a return"),
+            );
+        }
+    }
+
+    fn condition(&mut self, condition: &Node, message: impl FnOnce(bool) -> Option<String>) {
+        if let Some(message) = constant(condition).and_then(message) {
+            self.report_node(Rule::ConstantCondition, condition, condition, message);
+        }
+    }
+}
+
+impl NodeVisitor for Linter {
+    fn visit(&mut self, node: &Node) {
+        match node {
+            Node::Program { statements } => {
+                self.unreachable(statements);
+                visit_children(self, node);
+            }
+            Node::FuncDeclearion {
+                identifier,
+                arguments,
+                body,
+                ..
+            } => {
+                self.declare(identifier, None);
+                self.function(arguments, body);
+            }
+            Node::StructDeclaration { identifier, fields } => {
+                self.declare(identifier, None);
+                self.push(true);
+                for default in fields.iter().filter_map(|field| field.default.as_ref()) {
+                    self.visit(default);
+                }
+                self.pop();
+            }
+            Node::Impl { methods, .. } => {
+                for method in methods {
+                    if let Node::FuncDeclearion {
+                        arguments, body, ..
+                    } = method.as_ref()
+                    {
+                        self.function(arguments, body);
+                    }
+                }
+            }
+            Node::EnumDeclaration { identifier, .. } => self.declare(identifier, None),
+            Node::Assignment {
+                identifier,
+                value,
+                is_declaration,
+                ..
+            } => {
+                self.visit(value);
+                if *is_declaration {
+                    self.declare(identifier, Some(Rule::UnusedVariable));
+                }
+            }
+            Node::Destructuring {
+                pattern,
+                value,
+                is_declaration,
+                ..
+            } => {
+                self.visit(value);
+                if *is_declaration {
+                    self.declare_pattern(pattern);
+                }
+            }
+            Node::Access { identifier, .. }
+            | Node::Delete { identifier, .. }
+            | Node::FuncCall { identifier, .. }
+            | Node::Index { identifier, .. }
+            | Node::ListAssignment { identifier, .. } => {
+                visit_children(self, node);
+                self.read(identifier);
+            }
+            // The variable is shared with the function, which counts as using it.
+            Node::Global { identifiers, .. } => {
+                for identifier in identifiers {
+                    self.read(identifier);
+                }
+            }
+            Node::For {
+                identifier,
+                iterable,
+                block,
+                ..
+            } => {
+                self.visit(iterable);
+                self.declare(identifier, Some(Rule::UnusedVariable));
+                self.visit(block);
+            }
+            Node::Comprehension {
+                value,
+                identifier,
+                iterable,
+                condition,
+                ..
+            } => {
+                self.visit(iterable);
+                self.push(false);
+                self.declare(identifier, Some(Rule::UnusedVariable));
+                if let Some(condition) = condition {
+                    self.condition(condition, |value| {
+                        Some(format!("Condition is always {}", title(value)))
+                    });
+                    self.visit(condition);
+                }
+                self.visit(value);
+                self.pop();
+            }
+            Node::Match { value, arms } => {
+                self.visit(value);
+                for arm in arms {
+                    self.declare_case_pattern(&arm.pattern);
+                    if let Some(guard) = &arm.guard {
+                        self.condition(guard, |value| {
+                            Some(format!("Guard is always {}", title(value)))
+                        });
+                        self.visit(guard);
+                    }
+                    self.visit(&arm.block);
+                }
+            }
+            Node::If { condition, .. } => {
+                self.condition(condition, |value| {
+                    Some(format!("Condition is always {}", title(value)))
+                });
+                visit_children(self, node);
+            }
+            // `while True` is how a loop that returns from its body is
+            // written, it's only reported when nothing in it does.
+            Node::While { condition, block } => {
+                self.condition(condition, |value| match value {
+                    true => (!exits(block)).then(|| String::from("Loop never ends")),
+                    false => Some(String::from("Loop never runs")),
+                });
+                visit_children(self, node);
+            }
+            Node::DoWhile { block, condition } => {
+                self.condition(condition, |value| match value {
+                    true => (!exits(block)).then(|| String::from("Loop never ends")),
+                    false => Some(String::from("Loop runs only once")),
+                });
+                visit_children(self, node);
+            }
+            _ => visit_children(self, node),
+        }
+    }
+}
+
+// Whether the statement always returns, so nothing after it runs.
+fn returns(node: &Node) -> bool {
+    match node {
+        Node::Return { .. } => true,
+        Node::Program { statements } => statements.iter().any(|statement| returns(statement)),
+        Node::If {
+            block,
+            else_block: Some(else_block),
+            ..
+        } => returns(block) && returns(else_block),
+        Node::DoWhile { block, .. } => returns(block),
+        _ => false,
+    }
+}
+
+// Whether anything in a loop's block can leave it: a `return`, or a call to
+// `exit`. Functions declared in it don't count.
+fn exits(block: &Node) -> bool {
+    struct Exits(bool);
+
+    impl NodeVisitor for Exits {
+        fn visit(&mut self, node: &Node) {
+            match node {
+                Node::Return { .. } => self.0 = true,
+                Node::FuncCall { identifier, .. }
+                    if symbol(identifier).is_some_and(|name| name.as_ref() == "exit") =>
+                {
+                    self.0 = true
+                }
+                Node::FuncDeclearion { .. } | Node::Impl { .. } => {}
+                _ => visit_children(self, node),
+            }
+        }
+    }
+
+    let mut exits = Exits(false);
+    exits.visit(block);
+    exits.0
+}
+
+// The value of a condition made only of literals, as the optimizer folds it.
+fn constant(condition: &Node) -> Option<bool> {
+    match Optimizer::optimize(condition.clone()) {
+        Node::Atom {
+            value:
+                Token {
+                    kind: TokenKind::Boolean(value),
+                    ..
+                },
+        } => Some(value),
+        _ => None,
+    }
+}
+
+fn title(value: bool) -> &'static str {
+    match value {
+        true => "True",
+        false => "False",
+    }
+}
+
+fn symbol(identifier: &Token) -> Option<Symbol> {
+    match &identifier.kind {
+        TokenKind::Identifier(name) => Some(name.clone()),
+        _ => None,
+    }
+}
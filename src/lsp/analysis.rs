@@ -0,0 +1,152 @@
+use crate::evaluator::builtins;
+use crate::evaluator::symbol_table::SymbolTable;
+use crate::evaluator::value::Value;
+use crate::models::error::MonoError;
+use crate::models::position::Position;
+use crate::parser::resolver::{Declaration, DeclarationKind, Resolver};
+use crate::parser::Parser;
+use crate::tokenizer::symbol::Symbol;
+use crate::tokenizer::token::{Token, TokenKind, KEYWORDS};
+use crate::tokenizer::Tokenizer;
+
+pub enum Completion {
+    Keyword,
+    Builtin,
+    Declared(DeclarationKind),
+}
+
+// What the language server knows about a document. The statements before a
+// syntax error are still resolved, so declarations stay available while the
+// program is being typed.
+pub struct Analysis {
+    tokens: Vec<Token>,
+    declarations: Vec<Declaration>,
+    builtins: SymbolTable,
+    pub error: Option<MonoError>,
+}
+
+impl Analysis {
+    // Editors count a tab as one character, and so do the positions here.
+    pub fn new(source: &str) -> Self {
+        let tokens = Tokenizer::with_tab_width(source.chars(), 1)
+            .map_while(Result::ok)
+            .collect();
+
+        let mut parser = Parser::new(Tokenizer::with_tab_width(source.chars(), 1));
+        let mut resolver = Resolver::recording();
+        let mut error = None;
+        for statement in parser.statements() {
+            match statement {
+                Ok(mut statement) => resolver.resolve(&mut statement),
+                Err(failure) => error = Some(failure),
+            }
+        }
+
+        let mut builtins = SymbolTable::new();
+        builtins.add_builtins();
+        Self {
+            tokens,
+            declarations: resolver.declarations(),
+            builtins,
+            error,
+        }
+    }
+
+    pub fn end(&self) -> Position {
+        self.tokens
+            .last()
+            .map(|token| token.end.clone())
+            .unwrap_or_else(|| Position::new(1, 1))
+    }
+
+    // Positions are as the tokenizer counts them: rows and columns from 1.
+    pub fn identifier_at(&self, position: &Position) -> Option<(&Token, &Symbol)> {
+        self.tokens.iter().find_map(|token| match &token.kind {
+            TokenKind::Identifier(name) if token.start <= *position && *position <= token.end => {
+                Some((token, name))
+            }
+            _ => None,
+        })
+    }
+
+    // The closest declaration of the name before its use, or the first one
+    // after it when there's none before.
+    pub fn definition(&self, identifier: &Token, name: &str) -> Option<&Declaration> {
+        let declarations = || {
+            self.declarations.iter().filter(|declaration| {
+                matches!(&declaration.identifier.kind, TokenKind::Identifier(declared) if declared.as_ref() == name)
+            })
+        };
+        declarations()
+            .rev()
+            .find(|declaration| declaration.identifier.start <= identifier.start)
+            .or_else(|| declarations().next())
+    }
+
+    // Declarations hiding a builtin of the same name.
+    pub fn shadowed(&self) -> impl Iterator<Item = (&Symbol, &Declaration)> {
+        self.declarations
+            .iter()
+            .filter_map(|declaration| match &declaration.identifier.kind {
+                TokenKind::Identifier(name) if self.builtins.get(name).is_some() => {
+                    Some((name, declaration))
+                }
+                _ => None,
+            })
+    }
+
+    pub fn hover(&self, position: &Position) -> Option<String> {
+        let (identifier, name) = self.identifier_at(position)?;
+        if let Some(declaration) = self.definition(identifier, name) {
+            return Some(declaration.detail.clone());
+        }
+        self.builtins.get(name).map(|value| describe(name, &value))
+    }
+
+    // Keywords, builtins and every name declared before the position.
+    pub fn completions(&self, position: &Position) -> Vec<(String, Completion)> {
+        let mut completions: Vec<(String, Completion)> = KEYWORDS
+            .iter()
+            .map(|keyword| (keyword.to_string(), Completion::Keyword))
+            .collect();
+        completions.extend(
+            self.builtins
+                .builtins()
+                .map(|(name, _)| (name.to_string(), Completion::Builtin)),
+        );
+        for declaration in &self.declarations {
+            if declaration.identifier.start > *position {
+                break;
+            }
+            if let TokenKind::Identifier(name) = &declaration.identifier.kind {
+                if completions
+                    .iter()
+                    .all(|(label, _)| label.as_str() != name.as_ref())
+                {
+                    completions.push((name.to_string(), Completion::Declared(declaration.kind)));
+                }
+            }
+        }
+        completions
+    }
+}
+
+fn describe(name: &str, value: &Value) -> String {
+    match value {
+        Value::BuiltInFunction {
+            arguments, types, ..
+        } => {
+            format!(
+                "builtin {}({})",
+                name,
+                builtins::signature(arguments, types).join(", ")
+            )
+        }
+        Value::Namespace { members, .. } => {
+            let mut members = members.keys().map(ToString::to_string).collect::<Vec<_>>();
+            members.sort();
+            format!("namespace {} {{ {} }}", name, members.join(", "))
+        }
+        value => format!("{}: {}", name, value.to_type()),
+    }
+}
@@ -0,0 +1,212 @@
+pub mod analysis;
+
+use crate::lsp::analysis::{Analysis, Completion};
+use crate::models::diagnostic::{Diagnostic, Severity};
+use crate::models::position::Position;
+use crate::parser::resolver::DeclarationKind;
+use serde_json::{json, Value as Json};
+use std::collections::HashMap;
+use std::io::{self, BufRead, Write};
+
+// A language server speaking JSON-RPC over stdin and stdout. It keeps the
+// open documents in full (clients send the whole text on every change) and
+// re-analyses a document whenever it changes.
+pub fn run() -> io::Result<()> {
+    let mut input = io::stdin().lock();
+    let mut output = io::stdout().lock();
+    let mut documents: HashMap<String, Analysis> = HashMap::new();
+
+    while let Some(message) = read_message(&mut input)? {
+        let id = message.get("id").cloned();
+        let params = &message["params"];
+        let uri = params["textDocument"]["uri"]
+            .as_str()
+            .unwrap_or("")
+            .to_string();
+        let position = position(&params["position"]);
+
+        let result = match message["method"].as_str().unwrap_or("") {
+            "initialize" => json!({
+                "capabilities": {
+                    "textDocumentSync": 1,
+                    "definitionProvider": true,
+                    "hoverProvider": true,
+                    "completionProvider": {},
+                },
+                "serverInfo": { "name": "mono" },
+            }),
+            "shutdown" => Json::Null,
+            "exit" => return Ok(()),
+            "textDocument/didOpen" => {
+                let text = params["textDocument"]["text"].as_str().unwrap_or("");
+                let analysis = Analysis::new(text);
+                write_message(&mut output, &diagnostics(&uri, &analysis))?;
+                documents.insert(uri, analysis);
+                continue;
+            }
+            "textDocument/didChange" => {
+                let changes = params["contentChanges"].as_array();
+                let text = changes
+                    .and_then(|changes| changes.last())
+                    .and_then(|change| change["text"].as_str())
+                    .unwrap_or("");
+                let analysis = Analysis::new(text);
+                write_message(&mut output, &diagnostics(&uri, &analysis))?;
+                documents.insert(uri, analysis);
+                continue;
+            }
+            "textDocument/didClose" => {
+                documents.remove(&uri);
+                let cleared = json!({
+                    "jsonrpc": "2.0",
+                    "method": "textDocument/publishDiagnostics",
+                    "params": { "uri": uri, "diagnostics": [] },
+                });
+                write_message(&mut output, &cleared)?;
+                continue;
+            }
+            "textDocument/definition" => documents
+                .get(&uri)
+                .and_then(|analysis| {
+                    let (identifier, name) = analysis.identifier_at(&position)?;
+                    analysis.definition(identifier, name)
+                })
+                .map_or(Json::Null, |declaration| {
+                    let identifier = &declaration.identifier;
+                    json!({ "uri": uri, "range": range(&identifier.start, &identifier.end) })
+                }),
+            "textDocument/hover" => documents
+                .get(&uri)
+                .and_then(|analysis| analysis.hover(&position))
+                .map_or(Json::Null, |detail| {
+                    json!({ "contents": { "kind": "markdown", "value": format!("```mono\n{}\n```", detail) } })
+                }),
+            "textDocument/completion" => documents
+                .get(&uri)
+                .map(|analysis| analysis.completions(&position))
+                .unwrap_or_default()
+                .into_iter()
+                .map(|(label, completion)| json!({ "label": label, "kind": kind(completion) }))
+                .collect(),
+            method => match id {
+                Some(id) => {
+                    let error = json!({
+                        "jsonrpc": "2.0",
+                        "id": id,
+                        "error": { "code": -32601, "message": format!("Unknown method {}.", method) },
+                    });
+                    write_message(&mut output, &error)?;
+                    continue;
+                }
+                None => continue,
+            },
+        };
+
+        if let Some(id) = id {
+            write_message(
+                &mut output,
+                &json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            )?;
+        }
+    }
+    Ok(())
+}
+
+fn diagnostics(uri: &str, analysis: &Analysis) -> Json {
+    let mut diagnostics: Vec<Json> = analysis
+        .error
+        .iter()
+        .map(|error| {
+            let diagnostic = Diagnostic::from(error.as_ref());
+            // Errors at the end of the input have no span, they're shown at
+            // the last token.
+            let start = diagnostic.start.unwrap_or_else(|| analysis.end());
+            let end = diagnostic.end.unwrap_or_else(|| {
+                let mut end = start.clone();
+                end.next();
+                end
+            });
+            json!({
+                "range": range(&start, &end),
+                "severity": severity(diagnostic.severity),
+                "source": "mono",
+                "message": format!("{}: {}", diagnostic.kind, diagnostic.message),
+            })
+        })
+        .collect();
+    diagnostics.extend(analysis.shadowed().map(|(name, declaration)| {
+        let identifier = &declaration.identifier;
+        json!({
+            "range": range(&identifier.start, &identifier.end),
+            "severity": severity(Severity::Warning),
+            "source": "mono",
+            "message": format!("`{}` shadows a builtin of the same name.", name),
+        })
+    }));
+    json!({
+        "jsonrpc": "2.0",
+        "method": "textDocument/publishDiagnostics",
+        "params": { "uri": uri, "diagnostics": diagnostics },
+    })
+}
+
+fn severity(severity: Severity) -> u8 {
+    match severity {
+        Severity::Error => 1,
+        Severity::Warning => 2,
+    }
+}
+
+// LSP positions count lines and characters from 0, the tokenizer from 1. Both
+// ranges are half-open.
+fn range(start: &Position, end: &Position) -> Json {
+    json!({
+        "start": { "line": start.row().saturating_sub(1), "character": start.column().saturating_sub(1) },
+        "end": { "line": end.row().saturating_sub(1), "character": end.column().saturating_sub(1) },
+    })
+}
+
+fn position(position: &Json) -> Position {
+    let line = position["line"].as_u64().unwrap_or(0) as usize;
+    let character = position["character"].as_u64().unwrap_or(0) as usize;
+    Position::new(line + 1, character + 1)
+}
+
+fn kind(completion: Completion) -> u8 {
+    match completion {
+        Completion::Keyword => 14,
+        Completion::Builtin => 3,
+        Completion::Declared(DeclarationKind::Function) => 3,
+        Completion::Declared(DeclarationKind::Struct) => 22,
+        Completion::Declared(DeclarationKind::Enum) => 13,
+        Completion::Declared(DeclarationKind::Variable | DeclarationKind::Argument) => 6,
+    }
+}
+
+fn read_message(input: &mut impl BufRead) -> io::Result<Option<Json>> {
+    let mut length = None;
+    loop {
+        let mut header = String::new();
+        if input.read_line(&mut header)? == 0 {
+            return Ok(None);
+        }
+        let header = header.trim();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                length = value.trim().parse::<usize>().ok();
+            }
+        }
+    }
+    let mut body = vec![0; length.unwrap_or(0)];
+    input.read_exact(&mut body)?;
+    Ok(Some(serde_json::from_slice(&body).unwrap_or(Json::Null)))
+}
+
+fn write_message(output: &mut impl Write, message: &Json) -> io::Result<()> {
+    let body = message.to_string();
+    write!(output, "Content-Length: {}\r\n\r\n{}", body.len(), body)?;
+    output.flush()
+}
@@ -1,10 +1,20 @@
-use crate::mono::evaluator::Evaluator;
-use mono;
+use colored::Colorize;
+use mono::evaluator::Evaluator;
+use mono::extensions::datetime::DateTime;
+use mono::linter::Rule;
+use mono::models::error::{MonoError, MonoErrorKind};
+use mono::parser::incremental::Incremental;
+use mono::project::Project;
+use mono::ErrorFormat;
 use std::env;
-use std::fs::File;
-use std::io::{self, BufRead, Read, Write};
+use std::fmt;
+use std::fs;
+use std::io::{self, BufRead, IsTerminal, Read, Write};
+use std::net::TcpListener;
 use std::path::Path;
 use std::process::exit;
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 #[derive(Default)]
 enum Mode {
@@ -12,43 +22,120 @@ enum Mode {
     Parser,
     #[default]
     Evaluator,
+    Optimizer,
+    Profiler,
+    Stream,
+}
+
+impl Mode {
+    fn from_flag(flag: &str) -> Option<Self> {
+        match flag {
+            "-t" => Some(Mode::Tokenizer),
+            "-p" => Some(Mode::Parser),
+            "-e" => Some(Mode::Evaluator),
+            "-O" => Some(Mode::Optimizer),
+            "--profile" => Some(Mode::Profiler),
+            "--stream" => Some(Mode::Stream),
+            _ => None,
+        }
+    }
 }
 
 fn clear_screen() {
     if cfg!(windows) {
         std::process::Command::new("cmd")
-            .args(&["/C", "cls"])
+            .args(["/C", "cls"])
             .status()
             .expect("Failed to clear the screen.");
     } else {
         print!("\x1B[2J\x1B[1;1H");
     }
 }
-fn run(mode: &Mode, code: &str, evalutaor: Option<&mut Evaluator>) {
+
+// Exit statuses for the ways a run can fail. A script calling `exit(n)`
+// exits with `n` itself.
+const RUNTIME_ERROR: i32 = 1;
+const USAGE_ERROR: i32 = 2;
+const SYNTAX_ERROR: i32 = 3;
+// `mono lint` found something.
+const LINTS_FOUND: i32 = 1;
+
+fn run(mode: &Mode, code: &str, evalutaor: Option<&mut Evaluator>) -> Result<(), MonoError> {
     match (mode, evalutaor) {
-        (Mode::Tokenizer, _) => mono::tokenizer(code),
-        (Mode::Parser, _) => mono::parser(code),
-        (Mode::Evaluator, None) => mono::evaluator(code, &mut Evaluator::new()),
-        (Mode::Evaluator, Some(e)) => mono::evaluator(code, e),
+        (Mode::Tokenizer, _) => mono::tokenizer(code).map(drop),
+        (Mode::Parser, _) => mono::parser(code).map(drop),
+        (Mode::Evaluator, None) => mono::evaluator(code, &mut mono::session()).map(drop),
+        (Mode::Evaluator, Some(e)) => mono::evaluator(code, e).map(drop),
+        (Mode::Optimizer, None) => mono::optimized(code, &mut mono::session()).map(drop),
+        (Mode::Optimizer, Some(e)) => mono::optimized(code, e).map(drop),
+        (Mode::Profiler, _) => mono::profiler(code).map(drop),
+        (Mode::Stream, None) => mono::streamed(code, &mut mono::session()).map(drop),
+        (Mode::Stream, Some(e)) => mono::streamed(code, e).map(drop),
+    }
+}
+
+// Exits with the status of the error the program stopped with, which has
+// already been reported.
+fn finish(result: Result<(), MonoError>) {
+    if let Err(error) = result {
+        exit(match *error {
+            MonoErrorKind::Syntax(_) => SYNTAX_ERROR,
+            MonoErrorKind::Runtime(_) => RUNTIME_ERROR,
+        });
     }
 }
 
 fn usage() {
     eprintln!("Usage:");
-    eprintln!("");
+    eprintln!();
     eprintln!("    REPL:");
     eprintln!("        ./mono <flag>");
-    eprintln!("");
+    eprintln!();
     eprintln!("    File:");
     eprintln!("        ./mono <flag> <path>");
-    eprintln!("");
+    eprintln!();
+    eprintln!("    Stdin:");
+    eprintln!("        ./mono <flag> --stdin");
+    eprintln!();
     eprintln!("    Code:");
-    eprintln!("        ./mono -c <flag> <code>");
-    eprintln!("");
+    eprintln!("        ./mono <flag> -c <code> [-c <code> ...]");
+    eprintln!();
+    eprintln!("    Project (the nearest mono.toml):");
+    eprintln!("        ./mono run [--no-cache] [directory]");
+    eprintln!();
+    eprintln!("    Bundle (a script and the scripts it runs):");
+    eprintln!("        ./mono build <path> -o <output>");
+    eprintln!("        ./mono exec <output>");
+    eprintln!();
+    eprintln!("    Transpile to Rust (experimental):");
+    eprintln!("        ./mono transpile <path> [-o <output.rs>]");
+    eprintln!();
+    eprintln!("    Watch:");
+    eprintln!("        ./mono <flag> --watch <path>");
+    eprintln!("        ./mono watch <path>");
+    eprintln!();
+    eprintln!("    Debugger:");
+    eprintln!("        ./mono debug <path>");
+    eprintln!();
+    eprintln!("    Linter:");
+    eprintln!("        ./mono lint [--allow <rule>] [--only <rule>] <path>");
+    eprintln!();
+    eprintln!("    Language server (built with the `lsp` feature):");
+    eprintln!("        ./mono lsp");
+    eprintln!();
+    eprintln!("    Playground server:");
+    eprintln!("        ./mono serve [address]");
+    eprintln!();
     eprintln!("    Flags:");
     eprintln!("    -t          run the Tokenizer");
     eprintln!("    -p          run the Parser");
-    eprintln!("    -e          run the Evaluator")
+    eprintln!("    -e          run the Evaluator");
+    eprintln!("    -O          run the Evaluator on the optimized program");
+    eprintln!("    --profile   run the Evaluator and print per node evaluation counts and timings");
+    eprintln!("    --stream    run each statement as soon as it's parsed");
+    eprintln!("    --no-cache  parse the file even if its .monoc cache is up to date");
+    eprintln!();
+    eprintln!("    --error-format=json   report errors and lints as one JSON object per line")
 }
 
 fn logo() {
@@ -62,76 +149,362 @@ fn logo() {
 fn console(mode: Mode) -> Result<(), Box<dyn std::error::Error>> {
     clear_screen();
     logo();
-    let mut evalutaor = Evaluator::new();
+    let mut evalutaor = mono::session();
+    let mut input = Incremental::new();
     let mut buffer = String::new();
     let stdin = io::stdin();
     let mut handle = stdin.lock();
 
     loop {
-        print!("> ");
+        // Unclosed blocks and strings continue on the next line.
+        print!("{}", if input.is_pending() { ". " } else { "> " });
         io::stdout().flush()?;
         buffer.clear();
         handle.read_line(&mut buffer)?;
 
-        match buffer.trim() {
-            "quit" => return Ok(()),
-            "clear" => clear_screen(),
-            code => run(&mode, code, Some(&mut evalutaor)),
+        if !input.is_pending() {
+            match buffer.trim() {
+                "quit" => return Ok(()),
+                "clear" => {
+                    clear_screen();
+                    continue;
+                }
+                command => match command.split_once(' ') {
+                    // Persists the session's variables and functions, or
+                    // restores a persisted session.
+                    Some(("save", path)) => {
+                        let state = evalutaor.save_state();
+                        if let Err(error) = state.and_then(|state| fs::write(path.trim(), state)) {
+                            eprintln!("Error: {}", error);
+                        }
+                        continue;
+                    }
+                    Some(("load", path)) => {
+                        let state = fs::read(path.trim());
+                        if let Err(error) = state.and_then(|state| evalutaor.load_state(&state)) {
+                            eprintln!("Error: {}", error);
+                        }
+                        continue;
+                    }
+                    _ => {}
+                },
+            }
+        }
+        let code = match mode {
+            Mode::Tokenizer => Some(buffer.clone()),
+            _ => input.feed(&buffer),
+        };
+        if let Some(code) = code {
+            // The session goes on after an error.
+            let _ = run(&mode, code.trim(), Some(&mut evalutaor));
         }
     }
 }
 
-fn file(path: &str, mode: Mode) -> Result<(), Box<dyn std::error::Error>> {
-    let path = Path::new(path);
-    let mut file = File::open(&path)?;
-    let mut contents = String::new();
-    file.read_to_string(&mut contents)?;
+// What a run reads its program from.
+enum Source {
+    Console,
+    File(String),
+    // Run again from scratch whenever it changes.
+    Watched(String),
+    // Run one after the other in a single session.
+    Snippets(Vec<String>),
+    Stdin,
+}
+
+// What to run and how. Flags can come in any order, so `-t -c "1 + 2"` and
+// `-c "1 + 2" -t` are the same, and the last mode given wins.
+struct Options {
+    mode: Mode,
+    source: Source,
+    // Whether a file's parse is reused from its `.monoc` cache.
+    cache: bool,
+}
 
-    let ext = path
-        .extension()
-        .and_then(|ext| ext.to_str())
-        .ok_or_else(|| "File does not have an extension.")?;
+impl Options {
+    fn parse(arguments: &[String]) -> Result<Self, Box<dyn std::error::Error>> {
+        let mut mode = Mode::default();
+        let mut snippets = Vec::new();
+        let mut path = None;
+        let mut stdin = false;
+        let mut watch = false;
+        let mut cache = true;
+        let mut arguments = arguments.iter();
+        while let Some(argument) = arguments.next() {
+            match argument.as_str() {
+                "-c" => {
+                    let mut code = arguments.next().ok_or("Expected code after -c")?;
+                    // The older `-c <flag> <code>` order.
+                    if let Some(flag) = Mode::from_flag(code) {
+                        mode = flag;
+                        code = arguments.next().ok_or("Expected code after -c")?;
+                    }
+                    snippets.push(code.clone());
+                }
+                "-" | "--stdin" => stdin = true,
+                "--watch" => watch = true,
+                "--no-cache" => cache = false,
+                flag if flag.starts_with('-') => {
+                    mode = Mode::from_flag(flag).ok_or(format!("Unknown flag: {}", flag))?
+                }
+                _ if path.is_some() => return Err("Expected a single path".into()),
+                _ => path = Some(argument.clone()),
+            }
+        }
+        let source = match (path, snippets.is_empty(), stdin) {
+            (Some(path), true, false) if watch => Source::Watched(path),
+            _ if watch => return Err("Expected a path to --watch".into()),
+            (Some(path), true, false) => Source::File(path),
+            (None, false, false) => Source::Snippets(snippets),
+            (None, true, true) => Source::Stdin,
+            // `echo "1 + 2" | mono` runs what's piped in.
+            (None, true, false) if !io::stdin().is_terminal() => Source::Stdin,
+            (None, true, false) => Source::Console,
+            _ => return Err("Expected only one of a path, -c <code> or --stdin".into()),
+        };
+        Ok(Self {
+            mode,
+            source,
+            cache,
+        })
+    }
 
-    if ext == "mono" {
-        run(&mode, &contents, None);
+    fn run(self) -> Result<(), Box<dyn std::error::Error>> {
+        match self.source {
+            Source::Console => return console(self.mode),
+            // Any file is run whatever its extension, so an executable script
+            // starting with `#!/usr/bin/env mono` runs directly, the `#` line
+            // being a comment.
+            Source::File(path) => {
+                let code = fs::read_to_string(&path)?;
+                let path = Path::new(&path);
+                finish(match self.mode {
+                    Mode::Evaluator if self.cache => {
+                        mono::cached(path, &code, &mut mono::session(), false).map(drop)
+                    }
+                    Mode::Optimizer if self.cache => {
+                        mono::cached(path, &code, &mut mono::session(), true).map(drop)
+                    }
+                    _ => run(&self.mode, &code, None),
+                })
+            }
+            Source::Watched(path) => return rerun(Path::new(&path), &self.mode),
+            Source::Snippets(snippets) => {
+                let mut evaluator = mono::session();
+                for code in snippets {
+                    finish(run(&self.mode, &code, Some(&mut evaluator)));
+                }
+            }
+            // Statements run as they're read, so a long running pipe shows
+            // its output as it goes.
+            Source::Stdin if matches!(self.mode, Mode::Evaluator | Mode::Stream) => {
+                finish(mono::streamed_from(io::stdin().lock(), &mut mono::session()).map(drop))
+            }
+            Source::Stdin => {
+                let mut code = String::new();
+                io::stdin().read_to_string(&mut code)?;
+                finish(run(&self.mode, &code, None));
+            }
+        }
         Ok(())
-    } else {
-        Err(Box::from("File does not have the desired suffix."))
     }
 }
 
+// Runs the file in a fresh Evaluator each time it's modified, under a
+// banner with the time of the run. Errors are reported and the file is
+// watched on, since fixing them is what the next save is for.
+fn rerun(path: &Path, mode: &Mode) -> Result<(), Box<dyn std::error::Error>> {
+    mono::poll(path, |code| {
+        clear_screen();
+        let time = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |time| time.as_secs() as i64);
+        let time = DateTime::from_timestamp(time).format("%H:%M:%S");
+        println!(
+            "{}\n",
+            format!("[{}] {}", time.unwrap_or_default(), path.display())
+                .blue()
+                .bold()
+        );
+        let _ = run(mode, code, None);
+    })?;
+    Ok(())
+}
+
+// Runs the entry of the project the directory is in, the current one by
+// default, with `run_file` finding scripts from the project's root and search
+// paths.
+fn project(arguments: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let (cache, directory) = match arguments {
+        [flag, rest @ ..] if flag == "--no-cache" => (false, rest),
+        rest => (true, rest),
+    };
+    let directory = match directory {
+        [] => Path::new("."),
+        [directory] => Path::new(directory),
+        _ => return Err("Expected a single directory".into()),
+    };
+    let project = Project::find(directory)?;
+    let entry = project.entry();
+    let code = fs::read_to_string(&entry)?;
+    let mut evaluator = mono::session();
+    evaluator.set_paths(project.paths());
+    finish(
+        match cache {
+            true => mono::cached(&entry, &code, &mut evaluator, false),
+            false => mono::evaluator(&code, &mut evaluator),
+        }
+        .map(drop),
+    );
+    Ok(())
+}
+
+// Writes the Rust program equivalent to the file to `output`, or prints it.
+fn transpile(path: &str, output: Option<&String>) -> io::Result<()> {
+    let code = fs::read_to_string(path)?;
+    match (mono::transpiler(&code)?, output) {
+        (Ok(source), Some(output)) => fs::write(output, source)?,
+        (Ok(source), None) => print!("{}", source),
+        (Err(error), _) => finish(Err(error)),
+    }
+    Ok(())
+}
+
+fn bench() -> Result<(), Box<dyn std::error::Error>> {
+    for measurement in mono::benchmark::run(Duration::from_millis(500))? {
+        println!("{}", measurement);
+    }
+    Ok(())
+}
+
+fn serve(address: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let listener = TcpListener::bind(address)?;
+    println!("Serving on http://{}/eval", listener.local_addr()?);
+    mono::serve::serve(listener).map_err(Into::into)
+}
+
+// Takes `--error-format=<human|json>` out of the arguments, wherever it is.
+fn error_format(arguments: &mut Vec<String>) -> Result<(), Box<dyn std::error::Error>> {
+    let Some(index) = arguments
+        .iter()
+        .position(|argument| argument.starts_with("--error-format="))
+    else {
+        return Ok(());
+    };
+    let format = match &arguments.remove(index)["--error-format=".len()..] {
+        "human" => ErrorFormat::Human,
+        "json" => ErrorFormat::Json,
+        format => return Err(format!("Unknown error format: {}", format).into()),
+    };
+    mono::set_error_format(format);
+    Ok(())
+}
+
+// `--only` restricts the rules to those given, `--allow` turns one off. Both
+// can be repeated.
+fn lint(arguments: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let mut only = Vec::new();
+    let mut allowed = Vec::new();
+    let mut path = None;
+    let mut arguments = arguments.iter();
+    while let Some(argument) = arguments.next() {
+        let rules = match argument.as_str() {
+            "--only" => &mut only,
+            "--allow" => &mut allowed,
+            flag if flag.starts_with("-") => return Err(format!("Unknown flag: {}", flag).into()),
+            _ if path.is_some() => return Err("Only one path can be linted".into()),
+            _ => {
+                path = Some(argument);
+                continue;
+            }
+        };
+        let id = arguments.next().ok_or("Expected a rule")?;
+        rules.push(Rule::from_id(id).ok_or_else(|| {
+            let ids = Rule::ALL.map(|rule| rule.id()).join(", ");
+            format!("Unknown rule: {}, expected one of {}", id, ids)
+        })?);
+    }
+    let path = path.ok_or("Expected a path to lint")?;
+    let rules = match only.is_empty() {
+        true => Rule::ALL.to_vec(),
+        false => only,
+    };
+    let rules: Vec<Rule> = rules
+        .into_iter()
+        .filter(|rule| !allowed.contains(rule))
+        .collect();
+    let code = fs::read_to_string(path)?;
+    match mono::linter(&code, &rules) {
+        Ok(lints) if !lints.is_empty() => exit(LINTS_FOUND),
+        result => finish(result.map(drop)),
+    }
+    Ok(())
+}
+
 fn main() {
     #[cfg(target_os = "windows")]
     {
         use colored::control::set_virtual_terminal;
         set_virtual_terminal(true).expect("Failed to initialize virtual terminal!");
     }
-    let result = match env::args().collect::<Vec<String>>().as_slice() {
-        [_] => console(Mode::default()),
-        [_, flag] if flag == "-t" => console(Mode::Tokenizer),
-        [_, flag] if flag == "-p" => console(Mode::Parser),
-        [_, flag] if flag == "-e" => console(Mode::Evaluator),
-        [_, flag] if flag.starts_with("-") => Err(format!("Unknown flag: {}", flag).into()),
-        [_, path] => file(path, Mode::default()),
-        [_, flag, code] if flag == "-c" => Ok(run(&Mode::default(), &code, None)),
-        [_, flag, path] if flag == "-t" => file(path, Mode::Tokenizer),
-        [_, flag, path] if flag == "-p" => file(path, Mode::Parser),
-        [_, flag, path] if flag == "-e" => file(path, Mode::Evaluator),
-        [_, code_flag, mode_flag, code] if code_flag == "-c" && mode_flag == "-t" => {
-            Ok(run(&Mode::Tokenizer, &code, None))
+    // The main thread's stack is too small for the depth programs may reach.
+    let cli = thread::Builder::new()
+        .stack_size(mono::STACK_SIZE)
+        .spawn(cli)
+        .expect("Failed to start the CLI's thread!");
+    if cli.join().is_err() {
+        exit(RUNTIME_ERROR);
+    }
+}
+
+fn cli() {
+    let mut arguments = env::args().collect::<Vec<String>>();
+    if let Err(error) = error_format(&mut arguments) {
+        fail(error);
+    }
+    let result = match arguments.as_slice() {
+        [_, command, arguments @ ..] if command == "lint" => lint(arguments),
+        #[cfg(feature = "lsp")]
+        [_, command] if command == "lsp" => ran(mono::lsp::run()),
+        [_, command] if command == "serve" => ran(serve("127.0.0.1:8000")),
+        [_, command, address] if command == "serve" => ran(serve(address)),
+        [_, command, path] if command == "debug" => ran(mono::debug(Path::new(path))),
+        [_, command, arguments @ ..] if command == "run" => project(arguments),
+        [_, command, path, flag, output] if command == "build" && flag == "-o" => {
+            ran(mono::build(Path::new(path), Path::new(output)).map(finish))
         }
-        [_, code_flag, mode_flag, code] if code_flag == "-c" && mode_flag == "-p" => {
-            Ok(run(&Mode::Parser, &code, None))
+        // Not in the usage, it's for working on the evaluator.
+        [_, flag] if flag == "--bench-internal" => ran(bench()),
+        [_, command, path] if command == "transpile" => ran(transpile(path, None)),
+        [_, command, path, flag, output] if command == "transpile" && flag == "-o" => {
+            ran(transpile(path, Some(output)))
         }
-        [_, code_flag, mode_flag, code] if code_flag == "-c" && mode_flag == "-e" => {
-            Ok(run(&Mode::Evaluator, &code, None))
+        [_, command, path] if command == "exec" => {
+            ran(mono::exec(Path::new(path)).map(|result| finish(result.map(drop))))
         }
-        _ => Err("Invalid command line arguments".into()),
+        [_, command, path] if command == "watch" => ran(mono::watch(Path::new(path))),
+        [_, arguments @ ..] => Options::parse(arguments).and_then(Options::run),
+        [] => Options::parse(&[]).and_then(Options::run),
     };
 
     if let Err(error) = result {
-        usage();
+        fail(error);
+    }
+}
+
+fn fail(error: Box<dyn std::error::Error>) -> ! {
+    usage();
+    eprintln!("Error: {}", error);
+    exit(USAGE_ERROR);
+}
+
+// A command that was given what it needed and then failed, such as on a
+// file it couldn't read or a program it couldn't transpile. That's not a
+// usage error, so the usage isn't printed.
+fn ran(result: Result<(), impl fmt::Display>) -> Result<(), Box<dyn std::error::Error>> {
+    if let Err(error) = result {
         eprintln!("Error: {}", error);
-        exit(1);
+        exit(RUNTIME_ERROR);
     }
+    Ok(())
 }
@@ -0,0 +1,94 @@
+use super::error::MonoErrorKind;
+use super::position::Position;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+impl Severity {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        }
+    }
+}
+
+// An error or a lint as tools consume it. `kind` is the error's kind, such
+// as `SyntaxError`, or the lint's rule, and the span is half-open like a
+// token's. Errors at the end of the input have no span.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    pub kind: String,
+    pub message: String,
+    pub severity: Severity,
+    pub start: Option<Position>,
+    pub end: Option<Position>,
+}
+
+impl Diagnostic {
+    // A single line JSON object:
+    //
+    //     {"kind": "SyntaxError", "message": "...", "severity": "error",
+    //      "span": {"start": {"row": 1, "column": 5}, "end": {...}}}
+    //
+    // with a `null` span when there's none.
+    pub fn to_json(&self) -> String {
+        let span = match (&self.start, &self.end) {
+            (Some(start), Some(end)) => format!(
+                "{{\"start\": {}, \"end\": {}}}",
+                position(start),
+                position(end)
+            ),
+            _ => String::from("null"),
+        };
+        format!(
+            "{{\"kind\": {}, \"message\": {}, \"severity\": {}, \"span\": {}}}",
+            string(&self.kind),
+            string(&self.message),
+            string(self.severity.name()),
+            span
+        )
+    }
+}
+
+impl From<&MonoErrorKind> for Diagnostic {
+    fn from(error: &MonoErrorKind) -> Self {
+        Self {
+            kind: error.kind().to_string(),
+            message: error.to_string(),
+            severity: Severity::Error,
+            start: error.position().cloned(),
+            end: error.end(),
+        }
+    }
+}
+
+fn position(position: &Position) -> String {
+    format!(
+        "{{\"row\": {}, \"column\": {}}}",
+        position.row(),
+        position.column()
+    )
+}
+
+// A JSON string literal.
+pub(crate) fn string(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len() + 2);
+    escaped.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if c.is_control() => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
@@ -1,7 +1,8 @@
-use crate::parser::node::Node;
 use super::position::Position;
 use crate::evaluator::value::Value;
+use crate::parser::node::Node;
 use crate::tokenizer::token::{Token, TokenKind};
+use std::error::Error;
 use std::fmt;
 
 #[macro_export]
@@ -11,26 +12,210 @@ macro_rules! internal_err {
     };
 }
 
+// The error returned by every stage. It's boxed so results on the
+// evaluator's hot path stay small.
+pub type MonoError = Box<MonoErrorKind>;
+
+#[derive(Debug, PartialEq)]
+pub enum MonoErrorKind {
+    Syntax(Syntax),
+    Runtime(Runtime),
+}
+
+impl MonoErrorKind {
+    pub fn kind(&self) -> &str {
+        match self {
+            Self::Syntax(_) => "SyntaxError",
+            Self::Runtime(_) => "RuntimeError",
+        }
+    }
+
+    // Errors caused by the input ending early, more input may still make it valid.
+    pub fn is_incomplete(&self) -> bool {
+        matches!(
+            self,
+            Self::Syntax(
+                Syntax::UnexpectedEOF
+                    | Syntax::UnclosedStringDelimeter { .. }
+                    | Syntax::UnclosedTokenDelimeter { found: None, .. }
+            )
+        )
+    }
+
+    // Where the error was detected, if it's tied to a place in the source.
+    pub fn position(&self) -> Option<&Position> {
+        match self {
+            Self::Syntax(
+                Syntax::InvalidIntegerSize { start, .. }
+                | Syntax::InvalidFloatSize { start, .. }
+                | Syntax::UnclosedCharDelimeter { start, .. }
+                | Syntax::UnclosedStringDelimeter { start }
+                | Syntax::MultipleFloatingPoints { start, .. }
+                | Syntax::MissingFraction { start, .. }
+                | Syntax::InvalidExponent { start, .. }
+                | Syntax::InvalidIntegerLiteral { start, .. },
+            ) => Some(start),
+            Self::Syntax(
+                Syntax::InvalidEscape { position, .. }
+                | Syntax::UnexpectedChar { position, .. }
+                | Syntax::UnrecognizedChar { position, .. }
+                | Syntax::MultipleExpressions { position },
+            ) => Some(position),
+            Self::Runtime(Runtime::InvalidCondition { start, .. }) => start.as_ref(),
+            _ => self.token().map(|token| &token.start),
+        }
+    }
+
+    // Where the error's span ends, exclusive. Errors detected at a single
+    // character span just that character.
+    pub fn end(&self) -> Option<Position> {
+        match self {
+            Self::Syntax(
+                Syntax::InvalidIntegerSize { end, .. }
+                | Syntax::InvalidFloatSize { end, .. }
+                | Syntax::UnclosedCharDelimeter { end, .. }
+                | Syntax::MultipleFloatingPoints { end, .. }
+                | Syntax::MissingFraction { end, .. }
+                | Syntax::InvalidExponent { end, .. }
+                | Syntax::InvalidIntegerLiteral { end, .. },
+            ) => Some(end.clone()),
+            Self::Runtime(Runtime::InvalidCondition { end, .. }) => end.clone(),
+            _ => match self.token() {
+                Some(token) => Some(token.end.clone()),
+                None => self.position().cloned().map(|mut end| {
+                    end.next();
+                    end
+                }),
+            },
+        }
+    }
+
+    // The token the error points at, for errors tied to one.
+    fn token(&self) -> Option<&Token> {
+        match self {
+            Self::Syntax(error) => match error {
+                Syntax::UnclosedTokenDelimeter {
+                    found: Some(token), ..
+                }
+                | Syntax::UnclosedTokenDelimeter { start: token, .. }
+                | Syntax::UnexpectedToken { token, .. }
+                | Syntax::MissingDefaultValue { argument: token }
+                | Syntax::DuplicateArgument { argument: token }
+                | Syntax::NestingTooDeep { token, .. }
+                | Syntax::OuterArgument {
+                    argument: token, ..
+                } => Some(token),
+                _ => None,
+            },
+            Self::Runtime(error) => match error {
+                Runtime::DivisionByZero { division: token }
+                | Runtime::IntegerOverflow { operator: token }
+                | Runtime::InvalidOperation {
+                    operator: token, ..
+                }
+                | Runtime::UnknownIdentifier {
+                    identifier: token, ..
+                }
+                | Runtime::IncorrectParameters { call: token, .. }
+                | Runtime::InvalidIndex {
+                    identifier: token, ..
+                }
+                | Runtime::NonIndexable {
+                    identifier: token, ..
+                }
+                | Runtime::InvalidKeyword { keyword: token, .. }
+                | Runtime::UnknownMember { member: token, .. }
+                | Runtime::NonIterable {
+                    operator: token, ..
+                }
+                | Runtime::InvalidDestructuring {
+                    operator: token, ..
+                }
+                | Runtime::IoError { call: token, .. }
+                | Runtime::NoLength { call: token, .. }
+                | Runtime::Incomparable { call: token, .. }
+                | Runtime::InvalidComparison { call: token, .. }
+                | Runtime::EmptyValue { call: token, .. }
+                | Runtime::InvalidArgumentType { call: token, .. }
+                | Runtime::InvalidFormat { call: token, .. }
+                | Runtime::InvalidPattern { call: token, .. }
+                | Runtime::InvalidExitCode { call: token, .. }
+                | Runtime::InvalidDeclaration {
+                    identifier: token, ..
+                } => Some(token),
+                _ => None,
+            },
+        }
+    }
+}
+
+impl fmt::Display for MonoErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Syntax(error) => error.fmt(f),
+            Self::Runtime(error) => error.fmt(f),
+        }
+    }
+}
+
+impl Error for MonoErrorKind {}
 
-pub trait MonoError: fmt::Display {
-    fn kind(&self) -> &str;
+impl From<Syntax> for MonoError {
+    fn from(value: Syntax) -> Self {
+        Box::new(MonoErrorKind::Syntax(value))
+    }
 }
 
-impl From<Syntax> for Option<Result<Token, Box<dyn MonoError>>> {
+impl From<Runtime> for MonoError {
+    fn from(value: Runtime) -> Self {
+        Box::new(MonoErrorKind::Runtime(value))
+    }
+}
+
+impl From<Syntax> for Option<Result<Token, MonoError>> {
     fn from(value: Syntax) -> Self {
-        Some(Err(Box::new(value)))
+        Some(Err(value.into()))
     }
 }
 
-impl From<Syntax> for Result<Box<Node>, Box<dyn MonoError>> {
+impl From<Syntax> for Result<Box<Node>, MonoError> {
     fn from(value: Syntax) -> Self {
-        Err(Box::new(value))
+        Err(value.into())
     }
 }
 
-impl From<Runtime> for Result<Value, Box<dyn MonoError>> {
+impl From<Runtime> for Result<Value, MonoError> {
     fn from(value: Runtime) -> Self {
-        Err(Box::new(value))
+        Err(value.into())
+    }
+}
+
+// Errors outlive the evaluator and may be sent across threads, so the values
+// they mention are kept as their rendering and type rather than as `Value`s.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Snapshot {
+    pub value: String,
+    pub kind: &'static str,
+}
+
+impl From<Value> for Snapshot {
+    fn from(value: Value) -> Self {
+        Self::from(&value)
+    }
+}
+
+impl From<&Value> for Snapshot {
+    fn from(value: &Value) -> Self {
+        Self {
+            value: value.to_string(),
+            kind: value.to_type(),
+        }
+    }
+}
+
+impl fmt::Display for Snapshot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.value)
     }
 }
 
@@ -52,6 +237,10 @@ pub enum Syntax {
     UnclosedStringDelimeter {
         start: Position,
     },
+    InvalidEscape {
+        position: Position,
+        found: Option<char>,
+    },
     UnclosedTokenDelimeter {
         start: Token,
         found: Option<Token>,
@@ -65,6 +254,19 @@ pub enum Syntax {
         start: Position,
         end: Position,
     },
+    MissingFraction {
+        start: Position,
+        end: Position,
+    },
+    InvalidExponent {
+        start: Position,
+        end: Position,
+    },
+    InvalidIntegerLiteral {
+        start: Position,
+        end: Position,
+        radix: u32,
+    },
     UnrecognizedChar {
         position: Position,
         c: char,
@@ -72,11 +274,28 @@ pub enum Syntax {
     UnexpectedToken {
         token: Token,
         expected: Vec<TokenKind>,
+        // A keyword the token, or an identifier before it on the same line,
+        // is probably a misspelling of.
+        suggestion: Option<String>,
     },
     UnexpectedEOF,
     MultipleExpressions {
         position: Position,
     },
+    MissingDefaultValue {
+        argument: Token,
+    },
+    DuplicateArgument {
+        argument: Token,
+    },
+    NestingTooDeep {
+        token: Token,
+        maximum: usize,
+    },
+    OuterArgument {
+        argument: Token,
+        is_nonlocal: bool,
+    },
 }
 
 impl fmt::Display for Syntax {
@@ -104,6 +323,18 @@ impl fmt::Display for Syntax {
             } => {
                 write!(f, "Encountered unclosed Character delimiter `'`. Character deceleration starts at {} but a closing delimiter was not found.", start)
             }
+            Self::InvalidEscape {
+                position,
+                found: Some(c),
+            } => {
+                write!(f, "Invalid escape sequence `\\{}` at position {}. Supported escapes are \\n, \\t, \\r, \\0, \\\\, \\\" and \\', and \\xHH in byte strings.", c, position)
+            }
+            Self::InvalidEscape {
+                position,
+                found: None,
+            } => {
+                write!(f, "Encountered an unfinished escape sequence at position {}.", position)
+            }
             Self::UnclosedStringDelimeter { start } => {
                 write!(f, "Encountered unclosed String delimiter `\"`. String deceleration starts at {} but a closing delimiter was not found.", start)
             }
@@ -127,20 +358,30 @@ impl fmt::Display for Syntax {
             Self::MultipleFloatingPoints { start, end } => {
                 write!(f, "Multiple floating points detected between {} and {}. A number can only contain one decimal point.", start, end)
             }
+            Self::MissingFraction { start, end } => {
+                write!(f, "Missing digits after the decimal point between {} and {}. Write `1.0` rather than `1.`.", start, end)
+            }
+            Self::InvalidExponent { start, end } => {
+                write!(f, "Invalid exponent detected between {} and {}. An exponent must be followed by digits, as in `1e-3`.", start, end)
+            }
+            Self::InvalidIntegerLiteral { start, end, radix } => {
+                write!(f, "Invalid base {} integer literal between {} and {}.", radix, start, end)
+            }
             Self::UnrecognizedChar { position, c } => {
                 write!(f, "Encountered unrecognized character '{}' at position {}. Ensure your input only contains valid characters.", c, position)
             }
-            Self::UnexpectedToken { token, expected } => {
+            Self::UnexpectedToken { token, expected, suggestion } => {
                 write!(
                     f,
                     "Encountered unexpected token `{:?}` at position {}, expected one of the following: {}.",
-                    token.kind, 
-                    token.start, 
+                    token.kind,
+                    token.start,
                     expected.iter()
                             .map(|kind| kind.to_kind())
                             .collect::<Vec<_>>()
                             .join(", ")
-                )
+                )?;
+                did_you_mean(f, suggestion)
             }
             Self::UnexpectedEOF => {
                 write!(
@@ -151,48 +392,134 @@ impl fmt::Display for Syntax {
             Self::MultipleExpressions { position } => {
                 write!(f, "Detected multiple expressions at {}. Ensure you're providing a single, valid expression.", position)
             }
+            Self::MissingDefaultValue { argument } => {
+                write!(f, "Argument `{:?}` at {} follows an argument with a default value, so it must have a default value too.", argument.kind, argument.start)
+            }
+            Self::DuplicateArgument { argument } => {
+                write!(f, "Argument `{:?}` at {} is declared more than once, argument and field names must be unique.", argument.kind, argument.start)
+            }
+            Self::NestingTooDeep { token, maximum } => {
+                write!(f, "Encountered `{:?}` at {} nested more than {} levels deep. Consider splitting the expression up.", token.kind, token.start, maximum)
+            }
+            Self::OuterArgument { argument, is_nonlocal } => {
+                let keyword = if *is_nonlocal { "nonlocal" } else { "global" };
+                write!(f, "Argument `{:?}` at {} can't be declared {}, it's always a variable of its function.", argument.kind, argument.start, keyword)
+            }
         }
     }
 }
 
-impl MonoError for Syntax {
-    fn kind(&self) -> &str {
-        "SyntaxError"
-    }
-}
-
-
 #[derive(Debug, PartialEq)]
 pub enum Runtime {
     DivisionByZero {
         division: Token,
     },
+    IntegerOverflow {
+        operator: Token,
+    },
     InvalidOperation {
         operator: Token,
-        right: Option<Value>,
-        left: Value,
+        right: Option<Snapshot>,
+        left: Snapshot,
     },
     UnknownIdentifier {
         identifier: Token,
+        // The closest name in scope when the error occurred.
+        suggestion: Option<String>,
     },
     IncorrectParameters {
         name: String,
         call: Token,
         expected: Vec<String>,
-        found: Vec<Value>,
+        found: Vec<Snapshot>,
     },
     InvalidIndex {
         identifier: Token,
-        index: Value,
+        index: Snapshot,
     },
     NonIndexable {
         identifier: Token,
-        index: Value,
+        index: Snapshot,
     },
     InvalidValue {
-        expected: Value,
-        found: Value,
-    }
+        expected: Snapshot,
+        found: Snapshot,
+    },
+    InvalidKeyword {
+        name: String,
+        keyword: Token,
+    },
+    UnknownMember {
+        value: Snapshot,
+        member: Token,
+    },
+    NonIterable {
+        value: Snapshot,
+        operator: Token,
+    },
+    InvalidDestructuring {
+        pattern: String,
+        value: Snapshot,
+        operator: Token,
+    },
+    LimitExceeded {
+        limit: &'static str,
+        maximum: usize,
+    },
+    InvalidCondition {
+        found: Snapshot,
+        start: Option<Position>,
+        end: Option<Position>,
+    },
+    IoError {
+        call: Token,
+        message: String,
+    },
+    NoLength {
+        value: Snapshot,
+        call: Token,
+    },
+    Incomparable {
+        left: Snapshot,
+        right: Snapshot,
+        call: Token,
+    },
+    InvalidComparison {
+        found: Snapshot,
+        call: Token,
+    },
+    EmptyValue {
+        value: Snapshot,
+        call: Token,
+    },
+    InvalidArgumentType {
+        name: String,
+        call: Token,
+        argument: String,
+        expected: String,
+        found: Snapshot,
+    },
+    // A `global` or `nonlocal` declaration that can't be honoured.
+    InvalidDeclaration {
+        identifier: Token,
+        reason: &'static str,
+    },
+    // A `format` template that can't be formatted with its values.
+    InvalidFormat {
+        call: Token,
+        reason: String,
+    },
+    // A regular expression that doesn't compile.
+    InvalidPattern {
+        call: Token,
+        pattern: String,
+        reason: String,
+    },
+    // An `exit` status the parent process couldn't be given.
+    InvalidExitCode {
+        call: Token,
+        code: i32,
+    },
 }
 
 impl fmt::Display for Runtime {
@@ -201,43 +528,230 @@ impl fmt::Display for Runtime {
             Self::DivisionByZero { division } => {
                 write!(f, "Division by zero at position {}.", division.start)
             }
+            Self::IntegerOverflow { operator } => {
+                write!(
+                    f,
+                    "Integer overflow at position {}, the result doesn't fit in an Integer.",
+                    operator.start
+                )
+            }
             Self::InvalidOperation {
                 operator,
                 right,
                 left,
             } => {
                 if let Some(right) = right {
-                    write!(f, "Invalid binary operation detected. Operator `{}` was used with left value `{}` and right value `{}`.", operator, right, left)
+                    write!(f, "Invalid binary operation detected. Operator `{}` was used with left value `{}` and right value `{}`.", operator, right, left)?;
                 } else {
-                    write!(f, "Invalid unary operation detected. Operator `{}` was used with value `{}`.", operator, left)
+                    write!(
+                        f,
+                        "Invalid unary operation detected. Operator `{}` was used with value `{}`.",
+                        operator, left
+                    )?;
                 }
+                forgot_return(f, right.as_ref(), left)
             }
-            Self::UnknownIdentifier { identifier } => {
-                write!(f, "Unknown identifier `{}` detected.", identifier)
+            Self::UnknownIdentifier {
+                identifier,
+                suggestion,
+            } => {
+                write!(f, "Unknown identifier `{}` detected.", identifier)?;
+                did_you_mean(f, suggestion)
             }
-            Self::IncorrectParameters { expected, found, name, call } => {
-                write!(f, "Incorrect parameters: ({}) for function '{}' at {}, expected: ({}).", 
-                    found.iter().map(|v| format!("{}", v)).collect::<Vec<_>>().join(", "),
+            Self::IncorrectParameters {
+                expected,
+                found,
+                name,
+                call,
+            } => {
+                write!(
+                    f,
+                    "Incorrect parameters: ({}) for function '{}' at {}, expected: ({}).",
+                    found
+                        .iter()
+                        .map(|v| format!("{}", v))
+                        .collect::<Vec<_>>()
+                        .join(", "),
                     name,
                     call.start,
-                    expected.iter().map(|p| format!("{}", p)).collect::<Vec<_>>().join(", ")
+                    expected
+                        .iter()
+                        .map(|p| p.to_string())
+                        .collect::<Vec<_>>()
+                        .join(", ")
                 )
-            },
+            }
             Self::InvalidIndex { index, identifier } => {
-                write!(f, "Invalid index `{}` for iterable '{}'.", index, identifier)
+                write!(
+                    f,
+                    "Invalid index `{}` for iterable '{}'.",
+                    index, identifier
+                )
             }
             Self::NonIndexable { identifier, index } => {
-                write!(f, "Value '{}' isn't an iterable. But, was indexed with index `{}`.", identifier, index)
+                write!(
+                    f,
+                    "Value '{}' isn't an iterable. But, was indexed with index `{}`.",
+                    identifier, index
+                )
             }
             Self::InvalidValue { expected, found } => {
-                write!(f, "Invalid value encountered. Expected: `{}` but found `{}`.", expected.to_type(), found)
+                write!(
+                    f,
+                    "Invalid value encountered. Expected: `{}` but found `{}`.",
+                    expected.kind, found
+                )
+            }
+            Self::InvalidKeyword { name, keyword } => {
+                write!(f, "Invalid keyword parameter `{:?}` at {} for function '{}'. It is either not an argument of the function or was already given.", keyword.kind, keyword.start, name)
+            }
+            Self::InvalidDestructuring {
+                pattern,
+                value,
+                operator,
+            } => {
+                write!(
+                    f,
+                    "Can't destructure value `{}` into `{}` at position {}.",
+                    value, pattern, operator.start
+                )
+            }
+            Self::NonIterable { value, operator } => {
+                write!(
+                    f,
+                    "Value `{}` of type {} isn't iterable, used at position {}.",
+                    value, value.kind, operator.start
+                )
+            }
+            Self::LimitExceeded { limit, maximum } => {
+                write!(
+                    f,
+                    "Exceeded the limit of {} {}, the program was stopped.",
+                    maximum, limit
+                )
+            }
+            Self::InvalidCondition {
+                found,
+                start: Some(start),
+                end: Some(end),
+            } => {
+                write!(f, "Invalid condition at {} until {}. Expected a Boolean but found `{}` of type {}.", start, end, found, found.kind)
+            }
+            Self::InvalidCondition { found, .. } => {
+                write!(
+                    f,
+                    "Invalid condition. Expected a Boolean but found `{}` of type {}.",
+                    found, found.kind
+                )
+            }
+            Self::IoError { call, message } => {
+                write!(f, "I/O error in the call at {}: {}.", call.start, message)
+            }
+            Self::NoLength { value, call } => {
+                write!(
+                    f,
+                    "Value `{}` of type {} has no length, used in the call at {}.",
+                    value, value.kind, call.start
+                )
+            }
+            Self::Incomparable { left, right, call } => {
+                write!(
+                    f,
+                    "Values `{}` of type {} and `{}` of type {} can't be compared, in the call at {}.",
+                    left, left.kind, right, right.kind, call.start
+                )
+            }
+            Self::InvalidComparison { found, call } => {
+                write!(
+                    f,
+                    "Invalid comparison in the call at {}. Expected an Integer but found `{}` of type {}.",
+                    call.start, found, found.kind
+                )
+            }
+            Self::EmptyValue { value, call } => {
+                write!(
+                    f,
+                    "Value `{}` of type {} is empty, used in the call at {}.",
+                    value, value.kind, call.start
+                )
+            }
+            Self::InvalidArgumentType {
+                name,
+                call,
+                argument,
+                expected,
+                found,
+            } => {
+                write!(
+                    f,
+                    "Invalid argument `{}` for function '{}' at {}, expected {} but found `{}` of type {}.",
+                    argument, name, call.start, expected, found, found.kind
+                )
+            }
+            Self::UnknownMember { value, member } => {
+                write!(
+                    f,
+                    "Unknown member {} for value `{}` of type {}.",
+                    member, value, value.kind
+                )
+            }
+            Self::InvalidDeclaration { identifier, reason } => {
+                write!(
+                    f,
+                    "Invalid declaration of `{:?}` at position {}, {}.",
+                    identifier.kind, identifier.start, reason
+                )
+            }
+            Self::InvalidFormat { call, reason } => {
+                write!(
+                    f,
+                    "Invalid format in the call at {}, {}.",
+                    call.start, reason
+                )
+            }
+            Self::InvalidPattern {
+                call,
+                pattern,
+                reason,
+            } => {
+                write!(
+                    f,
+                    "Invalid pattern {:?} in the call at {}:\n{}",
+                    pattern, call.start, reason
+                )
+            }
+            Self::InvalidExitCode { call, code } => {
+                write!(
+                    f,
+                    "Invalid exit code {} in the call at {}, it must be between 0 and 255.",
+                    code, call.start
+                )
             }
         }
     }
 }
 
-impl MonoError for Runtime {
-    fn kind(&self) -> &str {
-        "RuntimeError"
+// Operating on `None` is most often the result of a function that ended
+// without a `return`.
+fn forgot_return(
+    f: &mut fmt::Formatter<'_>,
+    right: Option<&Snapshot>,
+    left: &Snapshot,
+) -> fmt::Result {
+    let is_none = |snapshot: &Snapshot| snapshot.kind == "None";
+    let value = match (right.map(is_none), is_none(left)) {
+        (Some(true), true) => "Both values are",
+        (Some(true), false) => "The left value is",
+        (Some(false), true) => "The right value is",
+        (None, true) => "The value is",
+        (_, false) => return Ok(()),
+    };
+    write!(f, " {} None, did a function forget to return?", value)
+}
+
+fn did_you_mean(f: &mut fmt::Formatter<'_>, suggestion: &Option<String>) -> fmt::Result {
+    match suggestion {
+        Some(suggestion) => write!(f, " Did you mean `{}`?", suggestion),
+        None => Ok(()),
     }
 }
@@ -1,2 +1,4 @@
+pub mod diagnostic;
 pub mod error;
 pub mod position;
+pub mod suggestion;
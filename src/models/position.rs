@@ -1,6 +1,6 @@
 use std::fmt;
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Position {
     row: usize,
     column: usize,
@@ -11,10 +11,24 @@ impl Position {
         Self { row, column }
     }
 
+    pub fn row(&self) -> usize {
+        self.row
+    }
+
+    pub fn column(&self) -> usize {
+        self.column
+    }
+
     pub fn next(&mut self) {
         self.column += 1;
     }
 
+    // Moves to the last column of a tab, the next character is at the
+    // following tab stop.
+    pub fn tab(&mut self, width: usize) {
+        self.column = (self.column.saturating_sub(1) / width + 1) * width;
+    }
+
     pub fn newline(&mut self) {
         self.row += 1;
         self.column = 0;
@@ -0,0 +1,36 @@
+// The candidate closest to a misspelt name, for "did you mean" hints. A
+// candidate must be within a third of the name's length in edits, so short
+// names only match exactly and nothing is suggested for them. Ties go to the
+// candidate first in alphabetical order, whatever order they come in.
+pub fn closest<'a>(name: &str, candidates: impl IntoIterator<Item = &'a str>) -> Option<&'a str> {
+    let name: Vec<char> = name.chars().collect();
+    let maximum = name.len() / 3;
+    candidates
+        .into_iter()
+        .filter_map(|candidate| {
+            let edits = distance(&name, &candidate.chars().collect::<Vec<_>>());
+            (0 < edits && edits <= maximum).then_some((edits, candidate))
+        })
+        .min()
+        .map(|(_, candidate)| candidate)
+}
+
+// Edits between two names: inserting, removing or replacing a character, or
+// swapping two adjacent ones, as in `whlie`.
+fn distance(a: &[char], b: &[char]) -> usize {
+    let mut rows = vec![(0..=b.len()).collect::<Vec<_>>()];
+    for i in 1..=a.len() {
+        let mut row = vec![i; b.len() + 1];
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            row[j] = (rows[i - 1][j] + 1)
+                .min(row[j - 1] + 1)
+                .min(rows[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                row[j] = row[j].min(rows[i - 2][j - 2] + 1);
+            }
+        }
+        rows.push(row);
+    }
+    rows[a.len()][b.len()]
+}
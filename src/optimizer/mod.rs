@@ -0,0 +1,120 @@
+use crate::evaluator::value::Value;
+use crate::parser::node::Node;
+use crate::parser::visitor::{fold_children, NodeFolder};
+use crate::tokenizer::token::{Token, TokenKind};
+use std::mem;
+
+// An optional pass between the parser and the evaluator. Arithmetic and
+// boolean expressions on literals are folded into a single literal, so a
+// loop no longer recomputes its constant subexpressions on every iteration,
+// and branches on a literal condition are replaced by the branch taken.
+// Expressions that would fail (such as `1 / 0`) are left for the evaluator
+// to report.
+//
+// Loop-invariant expressions that read variables aren't hoisted: any call in
+// the loop can reassign a variable of the caller through `nonlocal` or
+// `global`, and evaluating an expression before the loop would raise its
+// errors even when the loop never reaches it.
+pub struct Optimizer;
+
+impl Optimizer {
+    pub fn optimize(mut node: Node) -> Node {
+        Optimizer.fold(&mut node);
+        node
+    }
+}
+
+// Children are folded first, so a node only sees literals below it once
+// they're as folded as they get.
+impl NodeFolder for Optimizer {
+    fn fold(&mut self, node: &mut Node) {
+        fold_children(self, node);
+        match node {
+            Node::BinaryOp {
+                left,
+                operator,
+                right,
+            } => {
+                if let (Node::Atom { value: left }, Node::Atom { value: right }) =
+                    (left.as_ref(), right.as_ref())
+                {
+                    let value = Value::from(left).binary_operation(Value::from(right), operator);
+                    if let Some(folded) = literal(value.ok(), left, right) {
+                        *node = folded;
+                    }
+                }
+            }
+            Node::UnaryOp { operator, value } => {
+                if let Node::Atom { value } = value.as_ref() {
+                    let folded = Value::from(value).unary_operation(operator);
+                    if let Some(folded) = literal(folded.ok(), operator, value) {
+                        *node = folded;
+                    }
+                }
+            }
+            Node::If {
+                condition,
+                block,
+                else_block,
+            } => match (boolean(condition), else_block) {
+                (Some(true), _) => *node = mem::replace(block.as_mut(), none(condition)),
+                (Some(false), Some(else_block)) => {
+                    *node = mem::replace(else_block.as_mut(), none(condition))
+                }
+                (Some(false), None) => *node = none(condition),
+                (None, _) => {}
+            },
+            Node::While { condition, .. } if boolean(condition) == Some(false) => {
+                *node = none(condition);
+            }
+            // The block runs exactly once.
+            Node::DoWhile { block, condition } if boolean(condition) == Some(false) => {
+                *node = mem::replace(block.as_mut(), none(condition));
+            }
+            _ => {}
+        }
+    }
+}
+
+fn boolean(node: &Node) -> Option<bool> {
+    match node {
+        Node::Atom {
+            value:
+                Token {
+                    kind: TokenKind::Boolean(value),
+                    ..
+                },
+        } => Some(*value),
+        _ => None,
+    }
+}
+
+// A `None` literal in place of a removed branch, positioned at its condition.
+fn none(condition: &Node) -> Node {
+    let mut none = condition.clone();
+    if let Node::Atom { value } = &mut none {
+        value.kind = TokenKind::None;
+    }
+    none
+}
+
+// Only values that have a literal form can be folded.
+fn literal(value: Option<Value>, first: &Token, last: &Token) -> Option<Node> {
+    let kind = match value? {
+        Value::Integer(value) => TokenKind::Integer(value),
+        Value::Float(value) => TokenKind::Float(value),
+        Value::Boolean(value) => TokenKind::Boolean(value),
+        Value::String(value) => TokenKind::String(value.to_string()),
+        Value::Bytes(value) => TokenKind::Bytes(value.to_vec()),
+        Value::Character(value) => TokenKind::Character(value),
+        Value::None => TokenKind::None,
+        _ => return None,
+    };
+    Some(Node::Atom {
+        value: Token {
+            start: first.start.clone(),
+            end: last.end.clone(),
+            kind,
+        },
+    })
+}
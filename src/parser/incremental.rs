@@ -0,0 +1,30 @@
+use crate::parser::Parser;
+use crate::tokenizer::Tokenizer;
+
+// Collects REPL input until it forms complete statements, so a block or a
+// multi-line string can be continued on the next line. The pending input is
+// parsed again as each line is added.
+#[derive(Default)]
+pub struct Incremental {
+    buffer: String,
+}
+
+impl Incremental {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Returns the complete input once it parses, or once it fails in a way
+    // more input can't fix. `None` means the input is still open.
+    pub fn feed(&mut self, line: &str) -> Option<String> {
+        self.buffer.push_str(line);
+        match Parser::new(Tokenizer::new(self.buffer.chars())).parse() {
+            Err(error) if error.is_incomplete() => None,
+            _ => Some(std::mem::take(&mut self.buffer)),
+        }
+    }
+
+    pub fn is_pending(&self) -> bool {
+        !self.buffer.is_empty()
+    }
+}
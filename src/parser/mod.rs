@@ -1,10 +1,15 @@
+pub mod incremental;
 pub mod node;
+pub mod resolver;
+mod source;
+pub mod visitor;
 
-use crate::models::error::{MonoError, Syntax};
-use crate::parser::node::Node;
-use crate::tokenizer::token::{Token, TokenKind};
-use crate::Tokenizer;
-use core::str::Chars;
+use crate::models::error::{MonoError, MonoErrorKind, Syntax};
+use crate::models::suggestion::closest;
+use crate::parser::node::{Argument, Arm, CasePattern, Node, Pattern};
+use crate::parser::resolver::Resolver;
+use crate::tokenizer::symbol::Symbol;
+use crate::tokenizer::token::{Token, TokenKind, KEYWORDS};
 
 use std::iter::Peekable;
 
@@ -16,16 +21,17 @@ macro_rules! atom {
 
 macro_rules! unexpected_token {
     ($token:expr, $expected:expr) => {
-        Err(Box::new(Syntax::UnexpectedToken {
+        Err(MonoError::from(Syntax::UnexpectedToken {
             token: $token,
             expected: $expected,
+            suggestion: None,
         }))
     };
 }
 
 macro_rules! unclosed_token {
     ($start:expr, $end:expr, $delimeter:expr) => {
-        Err(Box::new(Syntax::UnclosedTokenDelimeter {
+        Err(MonoError::from(Syntax::UnclosedTokenDelimeter {
             start: $start,
             found: $end,
             delimiter: $delimeter,
@@ -33,34 +39,101 @@ macro_rules! unclosed_token {
     };
 }
 
-type ParserItem = Result<Box<Node>, Box<dyn MonoError>>;
+type ParserItem = Result<Box<Node>, MonoError>;
+type CallParameters = (Vec<Box<Node>>, Vec<(Token, Box<Node>)>);
 
-pub struct Parser<'a> {
-    tokenizer: Tokenizer<Peekable<Chars<'a>>>,
+pub type ParserToken = Result<Token, MonoError>;
+
+// How deep expressions, blocks and patterns may nest. Parsing is recursive,
+// so this keeps crafted input from overflowing the stack.
+pub const MAX_NESTING: usize = 64;
+
+// Parses any stream of tokens, usually a `Tokenizer` but a pre-tokenized
+// `Vec<Token>` works as well through `tokens.into_iter().map(Ok)`.
+pub struct Parser<Tokens: Iterator<Item = ParserToken>> {
+    tokens: Peekable<Continued<Tokens>>,
+    depth: usize,
+    // The identifiers starting statements on the current line, a keyword
+    // they misspell is suggested when the line fails to parse.
+    line: Vec<Token>,
+    // The arguments of each function whose body is being parsed, the
+    // innermost last.
+    functions: Vec<Vec<Symbol>>,
 }
 
-impl<'a> Parser<'a> {
-    pub fn new(tokenizer: Tokenizer<Peekable<Chars<'a>>>) -> Self {
-        Self { tokenizer }
+impl<Tokens: Iterator<Item = ParserToken>> Parser<Tokens> {
+    pub fn new(tokens: Tokens) -> Self {
+        Self {
+            tokens: Continued {
+                tokens,
+                open: Vec::new(),
+            }
+            .peekable(),
+            depth: 0,
+            line: Vec::new(),
+            functions: Vec::new(),
+        }
     }
 
     pub fn parse(&mut self) -> ParserItem {
-        self.parse_program()
+        let mut program = self.parse_program()?;
+        Resolver::new().resolve(&mut program);
+        Ok(program)
     }
 
-    fn expect_token(&mut self, expected: TokenKind) -> Result<Token, Box<dyn MonoError>> {
-        match self.tokenizer.next() {
+    // Yields the program's top-level statements as they're parsed, so they
+    // can be evaluated without waiting for (or holding) the whole program.
+    pub fn statements(&mut self) -> Statements<'_, Tokens> {
+        Statements {
+            parser: self,
+            failed: false,
+        }
+    }
+
+    fn expect_token(&mut self, expected: TokenKind) -> Result<Token, MonoError> {
+        match self.tokens.next() {
             Some(Ok(token)) if token.kind == expected => Ok(token),
             Some(Ok(token)) => unexpected_token!(token, vec![expected]),
-            Some(Err(error)) => Err(error.into()),
-            None => Err(Box::new(Syntax::UnexpectedEOF)),
+            Some(Err(error)) => Err(error),
+            None => Err(MonoError::from(Syntax::UnexpectedEOF)),
+        }
+    }
+
+    // The token `peek` already found.
+    fn advance(&mut self) -> Result<Token, MonoError> {
+        self.tokens
+            .next()
+            .unwrap_or_else(|| Err(MonoError::from(Syntax::UnexpectedEOF)))
+    }
+
+    // The tokenizer error `peek` already found.
+    fn take_error(&mut self) -> MonoError {
+        match self.tokens.next() {
+            Some(Err(error)) => error,
+            _ => MonoError::from(Syntax::UnexpectedEOF),
+        }
+    }
+
+    // Runs `parse` one level deeper, failing once past `MAX_NESTING`.
+    fn nested<T>(&mut self, parse: fn(&mut Self) -> Result<T, MonoError>) -> Result<T, MonoError> {
+        if self.depth >= MAX_NESTING {
+            if let Some(Ok(token)) = self.tokens.peek() {
+                return Err(MonoError::from(Syntax::NestingTooDeep {
+                    token: token.clone(),
+                    maximum: MAX_NESTING,
+                }));
+            }
         }
+        self.depth += 1;
+        let result = parse(self);
+        self.depth -= 1;
+        result
     }
 
     fn consume(&mut self, kind: TokenKind) {
-        while let Some(Ok(token)) = self.tokenizer.peek() {
+        while let Some(Ok(token)) = self.tokens.peek() {
             if token.kind == kind {
-                self.tokenizer.next();
+                self.tokens.next();
             } else {
                 break;
             }
@@ -74,13 +147,13 @@ impl<'a> Parser<'a> {
         right: fn(&mut Self) -> ParserItem,
     ) -> ParserItem {
         let mut root = left(self)?;
-        while let Some(Ok(token)) = self.tokenizer.peek() {
+        while let Some(Ok(token)) = self.tokens.peek() {
             if !operators.contains(&token.kind) {
                 break;
             }
             root = Box::new(Node::BinaryOp {
                 left: root,
-                operator: self.tokenizer.next().unwrap()?,
+                operator: self.advance()?,
                 right: right(self)?,
             });
         }
@@ -93,42 +166,41 @@ impl<'a> Parser<'a> {
         operand: fn(&mut Self) -> ParserItem,
         defualt: fn(&mut Self) -> ParserItem,
     ) -> ParserItem {
-        match self.tokenizer.peek() {
+        match self.tokens.peek() {
             Some(Ok(token)) if operators.contains(&token.kind) => Ok(Box::new(Node::UnaryOp {
-                operator: self.tokenizer.next().unwrap()?,
-                value: operand(self)?,
+                operator: self.advance()?,
+                value: self.nested(operand)?,
             })),
             _ => defualt(self),
         }
     }
 
-    fn parse_parameters(
+    fn parse_separated<T>(
         &mut self,
         delimiter: TokenKind,
-    ) -> Result<Vec<Box<Node>>, Box<dyn MonoError>> {
+        item: fn(&mut Self) -> Result<T, MonoError>,
+    ) -> Result<Vec<T>, MonoError> {
         let mut parameters = Vec::new();
-        if matches!(self.tokenizer.peek(), Some(Ok(token)) if token.kind == delimiter) {
+        if matches!(self.tokens.peek(), Some(Ok(token)) if token.kind == delimiter) {
             return Ok(parameters);
         }
 
         loop {
-            parameters.push(self.parse_bool_expr()?);
-            match self.tokenizer.peek() {
+            parameters.push(item(self)?);
+            match self.tokens.peek() {
                 Some(Ok(token)) => match &token.kind {
                     k if k == &delimiter => break,
                     TokenKind::Comma => {
-                        self.tokenizer.next();
+                        self.tokens.next();
                     }
                     _ => {
                         return unexpected_token!(
-                            self.tokenizer.next().unwrap()?,
+                            self.advance()?,
                             vec![delimiter, TokenKind::Comma]
                         )
                     }
                 },
-                Some(Err(_)) => {
-                    return Err(self.tokenizer.next().expect("unreachable").unwrap_err())
-                }
+                Some(Err(_)) => return Err(self.take_error()),
                 None => break,
             }
         }
@@ -136,53 +208,100 @@ impl<'a> Parser<'a> {
         Ok(parameters)
     }
 
-    fn parse_arguments(&mut self) -> Result<Vec<Token>, Box<dyn MonoError>> {
-        let mut arguments = Vec::new();
+    fn parse_spread(&mut self) -> ParserItem {
+        match self.tokens.peek() {
+            Some(Ok(token)) if token.kind == TokenKind::Mul => Node::Spread {
+                operator: self.advance()?,
+                value: self.parse_bool_expr()?,
+            }
+            .into(),
+            _ => self.parse_bool_expr(),
+        }
+    }
+
+    fn parse_call_parameter(&mut self) -> Result<(Option<Token>, Box<Node>), MonoError> {
+        let value = self.parse_spread()?;
+        if !matches!(self.tokens.peek(), Some(Ok(token)) if token.kind == TokenKind::Assignment) {
+            return Ok((None, value));
+        }
+
+        let assignment = self.advance()?;
+        match *value {
+            Node::Access { identifier, .. } => Ok((Some(identifier), self.parse_bool_expr()?)),
+            _ => unexpected_token!(assignment, vec![TokenKind::Comma, TokenKind::RightParen]),
+        }
+    }
+
+    fn parse_arguments(&mut self, delimiter: TokenKind) -> Result<Vec<Argument>, MonoError> {
+        let mut arguments: Vec<Argument> = Vec::new();
         let mut expect_argument = true;
 
-        while let Some(Ok(token)) = self.tokenizer.peek() {
+        while let Some(Ok(token)) = self.tokens.peek() {
             match token.kind {
+                TokenKind::NewLine => {
+                    self.tokens.next();
+                }
+                ref kind if kind == &delimiter && (!expect_argument || arguments.is_empty()) => {
+                    break;
+                }
+                _ if arguments.last().is_some_and(|a| a.is_variadic) => {
+                    return unexpected_token!(self.advance()?, vec![delimiter]);
+                }
+                TokenKind::Mul if expect_argument => {
+                    self.tokens.next();
+                    let identifier = self.expect_token(TokenKind::Identifier("".into()))?;
+                    check_duplicate(&arguments, &identifier)?;
+                    arguments.push(Argument {
+                        identifier,
+                        default: None,
+                        is_variadic: true,
+                    });
+                    expect_argument = false;
+                }
                 TokenKind::Identifier(_) if expect_argument => {
-                    arguments.push(self.tokenizer.next().unwrap()?);
+                    let identifier = self.advance()?;
+                    check_duplicate(&arguments, &identifier)?;
+                    let default = match self.tokens.peek() {
+                        Some(Ok(token)) if token.kind == TokenKind::Assignment => {
+                            self.tokens.next();
+                            Some(self.parse_bool_expr()?)
+                        }
+                        _ => None,
+                    };
+                    if default.is_none() && arguments.iter().any(|a| a.default.is_some()) {
+                        return Err(MonoError::from(Syntax::MissingDefaultValue {
+                            argument: identifier,
+                        }));
+                    }
+                    arguments.push(Argument {
+                        identifier,
+                        default,
+                        is_variadic: false,
+                    });
                     expect_argument = false;
                 }
                 TokenKind::Identifier(_) if !expect_argument => {
-                    return unexpected_token!(
-                        self.tokenizer.next().unwrap()?,
-                        vec![TokenKind::RightParen, TokenKind::Comma]
-                    );
+                    return unexpected_token!(self.advance()?, vec![delimiter, TokenKind::Comma]);
                 }
                 TokenKind::Comma if !expect_argument => {
-                    self.tokenizer.next();
+                    self.tokens.next();
                     expect_argument = true;
                 }
-                TokenKind::RightParen if !expect_argument || arguments.is_empty() => {
-                    break;
-                }
                 _ if expect_argument => {
                     return unexpected_token!(
-                        self.tokenizer.next().unwrap()?,
-                        vec![TokenKind::Identifier(String::new())]
-                    )
-                }
-                _ => {
-                    return unexpected_token!(
-                        self.tokenizer.next().unwrap()?,
-                        vec![TokenKind::Comma, TokenKind::RightParen]
+                        self.advance()?,
+                        vec![TokenKind::Identifier("".into())]
                     )
                 }
+                _ => return unexpected_token!(self.advance()?, vec![TokenKind::Comma, delimiter]),
             }
         }
 
         Ok(arguments)
     }
 
-    fn close_delimiter(
-        &mut self,
-        start: Token,
-        delimiter: TokenKind,
-    ) -> Result<(), Box<dyn MonoError>> {
-        match self.tokenizer.next() {
+    fn close_delimiter(&mut self, start: Token, delimiter: TokenKind) -> Result<(), MonoError> {
+        match self.tokens.next() {
             None => unclosed_token!(start, None, delimiter),
             Some(Err(e)) => Err(e),
             Some(Ok(t)) if t.kind == delimiter => Ok(()),
@@ -191,36 +310,74 @@ impl<'a> Parser<'a> {
     }
 
     fn parse_atom(&mut self) -> ParserItem {
-        if let None = self.tokenizer.peek() {
-            return Syntax::UnexpectedEOF.into();
+        let mut object = self.parse_primary()?;
+        while let Some(Ok(token)) = self.tokens.peek() {
+            if token.kind != TokenKind::Dot {
+                break;
+            }
+            self.tokens.next();
+            let member = self.expect_token(TokenKind::Identifier("".into()))?;
+            object = match self.tokens.peek() {
+                Some(Ok(token)) if token.kind == TokenKind::LeftParen => {
+                    let (parameters, keywords) = self.parse_call_parameters()?;
+                    Box::new(Node::MethodCall {
+                        object,
+                        method: member,
+                        parameters,
+                        keywords,
+                    })
+                }
+                _ => Box::new(Node::Member { object, member }),
+            };
+        }
+        Ok(object)
+    }
+
+    fn parse_primary(&mut self) -> ParserItem {
+        match self.tokens.peek() {
+            None => return Syntax::UnexpectedEOF.into(),
+            Some(Ok(token)) if token.kind == TokenKind::If => return self.parse_if(),
+            Some(Ok(token)) if token.kind == TokenKind::While => return self.parse_while(),
+            Some(Ok(token)) if token.kind == TokenKind::Do => return self.parse_do_while(),
+            Some(Ok(token)) if token.kind == TokenKind::For => return self.parse_for(),
+            Some(Ok(token)) if token.kind == TokenKind::Match => return self.parse_match(),
+            _ => (),
         }
 
-        let token = self.tokenizer.next().unwrap()?;
+        let token = self.advance()?;
         match token.kind {
             TokenKind::LeftParen => {
-                let bool_expr = self.parse_bool_expr()?;
+                let expression = self.parse_parenthesized()?;
                 self.close_delimiter(token, TokenKind::RightParen)?;
-                Ok(bool_expr)
+                Ok(expression)
             }
             TokenKind::LeftBracket => {
-                let values = self.parse_parameters(TokenKind::RightBracket)?;
+                let list = self.parse_list()?;
                 self.close_delimiter(token, TokenKind::RightBracket)?;
-                Node::List { values }.into()
+                Ok(list)
             }
             TokenKind::Integer(_)
             | TokenKind::Float(_)
             | TokenKind::Boolean(_)
             | TokenKind::Character(_)
             | TokenKind::String(_)
+            | TokenKind::Bytes(_)
             | TokenKind::None => atom!(token),
-            TokenKind::Identifier(_) => match self.tokenizer.peek() {
+            TokenKind::Identifier(_) => match self.tokens.peek() {
+                Some(Ok(question)) if question.kind == TokenKind::Question => {
+                    self.parse_optional(token)
+                }
                 Some(Ok(paren)) if paren.kind == TokenKind::LeftParen => {
                     self.parse_func_call(token)
                 }
                 Some(Ok(bracket)) if bracket.kind == TokenKind::LeftBracket => {
                     self.parse_index(token)
                 }
-                _ => Node::Access { identifier: token }.into(),
+                _ => Node::Access {
+                    identifier: token,
+                    slot: None,
+                }
+                .into(),
             },
             _ => {
                 unexpected_token!(
@@ -229,37 +386,79 @@ impl<'a> Parser<'a> {
                         TokenKind::LeftParen,
                         TokenKind::Integer(0),
                         TokenKind::Float(0.0),
-                        TokenKind::Identifier("".to_string()),
+                        TokenKind::Identifier("".into()),
                         TokenKind::String("".to_string()),
                         TokenKind::Character(' '),
                         TokenKind::Add,
                         TokenKind::Sub,
+                        TokenKind::If,
+                        TokenKind::While,
+                        TokenKind::Do,
+                        TokenKind::For,
                     ]
                 )
             }
         }
     }
 
+    // `f?(...)` or `xs?[...]`, anything else after the `?` is expected to be
+    // an index.
+    fn parse_optional(&mut self, identifier: Token) -> ParserItem {
+        self.expect_token(TokenKind::Question)?;
+        let value = match self.tokens.peek() {
+            Some(Ok(paren)) if paren.kind == TokenKind::LeftParen => {
+                self.parse_func_call(identifier)?
+            }
+            _ => self.parse_index(identifier)?,
+        };
+        Node::Optional { value }.into()
+    }
+
     fn parse_index(&mut self, identifier: Token) -> ParserItem {
         let start = self.expect_token(TokenKind::LeftBracket)?;
         let index = self.parse_expr()?;
         self.close_delimiter(start, TokenKind::RightBracket)?;
-        Node::Index { identifier, index }.into()
+        Node::Index {
+            identifier,
+            slot: None,
+            index,
+        }
+        .into()
     }
 
     fn parse_func_call(&mut self, identifier: Token) -> ParserItem {
-        let start = self.expect_token(TokenKind::LeftParen)?;
-        let parameters = self.parse_parameters(TokenKind::RightParen)?;
-        self.close_delimiter(start, TokenKind::RightParen)?;
+        let (parameters, keywords) = self.parse_call_parameters()?;
         Node::FuncCall {
             identifier,
+            slot: None,
             parameters,
+            keywords,
         }
         .into()
     }
 
+    fn parse_call_parameters(&mut self) -> Result<CallParameters, MonoError> {
+        let start = self.expect_token(TokenKind::LeftParen)?;
+        let mut parameters = Vec::new();
+        let mut keywords = Vec::new();
+        for (keyword, value) in
+            self.parse_separated(TokenKind::RightParen, Self::parse_call_parameter)?
+        {
+            match keyword {
+                Some(keyword) => keywords.push((keyword, value)),
+                None => parameters.push(value),
+            }
+        }
+        self.close_delimiter(start, TokenKind::RightParen)?;
+        Ok((parameters, keywords))
+    }
+
     fn parse_power(&mut self) -> ParserItem {
-        self.parse_binary_op(&[TokenKind::Pow], Self::parse_atom, Self::parse_factor)
+        self.parse_binary_op(
+            &[TokenKind::Pow],
+            |parser| parser.nested(Self::parse_atom),
+            |parser| parser.nested(Self::parse_factor),
+        )
     }
 
     fn parse_factor(&mut self) -> ParserItem {
@@ -288,7 +487,7 @@ impl<'a> Parser<'a> {
 
     fn parse_comparison(&mut self) -> ParserItem {
         self.parse_binary_op(
-            &Token::COMPERATORS.to_vec(),
+            Token::COMPERATORS.as_ref(),
             Self::parse_expr,
             Self::parse_expr,
         )
@@ -310,7 +509,7 @@ impl<'a> Parser<'a> {
         )
     }
 
-    fn parse_bool_expr(&mut self) -> ParserItem {
+    fn parse_or(&mut self) -> ParserItem {
         self.parse_binary_op(
             &[TokenKind::Or],
             Self::parse_bool_term,
@@ -318,6 +517,11 @@ impl<'a> Parser<'a> {
         )
     }
 
+    // `??` binds loosest, so `a or b ?? c` falls back when `a or b` is `None`.
+    fn parse_bool_expr(&mut self) -> ParserItem {
+        self.parse_binary_op(&[TokenKind::Coalesce], Self::parse_or, Self::parse_or)
+    }
+
     fn parse_block(&mut self) -> ParserItem {
         let start = self.expect_token(TokenKind::LeftCurly)?;
         let program = self.parse_program()?;
@@ -326,23 +530,15 @@ impl<'a> Parser<'a> {
     }
 
     fn parse_assignment(&mut self, identifier: Token, is_declaration: bool) -> ParserItem {
-        match self.tokenizer.next() {
-            None => Err(Box::new(Syntax::UnexpectedEOF)),
-            Some(Err(error)) => Err(error.into()),
+        match self.tokens.next() {
+            None => Err(MonoError::from(Syntax::UnexpectedEOF)),
+            Some(Err(error)) => Err(error),
             Some(Ok(token)) if token.kind == TokenKind::LeftParen => {
-                let arguments = self.parse_arguments()?;
-                self.close_delimiter(token, TokenKind::RightParen)?;
-                self.expect_token(TokenKind::DoubleArrow)?;
-                let body = self.parse_block()?;
-                Node::FuncDeclearion {
-                    identifier,
-                    arguments,
-                    body,
-                }
-                .into()
+                self.parse_func_declaration(identifier, token)
             }
             Some(Ok(token)) if token.kind == TokenKind::Assignment => Node::Assignment {
                 identifier,
+                slot: None,
                 value: self.parse_bool_expr()?,
                 is_declaration,
             }
@@ -353,37 +549,131 @@ impl<'a> Parser<'a> {
         }
     }
 
-    fn parse_if(&mut self) -> ParserItem {
-        self.tokenizer.next(); // Going over the 'If' token
+    fn parse_func_declaration(&mut self, identifier: Token, start: Token) -> ParserItem {
+        let arguments = self.parse_arguments(TokenKind::RightParen)?;
+        self.close_delimiter(start, TokenKind::RightParen)?;
+        self.expect_token(TokenKind::DoubleArrow)?;
+        self.parse_func_body(identifier, arguments)
+    }
 
-        let condition = self.parse_bool_expr()?;
-        let block = self.parse_block()?;
+    // `def f(a, b) { ... }`, the same function as `let f(a, b) => { ... }`.
+    fn parse_def(&mut self) -> ParserItem {
+        self.tokens.next(); // Going over the 'Def' token.
+        let identifier = self.expect_token(TokenKind::Identifier("".into()))?;
+        let start = self.expect_token(TokenKind::LeftParen)?;
+        let arguments = self.parse_arguments(TokenKind::RightParen)?;
+        self.close_delimiter(start, TokenKind::RightParen)?;
+        self.parse_func_body(identifier, arguments)
+    }
 
+    fn parse_func_body(&mut self, identifier: Token, arguments: Vec<Argument>) -> ParserItem {
+        self.functions.push(
+            arguments
+                .iter()
+                .map(|argument| argument.name().clone())
+                .collect(),
+        );
+        let body = self.parse_block();
+        self.functions.pop();
+        let body = body?;
+        Node::FuncDeclearion {
+            identifier,
+            arguments,
+            body,
+            locals: Vec::new(),
+        }
+        .into()
+    }
+
+    fn parse_enum(&mut self) -> ParserItem {
+        self.tokens.next(); // Going over the 'Enum' token.
+        let identifier = self.expect_token(TokenKind::Identifier("".into()))?;
+        let start = self.expect_token(TokenKind::LeftCurly)?;
         self.consume(TokenKind::NewLine);
+        let variants = self.parse_separated(TokenKind::RightCurly, |parser| {
+            parser.consume(TokenKind::NewLine);
+            let variant = parser.expect_token(TokenKind::Identifier("".into()))?;
+            parser.consume(TokenKind::NewLine);
+            Ok(variant)
+        })?;
+        self.close_delimiter(start, TokenKind::RightCurly)?;
+        Node::EnumDeclaration {
+            identifier,
+            variants,
+        }
+        .into()
+    }
 
-        match self.tokenizer.peek() {
-            Some(Ok(token)) if token.kind == TokenKind::Else => (),
-            _ => {
-                return Node::If {
-                    condition,
-                    block,
-                    else_block: None,
+    fn parse_impl(&mut self) -> ParserItem {
+        self.tokens.next(); // Going over the 'Impl' token.
+        let identifier = self.expect_token(TokenKind::Identifier("".into()))?;
+        let start = self.expect_token(TokenKind::LeftCurly)?;
+
+        let mut methods = Vec::new();
+        while let Some(result) = self.tokens.peek() {
+            if let Ok(token) = result {
+                if token.kind == TokenKind::RightCurly {
+                    break;
+                }
+                if token.kind == TokenKind::NewLine {
+                    self.tokens.next();
+                    continue;
                 }
-                .into();
             }
+            let name = self.expect_token(TokenKind::Identifier("".into()))?;
+            let arguments_start = self.expect_token(TokenKind::LeftParen)?;
+            methods.push(self.parse_func_declaration(name, arguments_start)?);
         }
 
-        self.tokenizer.next(); // Going over the 'Else' token
+        self.close_delimiter(start, TokenKind::RightCurly)?;
+        Node::Impl {
+            identifier,
+            methods,
+        }
+        .into()
+    }
 
-        self.consume(TokenKind::NewLine);
+    // The branches of an `if`, `elif` and `else if` chain are read in a loop
+    // rather than recursively, then nested as each one's else block.
+    fn parse_if(&mut self) -> ParserItem {
+        let (condition, block) = self.parse_branch()?;
+        let mut branches = Vec::new();
+        let mut else_block = None;
 
-        let else_block = if matches!(self.tokenizer.peek(), Some(Ok(token)) if token.kind == TokenKind::If)
-        {
-            Some(self.parse_if()?)
-        } else {
-            Some(self.parse_block()?)
-        };
+        loop {
+            self.consume(TokenKind::NewLine);
+            match self.tokens.peek() {
+                Some(Ok(token)) if token.kind == TokenKind::Elif => {
+                    branches.push(self.parse_branch()?);
+                }
+                Some(Ok(token)) if token.kind == TokenKind::Else => {
+                    self.tokens.next(); // Going over the 'Else' token
+                    self.consume(TokenKind::NewLine);
+                    match self.tokens.peek() {
+                        Some(Ok(token)) if token.kind == TokenKind::If => {
+                            branches.push(self.parse_branch()?);
+                        }
+                        _ => {
+                            else_block = Some(self.parse_block()?);
+                            break;
+                        }
+                    }
+                }
+                _ => break,
+            }
+        }
 
+        let else_block =
+            branches
+                .into_iter()
+                .rev()
+                .fold(else_block, |else_block, (condition, block)| {
+                    Some(Box::new(Node::If {
+                        condition,
+                        block,
+                        else_block,
+                    }))
+                });
         Node::If {
             condition,
             block,
@@ -392,8 +682,242 @@ impl<'a> Parser<'a> {
         .into()
     }
 
+    // The condition and block after an `if` or `elif`.
+    fn parse_branch(&mut self) -> Result<(Box<Node>, Box<Node>), MonoError> {
+        self.tokens.next(); // Going over the 'If' or 'Elif' token
+        let condition = self.parse_bool_expr()?;
+        let block = self.parse_block()?;
+        Ok((condition, block))
+    }
+
+    // Either a grouped expression `(a)` or a tuple `()`, `(a,)`, `(a, b)`.
+    fn parse_parenthesized(&mut self) -> ParserItem {
+        if matches!(self.tokens.peek(), Some(Ok(token)) if token.kind == TokenKind::RightParen) {
+            return Node::Tuple { values: Vec::new() }.into();
+        }
+
+        let first = self.parse_bool_expr()?;
+        match self.tokens.peek() {
+            Some(Ok(token)) if token.kind == TokenKind::Comma => {
+                self.tokens.next();
+            }
+            _ => return Ok(first),
+        }
+
+        let mut values = vec![first];
+        values.extend(self.parse_separated(TokenKind::RightParen, Self::parse_bool_expr)?);
+        Node::Tuple { values }.into()
+    }
+
+    // A comma separated list of expressions, more than one becomes a tuple.
+    fn parse_tuple_expr(&mut self) -> ParserItem {
+        let first = self.parse_bool_expr()?;
+        let mut values = vec![first];
+        while let Some(Ok(token)) = self.tokens.peek() {
+            if token.kind != TokenKind::Comma {
+                break;
+            }
+            self.tokens.next();
+            values.push(self.parse_bool_expr()?);
+        }
+
+        match <[_; 1]>::try_from(values) {
+            Ok([value]) => Ok(value),
+            Err(values) => Node::Tuple { values }.into(),
+        }
+    }
+
+    fn parse_pattern(&mut self) -> Result<Pattern, MonoError> {
+        let token = match self.tokens.next() {
+            Some(token) => token?,
+            None => return Err(MonoError::from(Syntax::UnexpectedEOF)),
+        };
+        match token.kind {
+            TokenKind::Identifier(_) => Ok(Pattern::Identifier(token)),
+            TokenKind::Mul => Ok(Pattern::Rest(
+                self.expect_token(TokenKind::Identifier("".into()))?,
+            )),
+            TokenKind::LeftBracket => {
+                let patterns = self.parse_separated(TokenKind::RightBracket, |parser| {
+                    parser.nested(Self::parse_pattern)
+                })?;
+                self.close_delimiter(token, TokenKind::RightBracket)?;
+                Ok(Pattern::List(patterns))
+            }
+            TokenKind::LeftParen => {
+                let patterns = self.parse_separated(TokenKind::RightParen, |parser| {
+                    parser.nested(Self::parse_pattern)
+                })?;
+                self.close_delimiter(token, TokenKind::RightParen)?;
+                Ok(Pattern::Tuple(patterns))
+            }
+            _ => unexpected_token!(
+                token,
+                vec![
+                    TokenKind::Identifier("".into()),
+                    TokenKind::Mul,
+                    TokenKind::LeftBracket,
+                    TokenKind::LeftParen,
+                ]
+            ),
+        }
+    }
+
+    // Reinterprets an already parsed expression, such as the `[a, b]` of
+    // `[a, b] = b, a`, as the pattern of a destructuring assignment.
+    fn into_pattern(node: &Node) -> Option<Pattern> {
+        let patterns = |values: &[Box<Node>]| {
+            values
+                .iter()
+                .map(|value| Self::into_pattern(value))
+                .collect::<Option<Vec<Pattern>>>()
+        };
+        match node {
+            Node::Access { identifier, .. } => Some(Pattern::Identifier(identifier.clone())),
+            Node::Spread { value, .. } => match value.as_ref() {
+                Node::Access { identifier, .. } => Some(Pattern::Rest(identifier.clone())),
+                _ => None,
+            },
+            Node::List { values } => patterns(values).map(Pattern::List),
+            Node::Tuple { values } => patterns(values).map(Pattern::Tuple),
+            _ => None,
+        }
+    }
+
+    // Parses the rest of `first, second = value`, where the first pattern was
+    // already consumed.
+    fn parse_destructuring(&mut self, first: Pattern, is_declaration: bool) -> ParserItem {
+        let mut patterns = vec![first];
+        while let Some(Ok(token)) = self.tokens.peek() {
+            if token.kind != TokenKind::Comma {
+                break;
+            }
+            self.tokens.next();
+            patterns.push(self.parse_pattern()?);
+        }
+
+        let pattern = match <[_; 1]>::try_from(patterns) {
+            Ok([pattern]) => pattern,
+            Err(patterns) => Pattern::Tuple(patterns),
+        };
+        let operator = self.expect_token(TokenKind::Assignment)?;
+        Node::Destructuring {
+            pattern,
+            operator,
+            value: self.parse_tuple_expr()?,
+            is_declaration,
+        }
+        .into()
+    }
+
+    fn parse_list(&mut self) -> ParserItem {
+        if matches!(self.tokens.peek(), Some(Ok(token)) if token.kind == TokenKind::RightBracket) {
+            return Node::List { values: Vec::new() }.into();
+        }
+
+        let first = self.parse_spread()?;
+        match self.tokens.peek() {
+            Some(Ok(token))
+                if token.kind == TokenKind::For && !matches!(*first, Node::Spread { .. }) =>
+            {
+                return self.parse_comprehension(first);
+            }
+            Some(Ok(token)) if token.kind == TokenKind::Comma => {
+                self.tokens.next();
+            }
+            _ => {
+                return Node::List {
+                    values: vec![first],
+                }
+                .into()
+            }
+        }
+
+        let mut values = vec![first];
+        values.extend(self.parse_separated(TokenKind::RightBracket, Self::parse_spread)?);
+        Node::List { values }.into()
+    }
+
+    fn parse_comprehension(&mut self, value: Box<Node>) -> ParserItem {
+        self.tokens.next(); // Going over the 'For' token.
+        let identifier = self.expect_token(TokenKind::Identifier("".into()))?;
+        let operator = self.expect_token(TokenKind::In)?;
+        let iterable = self.parse_bool_expr()?;
+        let condition = match self.tokens.peek() {
+            Some(Ok(token)) if token.kind == TokenKind::If => {
+                self.tokens.next();
+                Some(self.parse_bool_expr()?)
+            }
+            _ => None,
+        };
+        Node::Comprehension {
+            value,
+            identifier,
+            operator,
+            iterable,
+            condition,
+        }
+        .into()
+    }
+
+    // `for a, b in pairs { ... }` loops over a variable named after the
+    // pattern, which can't clash with a real one, and destructures it at the
+    // start of the block.
+    fn parse_for(&mut self) -> ParserItem {
+        self.tokens.next(); // Going over the 'For' token.
+        let mut patterns = vec![self.parse_pattern()?];
+        while let Some(Ok(token)) = self.tokens.peek() {
+            if token.kind != TokenKind::Comma {
+                break;
+            }
+            self.tokens.next();
+            patterns.push(self.parse_pattern()?);
+        }
+        let operator = self.expect_token(TokenKind::In)?;
+        let iterable = self.parse_bool_expr()?;
+        let mut block = self.parse_block()?;
+
+        let identifier = match patterns.as_slice() {
+            [Pattern::Identifier(identifier)] => identifier.clone(),
+            _ => {
+                let pattern = match <[_; 1]>::try_from(patterns) {
+                    Ok([pattern]) => pattern,
+                    Err(patterns) => Pattern::Tuple(patterns),
+                };
+                let identifier = Token::new(
+                    operator.start.clone(),
+                    operator.end.clone(),
+                    TokenKind::Identifier(pattern.to_string().into()),
+                );
+                if let Node::Program { statements } = block.as_mut() {
+                    statements.insert(
+                        0,
+                        Box::new(Node::Destructuring {
+                            pattern,
+                            operator: operator.clone(),
+                            value: Box::new(Node::Access {
+                                identifier: identifier.clone(),
+                                slot: None,
+                            }),
+                            is_declaration: true,
+                        }),
+                    );
+                }
+                identifier
+            }
+        };
+        Node::For {
+            identifier,
+            slot: None,
+            operator,
+            iterable,
+            block,
+        }
+        .into()
+    }
+
     fn parse_while(&mut self) -> ParserItem {
-        self.tokenizer.next(); // Going over the 'While' token.
+        self.tokens.next(); // Going over the 'While' token.
         Node::While {
             condition: self.parse_bool_expr()?,
             block: self.parse_block()?,
@@ -401,86 +925,450 @@ impl<'a> Parser<'a> {
         .into()
     }
 
-    fn parse_return(&mut self) -> ParserItem {
-        self.tokenizer.next(); // Going over the 'Return' token.
+    fn parse_match(&mut self) -> ParserItem {
+        self.tokens.next(); // Going over the 'Match' token.
         let value = self.parse_bool_expr()?;
+        let start = self.expect_token(TokenKind::LeftCurly)?;
+        let mut arms = Vec::new();
+        loop {
+            self.consume(TokenKind::NewLine);
+            if matches!(self.tokens.peek(), Some(Ok(token)) if token.kind == TokenKind::RightCurly)
+            {
+                break;
+            }
+            self.expect_token(TokenKind::Case)?;
+            let pattern = self.parse_case_pattern()?;
+            let guard = match self.tokens.peek() {
+                Some(Ok(token)) if token.kind == TokenKind::If => {
+                    self.tokens.next();
+                    Some(self.parse_bool_expr()?)
+                }
+                _ => None,
+            };
+            self.expect_token(TokenKind::DoubleArrow)?;
+            arms.push(Arm {
+                pattern,
+                guard,
+                block: self.parse_block()?,
+            });
+        }
+        self.close_delimiter(start, TokenKind::RightCurly)?;
+        Node::Match { value, arms }.into()
+    }
+
+    // Alternatives separated by `|`, such as `1 | 2`.
+    fn parse_case_pattern(&mut self) -> Result<CasePattern, MonoError> {
+        let mut patterns = vec![self.nested(Self::parse_case_alternative)?];
+        while let Some(Ok(token)) = self.tokens.peek() {
+            if token.kind != TokenKind::Pipe {
+                break;
+            }
+            self.tokens.next();
+            patterns.push(self.nested(Self::parse_case_alternative)?);
+        }
+        match <[_; 1]>::try_from(patterns) {
+            Ok([pattern]) => Ok(pattern),
+            Err(patterns) => Ok(CasePattern::Or(patterns)),
+        }
+    }
+
+    fn parse_case_alternative(&mut self) -> Result<CasePattern, MonoError> {
+        let token = match self.tokens.next() {
+            Some(token) => token?,
+            None => return Err(MonoError::from(Syntax::UnexpectedEOF)),
+        };
+        match &token.kind {
+            TokenKind::Identifier(name) if name.as_ref() == "_" => Ok(CasePattern::Wildcard),
+            TokenKind::Identifier(_) => match self.tokens.peek() {
+                Some(Ok(dot)) if dot.kind == TokenKind::Dot => {
+                    self.tokens.next();
+                    Ok(CasePattern::Variant {
+                        enumeration: token,
+                        variant: self.expect_token(TokenKind::Identifier("".into()))?,
+                    })
+                }
+                Some(Ok(curly)) if curly.kind == TokenKind::LeftCurly => {
+                    let start = self.advance()?;
+                    self.parse_struct_pattern(Some(token), start)
+                }
+                _ => Ok(CasePattern::Binding(token)),
+            },
+            TokenKind::Integer(_)
+            | TokenKind::Float(_)
+            | TokenKind::Boolean(_)
+            | TokenKind::Character(_)
+            | TokenKind::String(_)
+            | TokenKind::Bytes(_)
+            | TokenKind::None => Ok(CasePattern::Literal(token)),
+            // Negative numbers, as there's no expression to fold them from.
+            TokenKind::Sub => {
+                let number = self.tokens.next().transpose()?;
+                let kind = match number.as_ref().map(|number| &number.kind) {
+                    Some(TokenKind::Integer(integer)) => TokenKind::Integer(-integer),
+                    Some(TokenKind::Float(float)) => TokenKind::Float(-float),
+                    _ => {
+                        return match number {
+                            Some(number) => unexpected_token!(
+                                number,
+                                vec![TokenKind::Integer(0), TokenKind::Float(0.0)]
+                            ),
+                            None => Err(MonoError::from(Syntax::UnexpectedEOF)),
+                        }
+                    }
+                };
+                let end = number.map_or_else(|| token.end.clone(), |number| number.end);
+                Ok(CasePattern::Literal(Token::new(token.start, end, kind)))
+            }
+            TokenKind::Mul => Ok(CasePattern::Rest(
+                self.expect_token(TokenKind::Identifier("".into()))?,
+            )),
+            TokenKind::LeftBracket => {
+                let patterns =
+                    self.parse_separated(TokenKind::RightBracket, Self::parse_case_pattern)?;
+                self.close_delimiter(token, TokenKind::RightBracket)?;
+                Ok(CasePattern::List(patterns))
+            }
+            TokenKind::LeftParen => {
+                let patterns =
+                    self.parse_separated(TokenKind::RightParen, Self::parse_case_pattern)?;
+                self.close_delimiter(token, TokenKind::RightParen)?;
+                Ok(CasePattern::Tuple(patterns))
+            }
+            TokenKind::LeftCurly => self.parse_struct_pattern(None, token),
+            _ => unexpected_token!(
+                token,
+                vec![
+                    TokenKind::Identifier("".into()),
+                    TokenKind::Integer(0),
+                    TokenKind::String("".to_string()),
+                    TokenKind::Mul,
+                    TokenKind::LeftBracket,
+                    TokenKind::LeftParen,
+                    TokenKind::LeftCurly,
+                ]
+            ),
+        }
+    }
+
+    // `{name: pattern, age}`, where a field on its own binds its value.
+    fn parse_struct_pattern(
+        &mut self,
+        identifier: Option<Token>,
+        start: Token,
+    ) -> Result<CasePattern, MonoError> {
+        let fields = self.parse_separated(TokenKind::RightCurly, |parser| {
+            let field = parser.expect_token(TokenKind::Identifier("".into()))?;
+            match parser.tokens.peek() {
+                Some(Ok(colon)) if colon.kind == TokenKind::Colon => {
+                    parser.tokens.next();
+                    Ok((field, parser.parse_case_pattern()?))
+                }
+                _ => Ok((field.clone(), CasePattern::Binding(field))),
+            }
+        })?;
+        self.close_delimiter(start, TokenKind::RightCurly)?;
+        Ok(CasePattern::Struct { identifier, fields })
+    }
+
+    fn parse_struct(&mut self) -> ParserItem {
+        self.tokens.next(); // Going over the 'Struct' token.
+        let identifier = self.expect_token(TokenKind::Identifier("".into()))?;
+        let start = self.expect_token(TokenKind::LeftCurly)?;
+        let fields = self.parse_arguments(TokenKind::RightCurly)?;
+        self.close_delimiter(start, TokenKind::RightCurly)?;
+        Node::StructDeclaration { identifier, fields }.into()
+    }
+
+    fn parse_do_while(&mut self) -> ParserItem {
+        self.tokens.next(); // Going over the 'Do' token.
+        let block = self.parse_block()?;
+        self.consume(TokenKind::NewLine);
+        self.expect_token(TokenKind::While)?;
+        Node::DoWhile {
+            block,
+            condition: self.parse_bool_expr()?,
+        }
+        .into()
+    }
+
+    fn parse_return(&mut self) -> ParserItem {
+        self.tokens.next(); // Going over the 'Return' token.
+        let value = self.parse_tuple_expr()?;
         Node::Return { value }.into()
     }
 
+    fn parse_delete(&mut self) -> ParserItem {
+        self.tokens.next(); // Going over the 'Del' token.
+        Node::Delete {
+            identifier: self.expect_token(TokenKind::Identifier("".into()))?,
+            slot: None,
+        }
+        .into()
+    }
+
+    // `global x` or `nonlocal x, y`. An argument is always a variable of its
+    // function, so it can't be declared either.
+    fn parse_global(&mut self) -> ParserItem {
+        let is_nonlocal = self.advance()?.kind == TokenKind::Nonlocal;
+        let mut identifiers = vec![self.expect_token(TokenKind::Identifier("".into()))?];
+        while let Some(Ok(token)) = self.tokens.peek() {
+            if token.kind != TokenKind::Comma {
+                break;
+            }
+            self.tokens.next();
+            identifiers.push(self.expect_token(TokenKind::Identifier("".into()))?);
+        }
+        if let Some(arguments) = self.functions.last() {
+            let argument = identifiers.iter().find(|identifier| {
+                matches!(&identifier.kind, TokenKind::Identifier(name) if arguments.contains(name))
+            });
+            if let Some(argument) = argument {
+                return Err(MonoError::from(Syntax::OuterArgument {
+                    argument: argument.clone(),
+                    is_nonlocal,
+                }));
+            }
+        }
+        Node::Global {
+            identifiers,
+            is_nonlocal,
+        }
+        .into()
+    }
+
     fn parse_statement(&mut self) -> ParserItem {
-        match self.tokenizer.peek() {
+        if let Some(Ok(token)) = self.tokens.peek() {
+            if let TokenKind::Identifier(_) = token.kind {
+                if self
+                    .line
+                    .last()
+                    .is_some_and(|last| last.start.row() != token.start.row())
+                {
+                    self.line.clear();
+                }
+                self.line.push(token.clone());
+            }
+        }
+        self.parse_keyword_statement()
+            .map_err(|error| self.suggest_keyword(error))
+    }
+
+    // An unexpected token that is, or follows, a misspelt keyword on its
+    // line, as in `whlie x < 3 {`, suggests the keyword.
+    fn suggest_keyword(&self, mut error: MonoError) -> MonoError {
+        if let MonoErrorKind::Syntax(Syntax::UnexpectedToken {
+            token, suggestion, ..
+        }) = error.as_mut()
+        {
+            let misspelt = self
+                .line
+                .iter()
+                .filter(|start| {
+                    start.start.row() == token.start.row() && start.start <= token.start
+                })
+                .chain([&*token]);
+            *suggestion = suggestion.take().or_else(|| {
+                misspelt
+                    .filter_map(|token| match &token.kind {
+                        TokenKind::Identifier(name) => closest(name, KEYWORDS),
+                        _ => None,
+                    })
+                    .next()
+                    .map(String::from)
+            });
+        }
+        error
+    }
+
+    fn parse_keyword_statement(&mut self) -> ParserItem {
+        match self.tokens.peek() {
             None => Syntax::UnexpectedEOF.into(),
-            Some(Err(_)) => Err(self.tokenizer.next().expect("unreachable").unwrap_err()),
+            Some(Err(_)) => Err(self.take_error()),
             Some(Ok(token)) => match token.kind {
                 TokenKind::Let => {
-                    self.tokenizer.next();
-                    let identifier = self.expect_token(TokenKind::Identifier(String::new()))?;
-                    self.parse_assignment(identifier, true)
+                    self.tokens.next();
+                    match self.tokens.peek() {
+                        Some(Ok(token)) if token.kind == TokenKind::Identifier("".into()) => {}
+                        _ => {
+                            let pattern = self.parse_pattern()?;
+                            return self.parse_destructuring(pattern, true);
+                        }
+                    }
+                    let identifier = self.expect_token(TokenKind::Identifier("".into()))?;
+                    match self.tokens.peek() {
+                        Some(Ok(token)) if token.kind == TokenKind::Comma => {
+                            self.parse_destructuring(Pattern::Identifier(identifier), true)
+                        }
+                        _ => self.parse_assignment(identifier, true),
+                    }
                 }
+                TokenKind::Def => self.parse_def(),
                 TokenKind::If => self.parse_if(),
                 TokenKind::While => self.parse_while(),
-                TokenKind::Identifier(_) => self.parse_identifier_statement(),
+                TokenKind::Do => self.parse_do_while(),
+                TokenKind::For => self.parse_for(),
+                TokenKind::Match => self.parse_match(),
+                TokenKind::Struct => self.parse_struct(),
+                TokenKind::Impl => self.parse_impl(),
+                TokenKind::Enum => self.parse_enum(),
                 TokenKind::Return => self.parse_return(),
-                _ => unexpected_token!(
-                    self.tokenizer.next().unwrap()?,
-                    vec![
-                        TokenKind::Let,
-                        TokenKind::If,
-                        TokenKind::While,
-                        TokenKind::Identifier(String::new()),
-                    ]
-                ),
+                TokenKind::Del => self.parse_delete(),
+                TokenKind::Global | TokenKind::Nonlocal => self.parse_global(),
+                _ => self.parse_expression_statement(),
             },
         }
     }
 
-    fn parse_identifier_statement(&mut self) -> ParserItem {
-        let identifier = self.expect_token(TokenKind::Identifier(String::new()))?;
-        match self.tokenizer.peek() {
-            Some(Ok(token)) if token.kind == TokenKind::Assignment => {
-                self.parse_assignment(identifier, false)
+    fn parse_expression_statement(&mut self) -> ParserItem {
+        let expression = self.parse_bool_expr()?;
+        match self.tokens.peek() {
+            Some(Ok(token)) if token.kind == TokenKind::Comma => {
+                return match Self::into_pattern(&expression) {
+                    Some(pattern) => self.parse_destructuring(pattern, false),
+                    None => unexpected_token!(
+                        self.advance()?,
+                        vec![TokenKind::NewLine, TokenKind::RightCurly]
+                    ),
+                };
             }
-            Some(Ok(token)) if token.kind == TokenKind::LeftParen => {
-                self.parse_func_call(identifier)
-            }
-            Some(Ok(token)) if token.kind == TokenKind::LeftBracket => {
-                let start = self.expect_token(TokenKind::LeftBracket)?;
-                let index = self.parse_expr()?;
-                self.close_delimiter(start, TokenKind::RightBracket)?;
-                self.expect_token(TokenKind::Assignment)?;
-                let value = self.parse_bool_expr()?;
-                Node::ListAssignment {
-                    identifier,
-                    index,
-                    value,
-                }
-                .into()
+            Some(Ok(token)) if token.kind == TokenKind::Assignment => (),
+            _ => return Ok(expression),
+        }
+
+        if let (Node::List { .. } | Node::Tuple { .. }, Some(pattern)) =
+            (expression.as_ref(), Self::into_pattern(&expression))
+        {
+            return self.parse_destructuring(pattern, false);
+        }
+
+        let assignment = self.advance()?;
+        match *expression {
+            Node::Access { identifier, .. } => Node::Assignment {
+                identifier,
+                slot: None,
+                value: self.parse_bool_expr()?,
+                is_declaration: false,
             }
-            Some(Ok(_)) => unexpected_token!(
-                self.tokenizer.next().unwrap()?,
-                vec![TokenKind::Assignment, TokenKind::LeftParen]
-            ),
-            Some(Err(_)) => Err(self.tokenizer.next().expect("unreachable").unwrap_err()),
-            None => Syntax::UnexpectedEOF.into(),
+            .into(),
+            Node::Index {
+                identifier, index, ..
+            } => Node::ListAssignment {
+                identifier,
+                slot: None,
+                index,
+                value: self.parse_bool_expr()?,
+            }
+            .into(),
+            Node::Member { object, member } => Node::MemberAssignment {
+                object,
+                member,
+                value: self.parse_bool_expr()?,
+            }
+            .into(),
+            _ => unexpected_token!(assignment, vec![TokenKind::NewLine, TokenKind::RightCurly]),
         }
     }
 
     fn parse_program(&mut self) -> ParserItem {
         let mut statements: Vec<Box<Node>> = Vec::new();
 
-        while let Some(result) = self.tokenizer.peek() {
+        while let Some(result) = self.tokens.peek() {
             if let Ok(token) = result {
                 if token.kind == TokenKind::RightCurly {
                     break;
                 }
                 if token.kind == TokenKind::NewLine {
-                    self.tokenizer.next();
+                    self.tokens.next();
                     continue;
                 }
             }
-            statements.push(self.parse_statement()?);
+            statements.push(self.nested(Self::parse_statement)?);
         }
 
         Node::Program { statements }.into()
     }
 }
+
+// Arguments (and struct fields) are bound by name, a repeated one would
+// silently shadow the earlier one.
+fn check_duplicate(arguments: &[Argument], identifier: &Token) -> Result<(), MonoError> {
+    let TokenKind::Identifier(name) = &identifier.kind else {
+        return Ok(());
+    };
+    match arguments.iter().any(|argument| argument.name() == name) {
+        true => Err(MonoError::from(Syntax::DuplicateArgument {
+            argument: identifier.clone(),
+        })),
+        false => Ok(()),
+    }
+}
+
+// Drops the newlines inside `(` and `[`, so an expression can be broken
+// across lines. A `{` opens a block, in which newlines separate statements
+// again until it's closed.
+struct Continued<Tokens> {
+    tokens: Tokens,
+    open: Vec<TokenKind>,
+}
+
+impl<Tokens: Iterator<Item = ParserToken>> Iterator for Continued<Tokens> {
+    type Item = ParserToken;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let item = self.tokens.next()?;
+            if let Ok(token) = &item {
+                match token.kind {
+                    TokenKind::LeftParen | TokenKind::LeftBracket | TokenKind::LeftCurly => {
+                        self.open.push(token.kind.clone())
+                    }
+                    TokenKind::RightParen | TokenKind::RightBracket | TokenKind::RightCurly => {
+                        self.open.pop();
+                    }
+                    TokenKind::NewLine
+                        if matches!(
+                            self.open.last(),
+                            Some(TokenKind::LeftParen | TokenKind::LeftBracket)
+                        ) =>
+                    {
+                        continue
+                    }
+                    _ => (),
+                }
+            }
+            return Some(item);
+        }
+    }
+}
+
+pub struct Statements<'p, Tokens: Iterator<Item = ParserToken>> {
+    parser: &'p mut Parser<Tokens>,
+    failed: bool,
+}
+
+impl<Tokens: Iterator<Item = ParserToken>> Iterator for Statements<'_, Tokens> {
+    type Item = ParserItem;
+
+    // Stops after the first error, like `Parser::parse` does.
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+        loop {
+            match self.parser.tokens.peek() {
+                None => return None,
+                Some(Ok(token)) if token.kind == TokenKind::RightCurly => return None,
+                Some(Ok(token)) if token.kind == TokenKind::NewLine => {
+                    self.parser.tokens.next();
+                }
+                Some(_) => break,
+            }
+        }
+        let statement = self.parser.parse_statement().map(|mut statement| {
+            Resolver::new().resolve(&mut statement);
+            statement
+        });
+        self.failed = statement.is_err();
+        Some(statement)
+    }
+}
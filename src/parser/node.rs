@@ -1,6 +1,145 @@
-use crate::{models::error::MonoError, tokenizer::token::Token};
+use crate::internal_err;
+use crate::models::error::MonoError;
+use crate::models::position::Position;
+use crate::parser::source::literal;
+use crate::tokenizer::symbol::Symbol;
+use crate::tokenizer::token::{Token, TokenKind};
 use std::fmt;
 
+#[derive(Debug, Clone, PartialEq)]
+pub struct Argument {
+    pub identifier: Token,
+    pub default: Option<Box<Node>>,
+    pub is_variadic: bool,
+}
+
+impl Argument {
+    pub fn name(&self) -> &Symbol {
+        let TokenKind::Identifier(name) = &self.identifier.kind else {
+            internal_err!("Token must be of kind Identifier.")
+        };
+        name
+    }
+}
+
+impl fmt::Display for Argument {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.is_variadic {
+            true => write!(f, "*{}", self.name()),
+            false => write!(f, "{}", self.name()),
+        }
+    }
+}
+
+// The left hand side of a destructuring assignment, such as `x, [head, *tail]`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Pattern {
+    Identifier(Token),
+    Rest(Token),
+    Tuple(Vec<Pattern>),
+    List(Vec<Pattern>),
+}
+
+impl fmt::Display for Pattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let join = |patterns: &[Pattern]| {
+            patterns
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<String>>()
+                .join(", ")
+        };
+        let name = |token: &Token| match &token.kind {
+            TokenKind::Identifier(name) => name.to_string(),
+            _ => internal_err!("Token must be of kind Identifier."),
+        };
+        match self {
+            Pattern::Identifier(identifier) => write!(f, "{}", name(identifier)),
+            Pattern::Rest(identifier) => write!(f, "*{}", name(identifier)),
+            Pattern::Tuple(patterns) => write!(f, "({})", join(patterns)),
+            Pattern::List(patterns) => write!(f, "[{}]", join(patterns)),
+        }
+    }
+}
+
+// What a `match` arm tests its value against. Unlike a `Pattern` it can be
+// refuted: `[x, *rest]`, `{name: n}`, `Color.Red` or `1 | 2`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CasePattern {
+    Wildcard,
+    Binding(Token),
+    Literal(Token),
+    Rest(Token),
+    Variant {
+        enumeration: Token,
+        variant: Token,
+    },
+    List(Vec<CasePattern>),
+    Tuple(Vec<CasePattern>),
+    // The fields a struct must have, optionally of a given struct type.
+    Struct {
+        identifier: Option<Token>,
+        fields: Vec<(Token, CasePattern)>,
+    },
+    Or(Vec<CasePattern>),
+}
+
+impl fmt::Display for CasePattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let join = |patterns: &[CasePattern], separator: &str| {
+            patterns
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<String>>()
+                .join(separator)
+        };
+        let name = |token: &Token| match &token.kind {
+            TokenKind::Identifier(name) => name.to_string(),
+            _ => internal_err!("Token must be of kind Identifier."),
+        };
+        match self {
+            CasePattern::Wildcard => write!(f, "_"),
+            CasePattern::Binding(identifier) => write!(f, "{}", name(identifier)),
+            CasePattern::Literal(token) => write!(f, "{}", literal(&token.kind)),
+            CasePattern::Rest(identifier) => write!(f, "*{}", name(identifier)),
+            CasePattern::Variant {
+                enumeration,
+                variant,
+            } => write!(f, "{}.{}", name(enumeration), name(variant)),
+            CasePattern::List(patterns) => write!(f, "[{}]", join(patterns, ", ")),
+            CasePattern::Tuple(patterns) => write!(f, "({})", join(patterns, ", ")),
+            CasePattern::Struct { identifier, fields } => {
+                if let Some(identifier) = identifier {
+                    write!(f, "{} ", name(identifier))?;
+                }
+                let fields = fields
+                    .iter()
+                    .map(|(field, pattern)| format!("{}: {}", name(field), pattern))
+                    .collect::<Vec<String>>();
+                write!(f, "{{{}}}", fields.join(", "))
+            }
+            CasePattern::Or(patterns) => write!(f, "{}", join(patterns, " | ")),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Arm {
+    pub pattern: CasePattern,
+    // `case x if x > 10 => ...`, checked with the pattern's names bound.
+    pub guard: Option<Box<Node>>,
+    pub block: Box<Node>,
+}
+
+// Where a function's local variable lives at runtime, `depth` scopes out
+// from the innermost one and `index` within it. Assigned by the resolver,
+// `None` means the variable is looked up by name.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Slot {
+    pub depth: usize,
+    pub index: usize,
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum Node {
     Atom {
@@ -9,6 +148,13 @@ pub enum Node {
     List {
         values: Vec<Box<Node>>,
     },
+    Tuple {
+        values: Vec<Box<Node>>,
+    },
+    Spread {
+        operator: Token,
+        value: Box<Node>,
+    },
     BinaryOp {
         left: Box<Node>,
         operator: Token,
@@ -20,28 +166,68 @@ pub enum Node {
     },
     FuncDeclearion {
         identifier: Token,
-        arguments: Vec<Token>,
+        arguments: Vec<Argument>,
         body: Box<Node>,
+        locals: Vec<Symbol>,
     },
     FuncCall {
         identifier: Token,
+        slot: Option<Slot>,
         parameters: Vec<Box<Node>>,
+        keywords: Vec<(Token, Box<Node>)>,
     },
     Assignment {
         identifier: Token,
+        slot: Option<Slot>,
+        value: Box<Node>,
+        is_declaration: bool,
+    },
+    Destructuring {
+        pattern: Pattern,
+        operator: Token,
         value: Box<Node>,
         is_declaration: bool,
     },
     ListAssignment {
         identifier: Token,
+        slot: Option<Slot>,
         index: Box<Node>,
         value: Box<Node>,
     },
     Access {
         identifier: Token,
+        slot: Option<Slot>,
+    },
+    Member {
+        object: Box<Node>,
+        member: Token,
+    },
+    MemberAssignment {
+        object: Box<Node>,
+        member: Token,
+        value: Box<Node>,
+    },
+    StructDeclaration {
+        identifier: Token,
+        fields: Vec<Argument>,
+    },
+    Impl {
+        identifier: Token,
+        methods: Vec<Box<Node>>,
+    },
+    EnumDeclaration {
+        identifier: Token,
+        variants: Vec<Token>,
+    },
+    MethodCall {
+        object: Box<Node>,
+        method: Token,
+        parameters: Vec<Box<Node>>,
+        keywords: Vec<(Token, Box<Node>)>,
     },
     Index {
         identifier: Token,
+        slot: Option<Slot>,
         index: Box<Node>,
     },
     If {
@@ -53,9 +239,48 @@ pub enum Node {
         condition: Box<Node>,
         block: Box<Node>,
     },
+    Comprehension {
+        value: Box<Node>,
+        identifier: Token,
+        operator: Token,
+        iterable: Box<Node>,
+        condition: Option<Box<Node>>,
+    },
+    For {
+        identifier: Token,
+        slot: Option<Slot>,
+        operator: Token,
+        iterable: Box<Node>,
+        block: Box<Node>,
+    },
+    DoWhile {
+        block: Box<Node>,
+        condition: Box<Node>,
+    },
     Return {
         value: Box<Node>,
     },
+    Delete {
+        identifier: Token,
+        slot: Option<Slot>,
+    },
+    // `global x, y` or `nonlocal x`: for the rest of the function the names
+    // refer to the global variables, or to those of the scope it was called
+    // from, rather than to variables of its own.
+    Global {
+        identifiers: Vec<Token>,
+        is_nonlocal: bool,
+    },
+    // `xs?[i]` or `f?()`, `None` when the indexed or called value is.
+    Optional {
+        value: Box<Node>,
+    },
+    // Runs the block of the first arm whose pattern matches the value, and
+    // is `None` when none does.
+    Match {
+        value: Box<Node>,
+        arms: Vec<Arm>,
+    },
     Program {
         statements: Vec<Box<Node>>,
     },
@@ -78,82 +303,161 @@ impl Node {
         };
 
         match self {
-            Node::Atom { value } => write!(f, "{}Atom {}\n", current_prefix, value),
+            Node::Atom { value } => writeln!(f, "{}Atom {}", current_prefix, value),
             Node::List { values } => {
-                write!(f, "{}List\n", current_prefix)?;
+                writeln!(f, "{}List", current_prefix)?;
+                for (index, value) in values.iter().enumerate() {
+                    let is_last = index == values.len() - 1;
+                    value.format_tree(f, &child_prefix, false, is_last)?;
+                }
+                Ok(())
+            }
+            Node::Tuple { values } => {
+                writeln!(f, "{}Tuple", current_prefix)?;
                 for (index, value) in values.iter().enumerate() {
                     let is_last = index == values.len() - 1;
                     value.format_tree(f, &child_prefix, false, is_last)?;
                 }
                 Ok(())
             }
+            Node::Destructuring {
+                pattern,
+                value,
+                is_declaration,
+                ..
+            } => {
+                writeln!(
+                    f,
+                    "{}Destructuring (Deceleration: {}) {}",
+                    current_prefix, is_declaration, pattern
+                )?;
+                writeln!(f, "{}│  Value", child_prefix)?;
+                value.format_tree(f, &child_prefix, false, true)
+            }
+            Node::Spread { operator, value } => {
+                writeln!(f, "{}Spread {}", current_prefix, operator)?;
+                value.format_tree(f, &child_prefix, false, true)
+            }
             Node::BinaryOp {
                 left,
                 operator,
                 right,
             } => {
-                write!(f, "{}BinaryOp {}\n", current_prefix, operator)?;
-                write!(f, "{}│  Left\n", child_prefix)?;
+                writeln!(f, "{}BinaryOp {}", current_prefix, operator)?;
+                writeln!(f, "{}│  Left", child_prefix)?;
                 left.format_tree(f, &child_prefix, false, false)?;
-                write!(f, "{}│  Right\n", child_prefix)?;
+                writeln!(f, "{}│  Right", child_prefix)?;
                 right.format_tree(f, &child_prefix, false, true)
             }
             Node::UnaryOp { operator, value } => {
-                write!(f, "{}UnaryOp {}\n", current_prefix, operator)?;
-                write!(f, "{}│  Value\n", child_prefix)?;
+                writeln!(f, "{}UnaryOp {}", current_prefix, operator)?;
+                writeln!(f, "{}│  Value", child_prefix)?;
                 value.format_tree(f, &child_prefix, false, true)
             }
             Node::Assignment {
                 identifier,
                 value,
                 is_declaration,
+                ..
             } => {
-                write!(
+                writeln!(
                     f,
-                    "{}Assignment (Deceleration: {}) {}\n",
+                    "{}Assignment (Deceleration: {}) {}",
                     current_prefix, is_declaration, identifier
                 )?;
-                write!(f, "{}│  Value\n", child_prefix)?;
+                writeln!(f, "{}│  Value", child_prefix)?;
                 value.format_tree(f, &child_prefix, false, true)
             }
             Node::ListAssignment {
                 identifier,
                 index,
                 value,
+                ..
             } => {
-                write!(f, "{}ListAssignment: {}\n", current_prefix, identifier)?;
-                write!(f, "{}│  Index\n", child_prefix)?;
+                writeln!(f, "{}ListAssignment: {}", current_prefix, identifier)?;
+                writeln!(f, "{}│  Index", child_prefix)?;
                 index.format_tree(f, &child_prefix, false, false)?;
-                write!(f, "{}│  Value\n", child_prefix)?;
+                writeln!(f, "{}│  Value", child_prefix)?;
                 value.format_tree(f, &child_prefix, false, true)
             }
-            Node::Access { identifier } => write!(f, "{}Access {}\n", current_prefix, identifier),
+            Node::Access { identifier, .. } => {
+                writeln!(f, "{}Access {}", current_prefix, identifier)
+            }
+            Node::Delete { identifier, .. } => {
+                writeln!(f, "{}Delete {}", current_prefix, identifier)
+            }
+            Node::Global {
+                identifiers,
+                is_nonlocal,
+            } => {
+                let keyword = if *is_nonlocal { "Nonlocal" } else { "Global" };
+                writeln!(f, "{}{}", current_prefix, keyword)?;
+                for identifier in identifiers {
+                    writeln!(f, "{}├──── {}", child_prefix, identifier)?;
+                }
+                Ok(())
+            }
+            Node::Member { object, member } => {
+                writeln!(f, "{}Member {}", current_prefix, member)?;
+                writeln!(f, "{}│  Object", child_prefix)?;
+                object.format_tree(f, &child_prefix, false, true)
+            }
+            Node::MemberAssignment {
+                object,
+                member,
+                value,
+            } => {
+                writeln!(f, "{}MemberAssignment {}", current_prefix, member)?;
+                writeln!(f, "{}│  Object", child_prefix)?;
+                object.format_tree(f, &child_prefix, false, false)?;
+                writeln!(f, "{}│  Value", child_prefix)?;
+                value.format_tree(f, &child_prefix, false, true)
+            }
+            Node::StructDeclaration { identifier, fields } => {
+                writeln!(f, "{}StructDeclaration {}", current_prefix, identifier)?;
+                writeln!(f, "{}│  Fields", child_prefix)?;
+                for field in fields.iter() {
+                    writeln!(f, "{}├──── {}", child_prefix, field)?;
+                    if let Some(default) = &field.default {
+                        writeln!(f, "{}│  Default", child_prefix)?;
+                        default.format_tree(f, &child_prefix, false, false)?;
+                    }
+                }
+                Ok(())
+            }
             Node::If {
                 condition,
                 block,
                 else_block,
             } => {
-                write!(f, "{}If\n", current_prefix)?;
-                write!(f, "{}│  Condition\n", child_prefix)?;
+                writeln!(f, "{}If", current_prefix)?;
+                writeln!(f, "{}│  Condition", child_prefix)?;
                 condition.format_tree(f, &child_prefix, false, false)?;
                 if let Some(some_else_block) = else_block {
-                    write!(f, "{}│  Block\n", child_prefix)?;
+                    writeln!(f, "{}│  Block", child_prefix)?;
                     block.format_tree(f, &child_prefix, false, false)?;
-                    write!(f, "{}│  Else Block\n", child_prefix)?;
+                    writeln!(f, "{}│  Else Block", child_prefix)?;
                     return some_else_block.format_tree(f, &child_prefix, false, true);
                 }
-                write!(f, "{}│  Block\n", child_prefix)?;
+                writeln!(f, "{}│  Block", child_prefix)?;
                 block.format_tree(f, &child_prefix, false, true)
             }
             Node::While { condition, block } => {
-                write!(f, "{}While\n", current_prefix)?;
-                write!(f, "{}│  Condition\n", child_prefix)?;
+                writeln!(f, "{}While", current_prefix)?;
+                writeln!(f, "{}│  Condition", child_prefix)?;
                 condition.format_tree(f, &child_prefix, false, false)?;
-                write!(f, "{}│  Block\n", child_prefix)?;
+                writeln!(f, "{}│  Block", child_prefix)?;
                 block.format_tree(f, &child_prefix, false, true)
             }
+            Node::DoWhile { block, condition } => {
+                writeln!(f, "{}DoWhile", current_prefix)?;
+                writeln!(f, "{}│  Block", child_prefix)?;
+                block.format_tree(f, &child_prefix, false, false)?;
+                writeln!(f, "{}│  Condition", child_prefix)?;
+                condition.format_tree(f, &child_prefix, false, true)
+            }
             Node::Program { statements } => {
-                write!(f, "{}Program\n", current_prefix)?;
+                writeln!(f, "{}Program", current_prefix)?;
                 for (index, statement) in statements.iter().enumerate() {
                     let is_last = index == statements.len() - 1;
                     statement.format_tree(f, &child_prefix, false, is_last)?;
@@ -164,48 +468,732 @@ impl Node {
                 identifier,
                 arguments,
                 body,
+                ..
             } => {
-                write!(f, "{}FuncDeclearion {}\n", current_prefix, identifier)?;
-                write!(f, "{}│  Arguments\n", child_prefix)?;
+                writeln!(f, "{}FuncDeclearion {}", current_prefix, identifier)?;
+                writeln!(f, "{}│  Arguments", child_prefix)?;
                 for argument in arguments.iter() {
-                    write!(f, "{}├──── {:?}\n", child_prefix, argument.kind)?;
+                    writeln!(f, "{}├──── {}", child_prefix, argument)?;
+                    if let Some(default) = &argument.default {
+                        writeln!(f, "{}│  Default", child_prefix)?;
+                        default.format_tree(f, &child_prefix, false, false)?;
+                    }
                 }
-                write!(f, "{}│  Body\n", child_prefix)?;
+                writeln!(f, "{}│  Body", child_prefix)?;
                 body.format_tree(f, &child_prefix, false, true)
             }
             Node::FuncCall {
                 identifier,
                 parameters,
+                keywords,
+                ..
+            } => {
+                writeln!(f, "{}FuncCall {}", current_prefix, identifier)?;
+                writeln!(f, "{}│  Parameters", child_prefix)?;
+                for (index, parameter) in parameters.iter().enumerate() {
+                    let is_last = index == parameters.len() - 1 && keywords.is_empty();
+                    parameter.format_tree(f, &child_prefix, false, is_last)?;
+                }
+                for (index, (keyword, value)) in keywords.iter().enumerate() {
+                    writeln!(f, "{}│  Keyword {:?}", child_prefix, keyword.kind)?;
+                    value.format_tree(f, &child_prefix, false, index == keywords.len() - 1)?;
+                }
+                Ok(())
+            }
+            Node::MethodCall {
+                object,
+                method,
+                parameters,
+                keywords,
             } => {
-                write!(f, "{}FuncCall {}\n", current_prefix, identifier)?;
-                write!(f, "{}│  Parameters\n", child_prefix)?;
+                writeln!(f, "{}MethodCall {}", current_prefix, method)?;
+                writeln!(f, "{}│  Object", child_prefix)?;
+                let is_last = parameters.is_empty() && keywords.is_empty();
+                object.format_tree(f, &child_prefix, false, is_last)?;
+                writeln!(f, "{}│  Parameters", child_prefix)?;
                 for (index, parameter) in parameters.iter().enumerate() {
-                    let is_last = index == parameters.len() - 1;
+                    let is_last = index == parameters.len() - 1 && keywords.is_empty();
                     parameter.format_tree(f, &child_prefix, false, is_last)?;
                 }
+                for (index, (keyword, value)) in keywords.iter().enumerate() {
+                    writeln!(f, "{}│  Keyword {:?}", child_prefix, keyword.kind)?;
+                    value.format_tree(f, &child_prefix, false, index == keywords.len() - 1)?;
+                }
                 Ok(())
             }
+            Node::Impl {
+                identifier,
+                methods,
+            } => {
+                writeln!(f, "{}Impl {}", current_prefix, identifier)?;
+                for (index, method) in methods.iter().enumerate() {
+                    method.format_tree(f, &child_prefix, false, index == methods.len() - 1)?;
+                }
+                Ok(())
+            }
+            Node::EnumDeclaration {
+                identifier,
+                variants,
+            } => {
+                writeln!(f, "{}EnumDeclaration {}", current_prefix, identifier)?;
+                writeln!(f, "{}│  Variants", child_prefix)?;
+                for variant in variants.iter() {
+                    writeln!(f, "{}├──── {}", child_prefix, variant)?;
+                }
+                Ok(())
+            }
+            Node::Comprehension {
+                value,
+                identifier,
+                iterable,
+                condition,
+                ..
+            } => {
+                writeln!(f, "{}Comprehension {}", current_prefix, identifier)?;
+                writeln!(f, "{}│  Value", child_prefix)?;
+                value.format_tree(f, &child_prefix, false, false)?;
+                writeln!(f, "{}│  In", child_prefix)?;
+                iterable.format_tree(f, &child_prefix, false, condition.is_none())?;
+                if let Some(condition) = condition {
+                    writeln!(f, "{}│  If", child_prefix)?;
+                    condition.format_tree(f, &child_prefix, false, true)?;
+                }
+                Ok(())
+            }
+            Node::For {
+                identifier,
+                iterable,
+                block,
+                ..
+            } => {
+                writeln!(f, "{}For {}", current_prefix, identifier)?;
+                writeln!(f, "{}│  In", child_prefix)?;
+                iterable.format_tree(f, &child_prefix, false, false)?;
+                writeln!(f, "{}│  Block", child_prefix)?;
+                block.format_tree(f, &child_prefix, false, true)
+            }
             Self::Return { value } => {
-                write!(f, "{}Return\n", current_prefix)?;
+                writeln!(f, "{}Return", current_prefix)?;
+                value.format_tree(f, &child_prefix, false, true)
+            }
+            Self::Optional { value } => {
+                writeln!(f, "{}Optional", current_prefix)?;
                 value.format_tree(f, &child_prefix, false, true)
             }
-            Node::Index { identifier, index } => {
-                write!(f, "{}Index {}\n", current_prefix, identifier)?;
-                write!(f, "{}│  At\n", child_prefix)?;
+            Node::Match { value, arms } => {
+                writeln!(f, "{}Match", current_prefix)?;
+                writeln!(f, "{}│  Value", child_prefix)?;
+                value.format_tree(f, &child_prefix, false, arms.is_empty())?;
+                for (index, arm) in arms.iter().enumerate() {
+                    writeln!(f, "{}│  Case {}", child_prefix, arm.pattern)?;
+                    if let Some(guard) = &arm.guard {
+                        writeln!(f, "{}│  If", child_prefix)?;
+                        guard.format_tree(f, &child_prefix, false, false)?;
+                    }
+                    arm.block
+                        .format_tree(f, &child_prefix, false, index == arms.len() - 1)?;
+                }
+                Ok(())
+            }
+            Node::Index {
+                identifier, index, ..
+            } => {
+                writeln!(f, "{}Index {}", current_prefix, identifier)?;
+                writeln!(f, "{}│  At", child_prefix)?;
                 index.format_tree(f, &child_prefix, false, true)
             }
         }
     }
 }
 
+impl Node {
+    // A string literal starting a function's body documents the function.
+    pub fn docstring(&self) -> Option<&str> {
+        let Node::Program { statements } = self else {
+            return None;
+        };
+        match statements.first()?.as_ref() {
+            Node::Atom {
+                value:
+                    Token {
+                        kind: TokenKind::String(docstring),
+                        ..
+                    },
+            } => Some(docstring),
+            _ => None,
+        }
+    }
+
+    // The position of the earliest token the node keeps. Keywords and
+    // delimiters aren't kept, so e.g. an `if` is placed at its condition.
+    pub fn position(&self) -> Option<&Position> {
+        match self {
+            Node::Atom { value } => Some(&value.start),
+            Node::Global { identifiers, .. } => Some(&identifiers.first()?.start),
+            Node::List { values }
+            | Node::Tuple { values }
+            | Node::Program { statements: values } => values.first()?.position(),
+            Node::Spread { operator, .. }
+            | Node::UnaryOp { operator, .. }
+            | Node::Destructuring { operator, .. } => Some(&operator.start),
+            Node::FuncDeclearion { identifier, .. }
+            | Node::FuncCall { identifier, .. }
+            | Node::Assignment { identifier, .. }
+            | Node::ListAssignment { identifier, .. }
+            | Node::Access { identifier, .. }
+            | Node::Delete { identifier, .. }
+            | Node::StructDeclaration { identifier, .. }
+            | Node::Impl { identifier, .. }
+            | Node::EnumDeclaration { identifier, .. }
+            | Node::Index { identifier, .. }
+            | Node::For { identifier, .. } => Some(&identifier.start),
+            Node::BinaryOp { left: value, .. }
+            | Node::Member { object: value, .. }
+            | Node::MemberAssignment { object: value, .. }
+            | Node::MethodCall { object: value, .. }
+            | Node::If {
+                condition: value, ..
+            }
+            | Node::While {
+                condition: value, ..
+            }
+            | Node::Comprehension { value, .. }
+            | Node::Optional { value }
+            | Node::Match { value, .. }
+            | Node::Return { value } => value.position(),
+            Node::DoWhile { block, condition } => block.position().or(condition.position()),
+        }
+    }
+
+    // The position of the latest token the node keeps, the counterpart of
+    // `position`. Closing delimiters aren't kept either.
+    pub fn end_position(&self) -> Option<&Position> {
+        match self {
+            Node::Atom { value } => end(value),
+            Node::Global { identifiers, .. } => end(identifiers.last()?),
+            Node::List { values }
+            | Node::Tuple { values }
+            | Node::Program { statements: values } => last(values),
+            Node::Spread { value, .. }
+            | Node::BinaryOp { right: value, .. }
+            | Node::UnaryOp { value, .. }
+            | Node::FuncDeclearion { body: value, .. }
+            | Node::Assignment { value, .. }
+            | Node::Destructuring { value, .. }
+            | Node::ListAssignment { value, .. }
+            | Node::MemberAssignment { value, .. }
+            | Node::Index { index: value, .. }
+            | Node::While { block: value, .. }
+            | Node::For { block: value, .. }
+            | Node::DoWhile {
+                condition: value, ..
+            }
+            | Node::Optional { value }
+            | Node::Return { value } => value.end_position(),
+            Node::Access { identifier, .. } | Node::Delete { identifier, .. } => end(identifier),
+            Node::Member { member, .. } => end(member),
+            Node::FuncCall {
+                identifier: name,
+                parameters,
+                keywords,
+                ..
+            }
+            | Node::MethodCall {
+                method: name,
+                parameters,
+                keywords,
+                ..
+            } => match keywords.last() {
+                Some((_, value)) => value.end_position(),
+                None => last(parameters).or(end(name)),
+            },
+            Node::StructDeclaration { identifier, fields } => match fields.last() {
+                Some(field) => end(&field.identifier),
+                None => end(identifier),
+            },
+            Node::Impl {
+                identifier,
+                methods,
+            } => last(methods).or(end(identifier)),
+            Node::EnumDeclaration {
+                identifier,
+                variants,
+            } => end(variants.last().unwrap_or(identifier)),
+            Node::If {
+                block, else_block, ..
+            } => match else_block {
+                Some(else_block) => else_block.end_position(),
+                None => block.end_position(),
+            },
+            Node::Comprehension {
+                iterable,
+                condition,
+                ..
+            } => match condition {
+                Some(condition) => condition.end_position(),
+                None => iterable.end_position(),
+            },
+            Node::Match { value, arms } => match arms.last() {
+                Some(arm) => arm.block.end_position(),
+                None => value.end_position(),
+            },
+        }
+    }
+
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Node::Atom { .. } => "Atom",
+            Node::List { .. } => "List",
+            Node::Tuple { .. } => "Tuple",
+            Node::Spread { .. } => "Spread",
+            Node::BinaryOp { .. } => "BinaryOp",
+            Node::UnaryOp { .. } => "UnaryOp",
+            Node::FuncDeclearion { .. } => "FuncDeclearion",
+            Node::FuncCall { .. } => "FuncCall",
+            Node::Assignment { .. } => "Assignment",
+            Node::Destructuring { .. } => "Destructuring",
+            Node::ListAssignment { .. } => "ListAssignment",
+            Node::Access { .. } => "Access",
+            Node::Member { .. } => "Member",
+            Node::MemberAssignment { .. } => "MemberAssignment",
+            Node::StructDeclaration { .. } => "StructDeclaration",
+            Node::Impl { .. } => "Impl",
+            Node::EnumDeclaration { .. } => "EnumDeclaration",
+            Node::MethodCall { .. } => "MethodCall",
+            Node::Index { .. } => "Index",
+            Node::If { .. } => "If",
+            Node::While { .. } => "While",
+            Node::Comprehension { .. } => "Comprehension",
+            Node::For { .. } => "For",
+            Node::DoWhile { .. } => "DoWhile",
+            Node::Return { .. } => "Return",
+            Node::Delete { .. } => "Delete",
+            Node::Global { .. } => "Global",
+            Node::Optional { .. } => "Optional",
+            Node::Match { .. } => "Match",
+            Node::Program { .. } => "Program",
+        }
+    }
+
+    // Structural equality that ignores the positions of the tokens, so moving
+    // code around without changing it keeps the nodes equivalent.
+    pub fn is_equivalent(&self, other: &Node) -> bool {
+        let tokens = |a: &Token, b: &Token| a.kind.same(&b.kind);
+        let nodes = |a: &[Box<Node>], b: &[Box<Node>]| {
+            a.len() == b.len() && a.iter().zip(b).all(|(a, b)| a.is_equivalent(b))
+        };
+        let keywords = |a: &[(Token, Box<Node>)], b: &[(Token, Box<Node>)]| {
+            a.len() == b.len()
+                && a.iter().zip(b).all(|((a, a_value), (b, b_value))| {
+                    tokens(a, b) && a_value.is_equivalent(b_value)
+                })
+        };
+        let arguments = |a: &[Argument], b: &[Argument]| {
+            a.len() == b.len()
+                && a.iter().zip(b).all(|(a, b)| {
+                    tokens(&a.identifier, &b.identifier)
+                        && a.is_variadic == b.is_variadic
+                        && match (&a.default, &b.default) {
+                            (Some(a), Some(b)) => a.is_equivalent(b),
+                            (None, None) => true,
+                            _ => false,
+                        }
+                })
+        };
+
+        match (self, other) {
+            (Node::Atom { value: a }, Node::Atom { value: b }) => tokens(a, b),
+            (Node::List { values: a }, Node::List { values: b }) => nodes(a, b),
+            (Node::Tuple { values: a }, Node::Tuple { values: b }) => nodes(a, b),
+            (
+                Node::Destructuring {
+                    pattern: a_pattern,
+                    value: a_value,
+                    is_declaration: a_is_declaration,
+                    ..
+                },
+                Node::Destructuring {
+                    pattern: b_pattern,
+                    value: b_value,
+                    is_declaration: b_is_declaration,
+                    ..
+                },
+            ) => {
+                a_is_declaration == b_is_declaration
+                    && a_pattern.to_string() == b_pattern.to_string()
+                    && a_value.is_equivalent(b_value)
+            }
+            (Node::Spread { value: a, .. }, Node::Spread { value: b, .. }) => a.is_equivalent(b),
+            (
+                Node::BinaryOp {
+                    left: a_left,
+                    operator: a_operator,
+                    right: a_right,
+                },
+                Node::BinaryOp {
+                    left: b_left,
+                    operator: b_operator,
+                    right: b_right,
+                },
+            ) => {
+                tokens(a_operator, b_operator)
+                    && a_left.is_equivalent(b_left)
+                    && a_right.is_equivalent(b_right)
+            }
+            (
+                Node::UnaryOp {
+                    operator: a_operator,
+                    value: a_value,
+                },
+                Node::UnaryOp {
+                    operator: b_operator,
+                    value: b_value,
+                },
+            ) => tokens(a_operator, b_operator) && a_value.is_equivalent(b_value),
+            (
+                Node::FuncDeclearion {
+                    identifier: a_identifier,
+                    arguments: a_arguments,
+                    body: a_body,
+                    ..
+                },
+                Node::FuncDeclearion {
+                    identifier: b_identifier,
+                    arguments: b_arguments,
+                    body: b_body,
+                    ..
+                },
+            ) => {
+                tokens(a_identifier, b_identifier)
+                    && arguments(a_arguments, b_arguments)
+                    && a_body.is_equivalent(b_body)
+            }
+            (
+                Node::FuncCall {
+                    identifier: a_identifier,
+                    parameters: a_parameters,
+                    keywords: a_keywords,
+                    ..
+                },
+                Node::FuncCall {
+                    identifier: b_identifier,
+                    parameters: b_parameters,
+                    keywords: b_keywords,
+                    ..
+                },
+            ) => {
+                tokens(a_identifier, b_identifier)
+                    && nodes(a_parameters, b_parameters)
+                    && keywords(a_keywords, b_keywords)
+            }
+            (
+                Node::Assignment {
+                    identifier: a_identifier,
+                    value: a_value,
+                    is_declaration: a_is_declaration,
+                    ..
+                },
+                Node::Assignment {
+                    identifier: b_identifier,
+                    value: b_value,
+                    is_declaration: b_is_declaration,
+                    ..
+                },
+            ) => {
+                a_is_declaration == b_is_declaration
+                    && tokens(a_identifier, b_identifier)
+                    && a_value.is_equivalent(b_value)
+            }
+            (
+                Node::ListAssignment {
+                    identifier: a_identifier,
+                    index: a_index,
+                    value: a_value,
+                    ..
+                },
+                Node::ListAssignment {
+                    identifier: b_identifier,
+                    index: b_index,
+                    value: b_value,
+                    ..
+                },
+            ) => {
+                tokens(a_identifier, b_identifier)
+                    && a_index.is_equivalent(b_index)
+                    && a_value.is_equivalent(b_value)
+            }
+            (Node::Access { identifier: a, .. }, Node::Access { identifier: b, .. })
+            | (Node::Delete { identifier: a, .. }, Node::Delete { identifier: b, .. }) => {
+                tokens(a, b)
+            }
+            (
+                Node::Global {
+                    identifiers: a_identifiers,
+                    is_nonlocal: a_is_nonlocal,
+                },
+                Node::Global {
+                    identifiers: b_identifiers,
+                    is_nonlocal: b_is_nonlocal,
+                },
+            ) => {
+                a_is_nonlocal == b_is_nonlocal
+                    && a_identifiers.len() == b_identifiers.len()
+                    && a_identifiers
+                        .iter()
+                        .zip(b_identifiers)
+                        .all(|(a, b)| tokens(a, b))
+            }
+            (
+                Node::EnumDeclaration {
+                    identifier: a_identifier,
+                    variants: a_variants,
+                },
+                Node::EnumDeclaration {
+                    identifier: b_identifier,
+                    variants: b_variants,
+                },
+            ) => {
+                tokens(a_identifier, b_identifier)
+                    && a_variants.len() == b_variants.len()
+                    && a_variants.iter().zip(b_variants).all(|(a, b)| tokens(a, b))
+            }
+            (
+                Node::MethodCall {
+                    object: a_object,
+                    method: a_method,
+                    parameters: a_parameters,
+                    keywords: a_keywords,
+                },
+                Node::MethodCall {
+                    object: b_object,
+                    method: b_method,
+                    parameters: b_parameters,
+                    keywords: b_keywords,
+                },
+            ) => {
+                tokens(a_method, b_method)
+                    && a_object.is_equivalent(b_object)
+                    && nodes(a_parameters, b_parameters)
+                    && keywords(a_keywords, b_keywords)
+            }
+            (
+                Node::Impl {
+                    identifier: a_identifier,
+                    methods: a_methods,
+                },
+                Node::Impl {
+                    identifier: b_identifier,
+                    methods: b_methods,
+                },
+            ) => tokens(a_identifier, b_identifier) && nodes(a_methods, b_methods),
+            (
+                Node::Member {
+                    object: a_object,
+                    member: a_member,
+                },
+                Node::Member {
+                    object: b_object,
+                    member: b_member,
+                },
+            ) => tokens(a_member, b_member) && a_object.is_equivalent(b_object),
+            (
+                Node::MemberAssignment {
+                    object: a_object,
+                    member: a_member,
+                    value: a_value,
+                },
+                Node::MemberAssignment {
+                    object: b_object,
+                    member: b_member,
+                    value: b_value,
+                },
+            ) => {
+                tokens(a_member, b_member)
+                    && a_object.is_equivalent(b_object)
+                    && a_value.is_equivalent(b_value)
+            }
+            (
+                Node::StructDeclaration {
+                    identifier: a_identifier,
+                    fields: a_fields,
+                },
+                Node::StructDeclaration {
+                    identifier: b_identifier,
+                    fields: b_fields,
+                },
+            ) => tokens(a_identifier, b_identifier) && arguments(a_fields, b_fields),
+            (
+                Node::Index {
+                    identifier: a_identifier,
+                    index: a_index,
+                    ..
+                },
+                Node::Index {
+                    identifier: b_identifier,
+                    index: b_index,
+                    ..
+                },
+            ) => tokens(a_identifier, b_identifier) && a_index.is_equivalent(b_index),
+            (
+                Node::If {
+                    condition: a_condition,
+                    block: a_block,
+                    else_block: a_else_block,
+                },
+                Node::If {
+                    condition: b_condition,
+                    block: b_block,
+                    else_block: b_else_block,
+                },
+            ) => {
+                a_condition.is_equivalent(b_condition)
+                    && a_block.is_equivalent(b_block)
+                    && match (a_else_block, b_else_block) {
+                        (Some(a), Some(b)) => a.is_equivalent(b),
+                        (None, None) => true,
+                        _ => false,
+                    }
+            }
+            (
+                Node::While {
+                    condition: a_condition,
+                    block: a_block,
+                },
+                Node::While {
+                    condition: b_condition,
+                    block: b_block,
+                },
+            ) => a_condition.is_equivalent(b_condition) && a_block.is_equivalent(b_block),
+            (
+                Node::Comprehension {
+                    value: a_value,
+                    identifier: a_identifier,
+                    iterable: a_iterable,
+                    condition: a_condition,
+                    ..
+                },
+                Node::Comprehension {
+                    value: b_value,
+                    identifier: b_identifier,
+                    iterable: b_iterable,
+                    condition: b_condition,
+                    ..
+                },
+            ) => {
+                tokens(a_identifier, b_identifier)
+                    && a_value.is_equivalent(b_value)
+                    && a_iterable.is_equivalent(b_iterable)
+                    && match (a_condition, b_condition) {
+                        (Some(a), Some(b)) => a.is_equivalent(b),
+                        (None, None) => true,
+                        _ => false,
+                    }
+            }
+            (
+                Node::For {
+                    identifier: a_identifier,
+                    iterable: a_iterable,
+                    block: a_block,
+                    ..
+                },
+                Node::For {
+                    identifier: b_identifier,
+                    iterable: b_iterable,
+                    block: b_block,
+                    ..
+                },
+            ) => {
+                tokens(a_identifier, b_identifier)
+                    && a_iterable.is_equivalent(b_iterable)
+                    && a_block.is_equivalent(b_block)
+            }
+            (
+                Node::DoWhile {
+                    block: a_block,
+                    condition: a_condition,
+                },
+                Node::DoWhile {
+                    block: b_block,
+                    condition: b_condition,
+                },
+            ) => a_block.is_equivalent(b_block) && a_condition.is_equivalent(b_condition),
+            (Node::Return { value: a }, Node::Return { value: b })
+            | (Node::Optional { value: a }, Node::Optional { value: b }) => a.is_equivalent(b),
+            (Node::Program { statements: a }, Node::Program { statements: b }) => nodes(a, b),
+            (
+                Node::Match {
+                    value: a_value,
+                    arms: a_arms,
+                },
+                Node::Match {
+                    value: b_value,
+                    arms: b_arms,
+                },
+            ) => {
+                a_value.is_equivalent(b_value)
+                    && a_arms.len() == b_arms.len()
+                    && a_arms.iter().zip(b_arms).all(|(a, b)| {
+                        a.pattern.to_string() == b.pattern.to_string()
+                            && a.block.is_equivalent(&b.block)
+                            && match (&a.guard, &b.guard) {
+                                (Some(a), Some(b)) => a.is_equivalent(b),
+                                (None, None) => true,
+                                _ => false,
+                            }
+                    })
+            }
+            _ => false,
+        }
+    }
+
+    // Compares two versions of a program, returning the top-level function
+    // declarations whose bodies changed. `None` means something other than a
+    // function body changed and the whole program has to be evaluated again.
+    pub fn changed_functions<'a>(&'a self, previous: &Node) -> Option<Vec<&'a Node>> {
+        let (Node::Program { statements }, Node::Program { statements: old }) = (self, previous)
+        else {
+            return None;
+        };
+        if statements.len() != old.len() {
+            return None;
+        }
+
+        let mut changed = Vec::new();
+        for (statement, old) in statements.iter().zip(old) {
+            if statement.is_equivalent(old) {
+                continue;
+            }
+            match (statement.as_ref(), old.as_ref()) {
+                (
+                    Node::FuncDeclearion { identifier, .. },
+                    Node::FuncDeclearion {
+                        identifier: old_identifier,
+                        ..
+                    },
+                ) if identifier.kind.same(&old_identifier.kind) => changed.push(statement.as_ref()),
+                _ => return None,
+            }
+        }
+        Some(changed)
+    }
+}
+
 impl fmt::Display for Node {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         self.format_tree(f, "", true, false)
     }
 }
 
-impl From<Node> for Result<Box<Node>, Box<dyn MonoError>> {
+impl From<Node> for Result<Box<Node>, MonoError> {
     fn from(value: Node) -> Self {
         Ok(Box::new(value))
     }
 }
+
+fn end(token: &Token) -> Option<&Position> {
+    Some(&token.end)
+}
+
+fn last(nodes: &[Box<Node>]) -> Option<&Position> {
+    nodes.last()?.end_position()
+}
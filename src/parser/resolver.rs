@@ -0,0 +1,386 @@
+use crate::parser::node::{Argument, CasePattern, Node, Pattern, Slot};
+use crate::parser::visitor::{fold_children, NodeFolder};
+use crate::tokenizer::symbol::Symbol;
+use crate::tokenizer::token::{Token, TokenKind};
+
+// Mirrors the scopes the evaluator pushes: one per function call (or struct
+// construction) and one for the loop variable of a comprehension. Blocks
+// don't introduce scopes.
+struct Scope {
+    locals: Vec<Symbol>,
+    // Names declared `global` or `nonlocal`, looked up by name as they
+    // belong to another scope.
+    outer: Vec<Symbol>,
+    is_function: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DeclarationKind {
+    Variable,
+    Argument,
+    Function,
+    Struct,
+    Enum,
+}
+
+// A name the program declares, as recorded for tooling such as the language
+// server.
+#[derive(Debug, Clone)]
+pub struct Declaration {
+    pub identifier: Token,
+    pub kind: DeclarationKind,
+    // A one line summary, such as a function's signature.
+    pub detail: String,
+}
+
+// Assigns every variable local to a function a slot in its frame, so the
+// evaluator indexes it rather than looking it up by name. Globals, builtins
+// and variables of enclosing functions are left unresolved, functions don't
+// capture their environment so those are only known at runtime.
+#[derive(Default)]
+pub struct Resolver {
+    scopes: Vec<Scope>,
+    declarations: Option<Vec<Declaration>>,
+}
+
+impl Resolver {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // A resolver that also records every declaration it passes, in order.
+    pub fn recording() -> Self {
+        Self {
+            scopes: Vec::new(),
+            declarations: Some(Vec::new()),
+        }
+    }
+
+    pub fn declarations(self) -> Vec<Declaration> {
+        self.declarations.unwrap_or_default()
+    }
+
+    pub fn resolve(&mut self, node: &mut Node) {
+        self.fold(node);
+    }
+
+    fn fold_all(&mut self, nodes: &mut [Box<Node>]) {
+        for node in nodes {
+            self.fold(node);
+        }
+    }
+
+    // Arguments take the first slots of the frame, in order. A default can
+    // only see the arguments before it, as they're bound one by one. A named
+    // function's frame also holds the function itself, so it can call itself
+    // wherever it's called from, even once the scope it was declared in is
+    // gone.
+    fn resolve_function(
+        &mut self,
+        name: Option<&Token>,
+        arguments: &mut [Argument],
+        body: Option<&mut Node>,
+    ) -> Vec<Symbol> {
+        self.scopes.push(Scope {
+            locals: Vec::new(),
+            outer: Vec::new(),
+            is_function: true,
+        });
+        for argument in arguments.iter_mut() {
+            if let Some(default) = &mut argument.default {
+                self.fold(default);
+            }
+            self.declare(&argument.identifier);
+            if body.is_some() {
+                self.record(&argument.identifier, DeclarationKind::Argument, || {
+                    format!("argument {}", argument)
+                });
+            }
+        }
+        if let Some(name) = name {
+            self.declare(name);
+        }
+        if let Some(body) = body {
+            self.fold(body);
+        }
+        self.scopes
+            .pop()
+            .map(|scope| scope.locals)
+            .unwrap_or_default()
+    }
+
+    fn declare(&mut self, identifier: &Token) -> Option<Slot> {
+        let name = symbol(identifier)?;
+        let scope = self.scopes.last_mut()?;
+        if scope.outer.contains(&name) {
+            return None;
+        }
+        let index = match scope.locals.iter().position(|local| *local == name) {
+            Some(index) => index,
+            None => {
+                scope.locals.push(name);
+                scope.locals.len() - 1
+            }
+        };
+        Some(Slot { depth: 0, index })
+    }
+
+    fn declare_pattern(&mut self, pattern: &Pattern) {
+        match pattern {
+            Pattern::Identifier(identifier) | Pattern::Rest(identifier) => {
+                self.record(identifier, DeclarationKind::Variable, || {
+                    format!("let {}", name(identifier))
+                });
+                self.declare(identifier);
+            }
+            Pattern::Tuple(patterns) | Pattern::List(patterns) => {
+                for pattern in patterns {
+                    self.declare_pattern(pattern);
+                }
+            }
+        }
+    }
+
+    fn declare_case_pattern(&mut self, pattern: &CasePattern) {
+        match pattern {
+            CasePattern::Binding(identifier) | CasePattern::Rest(identifier) => {
+                self.record(identifier, DeclarationKind::Variable, || {
+                    format!("case {}", name(identifier))
+                });
+                self.declare(identifier);
+            }
+            CasePattern::List(patterns)
+            | CasePattern::Tuple(patterns)
+            | CasePattern::Or(patterns) => {
+                for pattern in patterns {
+                    self.declare_case_pattern(pattern);
+                }
+            }
+            CasePattern::Struct { fields, .. } => {
+                for (_, pattern) in fields {
+                    self.declare_case_pattern(pattern);
+                }
+            }
+            CasePattern::Wildcard | CasePattern::Literal(_) | CasePattern::Variant { .. } => {}
+        }
+    }
+
+    fn record(
+        &mut self,
+        identifier: &Token,
+        kind: DeclarationKind,
+        detail: impl FnOnce() -> String,
+    ) {
+        if let Some(declarations) = &mut self.declarations {
+            declarations.push(Declaration {
+                identifier: identifier.clone(),
+                kind,
+                detail: detail(),
+            });
+        }
+    }
+
+    // Only the scopes up to the innermost function are searched, anything
+    // beyond it depends on the caller.
+    fn lookup(&self, identifier: &Token) -> Option<Slot> {
+        let name = symbol(identifier)?;
+        for (depth, scope) in self.scopes.iter().rev().enumerate() {
+            if let Some(index) = scope.locals.iter().position(|local| *local == name) {
+                return Some(Slot { depth, index });
+            }
+            if scope.is_function || scope.outer.contains(&name) {
+                break;
+            }
+        }
+        None
+    }
+}
+
+// Names are declared and looked up in the order the evaluator meets them,
+// nodes that don't bind or read a name are only walked through.
+impl NodeFolder for Resolver {
+    fn fold(&mut self, node: &mut Node) {
+        match node {
+            Node::FuncDeclearion {
+                identifier,
+                arguments,
+                body,
+                locals,
+            } => {
+                self.declare(identifier);
+                self.record(identifier, DeclarationKind::Function, || {
+                    format!("let {}({})", name(identifier), join(arguments))
+                });
+                *locals = self.resolve_function(Some(identifier), arguments, Some(body));
+            }
+            Node::FuncCall {
+                identifier,
+                slot,
+                parameters,
+                keywords,
+            } => {
+                self.fold_all(parameters);
+                for (_, value) in keywords.iter_mut() {
+                    self.fold(value);
+                }
+                *slot = self.lookup(identifier);
+            }
+            Node::Assignment {
+                identifier,
+                slot,
+                value,
+                is_declaration,
+            } => {
+                self.fold(value);
+                *slot = match is_declaration {
+                    true => {
+                        self.record(identifier, DeclarationKind::Variable, || {
+                            format!("let {}", name(identifier))
+                        });
+                        self.declare(identifier)
+                    }
+                    false => self.lookup(identifier),
+                };
+            }
+            Node::Destructuring {
+                pattern,
+                value,
+                is_declaration,
+                ..
+            } => {
+                self.fold(value);
+                if *is_declaration {
+                    self.declare_pattern(pattern);
+                }
+            }
+            Node::ListAssignment {
+                identifier,
+                slot,
+                index,
+                value,
+            } => {
+                self.fold(index);
+                self.fold(value);
+                *slot = self.lookup(identifier);
+            }
+            Node::Global { identifiers, .. } => {
+                if let Some(scope) = self.scopes.last_mut() {
+                    scope.outer.extend(identifiers.iter().filter_map(symbol));
+                }
+            }
+            Node::Access { identifier, slot } | Node::Delete { identifier, slot } => {
+                *slot = self.lookup(identifier)
+            }
+            Node::StructDeclaration { identifier, fields } => {
+                self.declare(identifier);
+                self.record(identifier, DeclarationKind::Struct, || {
+                    format!("struct {} {{ {} }}", name(identifier), join(fields))
+                });
+                self.resolve_function(None, fields, None);
+            }
+            Node::Impl { methods, .. } => {
+                for method in methods.iter_mut() {
+                    if let Node::FuncDeclearion {
+                        arguments,
+                        body,
+                        locals,
+                        ..
+                    } = method.as_mut()
+                    {
+                        *locals = self.resolve_function(None, arguments, Some(body));
+                    }
+                }
+            }
+            Node::EnumDeclaration {
+                identifier,
+                variants,
+            } => {
+                self.declare(identifier);
+                self.record(identifier, DeclarationKind::Enum, || {
+                    format!(
+                        "enum {} {{ {} }}",
+                        name(identifier),
+                        join(&variants.iter().map(name).collect::<Vec<_>>())
+                    )
+                });
+            }
+            Node::Index {
+                identifier,
+                slot,
+                index,
+            } => {
+                self.fold(index);
+                *slot = self.lookup(identifier);
+            }
+            Node::Comprehension {
+                value,
+                identifier,
+                iterable,
+                condition,
+                ..
+            } => {
+                self.fold(iterable);
+                self.record(identifier, DeclarationKind::Variable, || {
+                    format!("for {}", name(identifier))
+                });
+                self.scopes.push(Scope {
+                    locals: symbol(identifier).into_iter().collect(),
+                    outer: Vec::new(),
+                    is_function: false,
+                });
+                if let Some(condition) = condition {
+                    self.fold(condition);
+                }
+                self.fold(value);
+                self.scopes.pop();
+            }
+            Node::For {
+                identifier,
+                slot,
+                iterable,
+                block,
+                ..
+            } => {
+                self.fold(iterable);
+                self.record(identifier, DeclarationKind::Variable, || {
+                    format!("for {}", name(identifier))
+                });
+                *slot = self.declare(identifier);
+                self.fold(block);
+            }
+            Node::Match { value, arms } => {
+                self.fold(value);
+                for arm in arms.iter_mut() {
+                    self.declare_case_pattern(&arm.pattern);
+                    if let Some(guard) = &mut arm.guard {
+                        self.fold(guard);
+                    }
+                    self.fold(&mut arm.block);
+                }
+            }
+            _ => fold_children(self, node),
+        }
+    }
+}
+
+fn symbol(identifier: &Token) -> Option<Symbol> {
+    match &identifier.kind {
+        TokenKind::Identifier(name) => Some(name.clone()),
+        _ => None,
+    }
+}
+
+fn name(identifier: &Token) -> &str {
+    match &identifier.kind {
+        TokenKind::Identifier(name) => name,
+        _ => "",
+    }
+}
+
+fn join(items: &[impl ToString]) -> String {
+    items
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
@@ -0,0 +1,586 @@
+use crate::parser::node::{Argument, Node, Pattern};
+use crate::tokenizer::token::{Token, TokenKind};
+
+// How tightly an expression binds, following the parser from `??` down to
+// atoms. Control flow binds loosest: it's written bare as a statement or an
+// assigned value, and parenthesized anywhere else so a following `{` or
+// operator isn't taken as part of it.
+const CONTROL: u8 = 0;
+const COALESCE: u8 = 1;
+const OR: u8 = 2;
+const AND: u8 = 3;
+const NOT: u8 = 4;
+const COMPARISON: u8 = 5;
+const SUM: u8 = 6;
+const PRODUCT: u8 = 7;
+const UNARY: u8 = 8;
+const POWER: u8 = 9;
+const PRIMARY: u8 = 10;
+
+const INDENT: &str = "    ";
+
+impl Node {
+    // Renders the tree back into mono source that parses to an equivalent
+    // tree. Parentheses are only added where precedence needs them, and
+    // comments and the original layout are lost.
+    pub fn to_source(&self) -> String {
+        match self {
+            Node::Program { statements } => statements
+                .iter()
+                .map(|statement| self::statement(statement, 0))
+                .collect::<Vec<_>>()
+                .join("\n"),
+            node => statement(node, 0),
+        }
+    }
+}
+
+impl Argument {
+    // How a signature shows the argument, with its default if it has one,
+    // e.g. `b = 2` or `*rest`.
+    pub fn signature(&self) -> String {
+        match &self.default {
+            Some(default) => format!("{} = {}", self, expression(default, COALESCE, 0)),
+            None => self.to_string(),
+        }
+    }
+}
+
+fn statement(node: &Node, indent: usize) -> String {
+    match node {
+        Node::Assignment {
+            identifier,
+            value,
+            is_declaration,
+            ..
+        } => format!(
+            "{}{} = {}",
+            declaration(*is_declaration),
+            name(identifier),
+            expression(value, CONTROL, indent)
+        ),
+        Node::Destructuring {
+            pattern,
+            value,
+            is_declaration,
+            ..
+        } => {
+            // `a, *b = xs` rather than `(a, *b) = xs`, which would parse the
+            // left side as a tuple expression.
+            let pattern = match pattern {
+                Pattern::Tuple(patterns) if patterns.len() > 1 => join(patterns),
+                pattern => pattern.to_string(),
+            };
+            format!(
+                "{}{} = {}",
+                declaration(*is_declaration),
+                pattern,
+                expression(value, CONTROL, indent)
+            )
+        }
+        Node::ListAssignment {
+            identifier,
+            index,
+            value,
+            ..
+        } => format!(
+            "{}[{}] = {}",
+            name(identifier),
+            expression(index, SUM, indent),
+            expression(value, CONTROL, indent)
+        ),
+        Node::MemberAssignment {
+            object,
+            member,
+            value,
+        } => format!(
+            "{}.{} = {}",
+            self::object(object, indent),
+            name(member),
+            expression(value, CONTROL, indent)
+        ),
+        Node::FuncDeclearion {
+            identifier,
+            arguments,
+            body,
+            ..
+        } => format!("let {}", function(identifier, arguments, body, indent)),
+        Node::StructDeclaration { identifier, fields } => match fields.is_empty() {
+            true => format!("struct {} {{}}", name(identifier)),
+            false => format!(
+                "struct {} {{ {} }}",
+                name(identifier),
+                self::arguments(fields, indent)
+            ),
+        },
+        Node::Impl {
+            identifier,
+            methods,
+        } => {
+            let methods: Vec<String> = methods
+                .iter()
+                .map(|method| match method.as_ref() {
+                    Node::FuncDeclearion {
+                        identifier,
+                        arguments,
+                        body,
+                        ..
+                    } => function(identifier, arguments, body, indent + 1),
+                    method => statement(method, indent + 1),
+                })
+                .collect();
+            format!("impl {} {}", name(identifier), lines(&methods, indent))
+        }
+        Node::EnumDeclaration {
+            identifier,
+            variants,
+        } => match variants.is_empty() {
+            true => format!("enum {} {{}}", name(identifier)),
+            false => format!(
+                "enum {} {{ {} }}",
+                name(identifier),
+                variants.iter().map(name).collect::<Vec<_>>().join(", ")
+            ),
+        },
+        Node::Return { value } => format!("return {}", expression(value, CONTROL, indent)),
+        Node::Delete { identifier, .. } => format!("del {}", name(identifier)),
+        Node::Global {
+            identifiers,
+            is_nonlocal,
+        } => format!(
+            "{} {}",
+            if *is_nonlocal { "nonlocal" } else { "global" },
+            identifiers.iter().map(name).collect::<Vec<_>>().join(", ")
+        ),
+        node => expression(node, CONTROL, indent),
+    }
+}
+
+fn expression(node: &Node, minimum: u8, indent: usize) -> String {
+    let source = match node {
+        Node::Atom { value } => literal(&value.kind),
+        Node::Access { identifier, .. } => name(identifier).to_string(),
+        Node::List { values } => format!("[{}]", list(values, indent)),
+        Node::Tuple { values } => match values.as_slice() {
+            [value] => format!("({},)", expression(value, COALESCE, indent)),
+            values => format!("({})", list(values, indent)),
+        },
+        Node::Spread { value, .. } => format!("*{}", expression(value, COALESCE, indent)),
+        Node::BinaryOp {
+            left,
+            operator,
+            right,
+        } => {
+            let precedence = precedence(node);
+            let (left_minimum, right_minimum) = match operator.kind {
+                // `2 ^ 3 ^ 2` is `2 ^ (3 ^ 2)`, and its left side an atom.
+                TokenKind::Pow => (PRIMARY, UNARY),
+                _ => (precedence, precedence + 1),
+            };
+            format!(
+                "{} {} {}",
+                expression(left, left_minimum, indent),
+                symbol(&operator.kind),
+                expression(right, right_minimum, indent)
+            )
+        }
+        Node::UnaryOp { operator, value } => match operator.kind {
+            TokenKind::Not => format!("not {}", expression(value, NOT, indent)),
+            _ => {
+                let value = expression(value, UNARY, indent);
+                // `- -1`, as `--` isn't two operators.
+                let separator = match value.starts_with(['-', '+']) {
+                    true => " ",
+                    false => "",
+                };
+                format!("{}{}{}", symbol(&operator.kind), separator, value)
+            }
+        },
+        Node::FuncCall {
+            identifier,
+            parameters,
+            keywords,
+            ..
+        } => format!(
+            "{}({})",
+            name(identifier),
+            call(parameters, keywords, indent)
+        ),
+        Node::MethodCall {
+            object,
+            method,
+            parameters,
+            keywords,
+        } => format!(
+            "{}.{}({})",
+            self::object(object, indent),
+            name(method),
+            call(parameters, keywords, indent)
+        ),
+        Node::Member { object, member } => {
+            format!("{}.{}", self::object(object, indent), name(member))
+        }
+        Node::Index {
+            identifier, index, ..
+        } => format!("{}[{}]", name(identifier), expression(index, SUM, indent)),
+        Node::Optional { value } => match value.as_ref() {
+            Node::FuncCall {
+                identifier,
+                parameters,
+                keywords,
+                ..
+            } => format!(
+                "{}?({})",
+                name(identifier),
+                call(parameters, keywords, indent)
+            ),
+            Node::Index {
+                identifier, index, ..
+            } => format!("{}?[{}]", name(identifier), expression(index, SUM, indent)),
+            value => expression(value, minimum, indent),
+        },
+        Node::Comprehension {
+            value,
+            identifier,
+            iterable,
+            condition,
+            ..
+        } => {
+            let condition = match condition {
+                Some(condition) => format!(" if {}", expression(condition, COALESCE, indent)),
+                None => String::new(),
+            };
+            format!(
+                "[{} for {} in {}{}]",
+                expression(value, COALESCE, indent),
+                name(identifier),
+                expression(iterable, COALESCE, indent),
+                condition
+            )
+        }
+        Node::If { .. } => branches(node, indent),
+        // A block the optimizer left in place of the branch taken. A bare
+        // block doesn't parse, so it keeps a branch that's always taken.
+        Node::Program { .. } => format!("if True {}", self::block(node, indent)),
+        Node::While { condition, block } => format!(
+            "while {} {}",
+            expression(condition, COALESCE, indent),
+            self::block(block, indent)
+        ),
+        Node::DoWhile { block, condition } => format!(
+            "do {} while {}",
+            self::block(block, indent),
+            expression(condition, COALESCE, indent)
+        ),
+        Node::For {
+            identifier,
+            iterable,
+            block,
+            ..
+        } => {
+            let iterable = expression(iterable, COALESCE, indent);
+            match destructured(identifier, block) {
+                Some((pattern, statements)) => format!(
+                    "for {} in {} {}",
+                    pattern,
+                    iterable,
+                    self::statements(statements.iter().map(AsRef::as_ref), indent)
+                ),
+                None => format!(
+                    "for {} in {} {}",
+                    name(identifier),
+                    iterable,
+                    self::block(block, indent)
+                ),
+            }
+        }
+        Node::Match { value, arms } => {
+            let arms: Vec<String> = arms
+                .iter()
+                .map(|arm| {
+                    let guard = match &arm.guard {
+                        Some(guard) => format!(" if {}", expression(guard, COALESCE, indent + 1)),
+                        None => String::new(),
+                    };
+                    format!(
+                        "case {}{} => {}",
+                        arm.pattern,
+                        guard,
+                        block(&arm.block, indent + 1)
+                    )
+                })
+                .collect();
+            format!(
+                "match {} {}",
+                expression(value, COALESCE, indent),
+                lines(&arms, indent)
+            )
+        }
+        node => statement(node, indent),
+    };
+    match precedence(node) < minimum {
+        true => format!("({})", source),
+        false => source,
+    }
+}
+
+fn precedence(node: &Node) -> u8 {
+    match node {
+        Node::BinaryOp { operator, .. } => match operator.kind {
+            TokenKind::Coalesce => COALESCE,
+            TokenKind::Or => OR,
+            TokenKind::And => AND,
+            TokenKind::Add | TokenKind::Sub => SUM,
+            TokenKind::Mul | TokenKind::Div | TokenKind::Mod => PRODUCT,
+            TokenKind::Pow => POWER,
+            _ => COMPARISON,
+        },
+        Node::UnaryOp { operator, .. } if operator.kind == TokenKind::Not => NOT,
+        Node::UnaryOp { .. } => UNARY,
+        // Folded literals can be negative, and print with their sign.
+        Node::Atom { value } => match value.kind {
+            TokenKind::Integer(integer) if integer < 0 => UNARY,
+            TokenKind::Float(float) if float.is_sign_negative() => UNARY,
+            _ => PRIMARY,
+        },
+        Node::Spread { .. } => COALESCE,
+        Node::Optional { value } => precedence(value),
+        Node::Access { .. }
+        | Node::List { .. }
+        | Node::Tuple { .. }
+        | Node::FuncCall { .. }
+        | Node::MethodCall { .. }
+        | Node::Member { .. }
+        | Node::Index { .. }
+        | Node::Comprehension { .. } => PRIMARY,
+        _ => CONTROL,
+    }
+}
+
+// What's left of a `.`, where a number would take the dot for a decimal
+// point.
+fn object(node: &Node, indent: usize) -> String {
+    match node {
+        Node::Atom { value }
+            if matches!(value.kind, TokenKind::Integer(_) | TokenKind::Float(_)) =>
+        {
+            format!("({})", expression(node, PRIMARY, indent))
+        }
+        node => expression(node, PRIMARY, indent),
+    }
+}
+
+// An `if` followed by its `elif` and `else` branches.
+fn branches(node: &Node, indent: usize) -> String {
+    let mut source = String::new();
+    let mut branch = Some(node);
+    while let Some(node) = branch {
+        branch = None;
+        match node {
+            Node::If {
+                condition,
+                block,
+                else_block,
+            } => {
+                let keyword = match source.is_empty() {
+                    true => "if",
+                    false => " elif",
+                };
+                source.push_str(&format!(
+                    "{} {} {}",
+                    keyword,
+                    expression(condition, COALESCE, indent),
+                    self::block(block, indent)
+                ));
+                branch = else_block.as_deref();
+            }
+            node => source.push_str(&format!(" else {}", block(node, indent))),
+        }
+    }
+    source
+}
+
+// The pattern of `for a, b in pairs`, which the parser turns into a loop over
+// a variable named after it and a destructuring at the start of the block.
+fn destructured<'a>(identifier: &Token, block: &'a Node) -> Option<(String, &'a [Box<Node>])> {
+    let Node::Program { statements } = block else {
+        return None;
+    };
+    let (first, rest) = statements.split_first()?;
+    let Node::Destructuring {
+        pattern,
+        value,
+        is_declaration: true,
+        ..
+    } = first.as_ref()
+    else {
+        return None;
+    };
+    let pattern = pattern.to_string();
+    match value.as_ref() {
+        Node::Access {
+            identifier: variable,
+            ..
+        } if name(variable) == name(identifier) && pattern == name(identifier) => {
+            Some((pattern, rest))
+        }
+        _ => None,
+    }
+}
+
+fn function(identifier: &Token, arguments: &[Argument], body: &Node, indent: usize) -> String {
+    format!(
+        "{}({}) => {}",
+        name(identifier),
+        self::arguments(arguments, indent),
+        block(body, indent)
+    )
+}
+
+fn arguments(arguments: &[Argument], indent: usize) -> String {
+    arguments
+        .iter()
+        .map(|argument| match &argument.default {
+            Some(default) => format!("{}={}", argument, expression(default, COALESCE, indent)),
+            None => argument.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn call(parameters: &[Box<Node>], keywords: &[(Token, Box<Node>)], indent: usize) -> String {
+    parameters
+        .iter()
+        .map(|parameter| expression(parameter, COALESCE, indent))
+        .chain(keywords.iter().map(|(keyword, value)| {
+            format!("{}={}", name(keyword), expression(value, COALESCE, indent))
+        }))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn list(values: &[Box<Node>], indent: usize) -> String {
+    values
+        .iter()
+        .map(|value| expression(value, COALESCE, indent))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+// A block's statements, or a node standing in for a block (as an optimizer
+// may leave) as its only statement.
+fn block(node: &Node, indent: usize) -> String {
+    match node {
+        Node::Program { statements } => {
+            self::statements(statements.iter().map(AsRef::as_ref), indent)
+        }
+        node => self::statements([node].into_iter(), indent),
+    }
+}
+
+fn statements<'a>(statements: impl Iterator<Item = &'a Node>, indent: usize) -> String {
+    let statements: Vec<String> = statements
+        .map(|statement| self::statement(statement, indent + 1))
+        .collect();
+    lines(&statements, indent)
+}
+
+// Braces around one line per item, indented a level deeper than `indent`.
+fn lines(items: &[String], indent: usize) -> String {
+    if items.is_empty() {
+        return String::from("{}");
+    }
+    let inner = INDENT.repeat(indent + 1);
+    let items: Vec<String> = items
+        .iter()
+        .map(|item| format!("{}{}", inner, item))
+        .collect();
+    format!("{{\n{}\n{}}}", items.join("\n"), INDENT.repeat(indent))
+}
+
+// Literals as they're written, escaping what the tokenizer unescapes.
+pub(crate) fn literal(kind: &TokenKind) -> String {
+    match kind {
+        TokenKind::Integer(integer) => integer.to_string(),
+        TokenKind::Float(float) => format!("{:?}", float),
+        TokenKind::Boolean(true) => String::from("True"),
+        TokenKind::Boolean(false) => String::from("False"),
+        TokenKind::None => String::from("None"),
+        TokenKind::String(string) => {
+            format!("\"{}\"", string.chars().map(escape).collect::<String>())
+        }
+        TokenKind::Character(c) => match c {
+            '\'' => String::from("'\\''"),
+            c => format!("'{}'", escape(*c)),
+        },
+        TokenKind::Bytes(bytes) => format!(
+            "b\"{}\"",
+            bytes
+                .iter()
+                .map(|&byte| match byte {
+                    b'\\' | b'"' | b'\n' | b'\t' | b'\r' | b'\0' => escape(byte as char),
+                    b' '..=b'~' => (byte as char).to_string(),
+                    byte => format!("\\x{:02x}", byte),
+                })
+                .collect::<String>()
+        ),
+        kind => format!("{:?}", kind),
+    }
+}
+
+fn escape(c: char) -> String {
+    match c {
+        '\\' => String::from("\\\\"),
+        '"' => String::from("\\\""),
+        '\n' => String::from("\\n"),
+        '\t' => String::from("\\t"),
+        '\r' => String::from("\\r"),
+        '\0' => String::from("\\0"),
+        c => c.to_string(),
+    }
+}
+
+fn symbol(kind: &TokenKind) -> &'static str {
+    match kind {
+        TokenKind::Add => "+",
+        TokenKind::Sub => "-",
+        TokenKind::Mul => "*",
+        TokenKind::Div => "/",
+        TokenKind::Mod => "%",
+        TokenKind::Pow => "^",
+        TokenKind::Equals => "==",
+        TokenKind::NotEquals => "!=",
+        TokenKind::Greater => ">",
+        TokenKind::GreaterEq => ">=",
+        TokenKind::LessThan => "<",
+        TokenKind::LessThanEq => "<=",
+        TokenKind::In => "in",
+        TokenKind::Coalesce => "??",
+        TokenKind::And => "and",
+        TokenKind::Or => "or",
+        TokenKind::Not => "not",
+        _ => "?",
+    }
+}
+
+fn declaration(is_declaration: bool) -> &'static str {
+    match is_declaration {
+        true => "let ",
+        false => "",
+    }
+}
+
+fn name(identifier: &Token) -> &str {
+    match &identifier.kind {
+        TokenKind::Identifier(name) => name,
+        _ => "",
+    }
+}
+
+fn join(patterns: &[Pattern]) -> String {
+    patterns
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
@@ -0,0 +1,269 @@
+use crate::parser::node::Node;
+
+// Walks a tree without changing it. `visit` is called for every node, an
+// implementation matches the variants it cares about and calls
+// `visit_children` to keep going into the others (and into its own, if it
+// should).
+pub trait NodeVisitor {
+    fn visit(&mut self, node: &Node) {
+        visit_children(self, node);
+    }
+}
+
+// Like `NodeVisitor`, but each node can be rewritten in place, possibly into
+// another variant, as the optimizer does when it folds `1 + 2` into `3`.
+pub trait NodeFolder {
+    fn fold(&mut self, node: &mut Node) {
+        fold_children(self, node);
+    }
+}
+
+// Visits the direct children of a node in the order they're evaluated:
+// argument and field defaults, call parameters before keywords, and a match
+// arm's guard before its block.
+pub fn visit_children<V: NodeVisitor + ?Sized>(visitor: &mut V, node: &Node) {
+    match node {
+        Node::Atom { .. }
+        | Node::Access { .. }
+        | Node::Delete { .. }
+        | Node::Global { .. }
+        | Node::EnumDeclaration { .. } => {}
+        Node::List { values } | Node::Tuple { values } => visit_all(visitor, values),
+        Node::Program { statements } => visit_all(visitor, statements),
+        Node::Impl { methods, .. } => visit_all(visitor, methods),
+        Node::Spread { value, .. }
+        | Node::UnaryOp { value, .. }
+        | Node::Return { value }
+        | Node::Optional { value }
+        | Node::Assignment { value, .. }
+        | Node::Destructuring { value, .. } => visitor.visit(value),
+        Node::Member { object, .. } => visitor.visit(object),
+        Node::Index { index, .. } => visitor.visit(index),
+        Node::BinaryOp { left, right, .. } => {
+            visitor.visit(left);
+            visitor.visit(right);
+        }
+        Node::MemberAssignment { object, value, .. } => {
+            visitor.visit(object);
+            visitor.visit(value);
+        }
+        Node::ListAssignment { index, value, .. } => {
+            visitor.visit(index);
+            visitor.visit(value);
+        }
+        Node::FuncDeclearion {
+            arguments, body, ..
+        } => {
+            for default in arguments
+                .iter()
+                .filter_map(|argument| argument.default.as_ref())
+            {
+                visitor.visit(default);
+            }
+            visitor.visit(body);
+        }
+        Node::StructDeclaration { fields, .. } => {
+            for default in fields.iter().filter_map(|field| field.default.as_ref()) {
+                visitor.visit(default);
+            }
+        }
+        Node::FuncCall {
+            parameters,
+            keywords,
+            ..
+        } => {
+            visit_all(visitor, parameters);
+            for (_, value) in keywords {
+                visitor.visit(value);
+            }
+        }
+        Node::MethodCall {
+            object,
+            parameters,
+            keywords,
+            ..
+        } => {
+            visitor.visit(object);
+            visit_all(visitor, parameters);
+            for (_, value) in keywords {
+                visitor.visit(value);
+            }
+        }
+        Node::If {
+            condition,
+            block,
+            else_block,
+        } => {
+            visitor.visit(condition);
+            visitor.visit(block);
+            if let Some(else_block) = else_block {
+                visitor.visit(else_block);
+            }
+        }
+        Node::While { condition, block } => {
+            visitor.visit(condition);
+            visitor.visit(block);
+        }
+        Node::DoWhile { block, condition } => {
+            visitor.visit(block);
+            visitor.visit(condition);
+        }
+        Node::Comprehension {
+            value,
+            iterable,
+            condition,
+            ..
+        } => {
+            visitor.visit(iterable);
+            if let Some(condition) = condition {
+                visitor.visit(condition);
+            }
+            visitor.visit(value);
+        }
+        Node::For {
+            iterable, block, ..
+        } => {
+            visitor.visit(iterable);
+            visitor.visit(block);
+        }
+        Node::Match { value, arms } => {
+            visitor.visit(value);
+            for arm in arms {
+                if let Some(guard) = &arm.guard {
+                    visitor.visit(guard);
+                }
+                visitor.visit(&arm.block);
+            }
+        }
+    }
+}
+
+// The same children as `visit_children`, in the same order.
+pub fn fold_children<F: NodeFolder + ?Sized>(folder: &mut F, node: &mut Node) {
+    match node {
+        Node::Atom { .. }
+        | Node::Access { .. }
+        | Node::Delete { .. }
+        | Node::Global { .. }
+        | Node::EnumDeclaration { .. } => {}
+        Node::List { values } | Node::Tuple { values } => fold_all(folder, values),
+        Node::Program { statements } => fold_all(folder, statements),
+        Node::Impl { methods, .. } => fold_all(folder, methods),
+        Node::Spread { value, .. }
+        | Node::UnaryOp { value, .. }
+        | Node::Return { value }
+        | Node::Optional { value }
+        | Node::Assignment { value, .. }
+        | Node::Destructuring { value, .. } => folder.fold(value),
+        Node::Member { object, .. } => folder.fold(object),
+        Node::Index { index, .. } => folder.fold(index),
+        Node::BinaryOp { left, right, .. } => {
+            folder.fold(left);
+            folder.fold(right);
+        }
+        Node::MemberAssignment { object, value, .. } => {
+            folder.fold(object);
+            folder.fold(value);
+        }
+        Node::ListAssignment { index, value, .. } => {
+            folder.fold(index);
+            folder.fold(value);
+        }
+        Node::FuncDeclearion {
+            arguments, body, ..
+        } => {
+            for default in arguments
+                .iter_mut()
+                .filter_map(|argument| argument.default.as_mut())
+            {
+                folder.fold(default);
+            }
+            folder.fold(body);
+        }
+        Node::StructDeclaration { fields, .. } => {
+            for default in fields.iter_mut().filter_map(|field| field.default.as_mut()) {
+                folder.fold(default);
+            }
+        }
+        Node::FuncCall {
+            parameters,
+            keywords,
+            ..
+        } => {
+            fold_all(folder, parameters);
+            for (_, value) in keywords {
+                folder.fold(value);
+            }
+        }
+        Node::MethodCall {
+            object,
+            parameters,
+            keywords,
+            ..
+        } => {
+            folder.fold(object);
+            fold_all(folder, parameters);
+            for (_, value) in keywords {
+                folder.fold(value);
+            }
+        }
+        Node::If {
+            condition,
+            block,
+            else_block,
+        } => {
+            folder.fold(condition);
+            folder.fold(block);
+            if let Some(else_block) = else_block {
+                folder.fold(else_block);
+            }
+        }
+        Node::While { condition, block } => {
+            folder.fold(condition);
+            folder.fold(block);
+        }
+        Node::DoWhile { block, condition } => {
+            folder.fold(block);
+            folder.fold(condition);
+        }
+        Node::Comprehension {
+            value,
+            iterable,
+            condition,
+            ..
+        } => {
+            folder.fold(iterable);
+            if let Some(condition) = condition {
+                folder.fold(condition);
+            }
+            folder.fold(value);
+        }
+        Node::For {
+            iterable, block, ..
+        } => {
+            folder.fold(iterable);
+            folder.fold(block);
+        }
+        Node::Match { value, arms } => {
+            folder.fold(value);
+            for arm in arms {
+                if let Some(guard) = &mut arm.guard {
+                    folder.fold(guard);
+                }
+                folder.fold(&mut arm.block);
+            }
+        }
+    }
+}
+
+fn visit_all<V: NodeVisitor + ?Sized>(visitor: &mut V, nodes: &[Box<Node>]) {
+    for node in nodes {
+        visitor.visit(node);
+    }
+}
+
+fn fold_all<F: NodeFolder + ?Sized>(folder: &mut F, nodes: &mut [Box<Node>]) {
+    for node in nodes {
+        folder.fold(node);
+    }
+}
@@ -0,0 +1,140 @@
+//! Projects: a directory with a `mono.toml` manifest such as
+//!
+//! ```toml
+//! name = "game"
+//! entry = "src/main.mono"
+//! paths = ["lib", "vendor"]
+//! ```
+//!
+//! `entry` defaults to `main.mono`. Paths are relative to the manifest's
+//! directory, and `run_file` looks for relative paths in that directory and
+//! then in each of `paths`, so scripts find each other wherever the project
+//! is run from.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+pub const MANIFEST: &str = "mono.toml";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Manifest {
+    pub name: String,
+    pub entry: PathBuf,
+    pub paths: Vec<PathBuf>,
+}
+
+impl Manifest {
+    // Only the TOML the manifest needs: `key = "string"` and
+    // `key = ["string", ...]` lines, blank lines and `#` comments.
+    pub fn parse(source: &str) -> io::Result<Self> {
+        let mut name = None;
+        let mut entry = PathBuf::from("main.mono");
+        let mut paths = Vec::new();
+        for (index, line) in source.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let invalid = |message: &str| invalid(format!("line {}: {}", index + 1, message));
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| invalid("expected `key = value`"))?;
+            let value = value.trim();
+            match key.trim() {
+                "name" => name = Some(string(value).ok_or_else(|| invalid("expected a string"))?),
+                "entry" => {
+                    entry =
+                        PathBuf::from(string(value).ok_or_else(|| invalid("expected a string"))?)
+                }
+                "paths" => {
+                    paths = strings(value)
+                        .ok_or_else(|| invalid("expected a list of strings"))?
+                        .into_iter()
+                        .map(PathBuf::from)
+                        .collect()
+                }
+                key => return Err(invalid(&format!("unknown key `{}`", key))),
+            }
+        }
+        Ok(Self {
+            name: name.ok_or_else(|| invalid(String::from("missing `name`")))?,
+            entry,
+            paths,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Project {
+    // The directory holding the manifest.
+    pub root: PathBuf,
+    pub manifest: Manifest,
+}
+
+impl Project {
+    // The project `directory` is in, found by looking for a manifest in it
+    // and then in each of its parents.
+    pub fn find(directory: &Path) -> io::Result<Self> {
+        let directory = directory.canonicalize()?;
+        for root in directory.ancestors() {
+            let path = root.join(MANIFEST);
+            if path.is_file() {
+                let manifest = Manifest::parse(&fs::read_to_string(&path)?)
+                    .map_err(|error| invalid(format!("{}: {}", path.display(), error)))?;
+                return Ok(Self {
+                    root: root.to_path_buf(),
+                    manifest,
+                });
+            }
+        }
+        Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("No {} in {} or its parents", MANIFEST, directory.display()),
+        ))
+    }
+
+    pub fn entry(&self) -> PathBuf {
+        self.root.join(&self.manifest.entry)
+    }
+
+    // Where `run_file` looks for relative paths, the root first.
+    pub fn paths(&self) -> Vec<PathBuf> {
+        let paths = self.manifest.paths.iter().map(|path| self.root.join(path));
+        std::iter::once(self.root.clone()).chain(paths).collect()
+    }
+}
+
+// Where `run_file` reads a path from: as it is when it's absolute or found
+// from the working directory, otherwise in the first of `paths` holding it.
+pub fn resolve(path: &str, paths: &[PathBuf]) -> PathBuf {
+    let path = Path::new(path);
+    if path.is_absolute() || path.exists() {
+        return path.to_path_buf();
+    }
+    paths
+        .iter()
+        .map(|directory| directory.join(path))
+        .find(|candidate| candidate.exists())
+        .unwrap_or_else(|| path.to_path_buf())
+}
+
+fn invalid(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+fn string(value: &str) -> Option<String> {
+    let inner = value.strip_prefix('"')?.strip_suffix('"')?;
+    (!inner.contains('"')).then(|| inner.to_string())
+}
+
+fn strings(value: &str) -> Option<Vec<String>> {
+    let inner = value.strip_prefix('[')?.strip_suffix(']')?.trim();
+    inner
+        .split(',')
+        .map(str::trim)
+        // A trailing comma leaves an empty item.
+        .filter(|item| !item.is_empty())
+        .map(string)
+        .collect()
+}
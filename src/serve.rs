@@ -0,0 +1,213 @@
+use crate::evaluator::builtins::{self, intrinsic, namespace};
+use crate::evaluator::limits::Limits;
+use crate::evaluator::symbol_table::SymbolTable;
+use crate::evaluator::value::Value;
+use crate::evaluator::Evaluator;
+use crate::models::diagnostic::string;
+use crate::models::error::MonoError;
+use crate::parser::Parser;
+use crate::tokenizer::Tokenizer;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::thread;
+use std::time::Duration;
+
+const LIMITS: Limits = Limits {
+    steps: Some(1_000_000),
+    depth: Some(256),
+    length: Some(1_000_000),
+};
+const MAXIMUM_BODY: usize = 64 * 1024;
+const TIMEOUT: Duration = Duration::from_secs(5);
+// The parser recurses once per nesting level, a deeply nested program must
+// not overflow the stack as that would abort the whole server.
+const STACK_SIZE: usize = 256 * 1024 * 1024;
+
+// A playground backend: `POST /eval` with a program as the body evaluates it
+// in a fresh, limited evaluator and responds with a JSON object holding what
+// it printed and its value or error. Each request runs on its own thread.
+pub fn serve(listener: TcpListener) -> io::Result<()> {
+    for stream in listener.incoming().flatten() {
+        thread::Builder::new()
+            .stack_size(STACK_SIZE)
+            .spawn(move || handle(stream))?;
+    }
+    Ok(())
+}
+
+fn handle(mut stream: TcpStream) -> io::Result<()> {
+    stream.set_read_timeout(Some(TIMEOUT))?;
+    let mut reader = BufReader::new(stream.try_clone()?);
+
+    let mut request = String::new();
+    reader.read_line(&mut request)?;
+    let mut parts = request.split_whitespace();
+    let (method, path) = (parts.next().unwrap_or(""), parts.next().unwrap_or(""));
+
+    let mut length = 0;
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header)? == 0 || header.trim().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                length = value.trim().parse().unwrap_or(0);
+            }
+        }
+    }
+
+    let (status, body) = match (method, path) {
+        ("OPTIONS", _) => ("204 No Content", String::new()),
+        ("POST", "/eval") if length > MAXIMUM_BODY => {
+            ("413 Payload Too Large", error("The program is too long."))
+        }
+        ("POST", "/eval") => {
+            let mut code = vec![0; length];
+            reader.read_exact(&mut code)?;
+            match String::from_utf8(code) {
+                Ok(code) => ("200 OK", evaluate(&code)),
+                Err(_) => ("400 Bad Request", error("The program isn't valid UTF-8.")),
+            }
+        }
+        (_, "/eval") => ("405 Method Not Allowed", error("Use POST.")),
+        _ => ("404 Not Found", error("Not found.")),
+    };
+
+    write!(
+        stream,
+        "HTTP/1.1 {status}\r\n\
+         Content-Type: application/json\r\n\
+         Content-Length: {}\r\n\
+         Access-Control-Allow-Origin: *\r\n\
+         Access-Control-Allow-Methods: POST, OPTIONS\r\n\
+         Access-Control-Allow-Headers: Content-Type\r\n\
+         Connection: close\r\n\r\n{body}",
+        body.len()
+    )
+}
+
+fn evaluate(code: &str) -> String {
+    let (result, printed) = sandbox().capture(|evaluator| run(code, evaluator));
+    let (value, error) = match result {
+        Ok(Value::None) => (None, None),
+        Ok(value) => (Some(value.to_string()), None),
+        Err(error) => (None, Some(error)),
+    };
+    format!(
+        "{{\"output\": {}, \"value\": {}, \"error\": {}}}",
+        string(&printed),
+        value.as_deref().map_or("null".to_string(), string),
+        error.map_or("null".to_string(), |error| format!(
+            "{{\"kind\": {}, \"message\": {}}}",
+            string(error.kind()),
+            string(&error.to_string())
+        ))
+    )
+}
+
+fn run(code: &str, evaluator: &mut Evaluator) -> Result<Value, MonoError> {
+    let program = Parser::new(Tokenizer::new(code.chars())).parse()?;
+    evaluator.evaluate(&program)
+}
+
+// The builtins programs get. Programs can't read the server's input or
+// files, run code from strings, reach the network, run other programs, start
+// threads, hold a thread by sleeping or waiting on timers, or stop it. Any
+// builtin added later stays out until it's listed here, and `io` is replaced
+// by one that only prints.
+const ALLOWED: [&str; 55] = [
+    "println",
+    "print",
+    "dir",
+    "globals",
+    "params",
+    "arity",
+    "help",
+    "range",
+    "round",
+    "divmod",
+    "hex",
+    "bin",
+    "bool",
+    "is_none",
+    "len",
+    "enumerate",
+    "zip",
+    "sort",
+    "sort_by",
+    "min",
+    "max",
+    "sum",
+    "any",
+    "all",
+    "slice",
+    "string_builder",
+    "append",
+    "build",
+    "hex_encode",
+    "hex_decode",
+    "join_path",
+    "basename",
+    "dirname",
+    "time",
+    "clock",
+    "re_match",
+    "re_find_all",
+    "re_replace",
+    "re_split",
+    "now",
+    "date_format",
+    "date_parse",
+    "days",
+    "format",
+    "math",
+    "integer",
+    "float",
+    "try_integer",
+    "try_float",
+    "boolean",
+    "string",
+    "re",
+    "date",
+    "bytes",
+    "list",
+];
+
+fn sandbox() -> Evaluator {
+    let mut builtins = SymbolTable::new();
+    builtins.add_builtins();
+    let mut symbol_table = SymbolTable::new();
+    for name in ALLOWED {
+        if let Some(builtin) = builtins.remove_builtin(name) {
+            symbol_table.insert_builtin((name.to_string(), builtin));
+        }
+    }
+    symbol_table.insert_builtin(namespace(
+        "io",
+        vec![
+            intrinsic(
+                "println",
+                vec!["*values"],
+                "Prints the values separated by spaces, followed by a new line.",
+                builtins::println,
+            ),
+            intrinsic(
+                "print",
+                vec!["*values"],
+                "Prints the values separated by spaces.",
+                builtins::print,
+            ),
+        ],
+    ));
+    let mut evaluator = Evaluator::from(symbol_table);
+    evaluator.set_limits(LIMITS);
+    evaluator
+}
+
+fn error(message: &str) -> String {
+    format!(
+        "{{\"error\": {{\"kind\": \"RequestError\", \"message\": {}}}}}",
+        string(message)
+    )
+}
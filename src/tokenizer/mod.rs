@@ -1,45 +1,79 @@
+pub mod reader;
+pub mod symbol;
 pub mod token;
 
 use crate::models::error::{MonoError, Syntax};
 use crate::models::position::Position;
+use crate::tokenizer::reader::Utf8Chars;
+use crate::tokenizer::symbol::Interner;
 use crate::tokenizer::token::{Token, TokenKind};
+use std::io::{BufReader, Read};
 use std::iter::Peekable;
+use std::num::IntErrorKind;
+use unicode_ident::{is_xid_continue, is_xid_start};
 
+// A token of the character just consumed.
 #[macro_export]
 macro_rules! single {
-    ($Position:expr, $TokenKind:expr) => {
-        Some(Ok(Token::new($Position.clone(), None, $TokenKind)))
+    ($tokenizer:expr, $TokenKind:expr) => {
+        Some(Ok(Token::new(
+            $tokenizer.get_position(),
+            $tokenizer.end(),
+            $TokenKind,
+        )))
     };
 }
 
+// A token from `start` up to and including the character just consumed.
 #[macro_export]
 macro_rules! multi {
-    ($start:expr, $end:expr, $TokenKind:expr) => {
-        Some(Ok(Token::new($start, Some($end.clone()), $TokenKind)))
+    ($tokenizer:expr, $start:expr, $TokenKind:expr) => {
+        Some(Ok(Token::new($start, $tokenizer.end(), $TokenKind)))
     };
 }
 
-#[macro_export]
-macro_rules! raw {
-    ($start:expr, $end:expr, $TokenKind:expr) => {
-        Some(Ok(Token::new($start, $end, $TokenKind)))
-    };
-}
+pub type TokenizerItem = Option<Result<Token, MonoError>>;
 
-pub type TokenizerItem = Option<Result<Token, Box<dyn MonoError>>>;
+// How many columns apart tab stops are in positions.
+pub const TAB_WIDTH: usize = 4;
 
 pub struct Tokenizer<Chars: Iterator<Item = char>> {
     chars: Chars,
+    interner: Interner,
     overhead: TokenizerItem,
     position: Position,
+    tab_width: usize,
+    // Whether the last token was a `.`, after which keywords are plain
+    // member names, as in `re.match`.
+    after_dot: bool,
+    // Whether the last token ended an operand, after which `.5` is a member
+    // access rather than a float.
+    after_operand: bool,
+}
+
+// Tokenizes a file or a pipe as it's read, rather than reading it into a
+// `String` first.
+impl<R: Read> Tokenizer<Peekable<Utf8Chars<BufReader<R>>>> {
+    pub fn from_reader(reader: R) -> Self {
+        Self::new(Utf8Chars::new(BufReader::new(reader)))
+    }
 }
 
 impl<Chars: Iterator<Item = char>> Tokenizer<Peekable<Chars>> {
     pub fn new(chars: Chars) -> Self {
+        Self::with_tab_width(chars, TAB_WIDTH)
+    }
+
+    // A tab width of 1 counts a tab as a single column, as the LSP does.
+    pub fn with_tab_width(chars: Chars, tab_width: usize) -> Self {
         let mut tokenizer = Self {
             chars: chars.peekable(),
+            interner: Interner::new(),
             overhead: None,
             position: Position::new(1, 0),
+            tab_width: tab_width.max(1),
+            after_dot: false,
+            after_operand: false,
         };
         tokenizer.next();
         tokenizer
@@ -53,48 +87,96 @@ impl<Chars: Iterator<Item = char>> Tokenizer<Peekable<Chars>> {
         self.position.clone()
     }
 
-    fn _next(&mut self) -> TokenizerItem {
-        self.position.next();
+    // Tokens span `[start, end)`, `position` being the column of the last
+    // character consumed, so the end is the column after it.
+    fn end(&self) -> Position {
+        let mut end = self.position.clone();
+        end.next();
+        end
+    }
 
-        if let Some(c) = self.chars.next() {
-            match c {
-                ' ' => self._next(),
+    // Whitespace is skipped in a loop rather than by recursing, so no run of
+    // it can overflow the stack.
+    fn _next(&mut self) -> TokenizerItem {
+        let mut continued = false;
+        loop {
+            self.position.next();
+            let Some(c) = self.chars.next() else {
+                // A continuation ending the input is unfinished, as the REPL
+                // reads more after it.
+                return match continued {
+                    true => Syntax::UnexpectedEOF.into(),
+                    false => None,
+                };
+            };
+            return match c {
+                ' ' => continue,
+                '\t' => {
+                    self.position.tab(self.tab_width);
+                    continue;
+                }
+                // `\r\n` line endings end the line at the `\n`.
+                '\r' if self.chars.peek() == Some(&'\n') => continue,
                 '#' => self.next_comment(),
-                '+' => single!(self.position, TokenKind::Add),
-                '*' => single!(self.position, TokenKind::Mul),
-                '/' => single!(self.position, TokenKind::Div),
-                '%' => single!(self.position, TokenKind::Mod),
-                '^' => single!(self.position, TokenKind::Pow),
-                '(' => single!(self.position, TokenKind::LeftParen),
-                ')' => single!(self.position, TokenKind::RightParen),
-                '{' => single!(self.position, TokenKind::LeftCurly),
-                '}' => single!(self.position, TokenKind::RightCurly),
-                '[' => single!(self.position, TokenKind::LeftBracket),
-                ']' => single!(self.position, TokenKind::RightBracket),
-                ',' => single!(self.position, TokenKind::Comma),
-                '\n' | ';' => self.next_line(),
+                '+' => single!(self, TokenKind::Add),
+                '*' => single!(self, TokenKind::Mul),
+                '/' => single!(self, TokenKind::Div),
+                '%' => single!(self, TokenKind::Mod),
+                '^' => single!(self, TokenKind::Pow),
+                '(' => single!(self, TokenKind::LeftParen),
+                ')' => single!(self, TokenKind::RightParen),
+                '{' => single!(self, TokenKind::LeftCurly),
+                '}' => single!(self, TokenKind::RightCurly),
+                '[' => single!(self, TokenKind::LeftBracket),
+                ']' => single!(self, TokenKind::RightBracket),
+                ',' => single!(self, TokenKind::Comma),
+                // A leading dot starts a float, as in `.5`.
+                '.' if !self.after_operand
+                    && self.chars.peek().is_some_and(char::is_ascii_digit) =>
+                {
+                    self.next_number('.')
+                }
+                '.' => single!(self, TokenKind::Dot),
+                '|' => single!(self, TokenKind::Pipe),
+                ':' => single!(self, TokenKind::Colon),
+                '\n' => self.next_line(),
+                ';' => single!(self, TokenKind::NewLine),
                 '-' => self.next_dash(),
                 '=' => self.next_equals(),
                 '!' => self.next_exclemation(),
                 '>' => self.next_greater(),
                 '<' => self.next_less_than(),
-                '"' => self.next_string(),
+                '?' => self.next_question(),
+                '"' => self.next_string(self.get_position(), false),
+                'r' if self.chars.peek() == Some(&'"') => {
+                    let start = self.get_position();
+                    self.chars.next();
+                    self.position.next();
+                    self.next_string(start, true)
+                }
+                'b' if self.chars.peek() == Some(&'"') => self.next_bytes(),
                 '\'' => self.next_char(),
-                c if c.is_ascii_alphabetic() || c == '_' => self.next_identifier(c),
-                c if c.is_numeric() => self.next_number(c),
+                '\\' => match self.next_continuation() {
+                    Ok(()) => {
+                        continued = true;
+                        continue;
+                    }
+                    Err(error) => Some(Err(error)),
+                },
+                c if is_xid_start(c) || c == '_' => self.next_identifier(c),
+                c if c.is_ascii_digit() => self.next_number(c),
                 c => Syntax::UnrecognizedChar {
                     position: self.position.clone(),
                     c,
                 }
                 .into(),
-            }
-        } else {
-            None
+            };
         }
     }
 
     fn next_comment(&mut self) -> TokenizerItem {
         while let Some(c) = self.chars.next() {
+            self.position.next();
             if c == '\n' {
                 return self.next_line();
             }
@@ -103,7 +185,7 @@ impl<Chars: Iterator<Item = char>> Tokenizer<Peekable<Chars>> {
     }
 
     fn next_line(&mut self) -> TokenizerItem {
-        let token = single!(self.position, TokenKind::NewLine);
+        let token = single!(self, TokenKind::NewLine);
         self.position.newline();
         token
     }
@@ -114,9 +196,9 @@ impl<Chars: Iterator<Item = char>> Tokenizer<Peekable<Chars>> {
                 let start = self.get_position();
                 self.position.next();
                 self.chars.next();
-                multi!(start, self.position, TokenKind::Arrow)
+                multi!(self, start, TokenKind::Arrow)
             }
-            _ => single!(self.position, TokenKind::Sub),
+            _ => single!(self, TokenKind::Sub),
         }
     }
 
@@ -126,33 +208,47 @@ impl<Chars: Iterator<Item = char>> Tokenizer<Peekable<Chars>> {
                 let start = self.get_position();
                 self.chars.next();
                 self.position.next();
-                multi!(start, self.position, TokenKind::DoubleArrow)
+                multi!(self, start, TokenKind::DoubleArrow)
             }
             Some('=') => {
                 let start = self.get_position();
                 self.chars.next();
                 self.position.next();
-                multi!(start, self.position, TokenKind::Equals)
+                multi!(self, start, TokenKind::Equals)
             }
-            _ => single!(self.position, TokenKind::Assignment),
+            _ => single!(self, TokenKind::Assignment),
         }
     }
 
-    fn next_exclemation(&mut self) -> TokenizerItem {
+    // A `\\` ending a line continues the statement on the next one.
+    fn next_continuation(&mut self) -> Result<(), MonoError> {
+        let position = self.get_position();
+        if self.chars.next_if_eq(&'\r').is_some() {
+            self.position.next();
+        }
         match self.chars.next() {
+            Some('\n') => {
+                self.position.newline();
+                Ok(())
+            }
+            None => Err(Syntax::UnexpectedEOF.into()),
+            Some(_) => Err(Syntax::UnexpectedChar { position, c: '\\' }.into()),
+        }
+    }
+
+    fn next_exclemation(&mut self) -> TokenizerItem {
+        match self.chars.peek() {
             Some('=') => {
                 let start = self.get_position();
+                self.chars.next();
                 self.position.next();
-                multi!(start, self.position, TokenKind::NotEquals)
+                multi!(self, start, TokenKind::NotEquals)
             }
-            _ => {
-                self.position.next();
-                Syntax::UnexpectedChar {
-                    position: self.get_position(),
-                    c: '!',
-                }
-                .into()
+            _ => Syntax::UnexpectedChar {
+                position: self.get_position(),
+                c: '!',
             }
+            .into(),
         }
     }
 
@@ -162,9 +258,9 @@ impl<Chars: Iterator<Item = char>> Tokenizer<Peekable<Chars>> {
                 let start = self.get_position();
                 self.chars.next();
                 self.position.next();
-                multi!(start, self.position, TokenKind::GreaterEq)
+                multi!(self, start, TokenKind::GreaterEq)
             }
-            _ => single!(self.position, TokenKind::Greater),
+            _ => single!(self, TokenKind::Greater),
         }
     }
 
@@ -174,9 +270,21 @@ impl<Chars: Iterator<Item = char>> Tokenizer<Peekable<Chars>> {
                 let start = self.get_position();
                 self.chars.next();
                 self.position.next();
-                multi!(start, self.position, TokenKind::LessThanEq)
+                multi!(self, start, TokenKind::LessThanEq)
             }
-            _ => single!(self.position, TokenKind::LessThan),
+            _ => single!(self, TokenKind::LessThan),
+        }
+    }
+
+    fn next_question(&mut self) -> TokenizerItem {
+        match self.chars.peek() {
+            Some('?') => {
+                let start = self.get_position();
+                self.chars.next();
+                self.position.next();
+                multi!(self, start, TokenKind::Coalesce)
+            }
+            _ => single!(self, TokenKind::Question),
         }
     }
 
@@ -184,46 +292,123 @@ impl<Chars: Iterator<Item = char>> Tokenizer<Peekable<Chars>> {
         let start = self.get_position();
         let mut identifier = String::from(c);
 
-        while let Some(c) = self.chars.peek() {
-            if c.is_ascii_alphabetic() || c.is_numeric() || c == &'_' {
-                self.position.next();
-                identifier.push(self.chars.next().unwrap());
-            } else {
-                break;
+        while let Some(c) = self.chars.next_if(|c| is_xid_continue(*c)) {
+            self.position.next();
+            identifier.push(c);
+        }
+
+        match TokenKind::from_keyword(&identifier).filter(|_| !self.after_dot) {
+            Some(token_kind) => multi!(self, start, token_kind),
+            _ => multi!(
+                self,
+                start,
+                TokenKind::Identifier(self.interner.intern(&identifier))
+            ),
+        }
+    }
+
+    // Scans `"..."`, triple quoted `"""..."""` strings that may span lines and
+    // their raw `r"..."` forms, which keep backslashes as they are.
+    fn next_string(&mut self, start: Position, is_raw: bool) -> TokenizerItem {
+        let mut is_triple = false;
+        if let Some('"') = self.chars.peek() {
+            self.chars.next();
+            self.position.next();
+            if self.chars.peek() != Some(&'"') {
+                return multi!(self, start, TokenKind::String(String::new()));
             }
+            self.chars.next();
+            self.position.next();
+            is_triple = true;
         }
 
-        let end = if &self.position == &start {
-            None
-        } else {
-            Some(self.position.clone())
-        };
+        let mut string = String::new();
+        let mut quotes = 0;
+        loop {
+            let Some(c) = self.chars.next() else {
+                return Syntax::UnclosedStringDelimeter { start }.into();
+            };
+            match c {
+                '\n' => self.position.newline(),
+                _ => self.position.next(),
+            }
 
-        match TokenKind::from_str(&identifier) {
-            Some(token_kind) => raw!(start, end, token_kind),
-            _ => raw!(start, end, TokenKind::Identifier(identifier)),
+            match c {
+                '"' if !is_triple => break,
+                '"' => {
+                    quotes += 1;
+                    if quotes == 3 {
+                        break;
+                    }
+                    continue;
+                }
+                '\\' if !is_raw => {
+                    string.push_str(&"\"".repeat(quotes));
+                    quotes = 0;
+                    let position = self.get_position();
+                    let found = self.chars.next();
+                    match found.and_then(escape) {
+                        Some(escaped) => {
+                            self.position.next();
+                            string.push(escaped);
+                        }
+                        None => return Syntax::InvalidEscape { position, found }.into(),
+                    }
+                    continue;
+                }
+                c => {
+                    string.push_str(&"\"".repeat(quotes));
+                    quotes = 0;
+                    string.push(c);
+                }
+            }
         }
+
+        multi!(self, start, TokenKind::String(string))
     }
 
-    fn next_string(&mut self) -> TokenizerItem {
+    // Scans a `b"..."` byte string. Besides the string escapes, `\xHH` is
+    // any byte in hex, other characters are stored as their UTF-8 bytes.
+    fn next_bytes(&mut self) -> TokenizerItem {
         let start = self.get_position();
-        let mut string = String::new();
-        while let Some(&c) = self.chars.peek() {
-            if c == '"' {
-                break;
-            }
+        self.chars.next();
+        self.position.next();
+
+        let mut bytes = Vec::new();
+        loop {
+            let Some(c) = self.chars.next() else {
+                return Syntax::UnclosedStringDelimeter { start }.into();
+            };
             self.position.next();
-            string.push(self.chars.next().unwrap());
+            match c {
+                '"' => break,
+                '\\' => {
+                    let position = self.get_position();
+                    let found = self.chars.next();
+                    self.position.next();
+                    let byte = match found {
+                        Some('x') => self.next_hex_byte(),
+                        found => found.and_then(escape).map(|escaped| escaped as u8),
+                    };
+                    match byte {
+                        Some(byte) => bytes.push(byte),
+                        None => return Syntax::InvalidEscape { position, found }.into(),
+                    }
+                }
+                c => bytes.extend_from_slice(c.encode_utf8(&mut [0; 4]).as_bytes()),
+            }
         }
 
-        match self.chars.next() {
-            Some(c) if c == '"' => multi!(start, self.position, TokenKind::String(string)),
-            Some(_) => unreachable!(),
-            None => {
-                self.position.next();
-                Syntax::UnclosedStringDelimeter { start }.into()
-            }
+        multi!(self, start, TokenKind::Bytes(bytes))
+    }
+
+    fn next_hex_byte(&mut self) -> Option<u8> {
+        let mut digits = String::new();
+        for _ in 0..2 {
+            digits.push(self.chars.next_if(char::is_ascii_hexdigit)?);
+            self.position.next();
         }
+        u8::from_str_radix(&digits, 16).ok()
     }
 
     fn next_char(&mut self) -> TokenizerItem {
@@ -231,6 +416,18 @@ impl<Chars: Iterator<Item = char>> Tokenizer<Peekable<Chars>> {
         let result: char;
 
         match self.chars.next() {
+            Some('\\') => {
+                self.position.next();
+                let position = self.get_position();
+                let found = self.chars.next();
+                match found.and_then(escape) {
+                    Some(escaped) => {
+                        result = escaped;
+                        self.position.next();
+                    }
+                    None => return Syntax::InvalidEscape { position, found }.into(),
+                }
+            }
             Some(c) => {
                 result = c;
                 self.position.next();
@@ -244,38 +441,94 @@ impl<Chars: Iterator<Item = char>> Tokenizer<Peekable<Chars>> {
             }
         }
 
-        return match self.chars.next() {
+        match self.chars.next() {
             Some('\'') => {
                 self.position.next();
-                multi!(start, self.position, TokenKind::Character(result))
+                multi!(self, start, TokenKind::Character(result))
             }
             Some(c) => {
                 self.position.next();
                 Syntax::UnclosedCharDelimeter {
                     start,
-                    end: self.get_position(),
+                    end: self.end(),
                     found: Some(c),
                 }
                 .into()
             }
             None => Syntax::UnclosedCharDelimeter {
                 start,
-                end: self.get_position(),
+                end: self.end(),
                 found: None,
             }
             .into(),
-        };
+        }
+    }
+
+    // Scans the digits of `0xFF`, `0o755` or `0b1010` after their prefix.
+    fn next_radix_number(&mut self, start: Position, radix: u32) -> TokenizerItem {
+        let mut digits = String::new();
+        while let Some(c) = self
+            .chars
+            .next_if(|c| c.is_ascii_alphanumeric() || *c == '_')
+        {
+            self.position.next();
+            if c != '_' {
+                digits.push(c);
+            }
+        }
+
+        let end = self.end();
+        match i32::from_str_radix(&digits, radix) {
+            Ok(int) => Some(Ok(Token::new(start, end, TokenKind::Integer(int)))),
+            Err(error) if matches!(error.kind(), IntErrorKind::PosOverflow) => {
+                Syntax::InvalidIntegerSize { start, end }.into()
+            }
+            Err(_) => Syntax::InvalidIntegerLiteral { start, end, radix }.into(),
+        }
+    }
+
+    // Scans the `-3` of `1e-3`, the exponent has to have at least one digit.
+    fn next_exponent(&mut self, number: &mut String) -> bool {
+        if let Some(sign) = self.chars.next_if(|c| matches!(c, '+' | '-')) {
+            self.position.next();
+            number.push(sign);
+        }
+
+        let mut has_digits = false;
+        while let Some(digit) = self.chars.next_if(char::is_ascii_digit) {
+            self.position.next();
+            number.push(digit);
+            has_digits = true;
+        }
+        has_digits
     }
 
     fn next_number(&mut self, c: char) -> TokenizerItem {
         let start = self.get_position();
+        let radix = match (c, self.chars.peek()) {
+            ('0', Some('x' | 'X')) => Some(16),
+            ('0', Some('o' | 'O')) => Some(8),
+            ('0', Some('b' | 'B')) => Some(2),
+            _ => None,
+        };
+        if let Some(radix) = radix {
+            self.chars.next();
+            self.position.next();
+            return self.next_radix_number(start, radix);
+        }
+
         let mut number = String::from(c);
-        let mut is_float = false;
+        let mut is_float = c == '.';
 
         loop {
             match self.chars.peek() {
-                Some(c) if c.is_numeric() => {
-                    number.push(self.chars.next().unwrap());
+                Some(&c) if c.is_ascii_digit() => {
+                    self.chars.next();
+                    number.push(c);
+                    self.position.next();
+                }
+                Some('_') => {
+                    self.chars.next();
                     self.position.next();
                 }
                 Some('.') => {
@@ -283,49 +536,96 @@ impl<Chars: Iterator<Item = char>> Tokenizer<Peekable<Chars>> {
                     if is_float {
                         return Syntax::MultipleFloatingPoints {
                             start,
-                            end: self.get_position(),
+                            end: self.end(),
+                        }
+                        .into();
+                    }
+                    self.chars.next();
+                    number.push('.');
+                    is_float = true;
+                    // `1.` would read as a float or a member access, the
+                    // fraction must be written out.
+                    if !self.chars.peek().is_some_and(char::is_ascii_digit) {
+                        return Syntax::MissingFraction {
+                            start,
+                            end: self.end(),
                         }
                         .into();
                     }
-                    number.push(self.chars.next().unwrap());
+                }
+                Some(&exponent @ ('e' | 'E')) => {
+                    self.position.next();
+                    self.chars.next();
+                    number.push(exponent);
                     is_float = true;
+                    if !self.next_exponent(&mut number) {
+                        return Syntax::InvalidExponent {
+                            start,
+                            end: self.end(),
+                        }
+                        .into();
+                    }
+                    break;
                 }
                 _ => break,
             }
         }
 
-        let end = if &self.position == &start {
-            None
-        } else {
-            Some(self.position.clone())
-        };
-
         if is_float {
             return match number.parse::<f32>() {
-                Ok(float) => raw!(start, end, TokenKind::Float(float)),
+                Ok(float) if float.is_finite() => multi!(self, start, TokenKind::Float(float)),
                 _ => Syntax::InvalidFloatSize {
-                    start: start,
-                    end: end.unwrap(),
+                    start,
+                    end: self.end(),
                 }
                 .into(),
             };
         }
         match number.parse::<i32>() {
-            Ok(int) => raw!(start, end, TokenKind::Integer(int)),
+            Ok(int) => multi!(self, start, TokenKind::Integer(int)),
             _ => Syntax::InvalidIntegerSize {
-                start: start,
-                end: end.unwrap(),
+                start,
+                end: self.end(),
             }
             .into(),
         }
     }
 }
 
+fn escape(c: char) -> Option<char> {
+    match c {
+        'n' => Some('\n'),
+        't' => Some('\t'),
+        'r' => Some('\r'),
+        '0' => Some('\0'),
+        '\\' | '"' | '\'' => Some(c),
+        _ => None,
+    }
+}
+
 impl<Chars: Iterator<Item = char>> Iterator for Tokenizer<Peekable<Chars>> {
-    type Item = Result<Token, Box<dyn MonoError>>;
+    type Item = Result<Token, MonoError>;
 
-    fn next(&mut self) -> Option<Result<Token, Box<dyn MonoError>>> {
+    fn next(&mut self) -> Option<Result<Token, MonoError>> {
         let current = self.overhead.take();
+        self.after_dot = matches!(&current, Some(Ok(token)) if token.kind == TokenKind::Dot);
+        self.after_operand = matches!(
+            &current,
+            Some(Ok(Token {
+                kind: TokenKind::Identifier(_)
+                    | TokenKind::None
+                    | TokenKind::Character(_)
+                    | TokenKind::String(_)
+                    | TokenKind::Bytes(_)
+                    | TokenKind::Integer(_)
+                    | TokenKind::Float(_)
+                    | TokenKind::Boolean(_)
+                    | TokenKind::RightParen
+                    | TokenKind::RightBracket
+                    | TokenKind::RightCurly,
+                ..
+            }))
+        );
         self.overhead = self._next();
         current
     }
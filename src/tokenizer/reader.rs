@@ -0,0 +1,61 @@
+use std::io::{BufRead, ErrorKind};
+
+// Decodes UTF-8 from a reader one character at a time, so the input never
+// has to be read into memory as a whole. Invalid sequences decode to U+FFFD,
+// which the tokenizer reports unless it's inside a string. A read error ends
+// the input.
+pub struct Utf8Chars<R: BufRead> {
+    reader: R,
+}
+
+impl<R: BufRead> Utf8Chars<R> {
+    pub fn new(reader: R) -> Self {
+        Self { reader }
+    }
+
+    fn peek_byte(&mut self) -> Option<u8> {
+        loop {
+            match self.reader.fill_buf() {
+                Ok(buffer) => return buffer.first().copied(),
+                Err(error) if error.kind() == ErrorKind::Interrupted => continue,
+                Err(_) => return None,
+            }
+        }
+    }
+
+    fn next_byte(&mut self) -> Option<u8> {
+        let byte = self.peek_byte()?;
+        self.reader.consume(1);
+        Some(byte)
+    }
+}
+
+impl<R: BufRead> Iterator for Utf8Chars<R> {
+    type Item = char;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let first = self.next_byte()?;
+        let width = match first {
+            0x00..=0x7F => return Some(first as char),
+            0xC0..=0xDF => 2,
+            0xE0..=0xEF => 3,
+            0xF0..=0xF7 => 4,
+            _ => return Some(char::REPLACEMENT_CHARACTER),
+        };
+
+        let mut bytes = [first, 0, 0, 0];
+        for byte in bytes.iter_mut().take(width).skip(1) {
+            match self.peek_byte() {
+                Some(continuation) if continuation & 0xC0 == 0x80 => {
+                    *byte = continuation;
+                    self.reader.consume(1);
+                }
+                _ => return Some(char::REPLACEMENT_CHARACTER),
+            }
+        }
+        let character = std::str::from_utf8(&bytes[..width])
+            .ok()
+            .and_then(|decoded| decoded.chars().next());
+        Some(character.unwrap_or(char::REPLACEMENT_CHARACTER))
+    }
+}
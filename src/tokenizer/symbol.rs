@@ -0,0 +1,27 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+
+// Identifiers are interned while tokenizing, so every occurrence of a name
+// shares one allocation and cloning it in the evaluator's hot path is cheap.
+// It's atomically counted so tokens, and the errors holding them, are `Send`.
+pub type Symbol = Arc<str>;
+
+#[derive(Debug, Default)]
+pub struct Interner {
+    symbols: HashSet<Symbol>,
+}
+
+impl Interner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn intern(&mut self, name: &str) -> Symbol {
+        if let Some(symbol) = self.symbols.get(name) {
+            return symbol.clone();
+        }
+        let symbol: Symbol = Arc::from(name);
+        self.symbols.insert(symbol.clone());
+        symbol
+    }
+}
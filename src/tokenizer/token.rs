@@ -1,10 +1,11 @@
 use crate::models::position::Position;
+use crate::tokenizer::symbol::Symbol;
 use std::fmt;
 use std::mem::discriminant;
 
 #[derive(Debug, Clone)]
 pub enum TokenKind {
-    Identifier(String),
+    Identifier(Symbol),
 
     // Keywords
     None,
@@ -12,14 +13,28 @@ pub enum TokenKind {
     And,
     Or,
     Let,
+    Def,
     If,
     Else,
+    Elif,
     While,
+    Do,
+    For,
+    In,
+    Struct,
+    Impl,
+    Enum,
     Return,
+    Del,
+    Global,
+    Nonlocal,
+    Match,
+    Case,
 
     // Builtin types
     Character(char),
     String(String),
+    Bytes(Vec<u8>),
     Integer(i32),
     Float(f32),
     Boolean(bool),
@@ -38,6 +53,7 @@ pub enum TokenKind {
     GreaterEq,
     LessThan,
     LessThanEq,
+    Coalesce,
 
     // Brackets
     RightParen,
@@ -53,6 +69,10 @@ pub enum TokenKind {
 
     // Other
     Comma,
+    Dot,
+    Question,
+    Pipe,
+    Colon,
     NewLine,
 }
 
@@ -62,8 +82,14 @@ impl PartialEq for TokenKind {
     }
 }
 
+// Every word `TokenKind::from_keyword` recognizes.
+pub const KEYWORDS: [&str; 24] = [
+    "True", "False", "None", "not", "and", "or", "let", "def", "if", "elif", "else", "while", "do",
+    "for", "in", "struct", "impl", "enum", "return", "del", "global", "nonlocal", "match", "case",
+];
+
 impl TokenKind {
-    pub fn from_str(identifier: &str) -> Option<Self> {
+    pub fn from_keyword(identifier: &str) -> Option<Self> {
         match identifier {
             "True" => Some(Self::Boolean(true)),
             "False" => Some(Self::Boolean(false)),
@@ -72,10 +98,23 @@ impl TokenKind {
             "and" => Some(Self::And),
             "or" => Some(Self::Or),
             "let" => Some(Self::Let),
+            "def" => Some(Self::Def),
             "if" => Some(Self::If),
             "else" => Some(Self::Else),
+            "elif" => Some(Self::Elif),
             "while" => Some(Self::While),
+            "do" => Some(Self::Do),
+            "for" => Some(Self::For),
+            "in" => Some(Self::In),
+            "struct" => Some(Self::Struct),
+            "impl" => Some(Self::Impl),
+            "enum" => Some(Self::Enum),
             "return" => Some(Self::Return),
+            "del" => Some(Self::Del),
+            "global" => Some(Self::Global),
+            "nonlocal" => Some(Self::Nonlocal),
+            "match" => Some(Self::Match),
+            "case" => Some(Self::Case),
             _ => None,
         }
     }
@@ -85,41 +124,56 @@ impl TokenKind {
             Self::Identifier(_) => String::from("Identifier"),
             Self::Character(_) => String::from("Character"),
             Self::String(_) => String::from("String"),
+            Self::Bytes(_) => String::from("Bytes"),
             Self::Integer(_) => String::from("Integer"),
             Self::Float(_) => String::from("Float"),
             Self::Boolean(_) => String::from("Boolean"),
             _ => format!("{:?}", self),
         }
     }
+
+    // Whether the two are the same token, values included, where `==` only
+    // compares the kinds. Floats are compared bit for bit so a NaN is the
+    // same as itself.
+    pub fn same(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Identifier(a), Self::Identifier(b)) => a == b,
+            (Self::Character(a), Self::Character(b)) => a == b,
+            (Self::String(a), Self::String(b)) => a == b,
+            (Self::Bytes(a), Self::Bytes(b)) => a == b,
+            (Self::Integer(a), Self::Integer(b)) => a == b,
+            (Self::Float(a), Self::Float(b)) => a.to_bits() == b.to_bits(),
+            (Self::Boolean(a), Self::Boolean(b)) => a == b,
+            _ => self == other,
+        }
+    }
 }
 
 #[derive(Debug, PartialEq, Clone)]
 pub struct Token {
     pub start: Position,
-    pub end: Option<Position>,
+    pub end: Position,
     pub kind: TokenKind,
 }
 
 impl Token {
-    pub const COMPERATORS: [TokenKind; 6] = [
+    pub const COMPERATORS: [TokenKind; 7] = [
         TokenKind::Equals,
         TokenKind::NotEquals,
         TokenKind::Greater,
         TokenKind::GreaterEq,
         TokenKind::LessThan,
         TokenKind::LessThanEq,
+        TokenKind::In,
     ];
 
-    pub fn new(start: Position, end: Option<Position>, kind: TokenKind) -> Self {
+    pub fn new(start: Position, end: Position, kind: TokenKind) -> Self {
         Self { start, end, kind }
     }
 }
 
 impl fmt::Display for Token {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match &self.end {
-            Some(end) => write!(f, "<{}:{} {:?}>", self.start, end, self.kind),
-            None => write!(f, "<{} {:?}>", self.start, self.kind),
-        }
+        write!(f, "<{}:{} {:?}>", self.start, self.end, self.kind)
     }
 }
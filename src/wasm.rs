@@ -0,0 +1,51 @@
+use crate::evaluator::value::Value;
+use crate::evaluator::Evaluator;
+use crate::models::error::MonoError;
+use crate::parser::Parser;
+use crate::tokenizer::Tokenizer;
+use wasm_bindgen::prelude::*;
+
+// The result of running a program in the browser: what it printed, and either
+// the value it evaluated to or the error it stopped with.
+#[wasm_bindgen(getter_with_clone)]
+pub struct Evaluation {
+    pub output: String,
+    pub value: Option<String>,
+    pub error: Option<String>,
+    pub error_kind: Option<String>,
+}
+
+#[wasm_bindgen]
+pub fn evaluate(source: &str) -> Evaluation {
+    let (result, output) = Evaluator::new().capture(|evaluator| run(source, evaluator));
+    match result {
+        Ok(value) => Evaluation {
+            output,
+            value: (!matches!(value, Value::None)).then(|| value.to_string()),
+            error: None,
+            error_kind: None,
+        },
+        Err(error) => Evaluation {
+            output,
+            value: None,
+            error: Some(error.to_string()),
+            error_kind: Some(error.kind().to_string()),
+        },
+    }
+}
+
+// Everything printed followed by the value or error, as the CLI shows it.
+#[wasm_bindgen]
+pub fn eval(source: &str) -> String {
+    let evaluation = evaluate(source);
+    match (evaluation.value, evaluation.error, evaluation.error_kind) {
+        (_, Some(error), Some(kind)) => format!("{}{}: {}", evaluation.output, kind, error),
+        (Some(value), ..) => format!("{}{}", evaluation.output, value),
+        _ => evaluation.output,
+    }
+}
+
+fn run(source: &str, evaluator: &mut Evaluator) -> Result<Value, MonoError> {
+    let program = Parser::new(Tokenizer::new(source.chars())).parse()?;
+    evaluator.evaluate(&program)
+}
@@ -0,0 +1,66 @@
+mod common;
+
+use common::eval;
+use mono::Value;
+
+#[test]
+fn power_is_right_associative() {
+    assert_eq!(eval("2 ^ 3 ^ 2"), Value::Integer(512));
+    assert_eq!(eval("(2 ^ 3) ^ 2"), Value::Integer(64));
+}
+
+#[test]
+fn power_binds_tighter_than_unary_minus() {
+    assert_eq!(eval("-2 ^ 2"), Value::Integer(-4));
+    assert_eq!(eval("(-2) ^ 2"), Value::Integer(4));
+    assert_eq!(eval("2 ^ -2 ^ 2"), Value::Float(0.0625));
+}
+
+#[test]
+fn negative_exponents_give_floats() {
+    assert_eq!(eval("2 ^ -1"), Value::Float(0.5));
+    assert_eq!(eval("-4 ^ -1"), Value::Float(-0.25));
+    assert_eq!(eval("(-2) ^ -3"), Value::Float(-0.125));
+    assert_eq!(eval("2.0 ^ -2"), Value::Float(0.25));
+    assert_eq!(eval("1 ^ -5"), Value::Float(1.0));
+}
+
+#[test]
+fn division_rounds_down() {
+    assert_eq!(eval("7 / 2"), Value::Integer(3));
+    assert_eq!(eval("-7 / 2"), Value::Integer(-4));
+    assert_eq!(eval("7 / -2"), Value::Integer(-4));
+    assert_eq!(eval("-7 / -2"), Value::Integer(3));
+    assert_eq!(eval("-6 / 2"), Value::Integer(-3));
+    assert_eq!(eval("-7.0 / 2.0"), Value::Float(-3.5));
+}
+
+#[test]
+fn remainders_take_the_sign_of_the_divisor() {
+    assert_eq!(eval("-7 % 3"), Value::Integer(2));
+    assert_eq!(eval("7 % -3"), Value::Integer(-2));
+    assert_eq!(eval("-7 % -3"), Value::Integer(-1));
+    assert_eq!(eval("-6 % 3"), Value::Integer(0));
+    assert_eq!(eval("(-2147483647 - 1) % -1"), Value::Integer(0));
+    assert_eq!(eval("-7.5 % 2.0"), Value::Float(0.5));
+    assert_eq!(eval("7.5 % -2.0"), Value::Float(-0.5));
+}
+
+#[test]
+fn quotient_and_remainder_agree() {
+    for a in -10..=10 {
+        for b in (-4..=4).filter(|&b| b != 0) {
+            let code = format!("({a} / {b}) * {b} + {a} % {b} == {a}");
+            assert_eq!(eval(&code), Value::Boolean(true), "{}", code);
+        }
+    }
+}
+
+#[test]
+fn divmod_returns_both() {
+    assert_eq!(eval("divmod(-7, 3)").to_string(), "(-3, 2)");
+    assert_eq!(eval("divmod(7, 3)").to_string(), "(2, 1)");
+    assert_eq!(eval("divmod(-7.5, 2.0)").to_string(), "(-4.0, 0.5)");
+    assert!(mono::eval("divmod(1, 0)").is_err());
+    assert!(mono::eval("divmod(1, 2.0)").is_err());
+}
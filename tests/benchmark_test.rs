@@ -0,0 +1,75 @@
+use mono::benchmark::{measure, PROGRAMS};
+use mono::evaluator::limits::Limits;
+use mono::Evaluator;
+use std::time::{Duration, Instant};
+
+fn standard_loop(iterations: u32) -> String {
+    format!(
+        "let xs = []
+let i = 0
+let total = 0
+while i < {} {{
+    list.push(xs, i)
+    total = total + xs[i] * 2
+    i = i + 1
+}}
+total",
+        iterations
+    )
+}
+
+// The steps the program takes, failing past the budget.
+fn steps(code: &str, budget: u64) -> u64 {
+    let mut evaluator = Evaluator::new();
+    evaluator.set_limits(Limits {
+        steps: Some(budget),
+        ..Limits::default()
+    });
+    let program = mono::parse(code).unwrap();
+    evaluator.evaluate(&program).unwrap();
+    evaluator.steps()
+}
+
+#[test]
+fn loops_stay_within_a_linear_budget() {
+    let small = steps(&standard_loop(1_000), 50 * 1_000);
+    let large = steps(&standard_loop(16_000), 50 * 16_000);
+    // Sixteen times the iterations may take sixteen times the steps, and a
+    // little more for the setup, but nothing like the square of it.
+    assert!(large <= small * 16 + 100, "{} steps then {}", small, large);
+}
+
+// The fastest of a few runs, as the least disturbed by whatever else the
+// machine is doing.
+fn fastest(code: &str) -> Duration {
+    let program = mono::parse(code).unwrap();
+    (0..5)
+        .map(|_| {
+            let start = Instant::now();
+            Evaluator::new().evaluate(&program).unwrap();
+            start.elapsed()
+        })
+        .min()
+        .unwrap()
+}
+
+#[test]
+fn loops_take_linear_time() {
+    // Steps only count nodes, a builtin doing more work per call (say
+    // copying the list it pushes to) would still take a step each. Timing
+    // catches that: sixteen times the iterations should take about sixteen
+    // times as long, where quadratic work would take 256 times.
+    let small = fastest(&standard_loop(2_000));
+    let large = fastest(&standard_loop(32_000));
+    assert!(large < small * 64, "{:?} then {:?}", small, large);
+}
+
+#[test]
+fn benchmark_programs_run() {
+    for (name, code) in PROGRAMS {
+        let program = mono::parse(code).unwrap();
+        let measurement = measure(name, &program, Duration::ZERO).unwrap();
+        assert_eq!(measurement.runs, 1);
+        assert!(measurement.steps > 0);
+    }
+}
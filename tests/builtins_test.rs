@@ -0,0 +1,135 @@
+mod common;
+
+use common::{eval, show};
+use mono::models::error::{MonoErrorKind, Runtime};
+use mono::Value;
+
+fn runtime_error(code: &str) -> Runtime {
+    match *mono::eval(code).expect_err(code) {
+        MonoErrorKind::Runtime(error) => error,
+        error => panic!("{}: {}", code, error),
+    }
+}
+
+#[test]
+fn len_counts_elements() {
+    assert_eq!(
+        show("[len(\"héllo\"), len(b\"h\\xc3\\xa9\"), len([1, [2, 3]]), len((1,)), len([])]"),
+        "[5, 3, 2, 1, 0]"
+    );
+}
+
+#[test]
+fn len_fails_for_values_without_a_length() {
+    for code in ["len(5)", "len(None)", "len('a')", "len(len)"] {
+        assert!(
+            matches!(runtime_error(code), Runtime::NoLength { .. }),
+            "{}",
+            code
+        );
+    }
+}
+
+#[test]
+fn sort_returns_a_sorted_copy() {
+    assert_eq!(
+        show("let xs = [3, 1, 2]\nlet ys = sort(xs)\n[xs, ys]"),
+        "[[3, 1, 2], [1, 2, 3]]"
+    );
+    assert_eq!(show("sort(\"cab\")"), "[a, b, c]");
+    assert_eq!(show("sort((2, 1))"), "[1, 2]");
+}
+
+#[test]
+fn sorting_by_key_is_stable() {
+    let code = "sort([\"bb\", \"a\", \"cc\", \"d\"], len)";
+    assert_eq!(show(code), "[a, d, bb, cc]");
+    // The key is called once per element.
+    let code = "let calls = 0
+def key(x) {
+    calls = calls + 1
+    return -x
+}
+[sort([1, 3, 2, 5, 4], key), calls]";
+    assert_eq!(show(code), "[[5, 4, 3, 2, 1], 5]");
+}
+
+#[test]
+fn sort_by_uses_the_comparator() {
+    let code = "def descending(a, b) { return b - a }\nsort_by([2, 3, 1], descending)";
+    assert_eq!(show(code), "[3, 2, 1]");
+    let code = "def by_first(a, b) { return a[0] - b[0] }
+sort_by([[2, \"x\"], [1, \"y\"], [2, \"a\"]], by_first)";
+    assert_eq!(show(code), "[[1, y], [2, x], [2, a]]");
+}
+
+#[test]
+fn sorting_failures_are_errors() {
+    assert!(matches!(
+        runtime_error("sort([1, \"a\"])"),
+        Runtime::Incomparable { .. }
+    ));
+    assert!(matches!(
+        runtime_error("sort(5)"),
+        Runtime::NonIterable { .. }
+    ));
+    assert!(matches!(
+        runtime_error("def cmp(a, b) { return True }\nsort_by([1, 2], cmp)"),
+        Runtime::InvalidComparison { .. }
+    ));
+    assert!(matches!(
+        runtime_error("sort([1], len, len)"),
+        Runtime::IncorrectParameters { .. }
+    ));
+}
+
+#[test]
+fn min_and_max_keep_the_first_of_ties() {
+    assert_eq!(show("[min([3, 1, 2]), max([3, 1, 2])]"), "[1, 3]");
+    assert_eq!(
+        show("[min(\"cab\"), max((1, 2.5)), min(b\"\\x05\\x02\")]"),
+        "[a, 2.5, 2]"
+    );
+    // `1` and `1.0` are equal, the first one found is kept.
+    assert_eq!(show("[min([1, 1.0]), max([1.0, 1])]"), "[1, 1.0]");
+}
+
+#[test]
+fn sum_adds_the_elements() {
+    assert_eq!(eval("sum([1, 2, 3])"), Value::Integer(6));
+    assert_eq!(eval("sum([1.5, 2.5])"), Value::Float(4.0));
+    assert_eq!(eval("sum([])"), Value::Integer(0));
+}
+
+#[test]
+fn any_and_all_go_by_truthiness() {
+    assert_eq!(
+        show(
+            "[any([0, None, 1]), any([0, \"\"]), any([]), all([1, \"a\"]), all([1, []]), all([])]"
+        ),
+        "[True, False, False, True, False, True]"
+    );
+}
+
+#[test]
+fn aggregating_failures_are_errors() {
+    for code in ["min([])", "max(\"\")"] {
+        assert!(
+            matches!(runtime_error(code), Runtime::EmptyValue { .. }),
+            "{}",
+            code
+        );
+    }
+    assert!(matches!(
+        runtime_error("max([1, \"a\"])"),
+        Runtime::Incomparable { .. }
+    ));
+    assert!(matches!(
+        runtime_error("sum(\"ab\")"),
+        Runtime::InvalidOperation { .. }
+    ));
+    assert!(matches!(
+        runtime_error("any(1)"),
+        Runtime::NonIterable { .. }
+    ));
+}
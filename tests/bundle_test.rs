@@ -0,0 +1,94 @@
+use mono::bundle::{BuildError, Bundle};
+use mono::Evaluator;
+use std::fs;
+use std::path::PathBuf;
+
+// A fresh directory holding the files.
+fn scripts(name: &str, files: &[(&str, &str)]) -> PathBuf {
+    let root = std::env::temp_dir().join(format!("mono_bundle_{}_{}", name, std::process::id()));
+    for (path, code) in files {
+        let path = root.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, code).unwrap();
+    }
+    root
+}
+
+#[test]
+fn bundles_hold_the_scripts_run() {
+    let root = scripts(
+        "held",
+        &[
+            (
+                "main.mono",
+                "run_file(\"lib/a.mono\")\nrun_file(\"lib/a.mono\")\nx + y",
+            ),
+            ("lib/a.mono", "run_file(\"b.mono\")\nlet x = 40"),
+            ("lib/b.mono", "let y = 2"),
+        ],
+    );
+    let bundle = Bundle::build(&root.join("main.mono"));
+    fs::remove_dir_all(&root).unwrap();
+
+    let bundle = bundle.unwrap();
+    let names: Vec<_> = bundle
+        .modules
+        .iter()
+        .map(|(name, _)| name.as_str())
+        .collect();
+    assert_eq!(names, ["lib/a.mono", "b.mono"]);
+
+    let loaded = Bundle::from_bytes(&bundle.to_bytes().unwrap()).unwrap();
+    assert_eq!(loaded, bundle);
+    let mut evaluator = Evaluator::new();
+    let entry = loaded.install(&mut evaluator);
+    assert_eq!(
+        evaluator.evaluate(&entry).unwrap(),
+        mono::Value::Integer(42)
+    );
+}
+
+#[test]
+fn dynamic_paths_are_not_followed() {
+    let root = scripts(
+        "dynamic",
+        &[("main.mono", "let path = \"a.mono\"\nrun_file(path)")],
+    );
+    let bundle = Bundle::build(&root.join("main.mono"));
+    fs::remove_dir_all(&root).unwrap();
+    assert!(bundle.unwrap().modules.is_empty());
+}
+
+#[test]
+fn errors_name_their_file() {
+    let root = scripts(
+        "errors",
+        &[
+            (
+                "main.mono",
+                "run_file(\"broken.mono\")\nrun_file(\"missing.mono\")",
+            ),
+            ("broken.mono", "let x = ("),
+        ],
+    );
+    let broken = Bundle::build(&root.join("main.mono"));
+    fs::write(root.join("broken.mono"), "let x = 1").unwrap();
+    let missing = Bundle::build(&root.join("main.mono"));
+    fs::remove_dir_all(&root).unwrap();
+
+    assert!(matches!(broken, Err(BuildError::Syntax(path, _)) if path.ends_with("broken.mono")));
+    assert!(matches!(missing, Err(BuildError::Io(path, _)) if path.ends_with("missing.mono")));
+}
+
+#[test]
+fn invalid_bundles_are_errors() {
+    assert!(Bundle::from_bytes(b"").is_err());
+    assert!(Bundle::from_bytes(b"#!/usr/bin/env -S mono exec\nMONB").is_err());
+    let bundle = Bundle {
+        entry: mono::parse("1").unwrap(),
+        modules: Vec::new(),
+    };
+    let mut bytes = bundle.to_bytes().unwrap();
+    bytes.push(0);
+    assert!(Bundle::from_bytes(&bytes).is_err());
+}
@@ -0,0 +1,60 @@
+mod common;
+
+use common::{eval, show};
+use mono::models::error::{MonoErrorKind, Runtime};
+use mono::Value;
+
+#[test]
+fn byte_literals_and_escapes() {
+    assert_eq!(
+        eval("b\"a\\x00\\xff\\n\""),
+        Value::Bytes(vec![b'a', 0, 255, b'\n'].into())
+    );
+    assert_eq!(show("b\"a\\xff\""), "b\"a\\xff\"");
+}
+
+#[test]
+fn bytes_index_to_integers() {
+    assert_eq!(show("let b = b\"AB\"\n[b[0], b[1], len(b)]"), "[65, 66, 2]");
+    assert_eq!(show("[x for x in b\"ab\"]"), "[97, 98]");
+    let error = mono::eval("let b = b\"a\"\nb[1]").unwrap_err();
+    assert!(matches!(
+        *error,
+        MonoErrorKind::Runtime(Runtime::InvalidIndex { .. })
+    ));
+}
+
+#[test]
+fn bytes_concatenate_and_slice() {
+    assert_eq!(eval("b\"ab\" + b\"c\" == b\"abc\""), Value::Boolean(true));
+    assert_eq!(show("slice(b\"hello\", 1, 3)"), "b\"el\"");
+    assert_eq!(show("slice(\"héllo\", 1, 10)"), "éllo");
+    assert_eq!(show("slice([1, 2, 3], -5, 2)"), "[1, 2]");
+}
+
+#[test]
+fn bytes_convert_to_and_from_strings() {
+    assert_eq!(show("bytes.from(\"é\")"), "b\"\\xc3\\xa9\"");
+    assert_eq!(show("bytes.from([104, 105])"), "b\"hi\"");
+    assert_eq!(eval("bytes.from([256])"), Value::None);
+    assert_eq!(show("bytes.decode(b\"\\xc3\\xa9\")"), "é");
+    assert_eq!(eval("bytes.decode(b\"\\xff\")"), Value::None);
+}
+
+#[test]
+fn hex_round_trips() {
+    assert_eq!(show("hex_encode(b\"\\x01\\xab\")"), "01ab");
+    assert_eq!(show("hex_decode(\"01AB\")"), "b\"\\x01\\xab\"");
+    assert_eq!(eval("hex_decode(\"abc\")"), Value::None);
+    assert_eq!(eval("hex_decode(\"zz\")"), Value::None);
+    assert_eq!(show("bytes.from_hex(bytes.hex(b\"mono\"))"), "b\"mono\"");
+}
+
+#[test]
+fn files_read_as_bytes() {
+    let path = std::env::temp_dir().join(format!("mono_bytes_test_{}", std::process::id()));
+    std::fs::write(&path, [0u8, 1, 255]).unwrap();
+    let value = eval(&format!("read_file_bytes({:?})", path));
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(value, Value::Bytes(vec![0, 1, 255].into()));
+}
@@ -0,0 +1,45 @@
+use mono::cache;
+use std::fs;
+use std::path::PathBuf;
+
+fn script(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!(
+        "mono_cache_test_{}_{}.mono",
+        name,
+        std::process::id()
+    ))
+}
+
+#[test]
+fn unchanged_files_load_their_cache() {
+    let path = script("unchanged");
+    let code = "def double(x) { return x * 2 }\nlet y = double(21)\n";
+    let parsed = cache::parse(&path, code).unwrap();
+    let cached = fs::read(cache::path(&path));
+    let loaded = cache::parse(&path, code).unwrap();
+    fs::remove_file(cache::path(&path)).unwrap();
+
+    assert!(cached.is_ok());
+    assert_eq!(parsed, mono::parse(code).unwrap());
+    assert_eq!(loaded, parsed);
+}
+
+#[test]
+fn changed_files_are_parsed_again() {
+    let path = script("changed");
+    cache::parse(&path, "1 + 1").unwrap();
+    let changed = cache::parse(&path, "2 * 3").unwrap();
+    fs::write(cache::path(&path), b"MONC corrupt").unwrap();
+    let corrupt = cache::parse(&path, "2 * 3").unwrap();
+    fs::remove_file(cache::path(&path)).unwrap();
+
+    assert_eq!(changed, mono::parse("2 * 3").unwrap());
+    assert_eq!(corrupt, changed);
+}
+
+#[test]
+fn syntax_errors_are_not_cached() {
+    let path = script("error");
+    assert!(cache::parse(&path, "1 +").is_err());
+    assert!(!cache::path(&path).exists());
+}
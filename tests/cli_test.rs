@@ -0,0 +1,221 @@
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+// The status the CLI exits with when run with the arguments.
+fn status(arguments: &[&str]) -> Option<i32> {
+    Command::new(env!("CARGO_BIN_EXE_mono"))
+        .args(arguments)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .unwrap()
+        .code()
+}
+
+#[test]
+fn successful_runs_exit_with_zero() {
+    assert_eq!(status(&["-c", "1 + 1"]), Some(0));
+    assert_eq!(status(&["-c", "-p", "1 + 1"]), Some(0));
+}
+
+#[test]
+fn runtime_errors_exit_with_one() {
+    assert_eq!(status(&["-c", "undefined"]), Some(1));
+    assert_eq!(status(&["-c", "--profile", "1 / 0"]), Some(1));
+}
+
+#[test]
+fn deep_recursion_is_a_runtime_error() {
+    let count = "def count(n) {\n    if n == 0 { return 0 }\n    return 1 + count(n - 1)\n}\n";
+    assert_eq!(
+        status(&["-c", &format!("{}exit(count(900) - 900)", count)]),
+        Some(0)
+    );
+    assert_eq!(status(&["-c", &format!("{}count(100000)", count)]), Some(1));
+    assert_eq!(piped(&[], &format!("{}count(100000)\n", count)), Some(1));
+}
+
+#[test]
+fn usage_errors_exit_with_two() {
+    assert_eq!(status(&["--unknown"]), Some(2));
+    assert_eq!(status(&["/nonexistent/file.mono"]), Some(2));
+    assert_eq!(status(&["--error-format=xml", "-c", "1"]), Some(2));
+}
+
+#[test]
+fn failed_commands_are_runtime_errors() {
+    assert_eq!(status(&["debug", "/nonexistent/file.mono"]), Some(1));
+    assert_eq!(status(&["exec", "/nonexistent/file.monob"]), Some(1));
+    let path = std::env::temp_dir().join(format!("mono_cli_transpile_{}", std::process::id()));
+    std::fs::write(&path, "print(input())\n").unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_mono"))
+        .args(["transpile", path.to_str().unwrap()])
+        .output()
+        .unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    // They're not usage errors, so the usage isn't printed.
+    assert!(!String::from_utf8_lossy(&output.stderr).contains("Usage"));
+}
+
+#[test]
+fn syntax_errors_exit_with_three() {
+    assert_eq!(status(&["-c", "1 +"]), Some(3));
+    assert_eq!(status(&["-c", "-t", "'a"]), Some(3));
+    assert_eq!(status(&["-c", "-p", "("]), Some(3));
+}
+
+#[test]
+fn scripts_choose_their_own_status() {
+    assert_eq!(status(&["-c", "exit(7)"]), Some(7));
+    assert_eq!(status(&["-c", "exit(0)\nundefined"]), Some(0));
+}
+
+#[test]
+fn statuses_out_of_a_byte_are_runtime_errors() {
+    assert_eq!(status(&["-c", "exit(256)"]), Some(1));
+    assert_eq!(status(&["-c", "exit(-1)"]), Some(1));
+    assert_eq!(status(&["-c", "exit(255)"]), Some(255));
+}
+
+#[test]
+fn scripts_run_whatever_their_extension() {
+    let path = std::env::temp_dir().join(format!("mono_cli_test_{}", std::process::id()));
+    std::fs::write(&path, "#!/usr/bin/env mono\nexit(5)\n").unwrap();
+    let exited = status(&[path.to_str().unwrap()]);
+    std::fs::remove_file(&path).unwrap();
+    std::fs::remove_file(path.with_extension("monoc")).unwrap();
+    assert_eq!(exited, Some(5));
+}
+
+// The status the CLI exits with when run with the arguments and `input` on
+// stdin.
+fn piped(arguments: &[&str], input: &str) -> Option<i32> {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_mono"))
+        .args(arguments)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(input.as_bytes())
+        .unwrap();
+    child.wait().unwrap().code()
+}
+
+#[test]
+fn flags_come_in_any_order() {
+    assert_eq!(status(&["-c", "'a", "-t"]), Some(3));
+    assert_eq!(status(&["-t", "-c", "'a"]), Some(3));
+    assert_eq!(status(&["-c", "(", "-p"]), Some(3));
+}
+
+#[test]
+fn snippets_run_in_one_session() {
+    assert_eq!(status(&["-c", "let x = 1", "-c", "exit(x + 4)"]), Some(5));
+    assert_eq!(status(&["-c", "x", "-c", "let x = 1"]), Some(1));
+}
+
+#[test]
+fn piped_programs_run() {
+    assert_eq!(piped(&[], "let x = 2\nexit(x * 3)\n"), Some(6));
+    assert_eq!(piped(&["--stdin"], "exit(4)\n"), Some(4));
+    assert_eq!(piped(&["-"], "1 +\n"), Some(3));
+    assert_eq!(piped(&["--stdin", "-p"], "(\n"), Some(3));
+}
+
+#[test]
+fn sources_are_exclusive() {
+    assert_eq!(status(&["-c", "1", "--stdin"]), Some(2));
+    assert_eq!(status(&["-c", "1", "file.mono"]), Some(2));
+    assert_eq!(status(&["a.mono", "b.mono"]), Some(2));
+    assert_eq!(status(&["-c"]), Some(2));
+}
+
+#[test]
+fn watching_needs_a_path() {
+    assert_eq!(status(&["--watch"]), Some(2));
+    assert_eq!(status(&["--watch", "-c", "1"]), Some(2));
+    assert_eq!(status(&["--watch", "/nonexistent/file.mono"]), Some(2));
+}
+
+#[test]
+fn projects_run_their_entry() {
+    let root = std::env::temp_dir().join(format!("mono_cli_project_{}", std::process::id()));
+    std::fs::create_dir_all(root.join("src")).unwrap();
+    std::fs::write(
+        root.join("mono.toml"),
+        "name = \"test\"\nentry = \"src/main.mono\"\n",
+    )
+    .unwrap();
+    std::fs::write(
+        root.join("src/main.mono"),
+        "run_file(\"src/status.mono\")\n",
+    )
+    .unwrap();
+    std::fs::write(root.join("src/status.mono"), "exit(9)\n").unwrap();
+    let exited = status(&["run", root.to_str().unwrap()]);
+    let missing = status(&["run", "/"]);
+    std::fs::remove_dir_all(&root).unwrap();
+    assert_eq!(exited, Some(9));
+    assert_eq!(missing, Some(2));
+}
+
+#[test]
+fn files_are_cached_unless_asked_not_to() {
+    let path = std::env::temp_dir().join(format!("mono_cli_cache_{}.mono", std::process::id()));
+    let cached = path.with_extension("monoc");
+    std::fs::write(&path, "exit(3 + 3)\n").unwrap();
+    let uncached = status(&["--no-cache", path.to_str().unwrap()]);
+    let skipped = cached.exists();
+    let exited = status(&[path.to_str().unwrap()]);
+    let reused = status(&[path.to_str().unwrap()]);
+    let written = cached.exists();
+    std::fs::remove_file(&path).unwrap();
+    std::fs::remove_file(&cached).unwrap();
+    assert_eq!((uncached, exited, reused), (Some(6), Some(6), Some(6)));
+    assert!(!skipped && written);
+}
+
+#[test]
+fn bundles_run_without_their_sources() {
+    let root = std::env::temp_dir().join(format!("mono_cli_bundle_{}", std::process::id()));
+    std::fs::create_dir_all(&root).unwrap();
+    std::fs::write(
+        root.join("main.mono"),
+        "run_file(\"status.mono\")\nexit(status)\n",
+    )
+    .unwrap();
+    std::fs::write(root.join("status.mono"), "let status = 8\n").unwrap();
+    let (main, bundle) = (root.join("main.mono"), root.join("main.bundle"));
+    let built = status(&[
+        "build",
+        main.to_str().unwrap(),
+        "-o",
+        bundle.to_str().unwrap(),
+    ]);
+    std::fs::remove_file(root.join("status.mono")).unwrap();
+    let exited = status(&["exec", bundle.to_str().unwrap()]);
+    let invalid = status(&["exec", main.to_str().unwrap()]);
+    std::fs::write(&main, "(").unwrap();
+    let broken = status(&[
+        "build",
+        main.to_str().unwrap(),
+        "-o",
+        bundle.to_str().unwrap(),
+    ]);
+    std::fs::remove_dir_all(&root).unwrap();
+    assert_eq!((built, exited), (Some(0), Some(8)));
+    assert_eq!((invalid, broken), (Some(1), Some(3)));
+}
+
+#[test]
+fn streamed_programs_run_until_their_syntax_error() {
+    assert_eq!(status(&["--stream", "-c", "exit(4)\n1 +"]), Some(4));
+    assert_eq!(status(&["--stream", "-c", "let x = 1\n1 +"]), Some(3));
+}
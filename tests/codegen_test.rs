@@ -0,0 +1,81 @@
+mod common;
+
+use common::printed;
+use mono::codegen::transpile;
+use std::fs;
+use std::process::Command;
+
+fn unsupported(code: &str) -> String {
+    transpile(&mono::parse(code).unwrap())
+        .unwrap_err()
+        .to_string()
+}
+
+#[test]
+fn transpiled_programs_print_what_they_would() {
+    let code = "def fib(n) {
+    if n < 2 { return n }
+    return fib(n - 1) + fib(n - 2)
+}
+let i = 0
+while i < 12 {
+    println(fib(i))
+    i = i + 1
+}
+do {
+    if i > 13 { println(True) } elif i == 12 { println(None) } else { println(-i * 0) }
+    i = i + 1
+} while i < 15
+println(2 ^ -1, i, None)
+println(2 ^ 3 ^ 2, -2 ^ 2, 2.0 ^ -2)
+println(-7 / 2, -7 % 3, 7 % -3, -7.5 % 2.0)
+print()
+println(1.5 / 4.0 + 0.1)
+println(not (i >= 15 and 1 != 2))
+i
+";
+    let source = transpile(&mono::parse(code).unwrap()).unwrap();
+    let (value, expected) = printed(code);
+    let expected = format!("{}{}\n\n", expected, value.unwrap());
+
+    let directory = std::env::temp_dir().join(format!("mono_codegen_{}", std::process::id()));
+    fs::create_dir_all(&directory).unwrap();
+    fs::write(directory.join("main.rs"), source).unwrap();
+    let compiled = Command::new("rustc")
+        .args(["--edition", "2021", "-o"])
+        .arg(directory.join("main"))
+        .arg(directory.join("main.rs"))
+        .status()
+        .unwrap();
+    let ran = Command::new(directory.join("main")).output().unwrap();
+    fs::remove_dir_all(&directory).unwrap();
+
+    assert!(compiled.success());
+    assert!(ran.status.success());
+    assert_eq!(String::from_utf8(ran.stdout).unwrap(), expected);
+}
+
+#[test]
+fn runtime_errors_are_checked() {
+    let source = transpile(&mono::parse("let x = 2147483647\nx + 1").unwrap()).unwrap();
+    assert!(source.contains("checked_add"));
+    assert!(source.contains("fn program() -> Value"));
+}
+
+#[test]
+fn unsupported_code_is_an_error() {
+    assert_eq!(
+        unsupported("let x = \"a\""),
+        "Can't transpile values other than numbers, booleans and None at [1,9]."
+    );
+    assert_eq!(
+        unsupported("let xs = [1, 2]"),
+        "Can't transpile List at [1,11]."
+    );
+    assert!(unsupported("let x = 1\ndef f() { return x }").contains("the function's own"));
+    assert!(unsupported("def f(a) { return a }\nf(1, 2)").contains("wrong arity"));
+    assert!(unsupported("def f(a = 1) { return a }").contains("default arguments"));
+    assert!(unsupported("if True { def f() { return 1 } }").contains("declared in blocks"));
+    assert!(unsupported("len(1)").contains("builtins"));
+    assert!(unsupported("for x in 1 { x }").contains("For"));
+}
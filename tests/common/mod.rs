@@ -0,0 +1,26 @@
+// Helpers shared by the integration tests, not every test uses all of them.
+#![allow(dead_code)]
+
+use mono::{Evaluator, MonoError, Value};
+
+// Runs the code in a fresh evaluator, failing the test with the code and the
+// error if it doesn't run.
+pub fn eval(code: &str) -> Value {
+    mono::eval(code).unwrap_or_else(|error| panic!("{}: {}", code, error))
+}
+
+// The printed form of the code's value.
+pub fn show(code: &str) -> String {
+    eval(code).to_string()
+}
+
+// The code's result and what it printed, run in a fresh evaluator.
+pub fn printed(code: &str) -> (Result<Value, MonoError>, String) {
+    Evaluator::new()
+        .capture(|evaluator| mono::parse(code).and_then(|program| evaluator.evaluate(&program)))
+}
+
+// The error the code fails with, in parsing or evaluation.
+pub fn error(code: &str) -> MonoError {
+    mono::eval(code).expect_err(code)
+}
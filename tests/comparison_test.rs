@@ -0,0 +1,156 @@
+mod common;
+
+use common::eval;
+use mono::models::error::{MonoErrorKind, Runtime};
+use mono::Value;
+
+fn holds(code: &str) -> bool {
+    eval(code) == Value::Boolean(true)
+}
+
+// A small deterministic generator, so failures reproduce.
+struct Random(u64);
+
+impl Random {
+    fn next(&mut self, bound: u64) -> u64 {
+        self.0 = self
+            .0
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        (self.0 >> 33) % bound
+    }
+
+    // From a small range so that ties are common.
+    fn number(&mut self) -> String {
+        let whole = self.next(5) as i64 - 2;
+        match self.next(3) {
+            0 => format!("{}.0", whole),
+            1 => format!("{}.5", whole),
+            _ => format!("{}", whole),
+        }
+    }
+
+    fn numbers(&mut self) -> Vec<String> {
+        (0..self.next(4)).map(|_| self.number()).collect()
+    }
+
+    // Three values of one shape, as lists and tuples only compare with their
+    // own kind.
+    fn comparable(&mut self) -> [String; 3] {
+        let shape = self.next(4);
+        [(); 3].map(|_| match shape {
+            0 => self.number(),
+            1 => format!("[{}]", self.numbers().join(", ")),
+            2 => match self.numbers()[..] {
+                [ref number] => format!("({},)", number),
+                ref numbers => format!("({})", numbers.join(", ")),
+            },
+            _ => {
+                let lists: Vec<String> = (0..self.next(3))
+                    .map(|_| format!("[{}]", self.numbers().join(", ")))
+                    .collect();
+                format!("[{}]", lists.join(", "))
+            }
+        })
+    }
+}
+
+#[test]
+fn numbers_compare_across_integers_and_floats() {
+    assert!(holds("1 < 1.5"));
+    assert!(holds("2.5 >= 2"));
+    assert!(holds("1 == 1.0"));
+    assert!(holds("1 != 1.5"));
+    assert!(holds("1 in [1.0]"));
+    assert!(!holds("float(\"nan\") < 1"));
+    assert!(!holds("1 >= float(\"nan\")"));
+}
+
+#[test]
+fn lists_compare_lexicographically() {
+    assert!(holds("[1, 2] < [1, 3]"));
+    assert!(holds("[1, 2] < [1, 2, 0]"));
+    assert!(holds("[] < [0]"));
+    assert!(holds("[[1], 2] > [[0, 5], 3]"));
+    assert!(holds("[1, 2] == [1.0, 2]"));
+    assert!(holds("(1, 2) < (1.5,)"));
+    assert!(mono::eval("[1, \"a\"] < [1, 2]").is_err());
+    assert!(mono::eval("[1] < (1,)").is_err());
+}
+
+#[test]
+fn comparison_is_antisymmetric_and_transitive() {
+    let mut random = Random(4413);
+    for _ in 0..200 {
+        let [a, b, c] = random.comparable();
+        let less = |x: &str, y: &str| holds(&format!("{} < {}", x, y));
+        let equal = |x: &str, y: &str| holds(&format!("{} == {}", x, y));
+
+        // Exactly one of `<`, `==` and `>`, each agreeing with its mirror.
+        let outcomes = [less(&a, &b), equal(&a, &b), less(&b, &a)];
+        assert_eq!(outcomes.iter().filter(|&&x| x).count(), 1, "{} {}", a, b);
+        assert_eq!(holds(&format!("{} > {}", b, a)), outcomes[0], "{} {}", a, b);
+        assert_eq!(
+            holds(&format!("{} <= {}", a, b)),
+            !outcomes[2],
+            "{} {}",
+            a,
+            b
+        );
+        assert_eq!(
+            holds(&format!("{} != {}", a, b)),
+            !outcomes[1],
+            "{} {}",
+            a,
+            b
+        );
+
+        if less(&a, &b) && less(&b, &c) {
+            assert!(less(&a, &c), "{} {} {}", a, b, c);
+        }
+        if equal(&a, &b) && equal(&b, &c) {
+            assert!(equal(&a, &c), "{} {} {}", a, b, c);
+        }
+    }
+}
+
+#[test]
+fn sorting_uses_the_same_order() {
+    assert_eq!(
+        eval("sort([[2], [1, 5], [1.5], [1]])").to_string(),
+        eval("[[1], [1, 5], [1.5], [2]]").to_string()
+    );
+    assert_eq!(eval("sort([2, 1.5, 1])").to_string(), "[1, 1.5, 2]");
+}
+
+#[test]
+fn membership() {
+    assert!(holds("3 in [1, 2, 3]"));
+    assert!(holds("not (4 in [1, 2, 3])"));
+    assert!(holds("[1] in [[1], 2]"));
+    assert!(holds("'a' in \"cat\""));
+    assert!(holds("\"at\" in \"cat\""));
+    assert!(holds("\"\" in \"cat\""));
+    assert!(holds("None in (1, None)"));
+    assert!(holds("99 in b\"abc\""));
+    assert!(holds("not (300 in b\"abc\")"));
+    assert!(holds("b\"bc\" in b\"abc\""));
+    // `in` binds looser than arithmetic, like the other comparisons.
+    assert!(holds("1 + 1 in [2]"));
+}
+
+#[test]
+fn membership_needs_a_container() {
+    for code in ["1 in 5", "1 in \"123\"", "'a' in None"] {
+        let error = mono::eval(code).expect_err(code);
+        assert!(
+            matches!(
+                *error,
+                MonoErrorKind::Runtime(Runtime::InvalidOperation { .. })
+            ),
+            "{}: {}",
+            code,
+            error
+        );
+    }
+}
@@ -0,0 +1,135 @@
+mod common;
+
+use common::eval;
+use mono::models::error::{MonoErrorKind, Runtime};
+use mono::Value;
+
+#[test]
+fn if_yields_the_value_of_the_branch_taken() {
+    assert_eq!(
+        eval("let x = if 1 < 2 { 1 } else { 2 }\nx"),
+        Value::Integer(1)
+    );
+    assert_eq!(
+        eval("let x = if 1 > 2 { 1 } else { 2 }\nx"),
+        Value::Integer(2)
+    );
+    assert_eq!(
+        eval("let x = if False { 1 } elif True { 2 } else { 3 }\nx"),
+        Value::Integer(2)
+    );
+    // The last statement of the block is its value.
+    assert_eq!(
+        eval("let x = if True {\n    let y = 2\n    y + 1\n} else { 0 }\nx"),
+        Value::Integer(3)
+    );
+    assert_eq!(eval("(if True { 1 } else { 2 }) + 1"), Value::Integer(2));
+}
+
+#[test]
+fn if_without_a_value_gives_none() {
+    assert_eq!(eval("let x = if False { 1 }\nx"), Value::None);
+    assert_eq!(eval("let x = if True { }\nx"), Value::None);
+}
+
+#[test]
+fn while_yields_its_last_iterations_value() {
+    let code = "let i = 0
+let x = while i < 3 {
+    i = i + 1
+    i * 10
+}
+x";
+    assert_eq!(eval(code), Value::Integer(30));
+    assert_eq!(eval("let x = while False { 1 }\nx"), Value::None);
+}
+
+#[test]
+fn return_inside_a_branch_leaves_the_function() {
+    let code = "def sign(n) {
+    let kind = if n < 0 { return \"negative\" } else { \"positive\" }
+    return kind + \"!\"
+}
+[sign(-1), sign(1)]";
+    assert_eq!(eval(code).to_string(), "[negative, positive!]");
+}
+
+#[test]
+fn do_while_runs_its_block_at_least_once() {
+    assert_eq!(
+        eval("let i = 10\ndo { i = i + 1 } while i < 3\ni"),
+        Value::Integer(11)
+    );
+    assert_eq!(
+        eval("let i = 0\ndo {\n    i = i + 1\n} while i < 3\ni"),
+        Value::Integer(3)
+    );
+    // Like `while`, it yields the value of its last iteration.
+    assert_eq!(
+        eval("let i = 0\nlet x = do { i = i + 1\n i * 2 } while i < 4\nx"),
+        Value::Integer(8)
+    );
+}
+
+#[test]
+fn do_while_counts_zero_as_one_digit() {
+    let code = "def digits(n) {
+    let count = 0
+    do {
+        count = count + 1
+        n = n / 10
+    } while n > 0
+    return count
+}
+[digits(0), digits(7), digits(1234)]";
+    assert_eq!(eval(code).to_string(), "[1, 1, 4]");
+}
+
+#[test]
+fn conditions_must_be_booleans() {
+    for code in ["let x = if 1 { 2 }", "while \"\" { }", "do { } while None"] {
+        let error = mono::eval(code).expect_err(code);
+        assert!(
+            matches!(
+                *error,
+                MonoErrorKind::Runtime(Runtime::InvalidCondition { .. })
+            ),
+            "{}: {}",
+            code,
+            error
+        );
+    }
+}
+
+#[test]
+fn invalid_conditions_point_at_the_condition() {
+    let error = mono::eval("let n = 3\nif n + 1 { }").unwrap_err();
+    match *error {
+        MonoErrorKind::Runtime(Runtime::InvalidCondition {
+            start: Some(start),
+            end: Some(end),
+            ..
+        }) => {
+            assert_eq!((start.row(), start.column()), (2, 4));
+            assert_eq!((end.row(), end.column()), (2, 9));
+        }
+        error => panic!("{}", error),
+    }
+}
+
+#[test]
+fn bool_converts_by_truthiness() {
+    let code = "[bool(0), bool(0.0), bool(\"\"), bool([]), bool(()), bool(None), bool(False)]";
+    assert_eq!(
+        eval(code).to_string(),
+        "[False, False, False, False, False, False, False]"
+    );
+    let code =
+        "[bool(-1), bool(0.5), bool(\"a\"), bool([0]), bool((None,)), bool(True), bool(print)]";
+    assert_eq!(
+        eval(code).to_string(),
+        "[True, True, True, True, True, True, True]"
+    );
+    assert_eq!(eval("if bool(\"x\") { 1 } else { 2 }"), Value::Integer(1));
+    assert_eq!(eval("boolean.from([])"), Value::Boolean(false));
+}
@@ -0,0 +1,59 @@
+mod common;
+
+use common::eval;
+use mono::models::error::{MonoErrorKind, Runtime};
+use mono::{Evaluator, Value};
+
+#[test]
+fn rust_values_convert_into_values() {
+    assert_eq!(Value::from(3), Value::Integer(3));
+    assert_eq!(Value::from(1.5), Value::Float(1.5));
+    assert_eq!(Value::from(true), Value::Boolean(true));
+    assert_eq!(Value::from('a'), Value::Character('a'));
+    assert_eq!(Value::from(()), Value::None);
+    assert_eq!(Value::from(None::<i32>), Value::None);
+    assert_eq!(Value::from(vec![1, 2]), eval("[1, 2]"));
+    assert_eq!(Value::from((1, "a", 'b')), eval("(1, \"a\", 'b')"));
+    assert_eq!(Value::try_from(7i64).unwrap(), Value::Integer(7));
+    assert!(Value::try_from(i64::MAX).is_err());
+}
+
+#[test]
+fn values_convert_back_into_rust_values() {
+    assert_eq!(i32::try_from(eval("1 + 2")).unwrap(), 3);
+    assert_eq!(f64::try_from(eval("0.5")).unwrap(), 0.5);
+    assert_eq!(String::try_from(eval("\"mono\"")).unwrap(), "mono");
+    assert_eq!(
+        Vec::<i32>::try_from(eval("[i * 2 for i in range(0, 3)]")).unwrap(),
+        [0, 2, 4]
+    );
+    assert_eq!(
+        Vec::<char>::try_from(eval("('a', 'b')")).unwrap(),
+        ['a', 'b']
+    );
+    assert_eq!(Option::<bool>::try_from(eval("None")).unwrap(), None);
+    assert_eq!(Option::<bool>::try_from(eval("True")).unwrap(), Some(true));
+}
+
+#[test]
+fn mismatched_values_dont_convert() {
+    for result in [
+        i32::try_from(eval("\"1\"")).map(drop),
+        Vec::<i32>::try_from(eval("[1, \"2\"]")).map(drop),
+        String::try_from(eval("'a'")).map(drop),
+    ] {
+        assert!(matches!(
+            *result.unwrap_err(),
+            MonoErrorKind::Runtime(Runtime::InvalidValue { .. })
+        ));
+    }
+}
+
+#[test]
+fn hosts_round_trip_through_scripts() {
+    let mut evaluator = Evaluator::new();
+    evaluator.set_var("xs", vec![3, 1, 2]);
+    let program = mono::parse("sort(xs)").unwrap();
+    let sorted: Vec<i32> = evaluator.evaluate(&program).unwrap().try_into().unwrap();
+    assert_eq!(sorted, [1, 2, 3]);
+}
@@ -0,0 +1,52 @@
+mod common;
+
+use common::{eval, show};
+use mono::Value;
+
+#[test]
+fn dates_parse_and_format() {
+    let code = "let d = date_parse(\"2024-02-29 13:05:09\", \"%Y-%m-%d %H:%M:%S\")";
+    assert_eq!(show(&format!("{}\nd", code)), "2024-02-29T13:05:09Z");
+    assert_eq!(
+        show(&format!("{}\ndate_format(d, \"%d/%m/%Y %%\")", code)),
+        "29/02/2024 %"
+    );
+    // Missing fields start at the beginning of the epoch's day.
+    assert_eq!(show("date_parse(\"2000\", \"%Y\")"), "2000-01-01T00:00:00Z");
+    assert_eq!(
+        eval("date_parse(\"2023-02-29\", \"%Y-%m-%d\")"),
+        Value::None
+    );
+    assert_eq!(eval("date_parse(\"nope\", \"%Y\")"), Value::None);
+}
+
+#[test]
+fn dates_shift_by_seconds_and_days() {
+    let start = "let d = date_parse(\"2023-12-31 23:00:00\", \"%Y-%m-%d %H:%M:%S\")\n";
+    assert_eq!(show(&format!("{}d + 3600", start)), "2024-01-01T00:00:00Z");
+    assert_eq!(show(&format!("{}60 + d", start)), "2023-12-31T23:01:00Z");
+    assert_eq!(
+        show(&format!("{}d - days(365)", start)),
+        "2022-12-31T23:00:00Z"
+    );
+    assert_eq!(
+        eval(&format!("{}(d + days(2)) - d", start)),
+        Value::Integer(172800)
+    );
+}
+
+#[test]
+fn dates_compare() {
+    let code = "let a = date.from_timestamp(0)
+let b = date.from_timestamp(60)
+[a < b, a == b, b >= a, a == date.parse(\"1970\", \"%Y\"), date.timestamp(b)]";
+    assert_eq!(show(code), "[True, False, True, True, 60]");
+}
+
+#[test]
+fn now_is_the_current_time() {
+    assert_eq!(
+        eval("date.timestamp(now()) - time() <= 1"),
+        Value::Boolean(true)
+    );
+}
@@ -0,0 +1,90 @@
+use mono::evaluator::hook::Hook;
+use mono::evaluator::symbol_table::SymbolTable;
+use mono::parser::node::Node;
+use mono::Evaluator;
+use std::io::Write;
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+// The line and depth of a statement, and the value of `x` if it's visible.
+type Trace = (usize, usize, Option<String>);
+
+#[derive(Clone, Default)]
+struct Tracer(Arc<Mutex<Vec<Trace>>>);
+
+impl Hook for Tracer {
+    fn on_statement(&mut self, statement: &Node, symbol_table: &SymbolTable) {
+        let line = statement.position().unwrap().row();
+        let x = symbol_table.get("x").map(|value| value.to_string());
+        self.0.lock().unwrap().push((line, symbol_table.depth(), x));
+    }
+}
+
+#[test]
+fn hooks_see_every_statement_with_its_scope() {
+    let code = "def f(x) {
+    return x + 1
+}
+let x = 1
+f(5)";
+    let tracer = Tracer::default();
+    let mut evaluator = Evaluator::new();
+    evaluator.set_hook(tracer.clone());
+    evaluator.evaluate(&mono::parse(code).unwrap()).unwrap();
+    let traced = tracer.0.lock().unwrap().clone();
+    assert_eq!(
+        traced,
+        [
+            (1, 0, None),
+            (4, 0, None),
+            (5, 0, Some("1".to_string())),
+            (2, 1, Some("5".to_string())),
+        ]
+    );
+}
+
+// What `mono debug` prints for the program when given the commands.
+fn debug(code: &str, commands: &str) -> String {
+    static RUNS: AtomicUsize = AtomicUsize::new(0);
+    let path = std::env::temp_dir().join(format!(
+        "mono_debugger_test_{}_{}.mono",
+        std::process::id(),
+        RUNS.fetch_add(1, Ordering::Relaxed)
+    ));
+    std::fs::write(&path, code).unwrap();
+    let mut child = Command::new(env!("CARGO_BIN_EXE_mono"))
+        .args(["debug", path.to_str().unwrap()])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(commands.as_bytes())
+        .unwrap();
+    let output = child.wait_with_output().unwrap();
+    std::fs::remove_file(&path).unwrap();
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+#[test]
+fn breakpoints_pause_the_program() {
+    let code = "let x = 1\nx = x + 1\nx = x * 10\nprint(x)";
+    let printed = debug(code, "b 3\nc\np x\nc\n");
+    assert!(printed.contains("->    3 | x = x * 10"), "{}", printed);
+    assert!(printed.contains("(debug) 2\n"), "{}", printed);
+    assert!(printed.ends_with("20"), "{}", printed);
+}
+
+#[test]
+fn stepping_over_and_into_calls() {
+    let code = "def f() {\n    return 1\n}\nf()\nf()";
+    let stepped_in = debug(code, "n\nn\ns\nc\n");
+    assert!(stepped_in.contains("->    2 |"), "{}", stepped_in);
+    let stepped_over = debug(code, "n\nn\nn\nc\n");
+    assert!(!stepped_over.contains("->    2 |"), "{}", stepped_over);
+}
@@ -0,0 +1,77 @@
+mod common;
+
+use common::{error, show};
+use mono::models::error::{MonoErrorKind, Runtime};
+
+#[test]
+fn tuples_are_written_with_commas() {
+    assert_eq!(show("(1, \"a\")"), "(1, a)");
+    assert_eq!(show("(1,)"), "(1,)");
+    assert_eq!(show("()"), "()");
+    assert_eq!(show("(1, 2) == (1, 2)"), "True");
+    assert_eq!(show("let t = (4, 5)\nt[1]"), "5");
+}
+
+#[test]
+fn functions_return_several_values_as_a_tuple() {
+    let code = "def split(n) { return n / 10, n % 10 }\nsplit(42)";
+    assert_eq!(show(code), "(4, 2)");
+}
+
+#[test]
+fn declarations_destructure_tuples_and_lists() {
+    assert_eq!(show("let x, y = (1, 2)\n[x, y]"), "[1, 2]");
+    assert_eq!(
+        show("let head, *tail = [1, 2, 3]\n[head, tail]"),
+        "[1, [2, 3]]"
+    );
+    assert_eq!(show("let [a, [b, *c]] = [1, [2]]\n[a, b, c]"), "[1, 2, []]");
+    assert_eq!(
+        show("def pair() { return 1, 2 }\nlet a, b = pair()\na + b"),
+        "3"
+    );
+}
+
+#[test]
+fn mismatched_shapes_are_errors() {
+    for code in ["let x, y = (1, 2, 3)", "let [a, b] = [1]", "let a, b = 1"] {
+        let error = error(code);
+        assert!(
+            matches!(
+                *error,
+                MonoErrorKind::Runtime(Runtime::InvalidDestructuring { .. })
+            ),
+            "{}: {}",
+            code,
+            error
+        );
+    }
+}
+
+#[test]
+fn parallel_assignment_evaluates_the_right_side_first() {
+    assert_eq!(show("let x = 1\nlet y = 2\nx, y = y, x\n[x, y]"), "[2, 1]");
+    let code = "def fib(n) {
+    let a = 0
+    let b = 1
+    for i in range(0, n) {
+        a, b = b, a + b
+    }
+    return a
+}
+fib(10)";
+    assert_eq!(show(code), "55");
+    assert_eq!(
+        show("let a = 1\nlet b = 2\nlet c = 3\na, b, c = c, a, b\n(a, b, c)"),
+        "(3, 1, 2)"
+    );
+}
+
+#[test]
+fn parallel_assignment_needs_existing_variables() {
+    let error = error("x, y = 1, 2");
+    assert!(matches!(
+        *error,
+        MonoErrorKind::Runtime(Runtime::UnknownIdentifier { .. })
+    ));
+}
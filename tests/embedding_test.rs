@@ -0,0 +1,88 @@
+use mono::evaluator::shared::Shared;
+use mono::{Evaluator, Value};
+
+fn run(evaluator: &mut Evaluator, code: &str) -> Value {
+    let program = mono::parse(code).unwrap_or_else(|error| panic!("{}: {}", code, error));
+    evaluator
+        .evaluate(&program)
+        .unwrap_or_else(|error| panic!("{}: {}", code, error))
+}
+
+#[test]
+fn hosts_pass_variables_in_and_read_them_back() {
+    let mut evaluator = Evaluator::new();
+    evaluator.set_var("rate", 3);
+    evaluator.set_var("name", "mono");
+    run(&mut evaluator, "let result = rate * len(name)");
+    assert_eq!(evaluator.get_var("result"), Some(Value::Integer(12)));
+    assert_eq!(evaluator.get_var("missing"), None);
+    // Setting a variable again replaces it.
+    evaluator.set_var("rate", 1.5);
+    assert_eq!(run(&mut evaluator, "rate"), Value::Float(1.5));
+}
+
+#[test]
+fn the_symbol_table_lists_and_edits_the_globals() {
+    let mut evaluator = Evaluator::new();
+    run(&mut evaluator, "let a = 1\nlet b = 2\nlet c = 3");
+    let names = |evaluator: &Evaluator| -> Vec<String> {
+        evaluator
+            .symbol_table()
+            .iter()
+            .map(|(name, _)| name.to_string())
+            .collect()
+    };
+    assert_eq!(names(&evaluator), ["a", "b", "c"]);
+
+    for (_, value) in evaluator.symbol_table_mut().globals_mut() {
+        if let Value::Integer(n) = value {
+            *n *= 10;
+        }
+    }
+    assert_eq!(
+        evaluator.symbol_table_mut().remove("b"),
+        Some(Value::Integer(20))
+    );
+    assert_eq!(names(&evaluator), ["a", "c"]);
+    assert_eq!(run(&mut evaluator, "a + c"), Value::Integer(40));
+}
+
+#[test]
+fn scoped_evaluation_leaves_no_variables_behind() {
+    let mut evaluator = Evaluator::new();
+    evaluator.set_var("price", 20);
+    let value = evaluator
+        .eval_in_scope("let tax = price / 10\ndef total() { return price + tax }\ntotal()");
+    assert_eq!(value.unwrap(), Value::Integer(22));
+    assert_eq!(evaluator.get_var("tax"), None);
+    assert_eq!(evaluator.get_var("total"), None);
+    assert_eq!(
+        evaluator.eval_in_scope("return price * 2").unwrap(),
+        Value::Integer(40)
+    );
+    assert!(evaluator.eval_in_scope("let x = 1 +").is_err());
+    assert!(evaluator.eval_in_scope("let y = 1\ny + \"a\"").is_err());
+    assert_eq!(evaluator.get_var("y"), None);
+    // Globals are still shared.
+    evaluator.eval_in_scope("price = 30").unwrap();
+    assert_eq!(evaluator.get_var("price"), Some(Value::Integer(30)));
+}
+
+#[test]
+fn reading_a_variable_shares_its_contents() {
+    let mut evaluator = Evaluator::new();
+    run(
+        &mut evaluator,
+        "let name = \"a long string\"\ndef f(x) { return x }",
+    );
+    match (evaluator.get_var("name"), evaluator.get_var("name")) {
+        (Some(Value::String(a)), Some(Value::String(b))) => assert!(Shared::ptr_eq(&a, &b)),
+        values => panic!("{:?}", values),
+    }
+    match (evaluator.get_var("f"), evaluator.get_var("f")) {
+        (Some(Value::Function { body: a, .. }), Some(Value::Function { body: b, .. })) => {
+            assert!(Shared::ptr_eq(&a, &b))
+        }
+        values => panic!("{:?}", values),
+    }
+}
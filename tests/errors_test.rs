@@ -0,0 +1,40 @@
+use mono::models::error::{MonoError, MonoErrorKind, Runtime, Syntax};
+use std::error::Error;
+use std::thread;
+
+fn assert_error<E: Error + Send + Sync + 'static>() {}
+
+// Hosts propagate mono's errors along with their own.
+fn run(code: &str) -> Result<String, Box<dyn Error + Send + Sync>> {
+    Ok(mono::eval(code)?.to_string())
+}
+
+#[test]
+fn errors_are_send_and_sync() {
+    assert_error::<MonoErrorKind>();
+    assert_error::<MonoError>();
+    let error = thread::spawn(|| mono::eval("1 / 0").unwrap_err())
+        .join()
+        .unwrap();
+    assert!(matches!(
+        *error,
+        MonoErrorKind::Runtime(Runtime::DivisionByZero { .. })
+    ));
+}
+
+#[test]
+fn errors_convert_into_boxed_errors() {
+    assert_eq!(run("1 + 2").unwrap(), "3");
+    let error = run("1 +").unwrap_err();
+    let error = error.downcast::<MonoError>().unwrap();
+    assert!(matches!(
+        **error,
+        MonoErrorKind::Syntax(Syntax::UnexpectedEOF)
+    ));
+}
+
+#[test]
+fn errors_name_their_stage() {
+    assert_eq!(mono::eval("1 +").unwrap_err().kind(), "SyntaxError");
+    assert_eq!(mono::eval("x").unwrap_err().kind(), "RuntimeError");
+}
@@ -0,0 +1,60 @@
+mod common;
+
+use common::eval;
+use mono::models::error::{MonoErrorKind, Runtime, Syntax};
+use mono::Value;
+
+#[test]
+fn eval_runs_in_the_current_scope() {
+    assert_eq!(eval("let x = 2\neval(\"x * 3\")"), Value::Integer(6));
+    assert_eq!(eval("eval(\"let y = 4\")\ny"), Value::Integer(4));
+    let code = "def f(a) {
+    eval(\"let b = a + 1\")
+    return b
+}
+f(1)";
+    assert_eq!(eval(code), Value::Integer(2));
+}
+
+#[test]
+fn returning_ends_only_the_evaluated_code() {
+    let code = "def f() {
+    let x = eval(\"return 1\\n2\")
+    return x + 10
+}
+f()";
+    assert_eq!(eval(code), Value::Integer(11));
+}
+
+#[test]
+fn errors_in_evaluated_code_propagate() {
+    let error = mono::eval("eval(\"1 +\")").unwrap_err();
+    assert!(matches!(
+        *error,
+        MonoErrorKind::Syntax(Syntax::UnexpectedEOF)
+    ));
+    let error = mono::eval("eval(\"undefined\")").unwrap_err();
+    assert!(matches!(
+        *error,
+        MonoErrorKind::Runtime(Runtime::UnknownIdentifier { .. })
+    ));
+}
+
+#[test]
+fn run_file_runs_another_script() {
+    let path = std::env::temp_dir().join(format!("mono_eval_test_{}.mono", std::process::id()));
+    std::fs::write(&path, "let shared = base + 1\nshared * 2").unwrap();
+    let code = format!(
+        "let base = 1\nlet result = run_file({:?})\n[result, shared]",
+        path
+    );
+    let value = eval(&code);
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(value.to_string(), "[4, 2]");
+
+    let error = mono::eval("run_file(\"/nonexistent/file.mono\")").unwrap_err();
+    assert!(matches!(
+        *error,
+        MonoErrorKind::Runtime(Runtime::IoError { .. })
+    ));
+}
@@ -0,0 +1,69 @@
+#![cfg(unix)]
+
+mod common;
+
+use common::{eval, show};
+use mono::models::error::{MonoErrorKind, Runtime};
+use mono::Value;
+use std::fs;
+
+#[test]
+fn directories_are_created_listed_and_emptied() {
+    let root = std::env::temp_dir().join(format!("mono_files_test_{}", std::process::id()));
+    let root = root.to_str().unwrap();
+    // Making a directory that exists does nothing.
+    let code = format!(
+        "let root = {:?}
+mkdir(join_path(root, \"a/b\"))
+mkdir(join_path(root, \"a/b\"))",
+        root
+    );
+    eval(&code);
+    fs::write(format!("{}/a/z.txt", root), "").unwrap();
+    assert_eq!(
+        show(&format!(
+            "let root = {:?}\nlist_dir(join_path(root, \"a\"))",
+            root
+        )),
+        "[b, z.txt]"
+    );
+    eval(&format!("remove_file({:?})", format!("{}/a/z.txt", root)));
+    assert_eq!(
+        show(&format!("list_dir({:?})", format!("{}/a", root))),
+        "[b]"
+    );
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn failing_calls_are_errors() {
+    for code in [
+        "list_dir(\"/nonexistent/directory\")",
+        "remove_file(\"/nonexistent/file\")",
+        "mkdir(\"/proc/mono\")",
+    ] {
+        let error = mono::eval(code).expect_err(code);
+        assert!(
+            matches!(*error, MonoErrorKind::Runtime(Runtime::IoError { .. })),
+            "{}: {}",
+            code,
+            error
+        );
+    }
+}
+
+#[test]
+fn cwd_is_the_current_directory() {
+    let cwd = std::env::current_dir().unwrap();
+    assert_eq!(eval("cwd()"), Value::from(cwd.to_str().unwrap()));
+}
+
+#[test]
+fn paths_are_split_and_joined() {
+    assert_eq!(show("join_path(\"a/b\", \"c.txt\")"), "a/b/c.txt");
+    assert_eq!(show("join_path(\"a\", \"/etc\")"), "/etc");
+    assert_eq!(show("basename(\"a/b/c.txt\")"), "c.txt");
+    assert_eq!(show("dirname(\"a/b/c.txt\")"), "a/b");
+    assert_eq!(eval("basename(\"/\")"), Value::None);
+    assert_eq!(eval("dirname(\"/\")"), Value::None);
+}
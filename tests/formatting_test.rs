@@ -0,0 +1,68 @@
+use mono::models::error::{MonoErrorKind, Runtime};
+
+fn format(code: &str) -> String {
+    mono::eval(code)
+        .unwrap_or_else(|error| panic!("{}: {}", code, error))
+        .to_string()
+}
+
+// The reason the template of the `format` call can't be formatted.
+fn reason(code: &str) -> String {
+    match *mono::eval(code).expect_err(code) {
+        MonoErrorKind::Runtime(Runtime::InvalidFormat { reason, .. }) => reason,
+        error => panic!("{}: {}", code, error),
+    }
+}
+
+#[test]
+fn placeholders_take_the_values_in_order_or_by_index() {
+    assert_eq!(format("format(\"{} + {} = {}\", 1, 2, 3)"), "1 + 2 = 3");
+    assert_eq!(format("format(\"{1}{0}{1}\", \"a\", \"b\")"), "bab");
+    assert_eq!(format("format(\"{{{}}}\", 1)"), "{1}");
+    assert_eq!(format("format(\"no placeholders\", 1)"), "no placeholders");
+}
+
+#[test]
+fn specs_align_pad_and_round() {
+    assert_eq!(format("format(\"[{:>5}]\", 42)"), "[   42]");
+    assert_eq!(format("format(\"[{:<5}]\", 42)"), "[42   ]");
+    assert_eq!(format("format(\"[{:*^6}]\", \"ab\")"), "[**ab**]");
+    assert_eq!(format("format(\"{:.2}\", 3.14159)"), "3.14");
+    assert_eq!(format("format(\"{0:>6.1}\", 2.25)"), "   2.2");
+}
+
+#[test]
+fn unclosed_braces_are_errors() {
+    assert!(reason("format(\"{\", 1)").contains("never closed"));
+    assert!(reason("format(\"a {0\", 1)").contains("character 3"));
+}
+
+#[test]
+fn unmatched_braces_are_errors() {
+    assert!(reason("format(\"}\", 1)").contains("no matching"));
+}
+
+#[test]
+fn missing_values_are_errors() {
+    assert_eq!(
+        reason("format(\"{} {}\", 1)"),
+        "`{}` refers to value 1 but only 1 was given"
+    );
+}
+
+#[test]
+fn indices_out_of_range_are_errors() {
+    assert_eq!(
+        reason("format(\"{5}\", 1)"),
+        "`{5}` refers to value 5 but only 1 was given"
+    );
+    assert!(reason("format(\"{a}\", 1)").contains("index"));
+}
+
+#[test]
+fn unknown_specs_are_errors() {
+    assert_eq!(
+        reason("format(\"{:x}\", 255)"),
+        "`x` isn't a valid format spec"
+    );
+}
@@ -0,0 +1,342 @@
+mod common;
+
+use common::{error, eval};
+use mono::models::error::{MonoErrorKind, Runtime, Syntax};
+use mono::Value;
+
+#[test]
+fn functions_without_a_return_give_none() {
+    for body in [
+        "{ }",
+        "{ 1 }",
+        "{ let x = 1 }",
+        "{ if x { return 1 } }",
+        "{ if x { 5 } else { 6 } }",
+        "{ while False { } }",
+        "{ for y in [1] { y } }",
+        "{ match x { case _ => { 6 } } }",
+    ] {
+        let code = format!("def f(x) {}\nf(False)", body);
+        assert_eq!(eval(&code), Value::None, "{}", code);
+    }
+    assert_eq!(eval("let f() => { 1 }\nf()"), Value::None);
+    assert_eq!(
+        eval("struct A { x }\nimpl A { f(self) => { self.x } }\nA(1).f()"),
+        Value::None
+    );
+}
+
+#[test]
+fn is_none() {
+    assert_eq!(eval("is_none(None)"), Value::Boolean(true));
+    assert_eq!(eval("def f() { }\nis_none(f())"), Value::Boolean(true));
+    for value in ["0", "False", "\"\"", "[]"] {
+        let code = format!("is_none({})", value);
+        assert_eq!(eval(&code), Value::Boolean(false), "{}", code);
+    }
+}
+
+#[test]
+fn functions_print_their_signature_and_position() {
+    assert_eq!(
+        eval("def f(a, b=1, *c) { }\nf").to_string(),
+        "<Function f(a, b, *c) at [1,5]>"
+    );
+    assert_eq!(eval("print").to_string(), "<Function print(*values)>");
+    assert_eq!(eval("math.sqrt").to_string(), "<Function math.sqrt(x)>");
+}
+
+#[test]
+fn functions_are_equal_only_to_themselves() {
+    let holds = |code: &str| eval(code) == Value::Boolean(true);
+    assert!(holds("def f() { }\nlet g = f\nf == g"));
+    assert!(holds("def f() { }\ndef g() { }\nf != g"));
+    // The same declaration run twice makes two functions.
+    assert!(holds(
+        "def make() {\n    def inner() { }\n    return inner\n}\nmake() != make()"
+    ));
+    assert!(holds("print == print"));
+    assert!(holds("print != println"));
+    assert!(holds("def f() { }\nf != print"));
+    assert!(holds("def f() { }\nf in [1, f]"));
+}
+
+#[test]
+fn nested_functions_can_call_themselves() {
+    let code = "def outer() {
+    def fact(n) {
+        if n < 2 { return 1 }
+        return n * fact(n - 1)
+    }
+    return fact
+}
+let f = outer()
+f(5)";
+    assert_eq!(eval(code), Value::Integer(120));
+    let code = "def outer() {
+    let count(n) => { return if n == 0 { 0 } else { 1 + count(n - 1) } }
+    return count
+}
+let count = outer()
+count(3)";
+    assert_eq!(eval(code), Value::Integer(3));
+}
+
+#[test]
+fn a_function_calls_itself_rather_than_what_its_name_became() {
+    let code = "def f(n) { if n == 0 { return 0 } return f(n - 1) }
+let g = f
+def f(n) { return 99 }
+g(3)";
+    assert_eq!(eval(code), Value::Integer(0));
+    // An argument of the same name shadows the function.
+    assert_eq!(eval("def f(f) { return f }\nf(3)"), Value::Integer(3));
+}
+
+#[test]
+fn top_level_functions_are_declared_before_the_program_runs() {
+    let code = "let result = even(10)
+def even(n) {
+    if n == 0 { return True }
+    return odd(n - 1)
+}
+def odd(n) {
+    if n == 0 { return False }
+    return even(n - 1)
+}
+result";
+    assert_eq!(eval(code), Value::Boolean(true));
+    assert_eq!(
+        eval("let x = square(3)\nlet square(x) => { return x * x }\nx"),
+        Value::Integer(9)
+    );
+    assert_eq!(
+        eval("eval(\"let y = twice(2)\\ndef twice(x) { return 2 * x }\")\ny"),
+        Value::Integer(4)
+    );
+}
+
+#[test]
+fn a_hoisted_function_is_the_one_its_declaration_makes() {
+    let code = "let g = f
+let f() => { return 1 }
+g == f";
+    assert_eq!(eval(code), Value::Boolean(true));
+    // Each of two declarations of a name is bound once it's reached.
+    let code = "let first = f()
+def f() { return 1 }
+let second = f()
+def f() { return 2 }
+[first, second, f()]";
+    assert_eq!(eval(code).to_string(), "[2, 1, 2]");
+}
+
+#[test]
+fn only_top_level_functions_are_hoisted() {
+    assert!(mono::eval("def outer() {\n    inner()\n    def inner() { }\n}\nouter()").is_err());
+    assert!(mono::eval("f()\nif True { def f() { } }").is_err());
+}
+
+#[test]
+fn global_assigns_the_global_variable() {
+    let code = "let count = 0
+def bump() {
+    global count
+    count = count + 1
+}
+bump()
+bump()
+count";
+    assert_eq!(eval(code), Value::Integer(2));
+    assert_eq!(
+        eval("def make() {\n    global made\n    made = 1\n}\nmake()\nmade"),
+        Value::Integer(1)
+    );
+    // Even when the caller has a variable of the same name.
+    let code = "let x = 1
+def read() {
+    global x
+    return x
+}
+def caller() {
+    let x = 2
+    return read()
+}
+caller()";
+    assert_eq!(eval(code), Value::Integer(1));
+}
+
+#[test]
+fn nonlocal_assigns_the_callers_variable() {
+    let code = "def total(xs) {
+    let sum = 0
+    def add(x) {
+        nonlocal sum
+        sum = sum + x
+    }
+    for x in xs { add(x) }
+    return sum
+}
+total([1, 2, 3])";
+    assert_eq!(eval(code), Value::Integer(6));
+}
+
+#[test]
+fn invalid_declarations_are_errors() {
+    for code in [
+        "nonlocal x",
+        "def f() { nonlocal x }\nf()",
+        "def f() {\n    global x\n    let x = 1\n}\nf()",
+        "def f() {\n    let x = 1\n    global x\n}\nf()",
+        "let x = 1\ndef f() {\n    global x\n    nonlocal x\n}\ndef g() {\n    let x = 2\n    f()\n}\ng()",
+    ] {
+        let error = mono::eval(code).expect_err(code);
+        assert!(
+            matches!(
+                *error,
+                MonoErrorKind::Runtime(Runtime::InvalidDeclaration { .. })
+            ),
+            "{}: {}",
+            code,
+            error
+        );
+    }
+}
+
+#[test]
+fn hoisted_functions_keep_their_place_among_the_globals() {
+    let code = "let a = 1
+def g() { }
+struct S { x }
+let b = g()
+def h() { }
+[pair[0] for pair in globals()]";
+    assert_eq!(eval(code).to_string(), "[a, g, S, b, h]");
+    assert_eq!(eval("let a = 1\ndef g() { }\ndir()").to_string(), "[a, g]");
+}
+
+#[test]
+fn arguments_cant_be_declared_global_or_nonlocal() {
+    for code in [
+        "def f(a) { global a }",
+        "def f(a, b = 1) {\n    nonlocal b\n}",
+        "let f(*rest) => { global x, rest }",
+        "struct A { x }\nimpl A { m(self) => { nonlocal self } }",
+    ] {
+        let error = mono::parse(code).expect_err(code);
+        assert!(
+            matches!(*error, MonoErrorKind::Syntax(Syntax::OuterArgument { .. })),
+            "{}: {}",
+            code,
+            error
+        );
+    }
+    // An enclosing function's argument is another scope's variable.
+    let code = "def f(a) {
+    def g() {
+        nonlocal a
+        a = 2
+    }
+    g()
+    return a
+}
+f(1)";
+    assert_eq!(eval(code), Value::Integer(2));
+}
+
+#[test]
+fn defaults_fill_the_arguments_left_out() {
+    let code = "def greet(name, greeting = \"hello\", mark = \"!\") {
+    return greeting + \" \" + name + mark
+}
+";
+    let call = |call: &str| eval(&format!("{}{}", code, call)).to_string();
+    assert_eq!(call("greet(\"x\")"), "hello x!");
+    assert_eq!(call("greet(\"x\", \"hi\")"), "hi x!");
+    assert_eq!(call("greet(\"x\", \"hi\", \"?\")"), "hi x?");
+    // A default sees the arguments before it.
+    assert_eq!(
+        eval("def f(a, b = a * 2) { return b }\n[f(1), f(1, 5)]").to_string(),
+        "[2, 5]"
+    );
+}
+
+#[test]
+fn keywords_bind_arguments_by_name() {
+    let code = "def greet(name, greeting = \"hello\", mark = \"!\") {
+    return greeting + \" \" + name + mark
+}
+";
+    let call = |call: &str| eval(&format!("{}{}", code, call)).to_string();
+    assert_eq!(call("greet(greeting = \"hi\", name = \"x\")"), "hi x!");
+    assert_eq!(call("greet(\"x\", mark = \"?\")"), "hello x?");
+    for call in [
+        "greet(\"x\", colour = \"red\")",
+        "greet(\"x\", name = \"y\")",
+    ] {
+        let error = error(&format!("{}{}", code, call));
+        assert!(
+            matches!(
+                *error,
+                MonoErrorKind::Runtime(Runtime::InvalidKeyword { .. })
+            ),
+            "{}: {}",
+            call,
+            error
+        );
+    }
+}
+
+#[test]
+fn missing_or_extra_arguments_are_errors() {
+    for code in [
+        "def f(a, b = 1) { }\nf()",
+        "def f(a, b = 1) { }\nf(1, 2, 3)",
+    ] {
+        let error = error(code);
+        assert!(
+            matches!(
+                *error,
+                MonoErrorKind::Runtime(Runtime::IncorrectParameters { .. })
+            ),
+            "{}: {}",
+            code,
+            error
+        );
+    }
+}
+
+#[test]
+fn variadic_arguments_collect_the_rest_into_a_list() {
+    let code = "def count(first, *rest) { return [first, rest] }\n";
+    let call = |call: &str| eval(&format!("{}{}", code, call)).to_string();
+    assert_eq!(call("count(1)"), "[1, []]");
+    assert_eq!(call("count(1, 2, 3)"), "[1, [2, 3]]");
+    assert_eq!(
+        eval("def total(*xs) { return sum(xs) }\ntotal()").to_string(),
+        "0"
+    );
+}
+
+#[test]
+fn lists_spread_into_positional_arguments() {
+    let code = "def add(a, b, c = 0) { return a + b + c }\n";
+    let call = |call: &str| eval(&format!("{}{}", code, call));
+    assert_eq!(call("add(*[1, 2])"), Value::Integer(3));
+    assert_eq!(call("add(1, *[2, 3])"), Value::Integer(6));
+    assert_eq!(call("let xs = [1]\nadd(*xs, 2)"), Value::Integer(3));
+    assert_eq!(
+        eval("def all(*xs) { return xs }\nall(*[1, 2], 3, *[])").to_string(),
+        "[1, 2, 3]"
+    );
+    assert_eq!(eval("[0, *[1, 2], 3]").to_string(), "[0, 1, 2, 3]");
+    let error = error(&format!("{}add(*[1, 2, 3, 4])", code));
+    assert!(
+        matches!(
+            *error,
+            MonoErrorKind::Runtime(Runtime::IncorrectParameters { .. })
+        ),
+        "{}",
+        error
+    );
+}
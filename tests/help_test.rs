@@ -0,0 +1,57 @@
+mod common;
+
+use common::printed;
+
+// What `help` prints after the code ran.
+fn help(code: &str, value: &str) -> String {
+    let code = format!("{}\nhelp({})", code, value);
+    let (result, printed) = printed(&code);
+    result.unwrap_or_else(|error| panic!("{}: {}", code, error));
+    printed
+}
+
+#[test]
+fn functions_show_their_signature_and_docstring() {
+    let code = "def area(width, height) {
+    \"\"\"The area of the rectangle.
+    Both sides are in meters.\"\"\"
+    return width * height
+}";
+    assert_eq!(
+        help(code, "area"),
+        "area(width, height)\n    The area of the rectangle.\n    Both sides are in meters.\n"
+    );
+    assert_eq!(help("def f() { }", "f"), "f()\n    No documentation.\n");
+}
+
+#[test]
+fn signatures_show_defaults_and_variadic_arguments() {
+    assert_eq!(
+        help("def f(a, b = 2) { }", "f"),
+        "f(a, b = 2)\n    No documentation.\n"
+    );
+    assert_eq!(
+        help(
+            "let g(x, sep = \", \", end = [1, None], *rest) => { \"Joins.\" }",
+            "g"
+        ),
+        "g(x, sep = \", \", end = [1, None], *rest)\n    Joins.\n"
+    );
+}
+
+#[test]
+fn builtins_show_their_types_and_doc() {
+    assert_eq!(
+        help("", "print"),
+        "print(*values)\n    Prints the values separated by spaces.\n"
+    );
+    assert_eq!(
+        help("", "math.sqrt"),
+        "math.sqrt(x: Integer | Float)\n    The square root of x.\n"
+    );
+}
+
+#[test]
+fn other_values_show_their_type() {
+    assert_eq!(help("", "1"), "1: Integer\n    No documentation.\n");
+}
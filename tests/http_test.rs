@@ -0,0 +1,106 @@
+#![cfg(feature = "http")]
+
+mod common;
+
+use common::eval;
+use mono::models::error::{MonoErrorKind, Runtime};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::thread::{self, JoinHandle};
+
+// Serves a single request with the response, returning the server's address
+// and a handle joining to the request it received.
+fn serve(response: &'static str) -> (String, JoinHandle<String>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap().to_string();
+    let handle = thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        let mut reader = BufReader::new(stream);
+        let mut request = String::new();
+        let mut length = 0;
+        loop {
+            let mut line = String::new();
+            reader.read_line(&mut line).unwrap();
+            if let Some(value) = line.strip_prefix("Content-Length: ") {
+                length = value.trim().parse().unwrap();
+            }
+            request.push_str(&line);
+            if line == "\r\n" {
+                break;
+            }
+        }
+        let mut body = vec![0; length];
+        reader.read_exact(&mut body).unwrap();
+        request.push_str(&String::from_utf8(body).unwrap());
+        reader.get_mut().write_all(response.as_bytes()).unwrap();
+        request
+    });
+    (address, handle)
+}
+
+#[test]
+fn get_returns_the_response() {
+    let (address, server) =
+        serve("HTTP/1.1 200 OK\r\nContent-Type: text/plain\r\nContent-Length: 5\r\n\r\nhello");
+    let code = format!(
+        "let r = http_get(\"http://{}/path?q=1\")\nlet headers = r.headers\n[r.status, headers[0], r.body]",
+        address
+    );
+    assert_eq!(
+        eval(&code).to_string(),
+        "[200, (Content-Type, text/plain), hello]"
+    );
+    let request = server.join().unwrap();
+    assert!(
+        request.starts_with("GET /path?q=1 HTTP/1.1\r\n"),
+        "{}",
+        request
+    );
+    assert!(
+        request.contains(&format!("Host: {}\r\n", address)),
+        "{}",
+        request
+    );
+}
+
+#[test]
+fn post_sends_the_body_and_headers() {
+    let (address, server) = serve(
+        "HTTP/1.1 201 Created\r\nTransfer-Encoding: chunked\r\n\r\n3\r\nabc\r\n2\r\nde\r\n0\r\n\r\n",
+    );
+    let code = format!(
+        "let r = http_post(\"http://{}\", \"{{}}\", [(\"X-Test\", \"yes\")])\n[r.status, r.body]",
+        address
+    );
+    assert_eq!(eval(&code).to_string(), "[201, abcde]");
+    let request = server.join().unwrap();
+    assert!(request.starts_with("POST / HTTP/1.1\r\n"), "{}", request);
+    assert!(request.contains("X-Test: yes\r\n"), "{}", request);
+    assert!(request.ends_with("\r\n\r\n{}"), "{}", request);
+}
+
+#[test]
+fn failed_requests_are_errors() {
+    // Nothing listens on the port once the listener is dropped.
+    let address = TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap();
+    for code in [
+        format!("http_get(\"http://{}\")", address),
+        String::from("http_get(\"https://example.com\")"),
+    ] {
+        let error = mono::eval(&code).unwrap_err();
+        assert!(
+            matches!(*error, MonoErrorKind::Runtime(Runtime::IoError { .. })),
+            "{}: {}",
+            code,
+            error
+        );
+    }
+    let error = mono::eval("http_post(\"http://localhost\", \"\", [1])").unwrap_err();
+    assert!(matches!(
+        *error,
+        MonoErrorKind::Runtime(Runtime::InvalidValue { .. })
+    ));
+}
@@ -0,0 +1,45 @@
+use mono::parser::incremental::Incremental;
+
+// What the REPL would run after each line, `None` while the input is open.
+fn fed(lines: &[&str]) -> Vec<Option<String>> {
+    let mut input = Incremental::new();
+    lines.iter().map(|line| input.feed(line)).collect()
+}
+
+#[test]
+fn complete_lines_run_at_once() {
+    assert_eq!(fed(&["let x = 1\n"]), [Some("let x = 1\n".to_string())]);
+}
+
+#[test]
+fn unclosed_blocks_continue_on_the_next_line() {
+    assert_eq!(
+        fed(&["def f(x) {\n", "    return x\n", "}\n"]),
+        [
+            None,
+            None,
+            Some("def f(x) {\n    return x\n}\n".to_string())
+        ]
+    );
+    assert_eq!(
+        fed(&["let xs = [1,\n", "2]\n"]),
+        [None, Some("let xs = [1,\n2]\n".to_string())]
+    );
+}
+
+#[test]
+fn unclosed_strings_continue_on_the_next_line() {
+    assert_eq!(
+        fed(&["let s = \"\"\"a\n", "b\"\"\"\n"]),
+        [None, Some("let s = \"\"\"a\nb\"\"\"\n".to_string())]
+    );
+}
+
+#[test]
+fn errors_more_input_cant_fix_end_the_input() {
+    let mut input = Incremental::new();
+    assert_eq!(input.feed("(]\n"), Some("(]\n".to_string()));
+    assert!(!input.is_pending());
+    assert_eq!(input.feed("while True {\n"), None);
+    assert!(input.is_pending());
+}
@@ -0,0 +1,28 @@
+use mono::{Evaluator, Value};
+use std::io::Cursor;
+
+// The code's value and what it printed, reading the input given.
+fn answered(input: &'static str, code: &str) -> (Value, String) {
+    let mut evaluator = Evaluator::new();
+    evaluator.set_input(Cursor::new(input));
+    let (value, printed) =
+        evaluator.capture(|evaluator| evaluator.evaluate(&mono::parse(code).unwrap()));
+    (value.unwrap(), printed)
+}
+
+#[test]
+fn blank_lines_are_empty_and_the_end_is_none() {
+    let (value, printed) = answered("first\r\n\n", "(input(), input(), input())");
+    assert_eq!(
+        value,
+        Value::from((Value::from("first"), Value::from(""), Value::None))
+    );
+    assert_eq!(printed, "");
+}
+
+#[test]
+fn the_prompt_is_printed_before_reading() {
+    let (value, printed) = answered("Ada\n", "input(\"Name\", \"?\")");
+    assert_eq!(value, Value::from("Ada"));
+    assert_eq!(printed, "Name ?");
+}
@@ -0,0 +1,128 @@
+use mono::linter::{Lint, Linter, Rule};
+use mono::models::position::Position;
+
+fn lint(source: &str) -> Vec<Lint> {
+    let program = mono::parse(source).unwrap_or_else(|error| panic!("{}: {}", source, error));
+    Linter::new().lint(&program)
+}
+
+// The rule and the row and column of each lint.
+fn found(source: &str) -> Vec<(Rule, usize, usize)> {
+    lint(source)
+        .iter()
+        .map(|lint| (lint.rule, lint.start.row(), lint.start.column()))
+        .collect()
+}
+
+#[test]
+fn unused_variables() {
+    assert_eq!(
+        found("let x = 1\nlet y = 2\nprintln(y)"),
+        [(Rule::UnusedVariable, 1, 5)]
+    );
+    assert_eq!(
+        found("for i in [1] { println(1) }\nlet [a, *b] = [1, 2]\nb"),
+        [(Rule::UnusedVariable, 1, 5), (Rule::UnusedVariable, 2, 6)]
+    );
+    assert_eq!(
+        found("match 1 { case n => { } }"),
+        [(Rule::UnusedVariable, 1, 16)]
+    );
+}
+
+#[test]
+fn underscored_names_are_never_unused() {
+    assert!(found("let _x = 1\nfor _ in [1] { }").is_empty());
+}
+
+#[test]
+fn globals_read_by_functions_are_used() {
+    assert!(found("let f() => { x }\nlet x = 1\nf()").is_empty());
+    assert!(found("let x = 1\nlet f() => { x }\nf()").is_empty());
+}
+
+#[test]
+fn unused_parameters() {
+    assert_eq!(
+        found("let f(a, b) => { a }\nf(1, 2)"),
+        [(Rule::UnusedParameter, 1, 10)]
+    );
+    assert!(found("struct P { x }\nimpl P { get(self) => { 1 } }").is_empty());
+}
+
+#[test]
+fn unreachable_code() {
+    let lints = lint("let f() => {\n    return 1\n    println(2)\n    3\n}\nf()");
+    assert_eq!(lints.len(), 1);
+    assert_eq!(lints[0].rule, Rule::UnreachableCode);
+    assert_eq!(lints[0].start, Position::new(3, 5));
+    assert_eq!(lints[0].end, Position::new(4, 6));
+    assert_eq!(
+        found("let f(a) => {\n    if a { return 1 } else { return 2 }\n    3\n}\nf(1)"),
+        [(Rule::UnreachableCode, 3, 5)]
+    );
+    assert!(found("let f(a) => {\n    if a { return 1 }\n    2\n}\nf(1)").is_empty());
+}
+
+#[test]
+fn constant_conditions() {
+    assert_eq!(
+        found("if True { 1 }\nif 1 < 2 { 2 }\nwhile False { }\ndo { } while False"),
+        [
+            (Rule::ConstantCondition, 1, 4),
+            (Rule::ConstantCondition, 2, 4),
+            (Rule::ConstantCondition, 3, 7),
+            (Rule::ConstantCondition, 4, 14),
+        ]
+    );
+}
+
+#[test]
+fn loops_on_true_need_a_way_out() {
+    assert_eq!(
+        found("while True { println(1) }"),
+        [(Rule::ConstantCondition, 1, 7)]
+    );
+    assert!(found("let f() => { while True { return 1 } }\nf()").is_empty());
+    assert!(found("while True { exit(0) }").is_empty());
+}
+
+#[test]
+fn shadowed_names() {
+    let lints = lint("let x = 1\nlet f(x) => { x }\nf(x)");
+    assert_eq!(lints.len(), 1);
+    assert_eq!(lints[0].rule, Rule::ShadowedName);
+    assert_eq!(lints[0].start, Position::new(2, 7));
+    assert_eq!(found("let len = 1\nlen"), [(Rule::ShadowedName, 1, 5)]);
+    // Functions don't see the locals of the function they're declared in.
+    assert!(found("let f(a) => {\n    let g(a) => { a }\n    g(a)\n}\nf(1)").is_empty());
+}
+
+#[test]
+fn rules_can_be_allowed() {
+    let program = mono::parse("let x = 1\nif True { }").unwrap();
+    let mut linter = Linter::new();
+    linter.allow(Rule::UnusedVariable);
+    let lints = linter.lint(&program);
+    assert_eq!(lints.len(), 1);
+    assert_eq!(lints[0].rule, Rule::ConstantCondition);
+    let lints = Linter::with_rules(&[Rule::UnusedVariable]).lint(&program);
+    assert_eq!(lints.len(), 1);
+    assert_eq!(lints[0].rule, Rule::UnusedVariable);
+}
+
+#[test]
+fn rules_have_ids() {
+    for rule in Rule::ALL {
+        assert_eq!(Rule::from_id(rule.id()), Some(rule));
+    }
+    assert_eq!(Rule::from_id("unused"), None);
+}
+
+#[test]
+fn examples_are_clean() {
+    for name in ["primes", "recursion", "tic_tac_toe"] {
+        let source = std::fs::read_to_string(format!("examples/{}.mono", name)).unwrap();
+        assert_eq!(lint(&source), [], "{}", name);
+    }
+}
@@ -0,0 +1,101 @@
+mod common;
+
+use common::{error, eval, show};
+use mono::models::error::{MonoErrorKind, Runtime, Syntax};
+use mono::Value;
+
+#[test]
+fn floats_print_as_the_shortest_that_reads_back() {
+    assert_eq!(show("0.1 + 0.2"), "0.3");
+    assert_eq!(show("1.0"), "1.0");
+    assert_eq!(show("2.5 * 2.0"), "5.0");
+    assert_eq!(show("1.0 / 3.0"), "0.33333334");
+    // Floats and Integers print differently.
+    assert_eq!(show("[1, 1.0]"), "[1, 1.0]");
+}
+
+#[test]
+fn scientific_notation() {
+    assert_eq!(eval("1e3"), Value::Float(1000.0));
+    assert_eq!(eval("1e-3"), Value::Float(0.001));
+    assert_eq!(eval("2.5E+2"), Value::Float(250.0));
+    assert!(matches!(
+        *error("1e"),
+        MonoErrorKind::Syntax(Syntax::InvalidExponent { .. })
+    ));
+}
+
+#[test]
+fn rounding() {
+    assert_eq!(show("round(2.345, 2)"), "2.35");
+    assert_eq!(show("round(7, 1)"), "7");
+    assert_eq!(show("round(1234, -2)"), "1200.0");
+    assert_eq!(show("round(-1.25, 1)"), "-1.3");
+    assert_eq!(show("math.round(3.14159, 3)"), "3.142");
+    let error = mono::eval("round(\"a\", 1)").unwrap_err();
+    assert!(matches!(
+        *error,
+        MonoErrorKind::Runtime(Runtime::InvalidArgumentType { .. })
+    ));
+}
+
+#[test]
+fn integer_literals_in_other_bases() {
+    assert_eq!(eval("0xFF"), Value::Integer(255));
+    assert_eq!(eval("0o755"), Value::Integer(493));
+    assert_eq!(eval("0b1010"), Value::Integer(10));
+    assert_eq!(eval("1_000_000"), Value::Integer(1_000_000));
+    assert_eq!(eval("0xFF_FF"), Value::Integer(65535));
+    for code in ["0x", "0b102", "0o8"] {
+        assert!(
+            matches!(
+                *error(code),
+                MonoErrorKind::Syntax(Syntax::InvalidIntegerLiteral { .. })
+            ),
+            "{}",
+            code
+        );
+    }
+}
+
+#[test]
+fn hex_and_bin() {
+    assert_eq!(show("hex(255)"), "0xff");
+    assert_eq!(show("hex(-255)"), "-0xff");
+    assert_eq!(show("bin(10)"), "0b1010");
+    assert_eq!(show("bin(0)"), "0b0");
+    assert_eq!(eval("0xff == integer(\"255\")"), Value::Boolean(true));
+}
+
+#[test]
+fn escape_sequences() {
+    assert_eq!(eval(r#""a\tb\nc""#), Value::from("a\tb\nc"));
+    assert_eq!(
+        eval(r#""\"quoted\" \\ \0 \r""#),
+        Value::from("\"quoted\" \\ \0 \r")
+    );
+    assert_eq!(eval(r"'\''"), Value::Character('\''));
+    assert_eq!(eval(r"'\n'"), Value::Character('\n'));
+    assert!(matches!(
+        *error(r#""\q""#),
+        MonoErrorKind::Syntax(Syntax::InvalidEscape {
+            found: Some('q'),
+            ..
+        })
+    ));
+}
+
+#[test]
+fn raw_strings_keep_backslashes() {
+    assert_eq!(eval(r#"r"C:\path\new""#), Value::from(r"C:\path\new"));
+    assert_eq!(show(r#"len(r"\n")"#), "2");
+}
+
+#[test]
+fn triple_quoted_strings_span_lines() {
+    assert_eq!(
+        eval("\"\"\"first\n\"second\"\nthird\"\"\""),
+        Value::from("first\n\"second\"\nthird")
+    );
+    assert_eq!(eval("\"\"\"a\\tb\"\"\""), Value::from("a\tb"));
+}
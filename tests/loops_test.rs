@@ -0,0 +1,130 @@
+mod common;
+
+use common::{error, eval};
+use mono::models::error::{MonoErrorKind, Runtime};
+use mono::Value;
+
+// What a loop collects from iterating the value on the code's last line.
+fn collected(code: &str) -> String {
+    let (declarations, value) = code.rsplit_once('\n').unwrap_or(("", code));
+    eval(&format!(
+        "{}\nlet seen = []\nfor x in {} {{ list.push(seen, x) }}\nseen",
+        declarations, value
+    ))
+    .to_string()
+}
+
+#[test]
+fn sequences_are_iterated_in_order() {
+    assert_eq!(collected("[1, 2, 3]"), "[1, 2, 3]");
+    assert_eq!(collected("(1, \"a\")"), "[1, a]");
+    assert_eq!(collected("\"héllo\""), "[h, é, l, l, o]");
+    assert_eq!(collected("b\"ab\""), "[97, 98]");
+    assert_eq!(collected("range(2, 5)"), "[2, 3, 4]");
+    assert_eq!(collected("[]"), "[]");
+}
+
+#[test]
+fn structs_with_next_are_iterators() {
+    let code = "struct Countdown { n }
+impl Countdown {
+    next(self) => {
+        if self.n == 0 { return None }
+        self.n = self.n - 1
+        return self.n + 1
+    }
+}
+Countdown(3)";
+    assert_eq!(collected(code), "[3, 2, 1]");
+}
+
+#[test]
+fn structs_with_iter_hand_out_an_iterable() {
+    let code = "struct Bag { items }
+impl Bag { iter(self) => { return self.items } }
+Bag([1, 2])";
+    assert_eq!(collected(code), "[1, 2]");
+}
+
+#[test]
+fn the_loop_variable_outlives_the_loop() {
+    assert_eq!(eval("for x in [1, 2] { }\nx"), Value::Integer(2));
+}
+
+#[test]
+fn other_values_are_not_iterable() {
+    for code in [
+        "for x in 1 { }",
+        "for x in None { }",
+        "struct P { x }\nfor x in P(1) { }",
+    ] {
+        let error = error(code);
+        assert!(
+            matches!(*error, MonoErrorKind::Runtime(Runtime::NonIterable { .. })),
+            "{}: {}",
+            code,
+            error
+        );
+    }
+}
+
+#[test]
+fn comprehensions_map_and_filter() {
+    assert_eq!(
+        eval("let xs = [3, -1, 2]\n[x * 2 for x in xs if x > 0]").to_string(),
+        "[6, 4]"
+    );
+    assert_eq!(eval("[c for c in \"abc\"]").to_string(), "[a, b, c]");
+    assert_eq!(eval("[x for x in []]").to_string(), "[]");
+    assert_eq!(
+        eval("[[x * y for y in range(1, 3)] for x in range(1, 3)]").to_string(),
+        "[[1, 2], [2, 4]]"
+    );
+}
+
+#[test]
+fn comprehension_variables_stay_inside() {
+    let error = error("let ys = [x for x in [1]]\nx");
+    assert!(matches!(
+        *error,
+        MonoErrorKind::Runtime(Runtime::UnknownIdentifier { .. })
+    ));
+    // A variable of the same name outside is left alone.
+    assert_eq!(
+        eval("let x = 5\nlet ys = [x for x in [1]]\nx"),
+        Value::Integer(5)
+    );
+}
+
+#[test]
+fn enumerate_and_zip_make_pairs() {
+    assert_eq!(eval("enumerate(\"ab\")").to_string(), "[[0, a], [1, b]]");
+    // Zipping stops at the shorter sequence.
+    assert_eq!(
+        eval("zip([1, 2, 3], (\"a\", \"b\"))").to_string(),
+        "[[1, a], [2, b]]"
+    );
+    assert_eq!(eval("enumerate(5)"), Value::None);
+}
+
+#[test]
+fn for_loops_destructure_their_elements() {
+    let code = "let total = 0
+for i, (a, b) in enumerate([(1, 2), (3, 4)]) {
+    total = total + i * (a + b)
+}
+total";
+    assert_eq!(eval(code), Value::Integer(7));
+    assert_eq!(
+        eval("let seen = []\nfor x, y in zip([1, 2], [3, 4]) { list.push(seen, x * y) }\nseen")
+            .to_string(),
+        "[3, 8]"
+    );
+    for code in ["for a, b in [[1]] { }", "for a, b in [1] { }"] {
+        let error = mono::eval(code).expect_err(code);
+        assert!(matches!(
+            *error,
+            MonoErrorKind::Runtime(Runtime::InvalidDestructuring { .. })
+        ));
+    }
+}
@@ -0,0 +1,159 @@
+#![cfg(feature = "lsp")]
+
+use serde_json::{json, Value as Json};
+use std::io::{Read, Write};
+use std::process::{Command, Stdio};
+
+const URI: &str = "file:///test.mono";
+
+fn frame(message: &Json) -> String {
+    let body = message.to_string();
+    format!("Content-Length: {}\r\n\r\n{}", body.len(), body)
+}
+
+// Runs `mono lsp` over the messages, followed by a shutdown and an exit, and
+// returns every message it wrote back.
+fn session(messages: &[Json]) -> Vec<Json> {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_mono"))
+        .arg("lsp")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .unwrap();
+    let mut input: String = messages.iter().map(frame).collect();
+    input.push_str(&frame(
+        &json!({ "jsonrpc": "2.0", "id": 0, "method": "shutdown" }),
+    ));
+    input.push_str(&frame(&json!({ "jsonrpc": "2.0", "method": "exit" })));
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(input.as_bytes())
+        .unwrap();
+    let mut output = String::new();
+    child
+        .stdout
+        .take()
+        .unwrap()
+        .read_to_string(&mut output)
+        .unwrap();
+    assert!(child.wait().unwrap().success());
+
+    let mut replies: Vec<Json> = Vec::new();
+    let mut rest = output.as_str();
+    while let Some((header, body)) = rest.split_once("\r\n\r\n") {
+        let length: usize = header
+            .strip_prefix("Content-Length: ")
+            .and_then(|length| length.parse().ok())
+            .unwrap_or_else(|| panic!("bad header {:?}", header));
+        replies.push(serde_json::from_str(&body[..length]).unwrap());
+        rest = &body[length..];
+    }
+    // The shutdown's reply comes last.
+    assert_eq!(replies.pop().unwrap()["id"], 0);
+    replies
+}
+
+fn open(text: &str) -> Json {
+    json!({
+        "jsonrpc": "2.0",
+        "method": "textDocument/didOpen",
+        "params": { "textDocument": { "uri": URI, "text": text } },
+    })
+}
+
+fn hover(id: u64, line: u64, character: u64) -> Json {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "method": "textDocument/hover",
+        "params": {
+            "textDocument": { "uri": URI },
+            "position": { "line": line, "character": character },
+        },
+    })
+}
+
+#[test]
+fn initialize_lists_the_capabilities() {
+    let replies =
+        session(&[json!({ "jsonrpc": "2.0", "id": 1, "method": "initialize", "params": {} })]);
+    assert_eq!(replies.len(), 1);
+    assert_eq!(replies[0]["id"], 1);
+    let capabilities = &replies[0]["result"]["capabilities"];
+    assert_eq!(capabilities["textDocumentSync"], 1);
+    assert_eq!(capabilities["hoverProvider"], true);
+    assert_eq!(capabilities["definitionProvider"], true);
+    assert_eq!(replies[0]["result"]["serverInfo"]["name"], "mono");
+}
+
+#[test]
+fn opening_a_document_publishes_its_diagnostics() {
+    let replies = session(&[open("let len = 1\nlet x = 1 +")]);
+    assert_eq!(replies[0]["method"], "textDocument/publishDiagnostics");
+    assert_eq!(replies[0]["params"]["uri"], URI);
+    let diagnostics = replies[0]["params"]["diagnostics"].as_array().unwrap();
+    assert_eq!(diagnostics.len(), 2);
+    assert_eq!(diagnostics[0]["severity"], 1);
+    assert!(diagnostics[0]["message"]
+        .as_str()
+        .unwrap()
+        .starts_with("SyntaxError"));
+    assert_eq!(diagnostics[1]["severity"], 2);
+    assert_eq!(
+        diagnostics[1]["range"],
+        json!({ "start": { "line": 0, "character": 4 }, "end": { "line": 0, "character": 7 } })
+    );
+}
+
+#[test]
+fn changing_a_document_clears_fixed_diagnostics() {
+    let change = json!({
+        "jsonrpc": "2.0",
+        "method": "textDocument/didChange",
+        "params": {
+            "textDocument": { "uri": URI },
+            "contentChanges": [{ "text": "let x = 1 + 2" }],
+        },
+    });
+    let replies = session(&[open("let x = 1 +"), change]);
+    assert_eq!(replies.len(), 2);
+    assert_eq!(
+        replies[0]["params"]["diagnostics"]
+            .as_array()
+            .unwrap()
+            .len(),
+        1
+    );
+    assert_eq!(replies[1]["params"]["diagnostics"], json!([]));
+}
+
+#[test]
+fn hover_describes_declarations_and_builtins() {
+    let replies = session(&[
+        open("def add(a, b) { return a + b }\nprint(add(1, 2))"),
+        hover(1, 1, 7),
+        hover(2, 1, 1),
+        hover(3, 0, 3),
+    ]);
+    let contents = |reply: &Json| reply["result"]["contents"]["value"].clone();
+    assert_eq!(replies[1]["id"], 1);
+    assert_eq!(contents(&replies[1]), "```mono\nlet add(a, b)\n```");
+    assert_eq!(replies[2]["id"], 2);
+    assert_eq!(
+        contents(&replies[2]),
+        "```mono\nbuiltin print(*values)\n```"
+    );
+    // Nothing to describe over the keyword.
+    assert_eq!(replies[3]["id"], 3);
+    assert_eq!(replies[3]["result"], Json::Null);
+}
+
+#[test]
+fn unknown_requests_are_errors() {
+    let replies = session(&[json!({ "jsonrpc": "2.0", "id": 1, "method": "textDocument/rename" })]);
+    assert_eq!(replies[0]["id"], 1);
+    assert_eq!(replies[0]["error"]["code"], -32601);
+}
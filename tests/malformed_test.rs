@@ -0,0 +1,300 @@
+use mono::models::diagnostic::{Diagnostic, Severity};
+use mono::models::error::{MonoErrorKind, Runtime, Syntax};
+use mono::models::position::Position;
+use mono::Value;
+use std::panic;
+
+// Programs that are malformed or fail at runtime, each has to come back as
+// an error rather than a panic.
+const CORPUS: &[&str] = &[
+    // Unfinished or misplaced syntax.
+    "",
+    "(",
+    ")",
+    "[",
+    "]",
+    "{",
+    "=>",
+    "let",
+    "let =",
+    "let f() =>",
+    "let f(*a, b) => { }",
+    "let f(a=1, b) => { }",
+    "let f(a, a) => { }",
+    "struct",
+    "struct A { x, x }",
+    "impl A { let f() => { } }",
+    "enum",
+    "match",
+    "match x {",
+    "match x { case }",
+    "case 1 => { }",
+    "return",
+    "del",
+    "del 1",
+    "1 +",
+    "* 2",
+    "not",
+    "f(1, 2",
+    "x?",
+    "a.",
+    "a.1",
+    "1..2",
+    "\"unclosed",
+    "'ab'",
+    "'",
+    "\"\\q\"",
+    "b\"\\xZZ\"",
+    "0x",
+    "0xZZ",
+    "0b2",
+    "1e",
+    "1.2.3",
+    "99999999999",
+    "1e99",
+    "§",
+    // Nested deeper than the parser allows.
+    "((((((((((((((((((((((((((((((((((((((((((((((((((((((((((((((((((((1",
+    "-----------------------------------------------------------------------1",
+    // Runtime errors.
+    "1 / 0",
+    "1 % 0",
+    "1.0 / 0.0",
+    "2147483647 + 1",
+    "-2147483647 - 2",
+    "65536 * 65536",
+    "-(-2147483647 - 1)",
+    "(-2147483647 - 1) / -1",
+    "(-2147483647 - 1) % -1",
+    "2 ^ 40",
+    "sum([2147483647, 1])",
+    "\"abc\" * -1",
+    "\"a\" + 1",
+    "not []",
+    "1 < \"a\"",
+    "let l = [1]\nl[5]",
+    "let l = [1]\nl[-5]",
+    "let l = [1]\nl[-2147483647 - 1]",
+    "let l = []\nl[0] = 1",
+    "let s = \"abc\"\ns[9]",
+    "let b = b\"ab\"\nb[-9]",
+    "let t = (1, 2)\nt[9]",
+    "let x = 1\nx[0]",
+    "undefined",
+    "del undefined",
+    "global",
+    "global 1",
+    "nonlocal x",
+    "let f(a) => { return a }\nf(1, 2)",
+    "let f(a=b) => { return a }\nf()",
+    "let f = 1\nf(2)",
+    "struct A { x }\nA(1).y",
+    "struct A { x }\nlet a = A(1)\na.y = 2",
+    "enum E { A }\nE.B",
+    "None.x",
+    "\"a\".nope()",
+    "let a, b, c = [1, 2]",
+    "let [a, *b, *c] = [1]",
+    "for x in 1 { }",
+    "[x for x in 1]",
+    "if 1 { }",
+    "match 1 { case 1 if 1 => { } }",
+    "len(1)",
+    "min([])",
+    "max(1)",
+    "sort([1, \"a\"])",
+    "range(0, 10, 0)",
+    "eval(\"1 +\")",
+    "eval(1)",
+    "run_file(\"/nonexistent/mono\")",
+];
+
+#[test]
+fn malformed_programs_never_panic() {
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+    let panicked: Vec<&str> = CORPUS
+        .iter()
+        .copied()
+        .filter(|code| panic::catch_unwind(|| mono::eval(code)).is_err())
+        .collect();
+    panic::set_hook(hook);
+    assert!(panicked.is_empty(), "panicked on {:?}", panicked);
+}
+
+#[test]
+fn integer_overflow_is_an_error() {
+    for code in [
+        "2147483647 + 1",
+        "-(-2147483647 - 1)",
+        "2 ^ 31",
+        "math.abs(-2147483647 - 1)",
+        "math.floor(3000000000.0)",
+        "math.ceil(-3000000000.0)",
+        "math.floor(float.from(\"nan\"))",
+    ] {
+        let error = mono::eval(code).expect_err(code);
+        assert!(
+            matches!(
+                *error,
+                MonoErrorKind::Runtime(Runtime::IntegerOverflow { .. })
+            ),
+            "{}: {}",
+            code,
+            error
+        );
+    }
+}
+
+#[test]
+fn dividing_by_zero_is_an_error() {
+    for code in ["1 % 0", "0 ^ -1", "0.0 ^ -0.5"] {
+        let error = mono::eval(code).expect_err(code);
+        assert!(
+            matches!(
+                *error,
+                MonoErrorKind::Runtime(Runtime::DivisionByZero { .. })
+            ),
+            "{}: {}",
+            code,
+            error
+        );
+    }
+}
+
+#[test]
+fn builtins_check_their_argument_types() {
+    for (code, argument) in [
+        ("sleep(\"1\")", "milliseconds"),
+        ("math.sqrt(True)", "x"),
+        ("format(1, 2)", "template"),
+        ("list.push((1,), 2)", "list"),
+    ] {
+        match *mono::eval(code).expect_err(code) {
+            MonoErrorKind::Runtime(Runtime::InvalidArgumentType {
+                argument: found, ..
+            }) => assert_eq!(found, argument, "{}", code),
+            error => panic!("{}: {}", code, error),
+        }
+    }
+    assert_eq!(
+        mono::eval("math.abs(-1.5)").unwrap(),
+        mono::Value::Float(1.5)
+    );
+    assert_eq!(
+        mono::eval("format(\"{}\", True)").unwrap(),
+        mono::Value::from("True")
+    );
+}
+
+#[test]
+fn number_parsing_says_why_it_failed() {
+    let attempt = |code: &str| match mono::eval(code).unwrap() {
+        Value::Tuple(pair) => (pair[0].clone(), pair[1].to_string()),
+        value => panic!("{}: {}", code, value),
+    };
+    assert_eq!(
+        attempt("try_integer(\"-12\")"),
+        (Value::Integer(-12), String::from("None"))
+    );
+    assert_eq!(
+        attempt("try_integer(\"3000000000\")"),
+        (
+            Value::None,
+            String::from("`3000000000` doesn't fit in an Integer")
+        )
+    );
+    assert_eq!(
+        attempt("try_float(\"1.5x\")"),
+        (Value::None, String::from("`1.5x` isn't a Float"))
+    );
+    assert_eq!(mono::eval("float(\"1.5\")").unwrap(), Value::Float(1.5));
+    assert_eq!(mono::eval("integer(\"\")").unwrap(), Value::None);
+}
+
+fn suggestion(code: &str) -> Option<String> {
+    match *mono::eval(code).expect_err(code) {
+        MonoErrorKind::Runtime(Runtime::UnknownIdentifier { suggestion, .. })
+        | MonoErrorKind::Syntax(Syntax::UnexpectedToken { suggestion, .. }) => suggestion,
+        error => panic!("{}: {}", code, error),
+    }
+}
+
+#[test]
+fn unknown_identifiers_suggest_a_name() {
+    assert_eq!(suggestion("prnt(1)").as_deref(), Some("print"));
+    assert_eq!(suggestion("let count = 1\ncuont").as_deref(), Some("count"));
+    assert_eq!(
+        suggestion("let f(total) => { totla }\nf(1)").as_deref(),
+        Some("total")
+    );
+    assert_eq!(suggestion("undefined"), None);
+    // Too short to tell a typo from another name.
+    assert_eq!(suggestion("let ab = 1\nac"), None);
+}
+
+#[test]
+fn misspelt_keywords_are_suggested() {
+    assert_eq!(suggestion("whlie x < 3 { }").as_deref(), Some("while"));
+    assert_eq!(suggestion("if x { 1 } esle { 2 }").as_deref(), Some("else"));
+    assert_eq!(suggestion("strcut P { x }").as_deref(), Some("struct"));
+    assert_eq!(suggestion("x y {"), None);
+    assert_eq!(suggestion("whlie\nx {"), None);
+}
+
+#[test]
+fn suggestions_are_part_of_the_message() {
+    let error = mono::eval("prnt(1)").expect_err("prnt(1)");
+    assert!(
+        error.to_string().ends_with("Did you mean `print`?"),
+        "{}",
+        error
+    );
+}
+
+#[test]
+fn operating_on_none_asks_about_a_missing_return() {
+    let cases = [
+        ("def f() { }\nf() + 1", "The left value is None"),
+        ("1 < None", "The right value is None"),
+        ("None * None", "Both values are None"),
+        ("-None", "The value is None"),
+    ];
+    for (code, hint) in cases {
+        let error = mono::eval(code).expect_err(code).to_string();
+        assert!(
+            error.ends_with(&format!("{}, did a function forget to return?", hint)),
+            "{}",
+            error
+        );
+    }
+    let error = mono::eval("1 + 1.5").expect_err("1 + 1.5").to_string();
+    assert!(!error.contains("forget to return"), "{}", error);
+}
+
+#[test]
+fn errors_become_diagnostics() {
+    let error = mono::eval("let x = 1\nx + \"a\"").expect_err("x + \"a\"");
+    let diagnostic = Diagnostic::from(error.as_ref());
+    assert_eq!(diagnostic.kind, "RuntimeError");
+    assert_eq!(diagnostic.severity, Severity::Error);
+    assert_eq!(diagnostic.start, Some(Position::new(2, 3)));
+    assert_eq!(diagnostic.end, Some(Position::new(2, 4)));
+    assert_eq!(diagnostic.message, error.to_string());
+
+    let error = mono::eval("1 +").expect_err("1 +");
+    let diagnostic = Diagnostic::from(error.as_ref());
+    assert_eq!((diagnostic.start, diagnostic.end), (None, None));
+}
+
+#[test]
+fn diagnostics_print_as_json() {
+    let error = mono::eval("'a").expect_err("'a");
+    assert_eq!(
+        Diagnostic::from(error.as_ref()).to_json(),
+        "{\"kind\": \"SyntaxError\", \"message\": \"Encountered unclosed Character delimiter `'`. \
+         Character deceleration starts at [1,1] but a closing delimiter was not found.\", \
+         \"severity\": \"error\", \"span\": {\"start\": {\"row\": 1, \"column\": 1}, \
+         \"end\": {\"row\": 1, \"column\": 3}}}"
+    );
+}
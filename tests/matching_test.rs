@@ -0,0 +1,131 @@
+mod common;
+
+use common::{eval, show};
+use mono::models::error::{MonoErrorKind, Runtime};
+use mono::Value;
+
+#[test]
+fn literals_and_wildcards() {
+    let code = |value: &str| {
+        format!(
+            "match {} {{ case 1 => {{ \"one\" }} case \"a\" | 'b' => {{ \"letter\" }} case _ => {{ \"other\" }} }}",
+            value
+        )
+    };
+    assert_eq!(show(&code("1")), "one");
+    assert_eq!(show(&code("\"a\"")), "letter");
+    assert_eq!(show(&code("'b'")), "letter");
+    assert_eq!(show(&code("2")), "other");
+}
+
+#[test]
+fn no_arm_taken_gives_none() {
+    assert_eq!(eval("match 5 { case 1 => { \"one\" } }"), Value::None);
+    assert_eq!(
+        eval("match [1] { case [] => { 1 } case (a, b) => { 2 } }"),
+        Value::None
+    );
+}
+
+#[test]
+fn bindings_are_variables_of_the_enclosing_scope() {
+    assert_eq!(show("match 3 { case n => { n * 2 } }"), "6");
+    assert_eq!(show("match 3 { case n => { } }\nn"), "3");
+    // `_` binds nothing, even as a rest pattern.
+    let error = mono::eval("match [1, 2] { case [_, *_] => { } }\n_").unwrap_err();
+    assert!(matches!(
+        *error,
+        MonoErrorKind::Runtime(Runtime::UnknownIdentifier { .. })
+    ));
+    let code = "def f(x) {
+    match x { case y => { } }
+    return y
+}
+f(4)";
+    assert_eq!(show(code), "4");
+}
+
+#[test]
+fn nested_destructuring() {
+    assert_eq!(
+        show("match [1, [2, 3, 4], (5, 6)] { case [a, [b, *c], (d, _)] => { (a, b, c, d) } }"),
+        "(1, 2, [3, 4], 5)"
+    );
+    assert_eq!(
+        show("match [1, 2, 3] { case [*a, b] => { (a, b) } }"),
+        "([1, 2], 3)"
+    );
+    assert_eq!(show("match [1] { case [a, *b] => { b } }"), "[]");
+    // Lists and tuples only match their own kind, and only at their length.
+    assert_eq!(
+        show("match (1, 2) { case [a, b] => { \"list\" } case (a, b, c) => { \"three\" } case (a, b) => { \"pair\" } }"),
+        "pair"
+    );
+    let code = "struct Point { x, y }
+struct Line { start, end }
+match Line(Point(0, 1), Point(2, 3)) {
+    case Line { start: Point { x: 0, y }, end: { x } } => { (y, x) }
+}";
+    assert_eq!(show(code), "(1, 2)");
+}
+
+#[test]
+fn struct_patterns_check_the_type_and_fields() {
+    let code = |value: &str| {
+        format!(
+            "struct A {{ x }}
+struct B {{ x }}
+match {} {{ case A {{ x: 1 }} => {{ \"A one\" }} case A {{ x }} => {{ x }} case {{ x }} => {{ \"any\" }} }}",
+            value
+        )
+    };
+    assert_eq!(show(&code("A(1)")), "A one");
+    assert_eq!(show(&code("A(2)")), "2");
+    assert_eq!(show(&code("B(1)")), "any");
+    assert_eq!(eval(&code("1")), Value::None);
+}
+
+#[test]
+fn enum_variants() {
+    let code = |value: &str| {
+        format!(
+            "enum Color {{ Red, Green, Blue }}
+enum Light {{ Red }}
+match {} {{ case Color.Red => {{ \"red\" }} case Color.Green | Color.Blue => {{ \"cold\" }} case _ => {{ \"other\" }} }}",
+            value
+        )
+    };
+    assert_eq!(show(&code("Color.Red")), "red");
+    assert_eq!(show(&code("Color.Blue")), "cold");
+    // A variant of the same name in another enum is another value.
+    assert_eq!(show(&code("Light.Red")), "other");
+    assert_eq!(show(&code("\"Red\"")), "other");
+}
+
+#[test]
+fn guards_fall_through_to_later_arms() {
+    let code = "def sign(xs) {
+    match xs {
+        case [x, *_] if x > 0 => { return \"positive\" }
+        case [x, *_] if x < 0 => { return \"negative\" }
+        case [_, *_] => { return \"zero\" }
+        case _ => { return \"empty\" }
+    }
+}
+[sign([1, 2]), sign([-1]), sign([0]), sign([])]";
+    assert_eq!(show(code), "[positive, negative, zero, empty]");
+    // The guard sees the arm's bindings and whatever else is in scope.
+    assert_eq!(
+        show("let limit = 2\nmatch 3 { case n if n > limit => { n } case _ => { 0 } }"),
+        "3"
+    );
+}
+
+#[test]
+fn guards_must_be_booleans() {
+    let error = mono::eval("match 1 { case x if x => { } }").unwrap_err();
+    assert!(matches!(
+        *error,
+        MonoErrorKind::Runtime(Runtime::InvalidCondition { .. })
+    ));
+}
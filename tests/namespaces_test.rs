@@ -0,0 +1,90 @@
+mod common;
+
+use common::{error, eval, printed};
+use mono::models::error::{MonoErrorKind, Runtime};
+use mono::Value;
+
+#[test]
+fn builtins_are_grouped_into_namespaces() {
+    assert_eq!(eval("math.sqrt(16)"), Value::Float(4.0));
+    assert_eq!(eval("math.max(2, 5)"), Value::Integer(5));
+    assert_eq!(eval("string.upper(\"ab\")").to_string(), "AB");
+    assert_eq!(eval("integer.from(\"42\")"), Value::Integer(42));
+    assert_eq!(
+        eval("let xs = [1]\nlist.push(xs, 2)\n[list.pop(xs), xs]").to_string(),
+        "[2, [1]]"
+    );
+    let (result, printed) = printed("io.println(\"a\")");
+    result.unwrap();
+    assert_eq!(printed, "a\n");
+}
+
+#[test]
+fn flat_names_stay_as_aliases() {
+    let (result, printed) = printed("print(1)\nio.print(1)");
+    result.unwrap();
+    assert_eq!(printed, "11");
+    assert_eq!(
+        eval("[integer(\"7\"), integer.from(\"7\"), string(7), string.from(7)]").to_string(),
+        "[7, 7, 7, 7]"
+    );
+}
+
+#[test]
+fn namespaces_print_their_name() {
+    assert_eq!(eval("math").to_string(), "<Namespace: math>");
+}
+
+#[test]
+fn unknown_members_are_errors() {
+    assert!(matches!(
+        *error("math.tau"),
+        MonoErrorKind::Runtime(Runtime::UnknownMember { .. })
+    ));
+}
+
+#[test]
+fn shadowed_builtins_stay_reachable_through_builtins() {
+    assert_eq!(
+        eval("let len = 5\n[len, builtins.len(\"abc\")]").to_string(),
+        "[5, 3]"
+    );
+    let code = "def f(xs) {\n    let len = 0\n    return builtins.len(xs) + len\n}\nf([1, 2])";
+    assert_eq!(eval(code), Value::Integer(2));
+    // Shadowing in a function leaves the global builtin alone.
+    assert_eq!(
+        eval("def f() { let len = 1\nreturn len }\n[f(), len(\"ab\")]").to_string(),
+        "[1, 2]"
+    );
+}
+
+#[test]
+fn del_removes_a_variable_of_the_current_scope() {
+    let error = mono::eval("let x = 1\ndel x\nx").unwrap_err();
+    assert!(matches!(
+        *error,
+        MonoErrorKind::Runtime(Runtime::UnknownIdentifier { .. })
+    ));
+    assert_eq!(
+        eval("let x = 1\ndef f() {\n    let x = 2\n    del x\n    return x\n}\nf()"),
+        Value::Integer(1)
+    );
+    // Deleting a shadowing variable reveals the builtin again.
+    assert_eq!(eval("let len = 5\ndel len\nlen(\"ab\")"), Value::Integer(2));
+}
+
+#[test]
+fn builtins_cant_be_deleted() {
+    for code in ["del len", "del undefined", "let x = 1\ndel x\ndel x"] {
+        let error = mono::eval(code).expect_err(code);
+        assert!(
+            matches!(
+                *error,
+                MonoErrorKind::Runtime(Runtime::UnknownIdentifier { .. })
+            ),
+            "{}: {}",
+            code,
+            error
+        );
+    }
+}
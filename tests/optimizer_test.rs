@@ -0,0 +1,65 @@
+use mono::models::error::{MonoErrorKind, Runtime};
+use mono::{Evaluator, Optimizer, Value};
+
+// The optimized program, rendered back into source.
+fn optimize(code: &str) -> String {
+    let program = mono::parse(code).unwrap_or_else(|error| panic!("{}: {}", code, error));
+    Optimizer::optimize(*program).to_source()
+}
+
+fn optimized(code: &str) -> Result<Value, mono::models::error::MonoError> {
+    mono::optimized(code, &mut Evaluator::new())
+}
+
+#[test]
+fn constant_expressions_are_folded() {
+    assert_eq!(optimize("let x = 1 + 2 * 3"), "let x = 7");
+    assert_eq!(optimize("let x = -(2 ^ 3)"), "let x = -8");
+    assert_eq!(optimize("let s = \"a\" + \"b\""), "let s = \"ab\"");
+    assert_eq!(optimize("let b = not (1 < 2) or False"), "let b = False");
+    // Only the constant part of an expression is folded.
+    assert_eq!(optimize("let y = x + 2 * 3"), "let y = x + 6");
+}
+
+#[test]
+fn loops_lose_their_constant_subexpressions() {
+    assert_eq!(
+        optimize("while i < 10 * 10 {\n    i = i + 60 * 60\n}"),
+        "while i < 100 {\n    i = i + 3600\n}"
+    );
+}
+
+#[test]
+fn branches_on_literals_are_replaced_by_the_branch_taken() {
+    assert_eq!(
+        optimize("if 1 < 2 { a() } else { b() }"),
+        "if True {\n    a()\n}"
+    );
+    assert_eq!(
+        optimize("if False { a() } else { b() }"),
+        "if True {\n    b()\n}"
+    );
+    assert_eq!(optimize("if False { a() }"), "None");
+    assert_eq!(optimize("while False { a() }"), "None");
+    assert_eq!(optimize("do { a() } while False"), "if True {\n    a()\n}");
+}
+
+#[test]
+fn variables_are_left_alone() {
+    // `k * 2` doesn't change in the loop, but `bump` could change `k`.
+    let code = "def bump() {\n    global k\n    k = k + 1\n}\nlet k = 1\nlet i = 0\nlet total = 0\nwhile i < 3 {\n    total = total + k * 2\n    bump()\n    i = i + 1\n}\ntotal";
+    assert_eq!(optimized(code).unwrap(), Value::Integer(12));
+    assert_eq!(optimized(code).unwrap(), mono::eval(code).unwrap());
+}
+
+#[test]
+fn failing_expressions_are_left_for_the_evaluator() {
+    assert_eq!(optimize("let x = 1 / 0"), "let x = 1 / 0");
+    let error = optimized("let x = 1 / 0").unwrap_err();
+    assert!(matches!(
+        *error,
+        MonoErrorKind::Runtime(Runtime::DivisionByZero { .. })
+    ));
+    // A loop that never runs never fails.
+    assert_eq!(optimized("while False { 1 / 0 }").unwrap(), Value::None);
+}
@@ -0,0 +1,40 @@
+mod common;
+
+use common::{eval, show};
+use mono::models::error::{MonoErrorKind, Runtime};
+use mono::Value;
+
+#[test]
+fn coalescing_replaces_only_none() {
+    assert_eq!(eval("None ?? 1"), Value::Integer(1));
+    assert_eq!(eval("0 ?? 1"), Value::Integer(0));
+    assert_eq!(eval("False ?? True"), Value::Boolean(false));
+    assert_eq!(eval("None ?? None ?? 3"), Value::Integer(3));
+    assert_eq!(eval("1 + (None ?? 2)"), Value::Integer(3));
+}
+
+#[test]
+fn coalescing_evaluates_the_default_only_when_needed() {
+    // `undefined` would be an error if it were evaluated.
+    assert_eq!(eval("1 ?? undefined"), Value::Integer(1));
+    let error = mono::eval("None ?? undefined").unwrap_err();
+    assert!(matches!(
+        *error,
+        MonoErrorKind::Runtime(Runtime::UnknownIdentifier { .. })
+    ));
+}
+
+#[test]
+fn optional_indexes_and_calls() {
+    assert_eq!(eval("let xs = None\nxs?[0]"), Value::None);
+    assert_eq!(eval("let xs = [5]\nxs?[0]"), Value::Integer(5));
+    assert_eq!(eval("let f = None\nf?(1)"), Value::None);
+    assert_eq!(show("let f = len\nf?(\"ab\")"), "2");
+    assert_eq!(show("let xs = None\nxs?[0] ?? \"empty\""), "empty");
+    // Only `None` is skipped, other failures still raise.
+    let error = mono::eval("let xs = [5]\nxs?[3]").unwrap_err();
+    assert!(matches!(
+        *error,
+        MonoErrorKind::Runtime(Runtime::InvalidIndex { .. })
+    ));
+}
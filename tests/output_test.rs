@@ -0,0 +1,56 @@
+use mono::Evaluator;
+use std::io::{self, Write};
+use std::sync::{Arc, Mutex};
+
+#[derive(Clone, Default)]
+struct Lines(Arc<Mutex<Vec<u8>>>);
+
+impl Write for Lines {
+    fn write(&mut self, bytes: &[u8]) -> io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(bytes);
+        Ok(bytes.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+fn run(evaluator: &mut Evaluator, code: &str) {
+    evaluator.evaluate(&mono::parse(code).unwrap()).unwrap();
+}
+
+#[test]
+fn hosts_install_their_own_output() {
+    let lines = Lines::default();
+    let mut evaluator = Evaluator::new();
+    evaluator.set_output(lines.clone());
+    run(&mut evaluator, "print(1, 2)\nprintln(\"a\")");
+    assert_eq!(String::from_utf8_lossy(&lines.0.lock().unwrap()), "1 2a\n");
+}
+
+#[test]
+fn captures_nest() {
+    let mut evaluator = Evaluator::new();
+    let (inner, outer) = evaluator.capture(|evaluator| {
+        run(evaluator, "print(\"outer \")");
+        evaluator
+            .capture(|evaluator| run(evaluator, "print(\"inner\")"))
+            .1
+    });
+    assert_eq!(inner, "inner");
+    assert_eq!(outer, "outer ");
+}
+
+#[test]
+fn each_evaluator_has_its_own_output() {
+    let (mut first, mut second) = (Evaluator::new(), Evaluator::new());
+    let ((_, printed_second), printed_first) = first.capture(|first| {
+        run(first, "print(1)");
+        second.capture(|second| run(second, "print(2)"))
+    });
+    assert_eq!(
+        (printed_first.as_str(), printed_second.as_str()),
+        ("1", "2")
+    );
+}
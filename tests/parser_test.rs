@@ -0,0 +1,615 @@
+use mono::models::error::{MonoError, MonoErrorKind, Syntax};
+use mono::models::position::Position;
+use mono::parser::node::{Argument, Node};
+use mono::parser::visitor::{fold_children, visit_children, NodeFolder, NodeVisitor};
+use mono::parser::Parser;
+use mono::tokenizer::token::{Token, TokenKind};
+use mono::tokenizer::Tokenizer;
+
+// Builds the tree a program is expected to parse to, compared with
+// `Node::is_equivalent` so the positions the builder makes up don't matter.
+//
+//     1, "s", True, None        atoms
+//     x                         access
+//     [a, b]                    list
+//     (a Add b)                 binary operation, named by its token kind
+//     (Sub a)                   unary operation
+//     (call f a b)              function call
+//     (let x v), (set x v)      declaration and assignment
+//     (return v)
+//     (if c { .. } else { .. }) `else (if ..)` for an else-if chain
+//     (fn f [x, (y 2), (*z)] { .. })
+//     { a b }                   a program or a block
+macro_rules! ast {
+    (None) => {
+        Box::new(Node::Atom {
+            value: token(TokenKind::None),
+        })
+    };
+    (True) => {
+        Box::new(Node::Atom {
+            value: token(TokenKind::Boolean(true)),
+        })
+    };
+    (False) => {
+        Box::new(Node::Atom {
+            value: token(TokenKind::Boolean(false)),
+        })
+    };
+    ($value:literal) => {
+        Box::new(Node::Atom {
+            value: token(Literal::kind($value)),
+        })
+    };
+    ($identifier:ident) => {
+        Box::new(Node::Access {
+            identifier: identifier(stringify!($identifier)),
+            slot: None,
+        })
+    };
+    ([$($value:tt),*]) => {
+        Box::new(Node::List {
+            values: vec![$(ast!($value)),*],
+        })
+    };
+    ({ $($statement:tt)* }) => {
+        Box::new(Node::Program {
+            statements: vec![$(ast!($statement)),*],
+        })
+    };
+    ((call $identifier:ident $($parameter:tt)*)) => {
+        Box::new(Node::FuncCall {
+            identifier: identifier(stringify!($identifier)),
+            slot: None,
+            parameters: vec![$(ast!($parameter)),*],
+            keywords: Vec::new(),
+        })
+    };
+    ((let $identifier:ident $value:tt)) => {
+        assignment(stringify!($identifier), ast!($value), true)
+    };
+    ((set $identifier:ident $value:tt)) => {
+        assignment(stringify!($identifier), ast!($value), false)
+    };
+    ((return $value:tt)) => {
+        Box::new(Node::Return {
+            value: ast!($value),
+        })
+    };
+    ((if $condition:tt $block:tt)) => {
+        Box::new(Node::If {
+            condition: ast!($condition),
+            block: ast!($block),
+            else_block: None,
+        })
+    };
+    ((if $condition:tt $block:tt else $else_block:tt)) => {
+        Box::new(Node::If {
+            condition: ast!($condition),
+            block: ast!($block),
+            else_block: Some(ast!($else_block)),
+        })
+    };
+    ((fn $identifier:ident [$($argument:tt),*] $body:tt)) => {
+        Box::new(Node::FuncDeclearion {
+            identifier: identifier(stringify!($identifier)),
+            arguments: vec![$(argument!($argument)),*],
+            body: ast!($body),
+            locals: Vec::new(),
+        })
+    };
+    (($left:tt $operator:ident $right:tt)) => {
+        Box::new(Node::BinaryOp {
+            left: ast!($left),
+            operator: token(TokenKind::$operator),
+            right: ast!($right),
+        })
+    };
+    (($operator:ident $value:tt)) => {
+        Box::new(Node::UnaryOp {
+            operator: token(TokenKind::$operator),
+            value: ast!($value),
+        })
+    };
+}
+
+// `x`, `(y default)` or `(*z)`.
+macro_rules! argument {
+    ((* $identifier:ident)) => {
+        argument(stringify!($identifier), None, true)
+    };
+    (($identifier:ident $default:tt)) => {
+        argument(stringify!($identifier), Some(ast!($default)), false)
+    };
+    ($identifier:ident) => {
+        argument(stringify!($identifier), None, false)
+    };
+}
+
+trait Literal {
+    fn kind(self) -> TokenKind;
+}
+
+impl Literal for i32 {
+    fn kind(self) -> TokenKind {
+        TokenKind::Integer(self)
+    }
+}
+
+impl Literal for f64 {
+    fn kind(self) -> TokenKind {
+        TokenKind::Float(self as f32)
+    }
+}
+
+impl Literal for &str {
+    fn kind(self) -> TokenKind {
+        TokenKind::String(self.to_string())
+    }
+}
+
+impl Literal for char {
+    fn kind(self) -> TokenKind {
+        TokenKind::Character(self)
+    }
+}
+
+fn token(kind: TokenKind) -> Token {
+    Token::new(Position::new(1, 1), Position::new(1, 2), kind)
+}
+
+fn identifier(name: &str) -> Token {
+    token(TokenKind::Identifier(name.into()))
+}
+
+fn assignment(name: &str, value: Box<Node>, is_declaration: bool) -> Box<Node> {
+    Box::new(Node::Assignment {
+        identifier: identifier(name),
+        slot: None,
+        value,
+        is_declaration,
+    })
+}
+
+fn argument(name: &str, default: Option<Box<Node>>, is_variadic: bool) -> Argument {
+    Argument {
+        identifier: identifier(name),
+        default,
+        is_variadic,
+    }
+}
+
+fn parse(source: &str) -> Result<Box<Node>, MonoError> {
+    Parser::new(Tokenizer::new(source.chars())).parse()
+}
+
+fn assert_parses(source: &str, expected: Box<Node>) {
+    let program = parse(source).unwrap_or_else(|error| panic!("{}: {}", source, error));
+    assert!(
+        program.is_equivalent(&expected),
+        "{}\nparsed:\n{}\nexpected:\n{}",
+        source,
+        program,
+        expected
+    );
+}
+
+fn syntax_error(source: &str) -> Syntax {
+    match *parse(source).expect_err(source) {
+        MonoErrorKind::Syntax(syntax) => syntax,
+        MonoErrorKind::Runtime(runtime) => panic!("{}: {}", source, runtime),
+    }
+}
+
+#[test]
+fn atoms() {
+    assert_parses(
+        "[1, 1.5, \"s\", 'c', True, False, None, x]",
+        ast!({ [1, 1.5, "s", 'c', True, False, None, x] }),
+    );
+}
+
+#[test]
+fn multiplication_binds_tighter_than_addition() {
+    assert_parses("1 + 2 * 3", ast!({ (1 Add (2 Mul 3)) }));
+    assert_parses("1 * 2 + 3", ast!({ ((1 Mul 2) Add 3) }));
+    assert_parses("1 - 2 / 3 % 4", ast!({ (1 Sub ((2 Div 3) Mod 4)) }));
+}
+
+#[test]
+fn arithmetic_is_left_associative() {
+    assert_parses("1 - 2 - 3", ast!({ ((1 Sub 2) Sub 3) }));
+    assert_parses("8 / 4 / 2", ast!({ ((8 Div 4) Div 2) }));
+}
+
+#[test]
+fn power_is_right_associative_and_binds_tightest() {
+    assert_parses("2 ^ 3 ^ 2", ast!({ (2 Pow (3 Pow 2)) }));
+    assert_parses("2 * 3 ^ 2", ast!({ (2 Mul (3 Pow 2)) }));
+    assert_parses("-a ^ 2", ast!({ (Sub (a Pow 2)) }));
+    assert_parses("a ^ -b ^ 2", ast!({ (a Pow (Sub (b Pow 2))) }));
+    assert_parses("(-a) ^ 2", ast!({ ((Sub a) Pow 2) }));
+}
+
+#[test]
+fn parentheses_override_precedence() {
+    assert_parses("(1 + 2) * 3", ast!({ ((1 Add 2) Mul 3) }));
+    assert_parses("2 ^ (1 + 1)", ast!({ (2 Pow (1 Add 1)) }));
+}
+
+#[test]
+fn comparisons_bind_looser_than_arithmetic() {
+    assert_parses(
+        "a + 1 < b * 2 == c",
+        ast!({ (((a Add 1) LessThan (b Mul 2)) Equals c) }),
+    );
+    assert_parses("a >= b != c", ast!({ ((a GreaterEq b) NotEquals c) }));
+}
+
+#[test]
+fn logical_operators() {
+    assert_parses("not a and b or c", ast!({ (((Not a) And b) Or c) }));
+    assert_parses("a or b and c", ast!({ (a Or (b And c)) }));
+    assert_parses("a == 1 and b", ast!({ ((a Equals 1) And b) }));
+}
+
+#[test]
+fn assignments() {
+    assert_parses("let x = 1", ast!({ (let x 1) }));
+    assert_parses("x = x + 1", ast!({ (set x (x Add 1)) }));
+    assert_parses("let y = f(1, a)", ast!({ (let y (call f 1 a)) }));
+}
+
+#[test]
+fn statements_are_separated_by_newlines() {
+    assert_parses("let x = 1\nx\n\nf()", ast!({ (let x 1) x (call f) }));
+}
+
+#[test]
+fn newlines_inside_parentheses_and_brackets_are_ignored() {
+    assert_parses(
+        "let y = f(\n    1,\n    a\n)",
+        ast!({ (let y (call f 1 a)) }),
+    );
+    assert_parses("[\n1,\n2\n]\nx", ast!({ [1, 2] x }));
+    assert_parses("(1 +\n2) * 3", ast!({ ((1 Add 2) Mul 3) }));
+}
+
+#[test]
+fn blocks_inside_parentheses_still_separate_statements() {
+    assert_parses(
+        "f(if a {\n    let x = 1\n    x\n})",
+        ast!({ (call f (if a { (let x 1) x })) }),
+    );
+}
+
+#[test]
+fn backslash_continues_a_line() {
+    assert_parses("let x = 1 + \\\n    2\nx", ast!({ (let x (1 Add 2)) x }));
+    assert_parses("let x = 1 \\\r\n+ 2", ast!({ (let x (1 Add 2)) }));
+    assert!(parse("let x = 1 \\").expect_err("\\").is_incomplete());
+}
+
+#[test]
+fn if_without_else() {
+    assert_parses(
+        "if a { 1 }",
+        ast!({
+            (if a {
+                1
+            })
+        }),
+    );
+}
+
+#[test]
+fn if_else() {
+    assert_parses(
+        "if a > 1 { x } else { y }",
+        ast!({ (if (a Greater 1) { x } else { y }) }),
+    );
+}
+
+#[test]
+fn else_if_chains_nest_in_the_else_block() {
+    assert_parses(
+        "if a { 1 } else if b { 2 } else if c { 3 } else { 4 }",
+        ast!({
+            (if a { 1 } else (if b { 2 } else (if c { 3 } else { 4 })))
+        }),
+    );
+    assert_parses(
+        "if a { 1 } else if b { 2 }",
+        ast!({ (if a { 1 } else (if b { 2 })) }),
+    );
+}
+
+#[test]
+fn elif_chains_like_else_if() {
+    let expected = || ast!({ (if a { 1 } else (if b { 2 } else (if c { 3 } else { 4 }))) });
+    assert_parses(
+        "if a { 1 } elif b { 2 } elif c { 3 } else { 4 }",
+        expected(),
+    );
+    assert_parses(
+        "if a { 1 } elif b { 2 } else if c { 3 } else { 4 }",
+        expected(),
+    );
+    assert_parses(
+        "if a { 1 } elif b { 2 }",
+        ast!({ (if a { 1 } else (if b { 2 })) }),
+    );
+}
+
+#[test]
+fn branches_may_start_on_their_own_lines() {
+    assert_parses(
+        "if a {\n    1\n}\nelif b {\n    2\n}\n\nelse\n{\n    3\n}\nx",
+        ast!({ (if a { 1 } else (if b { 2 } else { 3 })) x }),
+    );
+    assert_parses(
+        "if a { 1 }\nelse\nif b { 2 }\nx",
+        ast!({ (if a { 1 } else (if b { 2 })) x }),
+    );
+}
+
+#[test]
+fn deeply_chained_conditions() {
+    let branches = 500;
+    for separator in [" ", "\n", "\n\n"] {
+        for keyword in ["elif", "else if"] {
+            let mut source = String::from("if x == 0 { 0 }");
+            for i in 1..branches {
+                source.push_str(&format!("{}{} x == {} {{ {} }}", separator, keyword, i, i));
+            }
+            source.push_str(&format!("{}else {{ -1 }}", separator));
+
+            let program = parse(&source).unwrap_or_else(|error| panic!("{}", error));
+            let Node::Program { statements } = *program else {
+                panic!("not a program");
+            };
+            let mut depth = 0;
+            let mut node = statements.first().map(Box::as_ref);
+            while let Some(Node::If { else_block, .. }) = node {
+                depth += 1;
+                node = else_block.as_deref();
+            }
+            assert_eq!(depth, branches, "{:?} {:?}", separator, keyword);
+            assert!(matches!(node, Some(Node::Program { .. })));
+        }
+    }
+}
+
+#[test]
+fn elif_without_an_if() {
+    assert!(matches!(
+        syntax_error("elif a { 1 }"),
+        Syntax::UnexpectedToken {
+            token: Token {
+                kind: TokenKind::Elif,
+                ..
+            },
+            ..
+        }
+    ));
+    assert!(matches!(
+        syntax_error("if a { 1 } else { 2 } elif b { 3 }"),
+        Syntax::UnexpectedToken { .. }
+    ));
+}
+
+#[test]
+fn blocks_hold_several_statements() {
+    assert_parses(
+        "if a {\n    let x = 1\n    x\n}",
+        ast!({ (if a { (let x 1) x }) }),
+    );
+}
+
+#[test]
+fn function_declaration() {
+    assert_parses(
+        "let add(a, b) => { return a + b }",
+        ast!({ (fn add [a, b] { (return (a Add b)) }) }),
+    );
+}
+
+#[test]
+fn function_without_arguments() {
+    assert_parses("let f() => { }", ast!({ (fn f [] {}) }));
+}
+
+#[test]
+fn function_with_defaults_and_variadic_argument() {
+    assert_parses(
+        "let f(x, y=2, *z) => { return x }",
+        ast!({ (fn f [x, (y 2), (*z)] { (return x) }) }),
+    );
+    assert_parses("let g(x=1 + 1) => { }", ast!({ (fn g [(x (1 Add 1))] {}) }));
+}
+
+#[test]
+fn function_body_with_several_statements() {
+    assert_parses(
+        "let f(n) => {\n    let m = n * 2\n    return m\n}\nf(3)",
+        ast!({ (fn f [n] { (let m (n Mul 2)) (return m) }) (call f 3) }),
+    );
+}
+
+#[test]
+fn def_declares_the_same_function() {
+    let expected = || ast!({ (fn f [x, (y 2), (*z)] { (return x) }) });
+    assert_parses("def f(x, y=2, *z) { return x }", expected());
+    assert_parses("let f(x, y=2, *z) => { return x }", expected());
+    assert!(matches!(
+        syntax_error("def f(x) => { return x }"),
+        Syntax::UnexpectedToken {
+            token: Token {
+                kind: TokenKind::DoubleArrow,
+                ..
+            },
+            ..
+        }
+    ));
+}
+
+#[test]
+fn function_requires_a_block() {
+    assert!(matches!(
+        syntax_error("let f(x) => x"),
+        Syntax::UnexpectedToken {
+            expected,
+            ..
+        } if expected == vec![TokenKind::LeftCurly]
+    ));
+}
+
+#[test]
+fn variadic_argument_must_be_last() {
+    assert!(matches!(
+        syntax_error("let f(*a, b) => { }"),
+        Syntax::UnexpectedToken { .. }
+    ));
+}
+
+#[test]
+fn arguments_after_a_default_need_one() {
+    assert!(matches!(
+        syntax_error("let f(a=1, b) => { }"),
+        Syntax::MissingDefaultValue { .. }
+    ));
+}
+
+#[test]
+fn argument_names_are_unique() {
+    for (source, name) in [
+        ("let f(a, a) => { }", "a"),
+        ("def f(a, b = 1, *a) { }", "a"),
+        ("struct Point { x, y, x }", "x"),
+    ] {
+        match syntax_error(source) {
+            Syntax::DuplicateArgument { argument } => {
+                assert_eq!(
+                    argument.kind,
+                    TokenKind::Identifier(name.into()),
+                    "{}",
+                    source
+                )
+            }
+            error => panic!("{}: {}", source, error),
+        }
+    }
+}
+
+#[test]
+fn unclosed_delimiters() {
+    for (source, delimiter) in [
+        ("(1 + 2", TokenKind::RightParen),
+        ("f(1, 2", TokenKind::RightParen),
+        ("if a { 1", TokenKind::RightCurly),
+    ] {
+        match syntax_error(source) {
+            Syntax::UnclosedTokenDelimeter {
+                delimiter: found, ..
+            } => assert_eq!(found, delimiter, "{}", source),
+            error => panic!("{}: {}", source, error),
+        }
+    }
+}
+
+#[test]
+fn incomplete_expressions() {
+    for source in ["1 +", "let x =", "not"] {
+        let error = parse(source).expect_err(source);
+        assert!(error.is_incomplete(), "{}: {}", source, error);
+    }
+}
+
+#[test]
+fn declaration_needs_an_identifier() {
+    assert!(matches!(
+        syntax_error("let = 3"),
+        Syntax::UnexpectedToken {
+            token: Token {
+                kind: TokenKind::Assignment,
+                ..
+            },
+            ..
+        }
+    ));
+}
+
+#[test]
+fn operator_without_left_operand() {
+    assert!(matches!(
+        syntax_error("* 2"),
+        Syntax::UnexpectedToken { .. }
+    ));
+}
+
+// Counts the variables read anywhere in a program.
+struct Reads(usize);
+
+impl NodeVisitor for Reads {
+    fn visit(&mut self, node: &Node) {
+        if let Node::Access { .. } = node {
+            self.0 += 1;
+        }
+        visit_children(self, node);
+    }
+}
+
+#[test]
+fn visitor_reaches_every_node() {
+    let program =
+        parse("let f(a, b=c) => {\n    return [a, g(b, k=d)]\n}\nmatch x { case 1 if y => { z } }")
+            .unwrap();
+    let mut reads = Reads(0);
+    reads.visit(&program);
+    assert_eq!(reads.0, 7);
+}
+
+// Swaps the operands of every addition.
+struct Swap;
+
+impl NodeFolder for Swap {
+    fn fold(&mut self, node: &mut Node) {
+        fold_children(self, node);
+        if let Node::BinaryOp {
+            left,
+            operator,
+            right,
+        } = node
+        {
+            if operator.kind == TokenKind::Add {
+                std::mem::swap(left, right);
+            }
+        }
+    }
+}
+
+#[test]
+fn folder_rewrites_in_place() {
+    let mut program = parse("let x = (1 + 2) * (a + f(3 + b))").unwrap();
+    Swap.fold(&mut program);
+    assert!(program.is_equivalent(&ast!({ (let x ((2 Add 1) Mul ((call f (b Add 3)) Add a))) })));
+}
+
+#[test]
+fn tokens_can_come_from_anywhere() {
+    // Tokens made up by hand, rather than read by the tokenizer.
+    let tokens = vec![
+        token(TokenKind::Integer(1)),
+        token(TokenKind::Add),
+        identifier("x"),
+    ];
+    let program = Parser::new(tokens.into_iter().map(Ok)).parse().unwrap();
+    assert!(program.is_equivalent(&ast!({ (1 Add x) })), "{}", program);
+    // Errors in the stream are passed through.
+    let error = mono::tokenize("'a").unwrap_err();
+    let tokens = vec![Ok(token(TokenKind::Integer(1))), Err(error)];
+    assert!(matches!(
+        *Parser::new(tokens.into_iter()).parse().unwrap_err(),
+        MonoErrorKind::Syntax(Syntax::UnclosedCharDelimeter { .. })
+    ));
+}
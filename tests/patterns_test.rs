@@ -0,0 +1,52 @@
+mod common;
+
+use common::show;
+use mono::models::error::{MonoErrorKind, Runtime};
+use mono::Value;
+
+#[test]
+fn matches_and_their_groups() {
+    assert_eq!(
+        show("re_match(\"(\\\\w+)@(\\\\w+)\", \"me@host\")"),
+        "[me@host, me, host]"
+    );
+    assert_eq!(show("re.match(\"a(x)?b\", \"ab\")"), "[ab, None]");
+    assert_eq!(mono::eval("re_match(\"z\", \"abc\")").unwrap(), Value::None);
+}
+
+#[test]
+fn finding_replacing_and_splitting() {
+    assert_eq!(
+        show("re_find_all(\"\\\\d+\", \"a1b22c333\")"),
+        "[1, 22, 333]"
+    );
+    assert_eq!(
+        show("re.find_all(\"(\\\\w)=(\\\\d)\", \"a=1 b=2\")"),
+        "[[a, 1], [b, 2]]"
+    );
+    assert_eq!(
+        show("re_replace(\"(\\\\w+) (\\\\w+)\", \"hello world\", \"$2 $1\")"),
+        "world hello"
+    );
+    assert_eq!(show("re.split(\",\\\\s*\", \"a, b,c\")"), "[a, b, c]");
+}
+
+#[test]
+fn invalid_patterns_are_errors() {
+    for code in [
+        "re_match(\"(\", \"x\")",
+        "re_find_all(\"[a\", \"x\")",
+        "re_replace(\"*\", \"x\", \"y\")",
+        "re.split(\"a{2,1}\", \"x\")",
+    ] {
+        let error = mono::eval(code).expect_err(code);
+        match &*error {
+            MonoErrorKind::Runtime(Runtime::InvalidPattern { reason, .. }) => {
+                assert!(reason.contains("regex parse error"), "{}: {}", code, reason)
+            }
+            error => panic!("{}: {}", code, error),
+        }
+    }
+    let error = mono::eval("re_match(\"(\", \"x\")").unwrap_err();
+    assert!(error.to_string().contains("unclosed group"), "{}", error);
+}
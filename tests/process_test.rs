@@ -0,0 +1,42 @@
+#![cfg(unix)]
+
+mod common;
+
+use common::show;
+use mono::models::error::{MonoErrorKind, Runtime};
+use mono::Value;
+
+#[test]
+fn exec_passes_the_arguments_as_they_are() {
+    assert_eq!(
+        show("let p = exec(\"echo\", [\"a  b\", \"$HOME\"])\n[p.status, p.stdout, p.stderr]"),
+        "[0, a  b $HOME\n, ]"
+    );
+}
+
+#[test]
+fn shell_runs_a_command_line() {
+    assert_eq!(
+        show("let p = shell(\"echo out; echo err >&2; exit 3\")\n[p.status, p.stdout, p.stderr]"),
+        "[3, out\n, err\n]"
+    );
+}
+
+#[test]
+fn programs_that_cant_run_are_errors() {
+    let error = mono::eval("exec(\"/nonexistent/program\", [])").unwrap_err();
+    assert!(matches!(
+        *error,
+        MonoErrorKind::Runtime(Runtime::IoError { .. })
+    ));
+    let error = mono::eval("exec(\"echo\", [1])").unwrap_err();
+    assert!(matches!(
+        *error,
+        MonoErrorKind::Runtime(Runtime::InvalidValue { .. })
+    ));
+    // A program killed by a signal has no status.
+    assert_eq!(
+        mono::eval("shell(\"kill -9 $$\").status").unwrap(),
+        Value::None
+    );
+}
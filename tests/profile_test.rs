@@ -0,0 +1,50 @@
+use mono::Evaluator;
+use std::process::Command;
+
+fn profiled(code: &str) -> Evaluator {
+    let mut evaluator = Evaluator::new();
+    evaluator.enable_profiling();
+    let program = mono::parse(code).unwrap_or_else(|error| panic!("{}: {}", code, error));
+    evaluator
+        .evaluate(&program)
+        .unwrap_or_else(|error| panic!("{}: {}", code, error));
+    evaluator
+}
+
+#[test]
+fn profiling_is_off_by_default() {
+    assert!(Evaluator::new().profile().is_none());
+}
+
+#[test]
+fn nodes_are_counted_per_kind() {
+    let code = "def square(x) { return x * x }
+let total = 0
+let i = 0
+while i < 3 {
+    total = total + square(i)
+    i = i + 1
+}";
+    let evaluator = profiled(code);
+    let profile = evaluator.profile().unwrap();
+    assert_eq!(profile.get("FuncCall").unwrap().count, 3);
+    assert_eq!(profile.get("While").unwrap().count, 1);
+    assert_eq!(profile.get("Return").unwrap().count, 3);
+    assert!(profile.get("Match").is_none());
+    // A call's time includes the time of its body.
+    assert!(profile.get("FuncCall").unwrap().total >= profile.get("Return").unwrap().total);
+}
+
+#[test]
+fn the_cli_prints_the_profile() {
+    let output = Command::new(env!("CARGO_BIN_EXE_mono"))
+        .args(["-c", "--profile", "let x = [1, 2]"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let printed = String::from_utf8_lossy(&output.stdout).to_string()
+        + &String::from_utf8_lossy(&output.stderr);
+    assert!(printed.contains("Node"), "{}", printed);
+    assert!(printed.contains("Assignment"), "{}", printed);
+    assert!(printed.contains("List"), "{}", printed);
+}
@@ -0,0 +1,60 @@
+use mono::project::{Manifest, Project};
+use mono::Evaluator;
+use std::fs;
+use std::path::PathBuf;
+
+#[test]
+fn manifests_default_their_entry() {
+    let manifest = Manifest::parse("# A game.\nname = \"game\"\n").unwrap();
+    assert_eq!(manifest.name, "game");
+    assert_eq!(manifest.entry, PathBuf::from("main.mono"));
+    assert!(manifest.paths.is_empty());
+}
+
+#[test]
+fn manifests_list_search_paths() {
+    let manifest = Manifest::parse(
+        "name = \"game\"\nentry = \"src/game.mono\"\npaths = [\"lib\", \"vendor\",]",
+    )
+    .unwrap();
+    assert_eq!(manifest.entry, PathBuf::from("src/game.mono"));
+    assert_eq!(
+        manifest.paths,
+        vec![PathBuf::from("lib"), PathBuf::from("vendor")]
+    );
+}
+
+#[test]
+fn invalid_manifests_are_errors() {
+    assert!(Manifest::parse("entry = \"main.mono\"").is_err());
+    assert!(Manifest::parse("name = game").is_err());
+    assert!(Manifest::parse("name = \"game\"\npaths = \"lib\"").is_err());
+    assert!(Manifest::parse("name = \"game\"\nversion = \"1\"").is_err());
+    assert!(Manifest::parse("name").is_err());
+}
+
+#[test]
+fn projects_are_found_from_their_subdirectories() {
+    let root = std::env::temp_dir().join(format!("mono_project_test_{}", std::process::id()));
+    fs::create_dir_all(root.join("lib/nested")).unwrap();
+    fs::write(
+        root.join("mono.toml"),
+        "name = \"test\"\npaths = [\"lib\"]\n",
+    )
+    .unwrap();
+    fs::write(root.join("lib/helper.mono"), "let helped = 42\n").unwrap();
+
+    let canonical = root.canonicalize().unwrap();
+    let project = Project::find(&root.join("lib/nested"));
+    let mut evaluator = Evaluator::new();
+    if let Ok(project) = &project {
+        evaluator.set_paths(project.paths());
+    }
+    let helped = mono::evaluator("run_file(\"helper.mono\")\nhelped", &mut evaluator);
+    fs::remove_dir_all(&root).unwrap();
+
+    let project = project.unwrap();
+    assert_eq!(project.root, canonical);
+    assert_eq!(project.manifest.name, "test");
+    assert_eq!(helped.unwrap(), mono::Value::Integer(42));
+}
@@ -0,0 +1,62 @@
+mod common;
+
+use common::{eval, show};
+use mono::Value;
+
+#[test]
+fn dir_lists_the_names_in_scope() {
+    assert_eq!(show("dir()"), "[]");
+    let code = "let a = 1
+def f(x, y = 2) {
+    let z = 3
+    return dir()
+}
+f(1)";
+    // A call binds the function's own name right after its arguments.
+    assert_eq!(show(code), "[x, y, f, z, a]");
+    // Shadowed builtins are listed, the rest aren't.
+    assert_eq!(show("let len = 1\ndir()"), "[len]");
+}
+
+#[test]
+fn globals_pair_names_with_values() {
+    assert_eq!(
+        show("let b = 2\nlet a = \"x\"\nglobals()"),
+        "[(b, 2), (a, x)]"
+    );
+    // Only globals, whichever scope it's called from.
+    assert_eq!(
+        show("def f() {\n    let local = 1\n    return globals()\n}\nf()"),
+        "[(f, <Function f() at [1,5]>)]"
+    );
+}
+
+#[test]
+fn params_and_arity_describe_callables() {
+    let code = "def f(x, y = 2, *rest) { }\n[params(f), arity(f)]";
+    assert_eq!(show(code), "[[x, y, *rest], 3]");
+    assert_eq!(show("[params(print), arity(len)]"), "[[*values], 1]");
+    assert_eq!(show("struct Point { x, y }\nparams(Point)"), "[x, y]");
+    assert_eq!(eval("params(5)"), Value::None);
+    assert_eq!(eval("arity(\"f\")"), Value::None);
+}
+
+#[test]
+fn globals_keep_the_order_they_were_declared_in() {
+    let names: Vec<String> = (0..50).rev().map(|i| format!("v{}", i)).collect();
+    let code: String = names
+        .iter()
+        .map(|name| format!("let {} = 0\n", name))
+        .collect();
+    assert_eq!(
+        show(&format!("{}dir()", code)),
+        format!("[{}]", names.join(", "))
+    );
+    // Reassigning keeps a global's place, deleting and declaring it again
+    // moves it to the end.
+    assert_eq!(show("let a = 1\nlet b = 2\na = 3\ndir()"), "[a, b]");
+    assert_eq!(
+        show("let a = 1\nlet b = 2\ndel a\nlet a = 3\nglobals()"),
+        "[(b, 2), (a, 3)]"
+    );
+}
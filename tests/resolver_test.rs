@@ -0,0 +1,166 @@
+mod common;
+
+use common::{error, eval};
+use mono::models::error::{MonoErrorKind, Runtime};
+use mono::parser::node::{Node, Slot};
+use mono::parser::visitor::{visit_children, NodeVisitor};
+use mono::tokenizer::token::{Token, TokenKind};
+
+// Every name the program reads or assigns, with the slot the resolver gave it
+// as `(depth, index)`, in the order they're evaluated.
+struct Slots(Vec<(String, Option<(usize, usize)>)>);
+
+impl Slots {
+    fn push(&mut self, identifier: &Token, slot: &Option<Slot>) {
+        if let TokenKind::Identifier(name) = &identifier.kind {
+            let slot = slot.map(|slot| (slot.depth, slot.index));
+            self.0.push((name.to_string(), slot));
+        }
+    }
+}
+
+impl NodeVisitor for Slots {
+    fn visit(&mut self, node: &Node) {
+        match node {
+            Node::Access { identifier, slot }
+            | Node::Assignment {
+                identifier, slot, ..
+            }
+            | Node::FuncCall {
+                identifier, slot, ..
+            }
+            | Node::Index {
+                identifier, slot, ..
+            }
+            | Node::For {
+                identifier, slot, ..
+            }
+            | Node::Delete { identifier, slot } => self.push(identifier, slot),
+            _ => {}
+        }
+        visit_children(self, node);
+    }
+}
+
+fn slots(code: &str) -> Vec<(String, Option<(usize, usize)>)> {
+    let program = mono::parse(code).unwrap_or_else(|error| panic!("{}: {}", code, error));
+    let mut slots = Slots(Vec::new());
+    slots.visit(&program);
+    slots.0
+}
+
+fn slot(name: &str, slot: Option<(usize, usize)>) -> (String, Option<(usize, usize)>) {
+    (name.to_string(), slot)
+}
+
+#[test]
+fn top_level_names_are_looked_up_by_name() {
+    assert_eq!(
+        slots("let x = 1\nprint(x)"),
+        [slot("x", None), slot("print", None), slot("x", None)]
+    );
+}
+
+#[test]
+fn arguments_take_the_first_slots_then_locals_follow() {
+    assert_eq!(
+        slots("def f(a, b) {\n    let c = a + b\n    return c\n}"),
+        [
+            slot("c", Some((0, 3))),
+            slot("a", Some((0, 0))),
+            slot("b", Some((0, 1))),
+            slot("c", Some((0, 3))),
+        ]
+    );
+}
+
+#[test]
+fn shadowing_reuses_the_slot_of_the_name() {
+    // Declaring the argument's name again assigns the argument, and a global
+    // of the same name is never reached from inside the function.
+    assert_eq!(
+        slots("let x = 1\ndef f(x) {\n    let y = x\n    let x = 3\n    return x\n}"),
+        [
+            slot("x", None),
+            slot("y", Some((0, 2))),
+            slot("x", Some((0, 0))),
+            slot("x", Some((0, 0))),
+            slot("x", Some((0, 0))),
+        ]
+    );
+    assert_eq!(
+        eval(
+            "let x = 1\ndef f(x) {\n    let y = x\n    let x = 3\n    return [x, y]\n}\n[f(2), x]"
+        )
+        .to_string(),
+        "[[3, 2], 1]"
+    );
+}
+
+#[test]
+fn nested_functions_get_frames_of_their_own() {
+    let found = slots(
+        "def f(a) {
+    let b = a
+    def g(c) {
+        return [a, b, c, g, f]
+    }
+    return g(b)
+}",
+    );
+    assert_eq!(
+        found,
+        [
+            slot("b", Some((0, 2))),
+            slot("a", Some((0, 0))),
+            // Only `c` and `g` itself belong to `g`'s frame, the rest depend
+            // on where it's called from.
+            slot("a", None),
+            slot("b", None),
+            slot("c", Some((0, 0))),
+            slot("g", Some((0, 1))),
+            slot("f", None),
+            slot("g", Some((0, 3))),
+            slot("b", Some((0, 2))),
+        ]
+    );
+}
+
+#[test]
+fn comprehensions_reach_the_enclosing_frame() {
+    assert_eq!(
+        slots("def f(n) {\n    return [i * n for i in range(n)]\n}"),
+        [
+            slot("range", None),
+            slot("n", Some((0, 0))),
+            slot("i", Some((0, 0))),
+            slot("n", Some((1, 0))),
+        ]
+    );
+}
+
+#[test]
+fn unresolved_names_fall_back_to_dynamic_scope() {
+    // `x` isn't a variable of `inner`, so it's found in the frame of
+    // whichever function called it, then in the globals.
+    let code = "def inner() { return x }
+def outer() {
+    let x = 5
+    return inner()
+}
+let x = 1
+[outer(), inner()]";
+    assert_eq!(eval(code).to_string(), "[5, 1]");
+    assert!(slots(code).contains(&slot("x", None)));
+    let error = error("def f() { return y }\nf()");
+    assert!(matches!(
+        *error,
+        MonoErrorKind::Runtime(Runtime::UnknownIdentifier { .. })
+    ));
+}
+
+#[test]
+fn global_and_nonlocal_names_stay_unresolved() {
+    let found = slots("def f() {\n    global count\n    count = 1\n    let other = 2\n}");
+    assert_eq!(found, [slot("count", None), slot("other", Some((0, 1)))]);
+}
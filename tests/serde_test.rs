@@ -0,0 +1,43 @@
+#![cfg(feature = "serde")]
+
+mod common;
+
+use common::eval;
+use mono::Value;
+use serde_json::json;
+
+fn serialized(code: &str) -> serde_json::Value {
+    serde_json::to_value(eval(code)).unwrap_or_else(|error| panic!("{}: {}", code, error))
+}
+
+#[test]
+fn data_serializes_to_its_natural_form() {
+    assert_eq!(
+        serialized("[1, 2.5, True, None]"),
+        json!([1, 2.5, true, null])
+    );
+    assert_eq!(serialized("(\"a\", 'b')"), json!(["a", "b"]));
+    assert_eq!(
+        serialized("struct Point { x, y }\nPoint(1, [2])"),
+        json!({ "x": 1, "y": [2] })
+    );
+    assert_eq!(serialized("enum Color { Red }\nColor.Red"), json!("Red"));
+}
+
+#[test]
+fn functions_dont_serialize() {
+    let error = serde_json::to_value(eval("def f() { }\nf")).unwrap_err();
+    assert!(
+        error.to_string().contains("can't be serialized"),
+        "{}",
+        error
+    );
+}
+
+#[test]
+fn values_deserialize() {
+    let value: Value = serde_json::from_str("[1, -2, 0.5, \"s\", false, null, [[]]]").unwrap();
+    assert_eq!(value, eval("[1, -2, 0.5, \"s\", False, None, [[]]]"));
+    assert!(serde_json::from_str::<Value>("{\"a\": 1}").is_err());
+    assert!(serde_json::from_str::<Value>("4294967296").is_err());
+}
@@ -0,0 +1,77 @@
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::OnceLock;
+use std::thread;
+
+// The address of a playground server shared by the tests.
+fn server() -> SocketAddr {
+    static ADDRESS: OnceLock<SocketAddr> = OnceLock::new();
+    *ADDRESS.get_or_init(|| {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        thread::spawn(move || mono::serve::serve(listener));
+        address
+    })
+}
+
+// The body the server responds to `POST /eval` with.
+fn eval(code: &str) -> String {
+    let mut stream = TcpStream::connect(server()).unwrap();
+    write!(
+        stream,
+        "POST /eval HTTP/1.1\r\nContent-Length: {}\r\n\r\n{}",
+        code.len(),
+        code
+    )
+    .unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    let (head, body) = response.split_once("\r\n\r\n").unwrap();
+    assert!(head.starts_with("HTTP/1.1 200 OK"), "{}", head);
+    body.to_string()
+}
+
+#[test]
+fn programs_respond_with_their_output_and_value() {
+    assert_eq!(
+        eval("print(\"a\")\n1 + 2"),
+        "{\"output\": \"a\", \"value\": \"3\", \"error\": null}"
+    );
+}
+
+#[test]
+fn printing_takes_any_number_of_values() {
+    assert_eq!(
+        eval("io.println(1, 2)\nio.print(\"a\", \"b\")\nprintln(3, 4)"),
+        "{\"output\": \"1 2\\na b3 4\\n\", \"value\": null, \"error\": null}"
+    );
+}
+
+#[test]
+fn sandboxed_builtins_are_unknown() {
+    for name in [
+        "input",
+        "shell",
+        "sleep",
+        "after",
+        "every",
+        "run_loop",
+        "read_file_bytes",
+        "eval",
+        "exec",
+        "exit",
+        "run_file",
+        "remove_file",
+    ] {
+        let body = eval(&format!("{}()", name));
+        assert!(body.contains("Unknown identifier"), "{}: {}", name, body);
+    }
+}
+
+#[test]
+fn allowed_builtins_are_available() {
+    assert_eq!(
+        eval("len(string.upper(\"ab\")) + math.floor(2.5)"),
+        "{\"output\": \"\", \"value\": \"4\", \"error\": null}"
+    );
+}
@@ -0,0 +1,117 @@
+use std::fs;
+
+// Printing a tree and parsing the output again gives the same tree, and
+// printing that one gives the same source.
+fn assert_round_trips(source: &str) {
+    let program = mono::parse(source).unwrap_or_else(|error| panic!("{}: {}", source, error));
+    let printed = program.to_source();
+    let reparsed =
+        mono::parse(&printed).unwrap_or_else(|error| panic!("{}\n{}: {}", source, printed, error));
+    assert!(
+        program.is_equivalent(&reparsed),
+        "{}\nprinted:\n{}",
+        source,
+        printed
+    );
+    assert_eq!(printed, reparsed.to_source(), "{}", source);
+}
+
+fn printed(source: &str) -> String {
+    mono::parse(source).unwrap().to_source()
+}
+
+#[test]
+fn literals() {
+    assert_round_trips(r#"let s = "a\"b\\c\n\t\r\0 é""#);
+    assert_round_trips(r"let c = '\''");
+    assert_round_trips(r#"let b = b"\x00\xff\"\\ok\n""#);
+    assert_round_trips("let t = ((), (1,), (1, 2), 1.5, None, True)");
+}
+
+#[test]
+fn operators_keep_their_grouping() {
+    assert_round_trips("let xs = [-2, - -3, -(2 ^ 2), (-2) ^ 2, 2 ^ -1, 2 ^ 3 ^ 2, (2 ^ 3) ^ 2]");
+    assert_round_trips("let v = not (a and b) or not c == d");
+    assert_round_trips("let w = (a ?? b) or c ?? d");
+    assert_round_trips("let z = 1 - (2 - 3) + (4 + 5) * 6 / (7 % 8)");
+    assert_round_trips("x = 3 in [1, 2] == False");
+    assert_round_trips("let u = -x.y ^ 2");
+    assert_round_trips("(if a { 1 } else { 2 }) + 1");
+}
+
+#[test]
+fn redundant_parentheses_are_dropped() {
+    assert_eq!(
+        printed("let deep = ((1 + 2) * 3) - (4 - (5 - 6))"),
+        "let deep = (1 + 2) * 3 - (4 - (5 - 6))"
+    );
+}
+
+#[test]
+fn calls_and_members() {
+    assert_round_trips(r#"let q = (1).x + (1.5).y() + "s".upper()"#);
+    assert_round_trips("let r = f?(1, k=2) + xs?[i + 1] + g(*xs, key=(if a { 1 } else { 2 }))");
+    assert_round_trips("xs[i + 1] = 3\np.x = 4\np.y.z = 5\ndel xs");
+}
+
+#[test]
+fn control_flow() {
+    assert_round_trips("let m = if a { 1 } elif b { 2 } else { 3 }");
+    assert_round_trips("let n = [x * 2 for x in xs if x > 1]");
+    assert_round_trips("for a, b in pairs { println(a) }\nfor [a, *b] in pairs { }");
+    assert_round_trips("for x in (if a { xs } else { ys }) { }");
+    assert_round_trips("do { i = i + 1 } while i < 3\nwhile True { }");
+    assert_round_trips(
+        "match v { case 1 | -2 | 'c' | b\"x\" | None => { 1 } case [x, *rest] if x > 0 => { } \
+         case P { x, y: [1, z] } => { } case {name: n} => {} case Color.Red => {} case _ => {} }",
+    );
+}
+
+#[test]
+fn blocks_are_indented() {
+    assert_eq!(
+        printed("if a { 1 } elif b { 2 } else { 3 }"),
+        "if a {\n    1\n} elif b {\n    2\n} else {\n    3\n}"
+    );
+}
+
+#[test]
+fn declarations() {
+    assert_round_trips("a, *b = xs\nlet [c, [d, *e]] = ys\nlet (f, g) = 1, 2");
+    assert_round_trips("struct P { x, y=2 }\nstruct E {}\nenum Color { Red, Green }");
+    assert_round_trips("impl P { norm(self) => { return self.x ^ 2 } }");
+    assert_round_trips("let f(a, b=1, *c) => { \"doc\"\n return a, b }\ndef g() { }");
+    assert_round_trips("def f() { global a, b\n nonlocal c }");
+}
+
+#[test]
+fn examples() {
+    for entry in fs::read_dir("examples").unwrap() {
+        let path = entry.unwrap().path();
+        if path
+            .extension()
+            .is_some_and(|extension| extension == "mono")
+        {
+            assert_round_trips(&fs::read_to_string(path).unwrap());
+        }
+    }
+}
+
+#[test]
+fn blocks_left_by_the_optimizer() {
+    // The branch taken replaces the `if`, and a bare block doesn't parse.
+    for source in [
+        "if True { a() } else { b() }",
+        "let x = if 1 > 2 { 1 } else { 2 }\ndo { a() } while False",
+    ] {
+        let program = mono::Optimizer::optimize(*mono::parse(source).unwrap());
+        let printed = program.to_source();
+        let reparsed = mono::parse(&printed)
+            .unwrap_or_else(|error| panic!("{}\n{}: {}", source, printed, error));
+        assert_eq!(printed, reparsed.to_source(), "{}", source);
+    }
+    assert_eq!(
+        mono::Optimizer::optimize(*mono::parse("if True { a() }").unwrap()).to_source(),
+        "if True {\n    a()\n}"
+    );
+}
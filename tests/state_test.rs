@@ -0,0 +1,159 @@
+use mono::evaluator::external::External;
+use mono::evaluator::shared::Shared;
+use mono::{Evaluator, Value};
+use std::any::Any;
+use std::fmt;
+use std::io::ErrorKind;
+
+fn run(evaluator: &mut Evaluator, code: &str) -> Value {
+    let program = mono::parse(code).unwrap_or_else(|error| panic!("{}: {}", code, error));
+    evaluator
+        .evaluate(&program)
+        .unwrap_or_else(|error| panic!("{}: {}", code, error))
+}
+
+// The state of a session that ran the code.
+fn saved(code: &str) -> Vec<u8> {
+    let mut evaluator = Evaluator::new();
+    run(&mut evaluator, code);
+    evaluator.save_state().unwrap()
+}
+
+fn restored(state: &[u8]) -> Evaluator {
+    let mut evaluator = Evaluator::new();
+    evaluator.load_state(state).unwrap();
+    evaluator
+}
+
+#[test]
+fn sessions_round_trip() {
+    let state = saved(
+        "let n = 1
+let x = 2.5
+let s = \"naïve\"
+let c = 'c'
+let b = b\"\\x00\\xff\"
+let t = (1, None, True)
+let xs = [1, [2, 3]]
+def add(a, b = 2, *rest) { return a + b + len(rest) }
+struct Point { x, y }
+impl Point { norm(self) => { return self.x * self.x + self.y * self.y } }
+let p = Point(3, 4)
+enum Color { Red, Green }
+let color = Color.Green
+let root = math.sqrt",
+    );
+    let mut evaluator = restored(&state);
+    for (code, expected) in [
+        ("n", "1"),
+        ("x", "2.5"),
+        ("s", "naïve"),
+        ("c", "c"),
+        ("len(b)", "2"),
+        ("t", "(1, None, True)"),
+        ("xs", "[1, [2, 3]]"),
+        ("add(1) + add(1, 1, 5, 6)", "7"),
+        ("p.norm()", "25"),
+        ("Point(1, 1).norm()", "2"),
+        ("color == Color.Green", "True"),
+        ("root(16)", "4.0"),
+    ] {
+        assert_eq!(run(&mut evaluator, code).to_string(), expected, "{}", code);
+    }
+}
+
+#[test]
+fn shared_lists_stay_shared() {
+    let mut evaluator = restored(&saved("let a = [1]\nlet b = a"));
+    assert_eq!(
+        run(&mut evaluator, "list.push(a, 2)\nb").to_string(),
+        "[1, 2]"
+    );
+}
+
+#[test]
+fn dates_and_string_builders_round_trip() {
+    let state = saved(
+        "let day = date.from_timestamp(86400)
+let builder = string_builder()
+append(builder, \"ab\")
+let same = builder",
+    );
+    let mut evaluator = restored(&state);
+    assert_eq!(
+        run(&mut evaluator, "date.timestamp(day)"),
+        Value::Integer(86400)
+    );
+    assert_eq!(
+        run(&mut evaluator, "append(same, \"c\")\nbuild(builder)").to_string(),
+        "abc"
+    );
+}
+
+#[derive(Debug)]
+struct Handle;
+
+impl fmt::Display for Handle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "<Handle>")
+    }
+}
+
+impl External for Handle {
+    fn type_name(&self) -> &'static str {
+        "Handle"
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+#[test]
+fn host_externals_cant_be_saved() {
+    let mut evaluator = Evaluator::new();
+    evaluator.set_var("handle", Value::External(Shared::new(Handle)));
+    let error = evaluator.save_state().unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::InvalidData);
+    assert!(error.to_string().contains("Handle"), "{}", error);
+}
+
+#[test]
+fn other_versions_are_rejected() {
+    let mut state = saved("let n = 1");
+    state[4] = state[4].wrapping_add(1);
+    let error = Evaluator::new().load_state(&state).unwrap_err();
+    assert!(error.to_string().contains("version"), "{}", error);
+    let error = Evaluator::new().load_state(b"NOPE\x05").unwrap_err();
+    assert!(error.to_string().contains("version"), "{}", error);
+}
+
+#[test]
+fn truncated_and_corrupt_states_are_errors() {
+    let state = saved(
+        "def f(a) { match a { case [x, *_] if x > 1 => { return x } } }\nlet xs = [f, (1, 2)]",
+    );
+    // Nothing is restored from a state that fails to load.
+    for length in 0..state.len() {
+        let mut evaluator = Evaluator::new();
+        let error = evaluator.load_state(&state[..length]).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidData, "{}", length);
+        assert_eq!(evaluator.get_var("xs"), None);
+    }
+    let mut trailing = state.clone();
+    trailing.push(0);
+    assert!(Evaluator::new().load_state(&trailing).is_err());
+    // Flipping any byte either fails to load or loads something, but never
+    // panics.
+    for index in 5..state.len() {
+        let mut corrupt = state.clone();
+        corrupt[index] ^= 0xff;
+        let _ = Evaluator::new().load_state(&corrupt);
+    }
+}
+
+#[test]
+fn restored_globals_keep_their_order() {
+    let mut evaluator = restored(&saved("let z = 1\nlet a = 2\nlet m = 3"));
+    assert_eq!(run(&mut evaluator, "dir()").to_string(), "[z, a, m]");
+}
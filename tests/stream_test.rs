@@ -0,0 +1,46 @@
+use mono::models::error::MonoErrorKind;
+use mono::{Evaluator, Parser, Tokenizer, Value};
+
+#[test]
+fn statements_are_yielded_as_theyre_parsed() {
+    let mut parser = Parser::new(Tokenizer::new("let a = 1\n\nprint(a)\nlet b = ".chars()));
+    let statements: Vec<_> = parser.statements().collect();
+    assert_eq!(statements.len(), 3);
+    assert!(statements[0].is_ok() && statements[1].is_ok());
+    // Nothing is yielded after the first error.
+    assert!(matches!(
+        **statements[2].as_ref().unwrap_err(),
+        MonoErrorKind::Syntax(_)
+    ));
+}
+
+#[test]
+fn statements_before_a_syntax_error_still_run() {
+    let mut evaluator = Evaluator::new();
+    let (result, printed) = evaluator
+        .capture(|evaluator| mono::streamed("print(\"ran\")\nlet x = 2\nlet y = (", evaluator));
+    assert!(result.is_err());
+    assert_eq!(printed, "ran");
+    assert_eq!(evaluator.get_var("x"), Some(Value::Integer(2)));
+    assert_eq!(evaluator.get_var("y"), None);
+}
+
+#[test]
+fn returning_ends_the_stream() {
+    let mut evaluator = Evaluator::new();
+    let (result, printed) = evaluator.capture(|evaluator| {
+        mono::streamed("let x = 1\nreturn x + 1\nprint(\"not run\")", evaluator)
+    });
+    assert_eq!(result.unwrap(), Value::Integer(2));
+    assert_eq!(printed, "");
+}
+
+#[test]
+fn programs_stream_from_readers() {
+    let mut evaluator = Evaluator::new();
+    let (result, printed) = evaluator.capture(|evaluator| {
+        mono::streamed_from("let s = \"ï\"\nprint(s)\nlen(s)".as_bytes(), evaluator)
+    });
+    assert_eq!(result.unwrap(), Value::Integer(1));
+    assert_eq!(printed, "ï");
+}
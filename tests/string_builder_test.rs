@@ -0,0 +1,42 @@
+mod common;
+
+use common::show;
+use mono::evaluator::limits::Limits;
+use mono::{Evaluator, Value};
+
+#[test]
+fn builders_append_values_as_theyre_printed() {
+    let code = "let b = string_builder()
+for i in range(0, 3) {
+    append(b, i)
+    append(b, ',')
+}
+append(b, [True, None])
+build(b)";
+    assert_eq!(show(code), "0,1,2,[True, None]");
+    assert_eq!(show("build(string_builder())"), "");
+}
+
+#[test]
+fn builders_are_shared_not_copied() {
+    let code = "def add(builder) { append(builder, \"x\") }
+let b = string_builder()
+let c = b
+add(b)
+add(c)
+[build(b), b]";
+    assert_eq!(show(code), "[xx, <StringBuilder: \"xx\">]");
+}
+
+#[test]
+fn builders_respect_the_length_limit() {
+    let mut evaluator = Evaluator::new();
+    evaluator.set_limits(Limits {
+        length: Some(4),
+        ..Limits::default()
+    });
+    let code =
+        "let b = string_builder()\nappend(b, \"abc\")\nappend(b, \"de\")\nappend(b, 'd')\nbuild(b)";
+    let program = mono::parse(code).unwrap();
+    assert_eq!(evaluator.evaluate(&program).unwrap(), Value::from("abcd"));
+}
@@ -0,0 +1,114 @@
+mod common;
+
+use common::{error, eval};
+use mono::models::error::{MonoErrorKind, Runtime};
+use mono::Value;
+
+#[test]
+fn structs_are_constructed_with_their_fields_in_order() {
+    assert_eq!(
+        eval("struct Point { x, y }\nlet p = Point(1, 2)\n[p.x, p.y]").to_string(),
+        "[1, 2]"
+    );
+    assert_eq!(
+        eval("struct Point { x, y }\nPoint(1, 2)").to_string(),
+        "Point { x: 1, y: 2 }"
+    );
+    // Fields may have defaults and be given by name, as arguments can.
+    assert_eq!(
+        eval("struct Point { x, y = 0 }\n[Point(1).y, Point(y = 3, x = 1).y]").to_string(),
+        "[0, 3]"
+    );
+}
+
+#[test]
+fn fields_are_assigned_in_place() {
+    let code = "struct Point { x, y }
+let p = Point(1, 2)
+let same = p
+p.x = 3
+same.x";
+    assert_eq!(eval(code), Value::Integer(3));
+}
+
+#[test]
+fn unknown_fields_are_errors() {
+    assert!(matches!(
+        *error("struct Point { x, y }\nPoint(1, 2).z"),
+        MonoErrorKind::Runtime(Runtime::UnknownMember { .. })
+    ));
+    assert!(matches!(
+        *error("struct Point { x, y }\nPoint(1)"),
+        MonoErrorKind::Runtime(Runtime::IncorrectParameters { .. })
+    ));
+}
+
+#[test]
+fn structs_compare_by_their_fields() {
+    assert_eq!(
+        eval("struct P { x }\n[P(1) == P(1), P(1) == P(2)]").to_string(),
+        "[True, False]"
+    );
+}
+
+#[test]
+fn methods_get_the_struct_as_self() {
+    let code = "struct Counter { count }
+impl Counter {
+    bump(self, by = 1) => {
+        self.count = self.count + by
+        return self
+    }
+    double(self) => { return self.count * 2 }
+}
+let c = Counter(0)
+c.bump()
+c.bump(by = 3).double()";
+    assert_eq!(eval(code), Value::Integer(8));
+}
+
+#[test]
+fn impl_blocks_add_up() {
+    let code = "struct P { x }
+impl P { a(self) => { return self.x } }
+impl P { b(self) => { return self.a() + 1 } }
+P(1).b()";
+    assert_eq!(eval(code), Value::Integer(2));
+}
+
+#[test]
+fn unknown_methods_are_errors() {
+    assert!(matches!(
+        *error("struct P { x }\nP(1).missing()"),
+        MonoErrorKind::Runtime(Runtime::UnknownMember { .. })
+    ));
+    assert!(matches!(
+        *error("struct P { x }\nimpl P { m(self, y) => { } }\nP(1).m()"),
+        MonoErrorKind::Runtime(Runtime::IncorrectParameters { .. })
+    ));
+}
+
+#[test]
+fn enum_variants_are_values_of_their_enum() {
+    let code = "enum Color { Red, Green, Blue }
+let c = Color.Green
+[c == Color.Green, c == Color.Red, c != Color.Blue]";
+    assert_eq!(eval(code).to_string(), "[True, False, True]");
+    assert_eq!(
+        eval("enum Color { Red }\nColor.Red").to_string(),
+        "Color.Red"
+    );
+    // Variants of the same name in two enums are different values.
+    assert_eq!(
+        eval("enum A { X }\nenum B { X }\nA.X == B.X"),
+        Value::Boolean(false)
+    );
+}
+
+#[test]
+fn unknown_variants_are_errors() {
+    assert!(matches!(
+        *error("enum Color { Red }\nColor.Purple"),
+        MonoErrorKind::Runtime(Runtime::UnknownMember { .. })
+    ));
+}
@@ -0,0 +1,41 @@
+#![cfg(feature = "sync")]
+
+use mono::{Evaluator, Value};
+use std::thread;
+
+fn assert_send<T: Send>() {}
+
+#[test]
+fn values_and_evaluators_are_send() {
+    assert_send::<Value>();
+    assert_send::<Evaluator>();
+}
+
+#[test]
+fn evaluators_move_between_threads() {
+    let mut evaluator = Evaluator::new();
+    evaluator.set_var("x", 20);
+    let mut evaluator = thread::spawn(move || {
+        let program = mono::parse("let y = x + 1").unwrap();
+        evaluator.evaluate(&program).unwrap();
+        evaluator
+    })
+    .join()
+    .unwrap();
+    let program = mono::parse("y * 2").unwrap();
+    assert_eq!(evaluator.evaluate(&program).unwrap(), Value::Integer(42));
+}
+
+#[test]
+fn lists_stay_shared_across_threads() {
+    let list = mono::eval("[1]").unwrap();
+    let other = list.clone();
+    thread::spawn(move || {
+        if let Value::List(values) = other {
+            values.borrow_mut().push(Value::Integer(2));
+        }
+    })
+    .join()
+    .unwrap();
+    assert_eq!(list.to_string(), "[1, 2]");
+}
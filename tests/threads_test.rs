@@ -0,0 +1,85 @@
+#![cfg(feature = "sync")]
+
+mod common;
+
+use common::eval;
+use mono::evaluator::limits::Limits;
+use mono::evaluator::symbol_table::SymbolTable;
+use mono::{Evaluator, Value};
+
+#[test]
+fn join_returns_what_the_function_returns() {
+    let code = "def fib(n) {
+    if n < 2 { return n }
+    return fib(n - 1) + fib(n - 2)
+}
+join(spawn(fib, 15))";
+    assert_eq!(eval(code), Value::Integer(610));
+}
+
+#[test]
+fn receiving_ends_once_every_sender_is_gone() {
+    let code = "let (sender, receiver) = channel()
+def produce(sender, n) {
+    let i = 1
+    while i <= n { send(sender, i); i = i + 1 }
+}
+let threads = [spawn(produce, sender, 3), spawn(produce, sender, 4)]
+sender = None
+let total = 0
+let value = recv(receiver)
+while value != None { total = total + value; value = recv(receiver) }
+for thread in threads { join(thread) }
+total";
+    assert_eq!(eval(code), Value::Integer(16));
+}
+
+#[test]
+fn errors_surface_when_joined() {
+    let error = mono::eval("def f() { return 1 / 0 }\nlet t = spawn(f)\njoin(t)").unwrap_err();
+    assert!(error.to_string().contains("Division by zero"), "{}", error);
+    let error = mono::eval("let t = spawn(len, [])\njoin(t)\njoin(t)").unwrap_err();
+    assert!(error.to_string().contains("already joined"), "{}", error);
+}
+
+#[test]
+fn sending_fails_once_the_receiver_is_gone() {
+    let code = "let (sender, receiver) = channel()
+receiver = None
+send(sender, 1)";
+    assert_eq!(eval(code), Value::Boolean(false));
+}
+
+#[test]
+fn threads_get_the_spawning_evaluators_builtins() {
+    let mut symbol_table = SymbolTable::new();
+    symbol_table.add_builtins();
+    symbol_table.remove_builtin("len");
+    let mut evaluator = Evaluator::from(symbol_table);
+    let program = mono::parse("def f() { return len([1]) }\njoin(spawn(f))").unwrap();
+    let error = evaluator.evaluate(&program).unwrap_err();
+    assert!(error.to_string().contains("len"), "{}", error);
+}
+
+#[test]
+fn threads_get_the_spawning_evaluators_limits() {
+    let mut evaluator = Evaluator::new();
+    evaluator.set_limits(Limits {
+        steps: Some(10_000),
+        ..Limits::default()
+    });
+    let program = mono::parse("def f() { while True { } }\njoin(spawn(f))").unwrap();
+    let error = evaluator.evaluate(&program).unwrap_err();
+    assert!(error.to_string().contains("limit"), "{}", error);
+}
+
+#[test]
+fn threads_read_and_print_where_the_spawning_evaluator_does() {
+    let mut evaluator = Evaluator::new();
+    evaluator.set_input(std::io::Cursor::new("line\n"));
+    let code = "def echo() { println(input()) }\njoin(spawn(echo))";
+    let (result, printed) =
+        evaluator.capture(|evaluator| evaluator.evaluate(&mono::parse(code).unwrap()));
+    result.unwrap();
+    assert_eq!(printed, "line\n");
+}
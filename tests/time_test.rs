@@ -0,0 +1,35 @@
+mod common;
+
+use common::eval;
+use mono::evaluator::interrupt;
+use mono::Value;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+#[test]
+fn time_is_seconds_since_the_epoch() {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs() as i32;
+    match eval("time()") {
+        Value::Integer(seconds) => assert!((seconds - now).abs() <= 1, "{}", seconds),
+        value => panic!("{}", value),
+    }
+}
+
+// Both in one test, as interrupting is global.
+#[test]
+fn sleeping_advances_the_clock_until_interrupted() {
+    let code = "let start = clock()\nsleep(50)\nclock() - start";
+    match eval(code) {
+        Value::Integer(elapsed) => assert!((50..1000).contains(&elapsed), "{}", elapsed),
+        value => panic!("{}", value),
+    }
+
+    interrupt::interrupt();
+    let start = Instant::now();
+    eval("sleep(10000)");
+    interrupt::clear();
+    assert!(start.elapsed() < Duration::from_secs(5));
+    assert!(!interrupt::is_interrupted());
+}
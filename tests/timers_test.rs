@@ -0,0 +1,54 @@
+mod common;
+
+use common::printed;
+use mono::Value;
+
+fn run(code: &str) -> (Value, String) {
+    let (value, printed) = printed(code);
+    (
+        value.unwrap_or_else(|error| panic!("{}: {}", code, error)),
+        printed,
+    )
+}
+
+#[test]
+fn timers_run_in_order_until_none_are_left() {
+    let code = "def first() { print(1) }
+def second() { print(2) }
+def third() { print(3) }
+after(30, third)
+after(0, first)
+after(10, second)
+run_loop()";
+    assert_eq!(run(code), (Value::None, String::from("123")));
+}
+
+#[test]
+fn a_repeating_timer_runs_until_cancelled() {
+    let code = "let ticks = 0
+def tick() {
+    ticks = ticks + 1
+    if ticks == 3 { cancel(ticker) }
+}
+let ticker = every(5, tick)
+run_loop()
+ticks";
+    assert_eq!(run(code).0, Value::Integer(3));
+}
+
+#[test]
+fn cancelling_reports_whether_the_timer_was_pending() {
+    let code = "def f() { }
+let timer = after(1000, f)
+(cancel(timer), cancel(timer))";
+    assert_eq!(
+        run(code).0,
+        Value::from((Value::Boolean(true), Value::Boolean(false)))
+    );
+}
+
+#[test]
+fn an_error_ends_the_loop() {
+    let error = mono::eval("def f() { return 1 / 0 }\nafter(0, f)\nrun_loop()").unwrap_err();
+    assert!(error.to_string().contains("Division by zero"), "{}", error);
+}
@@ -0,0 +1,281 @@
+use mono::models::error::{MonoErrorKind, Syntax};
+use mono::models::position::Position;
+use mono::tokenizer::reader::Utf8Chars;
+use mono::tokenizer::token::TokenKind;
+use mono::tokenizer::Tokenizer;
+use mono::Value;
+use std::io::BufReader;
+use std::sync::Arc;
+
+// Every token's `[start, end)` span as `(row, start column, end column)`,
+// none of the tested tokens cross a line.
+fn spans(source: &str) -> Vec<(TokenKind, (usize, usize, usize))> {
+    mono::tokenize(source)
+        .unwrap_or_else(|error| panic!("{}: {}", source, error))
+        .into_iter()
+        .map(|token| {
+            assert_eq!(token.start.row(), token.end.row(), "{}", token);
+            let span = (token.start.row(), token.start.column(), token.end.column());
+            (token.kind, span)
+        })
+        .collect()
+}
+
+fn assert_spans(source: &str, expected: &[(usize, usize, usize)]) {
+    let found: Vec<_> = spans(source).into_iter().map(|(_, span)| span).collect();
+    assert_eq!(found, expected, "{}", source);
+}
+
+#[test]
+fn single_character_tokens_span_one_column() {
+    assert_spans("( + )", &[(1, 1, 2), (1, 3, 4), (1, 5, 6)]);
+}
+
+#[test]
+fn multi_character_tokens_span_every_character() {
+    assert_spans("a != bc", &[(1, 1, 2), (1, 3, 5), (1, 6, 8)]);
+    assert_spans(
+        "=> -> <= >= ??",
+        &[(1, 1, 3), (1, 4, 6), (1, 7, 9), (1, 10, 12), (1, 13, 15)],
+    );
+}
+
+#[test]
+fn literals_span_their_delimiters_and_prefixes() {
+    assert_spans("\"ab\" 'c'", &[(1, 1, 5), (1, 6, 9)]);
+    assert_spans("b\"ab\" r\"\\n\"", &[(1, 1, 6), (1, 7, 12)]);
+    assert_spans(
+        "12 3.5 0xff 1e3",
+        &[(1, 1, 3), (1, 4, 7), (1, 8, 12), (1, 13, 16)],
+    );
+}
+
+fn kinds(source: &str) -> Vec<TokenKind> {
+    spans(source).into_iter().map(|(kind, _)| kind).collect()
+}
+
+#[test]
+fn floats_can_start_with_a_dot() {
+    assert_eq!(kinds(".5"), [TokenKind::Float(0.5)]);
+    assert_eq!(
+        kinds("[.25e2"),
+        [TokenKind::LeftBracket, TokenKind::Float(25.0)]
+    );
+    // After an operand it's a member access.
+    assert_eq!(kinds("x.5")[1], TokenKind::Dot);
+    assert_eq!(kinds(").5")[1], TokenKind::Dot);
+}
+
+#[test]
+fn floats_need_digits_after_the_dot() {
+    for source in ["1.", "1.e3", "2.x"] {
+        match *mono::tokenize(source).expect_err(source) {
+            MonoErrorKind::Syntax(Syntax::MissingFraction { start, .. }) => {
+                assert_eq!(start, Position::new(1, 1), "{}", source)
+            }
+            error => panic!("{}: {}", source, error),
+        }
+    }
+}
+
+#[test]
+fn printed_floats_read_back_exactly() {
+    let mut floats = vec![
+        f32::MAX,
+        f32::MIN_POSITIVE,
+        f32::EPSILON,
+        1e-45,
+        0.1,
+        1.0 / 3.0,
+    ];
+    floats.extend((0..2000).map(|i| i as f32 * 0.37 + 1.0 / (i as f32 + 1.0)));
+    floats.extend((-40..38).map(|exponent| 1.7 * 10f32.powi(exponent)));
+    for float in floats {
+        let printed = Value::Float(float).to_string();
+        assert_eq!(kinds(&printed), [TokenKind::Float(float)], "{}", printed);
+        let parsed = mono::eval(&format!("float(string({}))", printed)).unwrap();
+        assert_eq!(parsed, Value::Float(float), "{}", printed);
+    }
+}
+
+#[test]
+fn keywords_span_their_word() {
+    assert_spans("let x", &[(1, 1, 4), (1, 5, 6)]);
+}
+
+#[test]
+fn newlines_end_the_row() {
+    let found = spans("a\nbb # c\nd");
+    let newlines: Vec<_> = found
+        .iter()
+        .filter(|(kind, _)| *kind == TokenKind::NewLine)
+        .map(|(_, span)| *span)
+        .collect();
+    assert_eq!(newlines, [(1, 2, 3), (2, 7, 8)]);
+    assert_eq!(found.last().map(|(_, span)| *span), Some((3, 1, 2)));
+}
+
+#[test]
+fn semicolons_stay_on_their_row() {
+    assert_spans("a; b", &[(1, 1, 2), (1, 2, 3), (1, 4, 5)]);
+}
+
+#[test]
+fn lone_exclamation_is_reported_at_itself() {
+    let error = mono::tokenize("a !b").expect_err("a !b");
+    match *error {
+        MonoErrorKind::Syntax(Syntax::UnexpectedChar { position, c: '!' }) => {
+            assert_eq!(position, Position::new(1, 3))
+        }
+        error => panic!("{}", error),
+    }
+}
+
+#[test]
+fn tabs_move_to_the_next_tab_stop() {
+    assert_spans("\ta", &[(1, 5, 6)]);
+    assert_spans("a\tb", &[(1, 1, 2), (1, 5, 6)]);
+    assert_spans("abcd\t\te", &[(1, 1, 5), (1, 13, 14)]);
+}
+
+#[test]
+fn tab_width_is_configurable() {
+    let tokens: Vec<_> = Tokenizer::with_tab_width("\ta\tb".chars(), 1)
+        .map(|token| token.unwrap().start.column())
+        .collect();
+    assert_eq!(tokens, [2, 4]);
+    let tokens: Vec<_> = Tokenizer::with_tab_width("\ta".chars(), 8)
+        .map(|token| token.unwrap().start.column())
+        .collect();
+    assert_eq!(tokens, [9]);
+}
+
+#[test]
+fn crlf_line_endings_are_newlines() {
+    let kinds: Vec<_> = spans("a\r\nb\r\n")
+        .into_iter()
+        .map(|(kind, span)| (kind, span.0, span.1))
+        .collect();
+    assert_eq!(
+        kinds,
+        [
+            (TokenKind::Identifier("a".into()), 1, 1),
+            (TokenKind::NewLine, 1, 3),
+            (TokenKind::Identifier("b".into()), 2, 1),
+            (TokenKind::NewLine, 2, 3),
+        ]
+    );
+}
+
+#[test]
+fn crlf_programs_run() {
+    let code = "let f(x) => {\r\n\treturn x * 2 # doubled\r\n}\r\n\r\nf(21)\r\n";
+    match mono::eval(code) {
+        Ok(value) => assert_eq!(value.to_string(), "42"),
+        Err(error) => panic!("{}", error),
+    }
+}
+
+#[test]
+fn lone_carriage_return_is_unrecognized() {
+    let error = mono::tokenize("a\rb").expect_err("a\\rb");
+    assert!(matches!(
+        *error,
+        MonoErrorKind::Syntax(Syntax::UnrecognizedChar { c: '\r', .. })
+    ));
+}
+
+#[test]
+fn shebang_line_is_a_comment() {
+    let found = spans("#!/usr/bin/env mono\nx");
+    assert_eq!(
+        found,
+        [
+            (TokenKind::NewLine, (1, 20, 21)),
+            (TokenKind::Identifier("x".into()), (2, 1, 2))
+        ]
+    );
+    assert_eq!(
+        mono::eval("#!/usr/bin/env -S mono -O\n1 + 2").map(|value| value.to_string()),
+        Ok(String::from("3"))
+    );
+}
+
+#[test]
+fn long_runs_of_spaces_are_skipped() {
+    let source = format!("{}x", " ".repeat(100_000));
+    assert_eq!(
+        spans(&source),
+        [(TokenKind::Identifier("x".into()), (1, 100_001, 100_002))]
+    );
+}
+
+#[test]
+fn long_runs_of_tabs_are_skipped() {
+    let source = format!("{}x", "\t".repeat(100_000));
+    let found = spans(&source);
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].1 .1, 100_000 * 4 + 1);
+}
+
+#[test]
+fn long_runs_of_continuations_are_skipped() {
+    let source = format!("x{}y", " \\\n".repeat(100_000));
+    let found = spans(&source);
+    assert_eq!(
+        found[1],
+        (TokenKind::Identifier("y".into()), (100_001, 1, 2))
+    );
+    let source = " \\\n".repeat(100_000);
+    let error = mono::tokenize(&source).expect_err("\\");
+    assert!(matches!(
+        *error,
+        MonoErrorKind::Syntax(Syntax::UnexpectedEOF)
+    ));
+}
+
+#[test]
+fn identifiers_are_interned() {
+    let tokens = mono::tokenize("count + count * other").unwrap();
+    let symbol = |index: usize| match &tokens[index].kind {
+        TokenKind::Identifier(symbol) => symbol.clone(),
+        kind => panic!("{:?}", kind),
+    };
+    assert!(Arc::ptr_eq(&symbol(0), &symbol(2)));
+    assert!(!Arc::ptr_eq(&symbol(0), &symbol(4)));
+}
+
+#[test]
+fn readers_are_decoded_as_theyre_read() {
+    // A one byte buffer splits every multi-byte character between reads.
+    let reader = BufReader::with_capacity(1, "naïve → 🦀".as_bytes());
+    assert_eq!(Utf8Chars::new(reader).collect::<String>(), "naïve → 🦀");
+    let invalid: &[u8] = &[b'a', 0xFF, 0xE2, 0x86, b'b'];
+    assert_eq!(
+        Utf8Chars::new(invalid).collect::<String>(),
+        "a\u{FFFD}\u{FFFD}b"
+    );
+
+    let tokens: Vec<TokenKind> = Tokenizer::from_reader("let s = \"ï\"".as_bytes())
+        .map(|token| token.unwrap().kind)
+        .collect();
+    assert_eq!(
+        tokens,
+        mono::tokenize("let s = \"ï\"")
+            .unwrap()
+            .into_iter()
+            .map(|token| token.kind)
+            .collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn same_tokens_have_the_same_values() {
+    assert!(TokenKind::Integer(1) == TokenKind::Integer(2));
+    assert!(!TokenKind::Integer(1).same(&TokenKind::Integer(2)));
+    assert!(TokenKind::Integer(1).same(&TokenKind::Integer(1)));
+    assert!(TokenKind::Float(f32::NAN).same(&TokenKind::Float(f32::NAN)));
+    assert!(!TokenKind::Float(0.0).same(&TokenKind::Float(-0.0)));
+    assert!(TokenKind::Add.same(&TokenKind::Add));
+    assert!(!TokenKind::Add.same(&TokenKind::Sub));
+}
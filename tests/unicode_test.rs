@@ -0,0 +1,37 @@
+mod common;
+
+use common::eval;
+use mono::models::error::{MonoErrorKind, Runtime};
+use mono::Value;
+
+#[test]
+fn identifiers_can_be_unicode() {
+    assert_eq!(eval("let café = 2\ncafé * 3"), Value::Integer(6));
+    assert_eq!(
+        eval("def größe(xs) { return len(xs) }\ngröße([1])"),
+        Value::Integer(1)
+    );
+}
+
+#[test]
+fn strings_are_indexed_by_characters() {
+    // The example in the README.
+    assert_eq!(eval("let s = \"naïve\"\ns[2]"), Value::Character('ï'));
+    assert_eq!(eval("let s = \"naïve\"\ns[4]"), Value::Character('e'));
+    assert_eq!(
+        eval("let s = \"日本\"\n[c for c in s]").to_string(),
+        "[日, 本]"
+    );
+}
+
+#[test]
+fn indices_past_the_last_character_are_errors() {
+    // "naïve" is six bytes long but only five characters.
+    for code in ["let s = \"naïve\"\ns[5]", "let s = \"naïve\"\ns[-9]"] {
+        let error = mono::eval(code).expect_err(code);
+        assert!(matches!(
+            *error,
+            MonoErrorKind::Runtime(Runtime::InvalidIndex { .. })
+        ));
+    }
+}
@@ -0,0 +1,72 @@
+#![cfg(feature = "units")]
+
+use mono::extensions::units::{self, Dimension, Quantity};
+use mono::models::error::{MonoErrorKind, Runtime};
+use mono::{Evaluator, MonoError, Value};
+
+fn run(code: &str) -> Result<Value, MonoError> {
+    let mut evaluator = Evaluator::new();
+    units::register(&mut evaluator);
+    let program = mono::parse(code).unwrap_or_else(|error| panic!("{}: {}", code, error));
+    evaluator.evaluate(&program)
+}
+
+fn show(code: &str) -> String {
+    run(code)
+        .unwrap_or_else(|error| panic!("{}: {}", code, error))
+        .to_string()
+}
+
+#[test]
+fn quantities_are_kept_in_base_units() {
+    assert_eq!(show("unit(3, \"km\")"), "3000 m");
+    assert_eq!(show("unit(2, \"min\")"), "120 s");
+    assert_eq!(show("unit(3, \"km\") / unit(20, \"min\")"), "2.5 m/s");
+    assert_eq!(
+        show("magnitude(unit(3, \"km\") / unit(20, \"min\"), \"km/h\")"),
+        "9.0"
+    );
+}
+
+#[test]
+fn dimensions_combine_through_operators() {
+    assert_eq!(
+        show("unit(80, \"kg\") * unit(9.8, \"m/s^2\")"),
+        "784 kg*m/s^2"
+    );
+    assert_eq!(show("unit(2, \"m\") ^ 2"), "4 m^2");
+    assert_eq!(show("1 / unit(4, \"s\")"), "0.25 1/s");
+    assert_eq!(show("2 * unit(1, \"m\")"), "2 m");
+    assert_eq!(show("-unit(1, \"m\")"), "-1 m");
+    assert_eq!(show("unit(1, \"km\") > unit(900, \"m\")"), "True");
+    assert_eq!(show("unit(1, \"m\") == unit(1, \"s\")"), "False");
+}
+
+#[test]
+fn mismatched_dimensions_are_errors() {
+    let error = run("unit(1, \"m\") + unit(1, \"s\")").unwrap_err();
+    assert!(matches!(
+        *error,
+        MonoErrorKind::Runtime(Runtime::InvalidOperation { .. })
+    ));
+    let error = run("unit(1, \"m\") / unit(0, \"s\")").unwrap_err();
+    assert!(matches!(
+        *error,
+        MonoErrorKind::Runtime(Runtime::DivisionByZero { .. })
+    ));
+    assert_eq!(
+        run("magnitude(unit(1, \"m\"), \"s\")").unwrap(),
+        Value::None
+    );
+    assert_eq!(run("unit(1, \"parsec\")").unwrap(), Value::None);
+}
+
+#[test]
+fn hosts_read_quantities_back() {
+    let Value::External(external) = run("unit(3, \"km\")").unwrap() else {
+        panic!("not an external value");
+    };
+    let quantity = external.downcast_ref::<Quantity>().unwrap();
+    assert_eq!(quantity.magnitude(), 3000.0);
+    assert_eq!(quantity.dimension(), Dimension::new(1, 0, 0));
+}
@@ -0,0 +1,31 @@
+#![cfg(feature = "wasm")]
+
+use mono::wasm;
+
+#[test]
+fn evaluations_hold_the_output_and_value() {
+    let evaluation = wasm::evaluate("print(\"hi\")\n1 + 2");
+    assert_eq!(evaluation.output, "hi");
+    assert_eq!(evaluation.value.as_deref(), Some("3"));
+    assert_eq!(evaluation.error, None);
+    assert_eq!(wasm::evaluate("let x = 1").value, None);
+}
+
+#[test]
+fn evaluations_hold_the_error_and_its_kind() {
+    let evaluation = wasm::evaluate("print(1)\nundefined");
+    assert_eq!(evaluation.output, "1");
+    assert_eq!(evaluation.value, None);
+    assert!(evaluation.error.unwrap().contains("undefined"));
+    assert_eq!(evaluation.error_kind.as_deref(), Some("RuntimeError"));
+    assert_eq!(
+        wasm::evaluate("1 +").error_kind.as_deref(),
+        Some("SyntaxError")
+    );
+}
+
+#[test]
+fn eval_shows_what_the_cli_would() {
+    assert_eq!(wasm::eval("println(1)\n2"), "1\n2");
+    assert!(wasm::eval("1 +").starts_with("SyntaxError: "));
+}
@@ -0,0 +1,112 @@
+use std::fs;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+
+fn temporary(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("mono_watch_{}_{}.mono", name, std::process::id()))
+}
+
+#[test]
+fn missing_files_are_errors_on_the_first_read() {
+    assert!(mono::poll(Path::new("/nonexistent/file.mono"), |_| {}).is_err());
+}
+
+#[test]
+fn files_replaced_while_watched_are_read_again() {
+    let path = temporary("replaced");
+    fs::write(&path, "1").unwrap();
+    let (sender, receiver) = mpsc::channel();
+    let watched = path.clone();
+    thread::spawn(move || mono::poll(&watched, |code| sender.send(code.to_string()).unwrap()));
+    let timeout = Duration::from_secs(5);
+    assert_eq!(receiver.recv_timeout(timeout).unwrap(), "1");
+
+    // Saving by replacing the file leaves it missing for a few polls.
+    fs::remove_file(&path).unwrap();
+    thread::sleep(Duration::from_millis(600));
+    fs::write(&path, "2").unwrap();
+    let reread = receiver.recv_timeout(timeout);
+    fs::remove_file(&path).unwrap();
+    assert_eq!(reread.unwrap(), "2");
+}
+
+#[test]
+fn only_changed_function_bodies_are_compared() {
+    let parse = |code: &str| mono::parse(code).unwrap();
+    let before = parse("def f() { return 1 }\ndef g() { return 2 }\nprint(f())");
+    let after = parse("def f() { return 1 }\ndef g() { return 3 }\nprint(f())");
+    let changed = after.changed_functions(&before).unwrap();
+    assert_eq!(changed.len(), 1);
+    assert!(
+        changed[0].to_source().contains("g() => {\n    return 3\n}"),
+        "{}",
+        changed[0].to_source()
+    );
+    assert_eq!(before.changed_functions(&before).unwrap().len(), 0);
+    // Anything else changing runs the whole program again.
+    let rewritten = parse("def f() { return 1 }\ndef g() { return 2 }\nprint(g())");
+    assert!(rewritten.changed_functions(&before).is_none());
+    let renamed = parse("def f() { return 1 }\ndef h() { return 2 }\nprint(f())");
+    assert!(renamed.changed_functions(&before).is_none());
+}
+
+// Waits for a line of the watcher's output containing the text, failing on
+// any line in `unexpected` first.
+fn expect(lines: &mpsc::Receiver<String>, text: &str, unexpected: &[&str]) {
+    loop {
+        let line = lines
+            .recv_timeout(Duration::from_secs(5))
+            .unwrap_or_else(|_| panic!("no line containing {:?}", text));
+        if line.contains(text) {
+            return;
+        }
+        assert!(
+            !unexpected
+                .iter()
+                .any(|unexpected| line.contains(unexpected)),
+            "{:?} before {:?}",
+            line,
+            text
+        );
+    }
+}
+
+#[test]
+fn watching_reloads_changed_functions_without_rerunning() {
+    let path = temporary("reload");
+    fs::write(&path, "def f() { return 1 }\nprintln(\"ran\", f())\n").unwrap();
+    let mut child = Command::new(env!("CARGO_BIN_EXE_mono"))
+        .args(["watch", path.to_str().unwrap()])
+        .env("NO_COLOR", "1")
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .unwrap();
+    let stdout = BufReader::new(child.stdout.take().unwrap());
+    let (sender, lines) = mpsc::channel();
+    thread::spawn(move || {
+        for line in stdout.lines().map_while(Result::ok) {
+            if sender.send(line).is_err() {
+                break;
+            }
+        }
+    });
+
+    expect(&lines, "ran 1", &[]);
+    thread::sleep(Duration::from_millis(300));
+    fs::write(&path, "def f() { return 2 }\nprintln(\"ran\", f())\n").unwrap();
+    expect(&lines, "Reloaded", &["ran"]);
+    expect(&lines, "f", &["ran"]);
+    thread::sleep(Duration::from_millis(300));
+    fs::write(&path, "def f() { return 2 }\nprintln(\"ran again\", f())\n").unwrap();
+    expect(&lines, "ran again 2", &[]);
+
+    child.kill().unwrap();
+    child.wait().unwrap();
+    fs::remove_file(&path).unwrap();
+}